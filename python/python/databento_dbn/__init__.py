@@ -3,6 +3,8 @@ from typing import Protocol, Sequence
 
 # Import native module
 from ._lib import *  # noqa: F403
+from ._async import AsyncDBNDecoder
+from ._dataframe import to_df
 
 
 class MappingInterval(Protocol):
@@ -0,0 +1,84 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any, AsyncIterable, AsyncIterator, Protocol, Union
+
+from ._lib import DBNDecoder
+from ._lib import DBNError
+from ._lib import VersionUpgradePolicy
+
+
+if TYPE_CHECKING:
+    from ._lib import _DBNRecord
+
+
+class _AsyncReader(Protocol):
+    async def read(self, n: int = -1) -> bytes: ...
+
+
+class AsyncDBNDecoder:
+    """
+    An asyncio-compatible decoder for DBN data read from an asynchronous
+    stream, such as an `asyncio.StreamReader`, an aiohttp response's
+    `content`, or a websocket connection.
+
+    Records are decoded as data arrives and can be iterated with `async for`.
+
+    Parameters
+    ----------
+    source : asyncio.StreamReader | AsyncIterable[bytes]
+        The source of DBN data. Objects with an async `read` method are read
+        in chunks of `chunk_size` bytes, otherwise `source` is iterated.
+    has_metadata : bool, default True
+        Whether the input bytes begin with DBN metadata. Pass False to decode
+        individual records or a fragment of a DBN stream.
+    ts_out : bool, default False
+        Whether the records include the server send timestamp ts_out. Only needs to be
+        specified if `has_metadata` is False.
+    input_version : int, default current DBN version
+        Specify the DBN version of the input. Only used when transcoding data without
+        metadata.
+    upgrade_policy : VersionUpgradePolicy, default UPGRADE
+        How to decode data from prior DBN versions. Defaults to upgrade decoding.
+    chunk_size : int, default 65536
+        The maximum number of bytes to read at a time from sources with a `read`
+        method.
+
+    """
+
+    def __init__(
+        self,
+        source: Union[_AsyncReader, AsyncIterable[bytes]],
+        has_metadata: bool = True,
+        ts_out: bool = False,
+        input_version: int | None = None,
+        upgrade_policy: VersionUpgradePolicy | None = None,
+        chunk_size: int = 2**16,
+    ) -> None:
+        self._source = source
+        self._decoder = DBNDecoder(
+            has_metadata=has_metadata,
+            ts_out=ts_out,
+            input_version=input_version,
+            upgrade_policy=upgrade_policy,
+        )
+        self._chunk_size = chunk_size
+
+    def __aiter__(self) -> AsyncIterator[_DBNRecord]:
+        return self._records()
+
+    async def _records(self) -> AsyncIterator[_DBNRecord]:
+        async for chunk in self._chunks():
+            self._decoder.write(chunk)
+            for record in self._decoder.decode():
+                yield record
+        if self._decoder.buffer().nbytes > 0:
+            raise DBNError("Stream ended with an incomplete record")
+
+    async def _chunks(self) -> AsyncIterator[Any]:
+        read = getattr(self._source, "read", None)
+        if read is not None:
+            while chunk := await read(self._chunk_size):
+                yield chunk
+        else:
+            async for chunk in self._source:  # type: ignore[union-attr]
+                yield chunk
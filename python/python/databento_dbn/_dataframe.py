@@ -0,0 +1,156 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any, Sequence, Union
+
+from ._lib import FIXED_PRICE_SCALE
+from ._lib import UNDEF_PRICE
+from ._lib import UNDEF_TIMESTAMP
+from ._lib import BBOMsg
+from ._lib import CBBOMsg
+from ._lib import DBNDecoder
+from ._lib import ImbalanceMsg
+from ._lib import InstrumentDefMsg
+from ._lib import MBOMsg
+from ._lib import MBP1Msg
+from ._lib import MBP10Msg
+from ._lib import Metadata
+from ._lib import OHLCVMsg
+from ._lib import Schema
+from ._lib import StatMsg
+from ._lib import StatusMsg
+from ._lib import TradeMsg
+
+
+if TYPE_CHECKING:
+    import pandas as pd
+
+
+_SCHEMA_RECORD_TYPES = {
+    Schema.MBO: MBOMsg,
+    Schema.MBP_1: MBP1Msg,
+    Schema.TBBO: MBP1Msg,
+    Schema.MBP_10: MBP10Msg,
+    Schema.TRADES: TradeMsg,
+    Schema.OHLCV_1S: OHLCVMsg,
+    Schema.OHLCV_1M: OHLCVMsg,
+    Schema.OHLCV_1H: OHLCVMsg,
+    Schema.OHLCV_1D: OHLCVMsg,
+    Schema.OHLCV_EOD: OHLCVMsg,
+    Schema.DEFINITION: InstrumentDefMsg,
+    Schema.STATISTICS: StatMsg,
+    Schema.STATUS: StatusMsg,
+    Schema.IMBALANCE: ImbalanceMsg,
+    Schema.CBBO: CBBOMsg,
+    Schema.CBBO_1S: CBBOMsg,
+    Schema.CBBO_1M: CBBOMsg,
+    Schema.TCBBO: CBBOMsg,
+    Schema.BBO_1S: BBOMsg,
+    Schema.BBO_1M: BBOMsg,
+}
+
+
+def to_df(
+    data: Union[bytes, bytearray, memoryview, Sequence[Any]],
+    schema: Schema | None = None,
+    pretty_px: bool = True,
+    pretty_ts: bool = True,
+) -> pd.DataFrame:
+    """
+    Convert DBN data into a pandas DataFrame. Requires pandas to be installed,
+    e.g. with the `pandas` extra.
+
+    Parameters
+    ----------
+    data : bytes | bytearray | memoryview | Sequence[DBNRecord]
+        Either uncompressed DBN data beginning with metadata, or a sequence of
+        decoded records of a single type.
+    schema : Schema | None, default None
+        The schema of the records to convert. Only records of this schema are
+        included. Defaults to the schema in the metadata. Ignored when `data`
+        is a sequence of records.
+    pretty_px : bool, default True
+        Whether to convert fixed-precision prices to floats. Undefined prices
+        are converted to NaN.
+    pretty_ts : bool, default True
+        Whether to convert nanosecond UNIX timestamps to UTC datetimes. Undefined
+        timestamps are converted to NaT.
+
+    Returns
+    -------
+    pandas.DataFrame
+        Indexed by `ts_recv` if the records have it, otherwise by `ts_event`.
+
+    Raises
+    ------
+    ValueError
+        When the data can't be decoded or the schema can't be determined.
+
+    """
+    import numpy as np
+    import pandas as pd
+
+    if isinstance(data, (bytes, bytearray, memoryview)):
+        data = bytes(data)
+        if schema is None:
+            schema = Metadata.decode(data).schema
+        if schema is None:
+            raise ValueError(
+                "A schema must be specified when converting mixed schema DBN",
+            )
+        record_type = _SCHEMA_RECORD_TYPES[schema]
+        decoder = DBNDecoder()
+        decoder.write(data)
+        array = decoder.decode_ndarray(schema)
+    else:
+        if len(data) == 0:
+            raise ValueError("Cannot determine the record type of an empty sequence")
+        record_type = type(data[0])
+        names, formats = zip(*record_type._dtypes)
+        dtype = np.dtype(
+            {
+                "names": names,
+                "formats": formats,
+                "itemsize": record_type.size_hint,
+            },
+        )
+        array = np.frombuffer(b"".join(bytes(rec) for rec in data), dtype=dtype)
+        if hasattr(data[0], "ts_out"):
+            array = _append_ts_out(array, [rec.ts_out for rec in data])
+
+    df = pd.DataFrame(array)
+    columns = list(record_type._ordered_fields)
+    if "ts_out" in df.columns:
+        columns.append("ts_out")
+    df = df[columns]
+
+    for column in columns:
+        if df[column].dtype == object:
+            df[column] = df[column].str.decode("utf-8")
+    if pretty_px:
+        for column in record_type._price_fields:
+            df[column] = df[column].mask(df[column] == UNDEF_PRICE) / FIXED_PRICE_SCALE
+    if pretty_ts:
+        timestamp_fields = list(record_type._timestamp_fields)
+        if "ts_out" in df.columns:
+            timestamp_fields.append("ts_out")
+        for column in timestamp_fields:
+            is_undef = df[column] == UNDEF_TIMESTAMP
+            df[column] = pd.to_datetime(
+                df[column].mask(is_undef, 0).astype("int64"),
+                unit="ns",
+                utc=True,
+            ).mask(is_undef)
+
+    index_column = "ts_recv" if "ts_recv" in df.columns else "ts_event"
+    return df.set_index(index_column)
+
+
+def _append_ts_out(array: Any, ts_outs: list[int]) -> Any:
+    import numpy as np
+
+    dtype = np.dtype(array.dtype.descr + [("ts_out", "u8")])
+    res = np.empty(len(array), dtype=dtype)
+    for name in array.dtype.names:
+        res[name] = array[name]
+    res["ts_out"] = ts_outs
+    return res
@@ -2,10 +2,12 @@
 from __future__ import annotations
 
 import datetime as dt
+from collections.abc import Callable
 from collections.abc import Iterable
+from collections.abc import Iterator
 from collections.abc import Sequence
 from enum import Enum
-from typing import BinaryIO, ClassVar, SupportsBytes, TextIO, TypedDict, Union
+from typing import Any, BinaryIO, ClassVar, SupportsBytes, TextIO, TypedDict, Union
 
 from databento_dbn import SymbolMapping
 
@@ -22,6 +24,8 @@ _DBNRecord = Union[
     MBP1Msg,
     MBP10Msg,
     OHLCVMsg,
+    BBOMsg,
+    CBBOMsg,
     TradeMsg,
     InstrumentDefMsg,
     InstrumentDefMsgV1,
@@ -35,6 +39,11 @@ _DBNRecord = Union[
     StatMsg,
 ]
 
+class DBNError(ValueError):
+    """
+    An error from decoding malformed DBN data.
+    """
+
 class Compression(Enum):
     """
     Data compression format.
@@ -108,6 +117,18 @@ class Schema(Enum):
         Exchange status.
     IMBALANCE
         Auction imbalance events.
+    CBBO
+        Consolidated best bid and offer.
+    CBBO_1S
+        Consolidated best bid and offer subsampled at one-second intervals, in addition to trades.
+    CBBO_1M
+        Consolidated best bid and offer subsampled at one-minute intervals, in addition to trades.
+    TCBBO
+        All trade events with the consolidated best bid and offer (CBBO) immediately before the effect of the trade.
+    BBO_1S
+        Best bid and offer subsampled at one-second intervals, in addition to trades.
+    BBO_1M
+        Best bid and offer subsampled at one-minute intervals, in addition to trades.
 
     """
 
@@ -125,12 +146,72 @@ class Schema(Enum):
     STATISTICS: str
     STATUS: str
     IMBALANCE: str
+    CBBO: str
+    CBBO_1S: str
+    CBBO_1M: str
+    TCBBO: str
+    BBO_1S: str
+    BBO_1M: str
 
     @classmethod
     def from_str(cls, value: str) -> Schema: ...
     @classmethod
     def variants(cls) -> Iterable[Schema]: ...
 
+class Side(Enum):
+    """
+    A side of the market. The side of the market for resting orders, or the side
+    of the aggressor for trades.
+
+    ASK
+        A sell order or sell aggressor in a trade.
+    BID
+        A buy order or a buy aggressor in a trade.
+    NONE
+        No side specified by the original source.
+
+    """
+
+    ASK: str
+    BID: str
+    NONE: str
+
+    @classmethod
+    def from_str(cls, value: str) -> Side: ...
+    @classmethod
+    def variants(cls) -> Iterable[Side]: ...
+
+class Action(Enum):
+    """
+    A tick action.
+
+    MODIFY
+        An existing order was modified.
+    TRADE
+        A trade executed.
+    FILL
+        An existing order was filled.
+    CANCEL
+        An order was cancelled.
+    ADD
+        A new order was added.
+    CLEAR
+        Reset the book; clear all orders for an instrument.
+
+    """
+
+    MODIFY: str
+    TRADE: str
+    FILL: str
+    CANCEL: str
+    ADD: str
+    CLEAR: str
+
+    @classmethod
+    def from_str(cls, value: str) -> Action: ...
+    @classmethod
+    def variants(cls) -> Iterable[Action]: ...
+
 class SType(Enum):
     """
     A DBN symbology type.
@@ -200,6 +281,21 @@ class RType(Enum):
         Denotes a statistics record from the publisher (not calculated by Databento).
     MBO
         Denotes a market by order record.
+    CBBO
+        Denotes a consolidated best bid and offer record.
+    CBBO_1S
+        Denotes a consolidated best bid and offer record subsampled on a one-second
+        interval.
+    CBBO_1M
+        Denotes a consolidated best bid and offer record subsampled on a one-minute
+        interval.
+    TCBBO
+        Denotes a consolidated best bid and offer trade record containing the
+        consolidated BBO before the trade.
+    BBO_1S
+        Denotes a best bid and offer record subsampled on a one-second interval.
+    BBO_1M
+        Denotes a best bid and offer record subsampled on a one-minute interval.
 
     """  # noqa: D405 D407 D411
 
@@ -258,18 +354,19 @@ class Metadata(SupportsBytes):
     def __init__(
         self,
         dataset: str,
-        start: int,
-        stype_out: SType,
-        symbols: list[str],
-        partial: list[str],
-        not_found: list[str],
-        mappings: Sequence[SymbolMapping],
-        schema: Schema | None = None,
-        stype_in: SType | None = None,
-        end: int | None = None,
+        start: int | dt.datetime,
+        stype_out: SType | str,
+        symbols: list[str] = [],
+        partial: list[str] = [],
+        not_found: list[str] = [],
+        mappings: Sequence[SymbolMapping] = [],
+        schema: Schema | str | None = None,
+        stype_in: SType | str | None = None,
+        end: int | dt.datetime | None = None,
         limit: int | None = None,
         ts_out: bool | None = None,
         version: int | None = None,
+        custom: dict[str, str] = {},
     ) -> None: ...
     def __bytes__(self) -> bytes: ...
     def __eq__(self, other) -> bool: ...
@@ -316,27 +413,33 @@ class Metadata(SupportsBytes):
         int
 
         """
+    @start.setter
+    def start(self, value: int | dt.datetime) -> None: ...
     @property
-    def end(self) -> int:
+    def end(self) -> int | None:
         """
         The UNIX nanosecond timestamp of the query end, or the last record if
         the file was split.
 
         Returns
         -------
-        int
+        int | None
 
         """
+    @end.setter
+    def end(self, value: int | dt.datetime | None) -> None: ...
     @property
-    def limit(self) -> int:
+    def limit(self) -> int | None:
         """
         The optional maximum number of records for the query.
 
         Returns
         -------
-        int
+        int | None
 
         """
+    @limit.setter
+    def limit(self, value: int | None) -> None: ...
     @property
     def stype_in(self) -> SType | None:
         """
@@ -378,6 +481,8 @@ class Metadata(SupportsBytes):
         list[str]
 
         """
+    @symbols.setter
+    def symbols(self, value: list[str]) -> None: ...
     @property
     def partial(self) -> list[str]:
         """
@@ -409,6 +514,19 @@ class Metadata(SupportsBytes):
         dict[str, list[dict[str, Any]]]:
 
         """
+    @property
+    def custom(self) -> dict[str, str]:
+        """
+        User-defined key-value properties, e.g. to record the provenance of the
+        data.
+
+        Returns
+        -------
+        dict[str, str]
+
+        """
+    @custom.setter
+    def custom(self, value: dict[str, str]) -> None: ...
     @classmethod
     def decode(
         cls, data: bytes, upgrade_policy: VersionUpgradePolicy | None = None
@@ -504,6 +622,16 @@ class RecordHeader:
         -------
         int
 
+        """
+    @property
+    def rtype_name(self) -> str:
+        """
+        The name of the record type, e.g. "mbo" or "instrument-def".
+
+        Returns
+        -------
+        str
+
         """
 
 class Record(SupportsBytes):
@@ -521,6 +649,49 @@ class Record(SupportsBytes):
     def __bytes__(self) -> bytes: ...
     def __eq__(self, other) -> bool: ...
     def __ne__(self, other) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __iter__(self) -> Iterator[tuple[str, Any]]: ...
+    @classmethod
+    def from_bytes(cls, data: bytes) -> Record:
+        """
+        Create a record from its DBN representation, e.g. the output of `bytes()`.
+
+        Parameters
+        ----------
+        data : bytes
+            The raw record bytes.
+
+        Returns
+        -------
+        Record
+
+        Raises
+        ------
+        ValueError
+            When `data` is too short or has a different rtype.
+
+        """
+    def to_bytes(self) -> bytes:
+        """
+        Return the record's DBN representation. Unlike `bytes()`, `ts_out` is
+        appended if present.
+
+        Returns
+        -------
+        bytes
+
+        """
+    def asdict(self) -> dict[str, Any]:
+        """
+        Return the record's fields as a `dict` in the same order as the CSV encoding.
+        Book levels are flattened, e.g. `bid_px_00`, and `ts_out` is included if
+        present.
+
+        Returns
+        -------
+        dict[str, Any]
+
+        """
     @property
     def hd(self) -> RecordHeader:
         """
@@ -532,6 +703,26 @@ class Record(SupportsBytes):
 
         """
     @property
+    def header(self) -> RecordHeader:
+        """
+        A copy of the common header, for handling records generically.
+
+        Returns
+        -------
+        RecordHeader
+
+        """
+    @property
+    def rtype_name(self) -> str:
+        """
+        The name of the record type, e.g. "mbo" or "instrument-def".
+
+        Returns
+        -------
+        str
+
+        """
+    @property
     def record_size(self) -> int:
         """
         Return the size of the record in bytes.
@@ -576,7 +767,7 @@ class Record(SupportsBytes):
 
         """
     @property
-    def pretty_ts_event(self) -> dt.datetime:
+    def pretty_ts_event(self) -> dt.datetime | None:
         """
         The matching-engine-received timestamp expressed as a
         datetime or a `pandas.Timestamp`, if available.
@@ -684,18 +875,18 @@ class _MBOBase:
 
         """
     @property
-    def action(self) -> str:
+    def action(self) -> Action | str:
         """
         The event action. Can be `A`dd, `C`ancel, `M`odify, clea`R`, `T`rade,
         or `F`ill.
 
         Returns
         -------
-        str
+        Action | str
 
         """
     @property
-    def side(self) -> str:
+    def side(self) -> Side | str:
         """
         The side that initiates the event. Can be `A`sk for a sell order (or sell
         aggressor in a trade), `B`id for a buy order (or buy aggressor in a trade), or
@@ -703,11 +894,11 @@ class _MBOBase:
 
         Returns
         -------
-        str
+        Side | str
 
         """
     @property
-    def pretty_ts_recv(self) -> dt.datetime:
+    def pretty_ts_recv(self) -> dt.datetime | None:
         """
         The capture-server-received timestamp as a datetime or
         `pandas.Timestamp`, if available.
@@ -754,6 +945,23 @@ class MBOMsg(Record, _MBOBase):
     A market-by-order (MBO) tick message.
     """
 
+    def __init__(
+        self,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        order_id: int | None = None,
+        price: int | None = None,
+        size: int | None = None,
+        channel_id: int | None = None,
+        action: int | None = None,
+        side: int | None = None,
+        ts_recv: int | None = None,
+        ts_in_delta: int | None = None,
+        sequence: int | None = None,
+        flags: int | None = None,
+    ) -> None: ...
+
 class BidAskPair:
     """
     A book level.
@@ -903,18 +1111,18 @@ class _MBPBase:
 
         """
     @property
-    def action(self) -> str:
+    def action(self) -> Action | str:
         """
         The event action. Can be `A`dd, `C`ancel, `M`odify, clea`R`, or
         `T`rade.
 
         Returns
         -------
-        str
+        Action | str
 
         """
     @property
-    def side(self) -> str:
+    def side(self) -> Side | str:
         """
         The side that initiates the event. Can be `A`sk for a sell order (or sell
         aggressor in a trade), `B`id for a buy order (or buy aggressor in a trade), or
@@ -922,7 +1130,7 @@ class _MBPBase:
 
         Returns
         -------
-        str
+        Side | str
 
         """
     @property
@@ -946,7 +1154,318 @@ class _MBPBase:
 
         """
     @property
-    def pretty_ts_recv(self) -> dt.datetime:
+    def pretty_ts_recv(self) -> dt.datetime | None:
+        """
+        The capture-server-received timestamp as a datetime or
+        `pandas.Timestamp`, if available.
+
+        Returns
+        -------
+        datetime.datetime
+
+        """
+    @property
+    def ts_recv(self) -> int:
+        """
+        The capture-server-received timestamp expressed as number of
+        nanoseconds since the UNIX epoch.
+
+        Returns
+        -------
+        int
+
+        """
+    @property
+    def ts_in_delta(self) -> int:
+        """
+        The delta of `ts_recv - ts_exchange_send`, max 2 seconds.
+
+        Returns
+        -------
+        int
+
+        """
+    @property
+    def sequence(self) -> int:
+        """
+        The message sequence number assigned at the venue.
+
+        Returns
+        -------
+        int
+
+        """
+
+class TradeMsg(Record, _MBPBase):
+    """
+    Market by price implementation with a book depth of 0.
+
+    Equivalent to MBP-0. The record of the `Trades` schema.
+
+    """
+
+    def __init__(
+        self,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        price: int | None = None,
+        size: int | None = None,
+        action: int | None = None,
+        side: int | None = None,
+        depth: int | None = None,
+        ts_recv: int | None = None,
+        ts_in_delta: int | None = None,
+        sequence: int | None = None,
+        flags: int | None = None,
+    ) -> None: ...
+
+class MBP1Msg(Record, _MBPBase):
+    """
+    Market by price implementation with a known book depth of 1.
+    """
+
+    def __init__(
+        self,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        price: int | None = None,
+        size: int | None = None,
+        action: int | None = None,
+        side: int | None = None,
+        flags: int | None = None,
+        depth: int | None = None,
+        ts_recv: int | None = None,
+        ts_in_delta: int | None = None,
+        sequence: int | None = None,
+        levels: BidAskPair | None = None,
+    ) -> None: ...
+
+    @property
+    def levels(self) -> list[BidAskPair]:
+        """
+        The top of the order book.
+
+        Returns
+        -------
+        list[BidAskPair]
+
+        Notes
+        -----
+        MBP1Msg contains 1 level of BidAskPair.
+
+        """
+
+class MBP10Msg(Record, _MBPBase):
+    """
+    Market by price implementation with a known book depth of 10.
+    """
+
+    def __init__(
+        self,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        price: int | None = None,
+        size: int | None = None,
+        action: int | None = None,
+        side: int | None = None,
+        flags: int | None = None,
+        depth: int | None = None,
+        ts_recv: int | None = None,
+        ts_in_delta: int | None = None,
+        sequence: int | None = None,
+        levels: list[BidAskPair] | None = None,
+    ) -> None: ...
+
+    @property
+    def levels(self) -> list[BidAskPair]:
+        """
+        The top 10 levels.
+
+        Returns
+        -------
+        list[BidAskPair]
+
+        Notes
+        -----
+        MBP10Msg contains 10 levels of BidAskPairs.
+
+        """
+
+class ConsolidatedBidAskPair:
+    """
+    A consolidated book level.
+    """
+
+    @property
+    def pretty_bid_px(self) -> float:
+        """
+        The bid price as a float.
+
+        Returns
+        -------
+        float
+
+        See Also
+        --------
+        bid_px
+
+        """
+    @property
+    def bid_px(self) -> int:
+        """
+        The bid price expressed as a signed integer where every 1 unit
+        corresponds to 1e-9, i.e. 1/1,000,000,000 or 0.000000001.
+
+        Returns
+        -------
+        int
+
+        See Also
+        --------
+        pretty_bid_px
+
+        """
+    @property
+    def pretty_ask_px(self) -> float:
+        """
+        The ask price as a float.
+
+        Returns
+        -------
+        float
+
+        See Also
+        --------
+        ask_px
+
+        """
+    @property
+    def ask_px(self) -> int:
+        """
+        The ask price as a signed integer where every 1 unit
+        corresponds to 1e-9, i.e. 1/1,000,000,000 or 0.000000001.
+
+        Returns
+        -------
+        int
+
+        See Also
+        --------
+        pretty_ask_px
+
+        """
+    @property
+    def bid_sz(self) -> int:
+        """
+        The bid size.
+
+        Returns
+        -------
+        int
+
+        """
+    @property
+    def ask_sz(self) -> int:
+        """
+        The ask size.
+
+        Returns
+        -------
+        int
+
+        """
+    @property
+    def bid_pb(self) -> int:
+        """
+        The publisher ID of the bid.
+
+        Returns
+        -------
+        int
+
+        """
+    @property
+    def ask_pb(self) -> int:
+        """
+        The publisher ID of the ask.
+
+        Returns
+        -------
+        int
+
+        """
+
+class _BBOBase:
+    """
+    Base for best-bid-and-offer messages.
+    """
+
+    @property
+    def pretty_price(self) -> float:
+        """
+        The price of the last trade as a float.
+
+        Returns
+        -------
+        float
+
+        See Also
+        --------
+        price
+
+        """
+    @property
+    def price(self) -> int:
+        """
+        The price of the last trade expressed as a signed integer where every 1
+        unit corresponds to 1e-9, i.e. 1/1,000,000,000 or 0.000000001.
+
+        Returns
+        -------
+        int
+
+        See Also
+        --------
+        pretty_price
+
+        """
+    @property
+    def size(self) -> int:
+        """
+        The quantity of the last trade.
+
+        Returns
+        -------
+        int
+
+        """
+    @property
+    def side(self) -> Side | str:
+        """
+        The side that initiated the last trade. Can be `A`sk for a sell order (or
+        sell aggressor in a trade), `B`id for a buy order (or buy aggressor in a
+        trade), or `N`one where no side is specified by the original source.
+
+        Returns
+        -------
+        Side | str
+
+        """
+    @property
+    def flags(self) -> int:
+        """
+        A combination of packet end with matching engine status.
+
+        Returns
+        -------
+        int
+
+        """
+    @property
+    def pretty_ts_recv(self) -> dt.datetime | None:
         """
         The capture-server-received timestamp as a datetime or
         `pandas.Timestamp`, if available.
@@ -960,17 +1479,8 @@ class _MBPBase:
     def ts_recv(self) -> int:
         """
         The capture-server-received timestamp expressed as number of
-        nanoseconds since the UNIX epoch.
-
-        Returns
-        -------
-        int
-
-        """
-    @property
-    def ts_in_delta(self) -> int:
-        """
-        The delta of `ts_recv - ts_exchange_send`, max 2 seconds.
+        nanoseconds since the UNIX epoch. For subsampled schemas, the end of
+        the interval.
 
         Returns
         -------
@@ -980,7 +1490,7 @@ class _MBPBase:
     @property
     def sequence(self) -> int:
         """
-        The message sequence number assigned at the venue.
+        The message sequence number assigned at the venue of the last update.
 
         Returns
         -------
@@ -988,18 +1498,26 @@ class _MBPBase:
 
         """
 
-class TradeMsg(Record, _MBPBase):
+class BBOMsg(Record, _BBOBase):
     """
-    Market by price implementation with a book depth of 0.
-
-    Equivalent to MBP-0. The record of the `Trades` schema.
-
+    Subsampled market by price with a known book depth of 1. The record of the
+    `Bbo1S` and `Bbo1M` schemas.
     """
 
-class MBP1Msg(Record, _MBPBase):
-    """
-    Market by price implementation with a known book depth of 1.
-    """
+    def __init__(
+        self,
+        rtype: int,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        price: int | None = None,
+        size: int | None = None,
+        side: int | None = None,
+        flags: int | None = None,
+        ts_recv: int | None = None,
+        sequence: int | None = None,
+        levels: BidAskPair | None = None,
+    ) -> None: ...
 
     @property
     def levels(self) -> list[BidAskPair]:
@@ -1012,27 +1530,43 @@ class MBP1Msg(Record, _MBPBase):
 
         Notes
         -----
-        MBP1Msg contains 1 level of BidAskPair.
+        BBOMsg contains 1 level of BidAskPair.
 
         """
 
-class MBP10Msg(Record, _MBPBase):
+class CBBOMsg(Record, _BBOBase):
     """
-    Market by price implementation with a known book depth of 10.
+    Consolidated market by price with a known book depth of 1. The record of the
+    `Cbbo`, `Cbbo1S`, `Cbbo1M`, and `Tcbbo` schemas.
     """
 
+    def __init__(
+        self,
+        rtype: int,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        price: int | None = None,
+        size: int | None = None,
+        side: int | None = None,
+        flags: int | None = None,
+        ts_recv: int | None = None,
+        sequence: int | None = None,
+        levels: ConsolidatedBidAskPair | None = None,
+    ) -> None: ...
+
     @property
-    def levels(self) -> list[BidAskPair]:
+    def levels(self) -> list[ConsolidatedBidAskPair]:
         """
-        The top 10 levels.
+        The top of the consolidated order book.
 
         Returns
         -------
-        list[BidAskPair]
+        list[ConsolidatedBidAskPair]
 
         Notes
         -----
-        MBP10Msg contains 10 levels of BidAskPairs.
+        CBBOMsg contains 1 level of ConsolidatedBidAskPair.
 
         """
 
@@ -1041,6 +1575,19 @@ class OHLCVMsg(Record):
     Open, high, low, close, and volume message.
     """
 
+    def __init__(
+        self,
+        rtype: int,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        open: int | None = None,
+        high: int | None = None,
+        low: int | None = None,
+        close: int | None = None,
+        volume: int | None = None,
+    ) -> None: ...
+
     @property
     def pretty_open(self) -> float:
         """
@@ -1174,7 +1721,7 @@ class InstrumentDefMsg(Record):
     """
 
     @property
-    def pretty_ts_recv(self) -> dt.datetime:
+    def pretty_ts_recv(self) -> dt.datetime | None:
         """
         The capture-server-received timestamp as a datetime or
         `pandas.Timestamp`, if available.
@@ -1236,7 +1783,7 @@ class InstrumentDefMsg(Record):
 
         """
     @property
-    def pretty_expiration(self) -> dt.datetime:
+    def pretty_expiration(self) -> dt.datetime | None:
         """
         The last eligible trade time expressed as a datetime or
         `pandas.Timestamp`, if available.
@@ -1258,7 +1805,7 @@ class InstrumentDefMsg(Record):
 
         """
     @property
-    def pretty_activation(self) -> dt.datetime:
+    def pretty_activation(self) -> dt.datetime | None:
         """
         The time of instrument activation expressed as a datetime or
         `pandas.Timestamp`, if available.
@@ -1396,6 +1943,21 @@ class InstrumentDefMsg(Record):
 
         """
     @property
+    def pretty_unit_of_measure_qty(self) -> float:
+        """
+        The contract size for each instrument, in combination with
+        `unit_of_measure`, as a float.
+
+        Returns
+        -------
+        float
+
+        See Also
+        --------
+        unit_of_measure_qty
+
+        """
+    @property
     def unit_of_measure_qty(self) -> int:
         """
         The contract size for each instrument, in combination with
@@ -1405,6 +1967,10 @@ class InstrumentDefMsg(Record):
         -------
         int
 
+        See Also
+        --------
+        pretty_unit_of_measure_qty
+
         """
     @property
     def pretty_min_price_increment_amount(self) -> float:
@@ -1979,7 +2545,7 @@ class InstrumentDefMsgV1(Record):
     """
 
     @property
-    def pretty_ts_recv(self) -> dt.datetime:
+    def pretty_ts_recv(self) -> dt.datetime | None:
         """
         The capture-server-received timestamp as a datetime or
         `pandas.Timestamp`, if available.
@@ -2041,7 +2607,7 @@ class InstrumentDefMsgV1(Record):
 
         """
     @property
-    def pretty_expiration(self) -> dt.datetime:
+    def pretty_expiration(self) -> dt.datetime | None:
         """
         The last eligible trade time expressed as a datetime or
         `pandas.Timestamp`, if available.
@@ -2063,7 +2629,7 @@ class InstrumentDefMsgV1(Record):
 
         """
     @property
-    def pretty_activation(self) -> dt.datetime:
+    def pretty_activation(self) -> dt.datetime | None:
         """
         The time of instrument activation expressed as a datetime or
         `pandas.Timestamp`, if available.
@@ -2783,8 +3349,35 @@ class ImbalanceMsg(Record):
     An auction imbalance message.
     """
 
+    def __init__(
+        self,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        ts_recv: int | None = None,
+        ref_price: int | None = None,
+        cont_book_clr_price: int | None = None,
+        auct_interest_clr_price: int | None = None,
+        paired_qty: int | None = None,
+        total_imbalance_qty: int | None = None,
+        auction_type: int | None = None,
+        side: int | None = None,
+        significant_imbalance: int | None = None,
+        auction_time: int | None = None,
+        ssr_filling_price: int | None = None,
+        ind_match_price: int | None = None,
+        upper_collar: int | None = None,
+        lower_collar: int | None = None,
+        market_imbalance_qty: int | None = None,
+        unpaired_qty: int | None = None,
+        auction_status: int | None = None,
+        freeze_status: int | None = None,
+        num_extensions: int | None = None,
+        unpaired_side: int | None = None,
+    ) -> None: ...
+
     @property
-    def pretty_ts_recv(self) -> dt.datetime:
+    def pretty_ts_recv(self) -> dt.datetime | None:
         """
         The capture-server-received timestamp as a datetime or
         `pandas.Timestamp`, if available.
@@ -2996,14 +3589,14 @@ class ImbalanceMsg(Record):
 
         """
     @property
-    def side(self) -> str:
+    def side(self) -> Side | str:
         """
         The market side of the `total_imbalance_qty`. Can be `A`sk, `B`id, or
         `N`one.
 
         Returns
         -------
-        str
+        Side | str
 
         """
     @property
@@ -3037,13 +3630,13 @@ class ImbalanceMsg(Record):
 
         """
     @property
-    def unpaired_side(self) -> str:
+    def unpaired_side(self) -> Side | str:
         """
         Reserved for future use.
 
         Returns
         -------
-        str
+        Side | str
 
         """
     @property
@@ -3067,8 +3660,25 @@ class StatMsg(Record):
 
     """
 
+    def __init__(
+        self,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        ts_recv: int | None = None,
+        ts_ref: int | None = None,
+        price: int | None = None,
+        quantity: int | None = None,
+        sequence: int | None = None,
+        ts_in_delta: int | None = None,
+        stat_type: int | None = None,
+        channel_id: int | None = None,
+        update_action: int | None = None,
+        stat_flags: int | None = None,
+    ) -> None: ...
+
     @property
-    def pretty_ts_recv(self) -> dt.datetime:
+    def pretty_ts_recv(self) -> dt.datetime | None:
         """
         The capture-server-received timestamp as a datetime or
         `pandas.Timestamp`, if available.
@@ -3248,6 +3858,19 @@ class SymbolMappingMsg(Record):
     another.
     """
 
+    def __init__(
+        self,
+        publisher_id: int,
+        instrument_id: int,
+        ts_event: int,
+        stype_in: SType | None = None,
+        stype_in_symbol: str | None = None,
+        stype_out: SType | None = None,
+        stype_out_symbol: str | None = None,
+        start_ts: int | None = None,
+        end_ts: int | None = None,
+    ) -> None: ...
+
     @property
     def stype_in(self) -> SType:
         """
@@ -3289,7 +3912,7 @@ class SymbolMappingMsg(Record):
 
         """
     @property
-    def pretty_start_ts(self) -> dt.datetime:
+    def pretty_start_ts(self) -> dt.datetime | None:
         """
         The start of the mapping interval expressed as a datetime
         or `pandas.Timestamp`, if available.
@@ -3311,7 +3934,7 @@ class SymbolMappingMsg(Record):
 
         """
     @property
-    def pretty_end_ts(self) -> dt.datetime:
+    def pretty_end_ts(self) -> dt.datetime | None:
         """
         The end of the mapping interval expressed as a datetime
         or `pandas.Timestamp`, if available.
@@ -3359,7 +3982,7 @@ class SymbolMappingMsgV1(Record):
 
         """
     @property
-    def pretty_start_ts(self) -> dt.datetime:
+    def pretty_start_ts(self) -> dt.datetime | None:
         """
         The start of the mapping interval expressed as a datetime
         or `pandas.Timestamp`, if available.
@@ -3381,7 +4004,7 @@ class SymbolMappingMsgV1(Record):
 
         """
     @property
-    def pretty_end_ts(self) -> dt.datetime:
+    def pretty_end_ts(self) -> dt.datetime | None:
         """
         The end of the mapping interval expressed as a datetime
         or `pandas.Timestamp`, if available.
@@ -3481,6 +4104,10 @@ class DBNDecoder:
         metadata.
     upgrade_policy : VersionUpgradePolicy
         How to decode data from prior DBN versions. Defaults to decoding as-is.
+    max_buffer_size : int, optional
+        The maximum number of bytes written but not yet decoded the decoder will
+        buffer. Writes that would exceed it raise a `ValueError`. Defaults to
+        unbounded.
     """
 
     def __init__(
@@ -3489,14 +4116,17 @@ class DBNDecoder:
         ts_out: bool = False,
         input_version: int = 2,
         upgrade_policy: VersionUpgradePolicy | None = None,
+        max_buffer_size: int | None = None,
     ): ...
-    def buffer(self) -> bytes:
+    def buffer(self) -> memoryview:
         """
-        Return the internal buffer of the decoder.
+        Return a read-only view of the internal buffer of the decoder. The
+        decoder can't be written to or decode while the view is alive, so
+        release it with `memoryview.release()` once finished.
 
         Returns
         -------
-        bytes
+        memoryview
 
         """
     def decode(
@@ -3511,25 +4141,139 @@ class DBNDecoder:
 
         Raises
         ------
-        ValueError
-            When the decoding fails.
+        DBNError
+            When the data is malformed.
 
         See Also
         --------
         write
 
+        """
+    def decode_by_schema(
+        self,
+    ) -> dict[str, Metadata | list[_DBNRecord]]:
+        """
+        Decode the buffered data into DBN records grouped by record type, which
+        simplifies handling streams with a mix of schemas, such as definitions
+        and market data.
+
+        Returns
+        -------
+        dict[str, Metadata | list[DBNRecord]]
+            The decoded records keyed by their rtype name, e.g. `"mbo"` or
+            `"instrument-def"`. The metadata, if decoded, is under the
+            `"metadata"` key.
+
+        Raises
+        ------
+        DBNError
+            When the data is malformed.
+
+        See Also
+        --------
+        decode
+
+        """
+    def process(self, callback: Callable[[_DBNRecord], bool | None]) -> int:
+        """
+        Decode the buffered data, passing each record, including the metadata,
+        to `callback` without accumulating them in a list.
+
+        Parameters
+        ----------
+        callback : Callable[[_DBNRecord], bool | None]
+            Called with each decoded record. Returning `False` stops decoding,
+            leaving the remaining records in the buffer.
+
+        Returns
+        -------
+        int
+            The number of records passed to `callback`.
+
+        Raises
+        ------
+        DBNError
+            When the data is malformed.
+        TypeError
+            When `callback` isn't callable.
+
+        """
+    def decode_ndarray(
+        self,
+        schema: Schema | None = None,
+    ) -> Any:
+        """
+        Decode the buffered data into a numpy structured array, copying the raw
+        record bytes without creating a Python object for each record. Records
+        not matching the schema are skipped. Requires numpy to be installed.
+
+        Parameters
+        ----------
+        schema : Schema | None, default None
+            The schema of the records to decode. Required if the metadata doesn't
+            specify a schema, e.g. for live data.
+
+        Returns
+        -------
+        numpy.ndarray
+            A structured array with one field per record field.
+
+        Raises
+        ------
+        ValueError
+            When the decoding fails or no schema is known.
+
+        See Also
+        --------
+        decode
+
+        """
+    def decode_arrow(
+        self,
+        schema: Schema | None = None,
+    ) -> Any:
+        """
+        Decode the buffered data into a pyarrow RecordBatch with one column per
+        record field. Records not matching the schema are skipped. Character and
+        string fields are converted to strings. The columns are passed to pyarrow
+        through the Arrow C data interface without an intermediate copy. Requires
+        pyarrow 14 or later.
+
+        Parameters
+        ----------
+        schema : Schema | None, default None
+            The schema of the records to decode. Required if the metadata doesn't
+            specify a schema, e.g. for live data.
+
+        Returns
+        -------
+        pyarrow.RecordBatch
+
+        Raises
+        ------
+        ValueError
+            When the decoding fails or no schema is known.
+
+        See Also
+        --------
+        decode_ndarray
+
         """
     def write(
         self,
-        bytes: bytes,
+        bytes: bytes | bytearray | memoryview,
     ) -> None:
         """
         Write a sequence of bytes to the internal buffer of the DBNDecoder.
+        Accepts any object implementing the buffer protocol.
 
         Raises
         ------
         ValueError
-            When the write to the internal buffer fails.
+            When the write to the internal buffer fails or would exceed
+            `max_buffer_size`.
+        BufferError
+            When a view of the internal buffer from `buffer()` is still alive.
 
         See Also
         --------
@@ -3537,6 +4281,83 @@ class DBNDecoder:
 
         """
 
+class InstrumentMap:
+    """
+    Maps instrument IDs to human-readable symbols over time.
+
+    Parameters
+    ----------
+    metadata : Metadata | None, default None
+        Metadata whose symbol mappings to insert.
+
+    Raises
+    ------
+    ValueError
+        When neither `stype_in` nor `stype_out` of the metadata is
+        `SType.INSTRUMENT_ID`.
+
+    """
+
+    def __init__(self, metadata: Metadata | None = None) -> None: ...
+    def __len__(self) -> int: ...
+    def insert_metadata(self, metadata: Metadata) -> None:
+        """
+        Insert the symbol mappings from `metadata`, replacing any existing
+        mappings for the same instrument and date.
+
+        Parameters
+        ----------
+        metadata : Metadata
+            The metadata whose symbol mappings to insert.
+
+        Raises
+        ------
+        ValueError
+            When neither `stype_in` nor `stype_out` of the metadata is
+            `SType.INSTRUMENT_ID`.
+
+        """
+    def insert_symbol_mapping_msg(
+        self,
+        msg: SymbolMappingMsg | SymbolMappingMsgV1,
+    ) -> None:
+        """
+        Insert the mapping from a symbol mapping record. Mappings without an
+        interval apply to the date of the record's `ts_event`.
+
+        Parameters
+        ----------
+        msg : SymbolMappingMsg | SymbolMappingMsgV1
+            The symbol mapping record.
+
+        """
+    def resolve(
+        self,
+        instrument_id: int,
+        ts: dt.date | dt.datetime | int,
+    ) -> str | None:
+        """
+        Resolve the symbol for an instrument ID on the UTC date of `ts`.
+
+        Parameters
+        ----------
+        instrument_id : int
+            The instrument ID to resolve.
+        ts : dt.date | dt.datetime | int
+            The date, datetime, or UNIX nanosecond timestamp to resolve the
+            symbol at. Naive datetimes are assumed to be in UTC.
+
+        Returns
+        -------
+        str | None
+            The symbol, or `None` if there's no mapping.
+
+        """
+    def clear(self) -> None:
+        """
+        Remove all mappings.
+        """
+
 class Transcoder:
     """
     A class for transcoding DBN i.e. converting it from one compression and encoding to
@@ -3544,8 +4365,9 @@ class Transcoder:
 
     Parameters
     ----------
-    file : BinaryIO | TextIO
-        The file-like object to write the transcoded output to.
+    file : BinaryIO | TextIO | None
+        The file-like object to write the transcoded output to. If None, the
+        transcoded output is buffered internally and can be retrieved with `read()`.
     encoding : Encoding
         The encoding for the output.
     compression : Compression
@@ -3581,7 +4403,7 @@ class Transcoder:
 
     def __init__(
         self,
-        file: BinaryIO | TextIO,
+        file: BinaryIO | TextIO | None,
         encoding: Encoding,
         compression: Compression,
         pretty_px: bool = True,
@@ -3626,6 +4448,122 @@ class Transcoder:
         ValueError
             When the write to the output fails.
         """
+    def read(self) -> bytes:
+        """
+        Return and clear the transcoded output. Only available when the
+        Transcoder was created without a `file`.
+
+        With compressed output, call `flush()` first to ensure all transcoded data
+        is returned.
+
+        Returns
+        -------
+        bytes
+
+        Raises
+        ------
+        ValueError
+            When the Transcoder was created with a `file`.
+        """
+
+def from_bytes(data: bytes) -> _DBNRecord:
+    """
+    Decode a single record of any type from its DBN representation, e.g. the
+    output of `Record.to_bytes()`. A trailing `ts_out` included in the record's
+    length is set as the record's `ts_out` attribute.
+
+    Parameters
+    ----------
+    data : bytes
+        The raw record bytes.
+
+    Returns
+    -------
+    DBNRecord
+
+    Raises
+    ------
+    ValueError
+        When `data` is too short or has an unknown rtype.
+
+    """
+
+def fmt_px(px: int) -> str:
+    """
+    Format a fixed-precision price as a decimal string, the same as the DBN
+    CLI.
+
+    Parameters
+    ----------
+    px : int
+        The fixed-precision price.
+
+    Returns
+    -------
+    str
+        The price with 9 decimal places, or "UNDEF_PRICE".
+
+    """
+
+def fmt_ts(ts: int) -> str:
+    """
+    Format a nanosecond UNIX timestamp as an ISO 8601 string with nanosecond
+    precision, the same as the DBN CLI.
+
+    Parameters
+    ----------
+    ts : int
+        The nanosecond UNIX timestamp.
+
+    Returns
+    -------
+    str
+        The UTC timestamp, or an empty string for 0.
+
+    """
+
+def parse_px(px: str) -> int:
+    """
+    Parse a decimal price string, e.g. from `fmt_px`, into a fixed-precision
+    price.
+
+    Parameters
+    ----------
+    px : str
+        The decimal price with at most 9 decimal places, or "UNDEF_PRICE".
+
+    Returns
+    -------
+    int
+
+    Raises
+    ------
+    ValueError
+        When `px` isn't a valid price.
+
+    """
+
+def parse_ts(ts: str) -> int:
+    """
+    Parse a timestamp string, e.g. from `fmt_ts`, into a nanosecond UNIX
+    timestamp.
+
+    Parameters
+    ----------
+    ts : str
+        The ISO 8601 UTC timestamp with nanosecond precision, or an integer
+        string.
+
+    Returns
+    -------
+    int
+
+    Raises
+    ------
+    ValueError
+        When `ts` isn't a valid timestamp.
+
+    """
 
 def update_encoded_metadata(
     file: BinaryIO,
@@ -3639,7 +4577,8 @@ def update_encoded_metadata(
     Parameters
     ----------
     file : BinaryIO
-        The file handle to update.
+        The file handle to update. Any binary file object supporting `read`,
+        `write`, and `seek`, such as an open file or `io.BytesIO`.
     start : int
         The UNIX nanosecond timestamp of the query start, or the
         first record if the file was split.
@@ -3658,46 +4597,35 @@ def update_encoded_metadata(
 
 def write_dbn_file(
     file: BinaryIO,
-    compression: str,
-    dataset: str,
-    schema: str,
-    start: int,
-    stype_in: str,
-    stype_out: str,
-    records: Sequence[Record],
-    end: int | None = None,
+    compression: Compression,
+    metadata: Metadata,
+    records: Iterable[Record],
 ) -> None:
     """
     Encode the given data in the DBN encoding and writes it to `file`.
 
+    Deprecated, use `Transcoder` instead.
+
     Parameters
     ----------
     file : BinaryIO
-        The file handle to update.
-    compression : str
+        The file handle to write to. Any binary file object supporting `read`,
+        `write`, and `seek`, such as an open file or `io.BytesIO`.
+    compression : Compression
         The DBN compression format.
-    dataset : str
-       The dataset code.
-    schema : str
-        The data record schema.
-    start : int
-        The UNIX nanosecond timestamp of the query start, or the
-        first record if the file was split.
-    stype_in : str
-        The input symbology type to map from.
-    stype_out : str
-        The output symbology type to map to.
-    records : Sequence[object]
-        A sequence of DBN record objects.
-    end : int | None
-        The UNIX nanosecond timestamp of the query end, or the
-        last record if the file was split.
+    metadata : Metadata
+        The metadata to encode before the records. Its schema determines the
+        record type.
+    records : Iterable[Record]
+        An iterable of DBN record objects, such as a list or generator. They're
+        encoded directly from their underlying Rust structs.
 
     Raises
     ------
     ValueError
-        When any of the enum arguments cannot be converted to their Rust equivalents.
+        When the metadata doesn't have a schema.
         When there's an issue writing the encoded to bytes.
-        When an expected field is missing from one of the dicts.
+    TypeError
+        When one of the records doesn't match the schema.
 
     """
@@ -0,0 +1,295 @@
+//! Export of decoded records to pyarrow through the
+//! [Arrow C data interface](https://arrow.apache.org/docs/format/CDataInterface.html).
+
+use std::{
+    ffi::{c_char, c_void, CString},
+    mem, ptr, slice,
+};
+
+use pyo3::{exceptions::PyValueError, intern, prelude::*, types::PyCapsule};
+
+/// Builds a pyarrow `RecordBatch` from `data`, a sequence of records of `rec_size`
+/// bytes with the layout described by `fields` in the format of `_dtypes`. Each field
+/// not in `hidden_fields` becomes a column, with character and string fields as
+/// Arrow strings.
+///
+/// The columns are built once in Rust and handed to pyarrow through the C data
+/// interface, so they aren't copied again. Requires pyarrow 14 or later.
+pub fn record_batch(
+    py: Python<'_>,
+    data: &[u8],
+    rec_size: usize,
+    fields: &[(String, String)],
+    hidden_fields: &[String],
+) -> PyResult<PyObject> {
+    let row_count = data.len() / rec_size;
+    let mut schemas = Vec::new();
+    let mut arrays = Vec::new();
+    let mut offset = 0;
+    for (name, dtype) in fields {
+        let width: usize = dtype
+            .get(1..)
+            .and_then(|width| width.parse().ok())
+            .ok_or_else(|| PyValueError::new_err(format!("Unsupported dtype {dtype}")))?;
+        if !hidden_fields.contains(name) {
+            let field_offset = offset;
+            let rows = (0..row_count).map(|i| {
+                let start = i * rec_size + field_offset;
+                &data[start..start + width]
+            });
+            let (format, array) = if dtype.starts_with('S') {
+                string_column(rows, row_count, width)?
+            } else {
+                (
+                    primitive_format(dtype)?,
+                    primitive_column(rows, row_count, width),
+                )
+            };
+            schemas.push(ArrowSchema::new(format, name, Vec::new()));
+            arrays.push(array);
+        }
+        offset += width;
+    }
+    let schema = ArrowSchema::new("+s", "", schemas);
+    let array = ArrowArray::new(row_count, Vec::new(), arrays);
+    let schema = PyCapsule::new_with_destructor(
+        py,
+        Exported(schema),
+        Some(CString::new("arrow_schema").unwrap()),
+        |_, _| {},
+    )?;
+    let array = PyCapsule::new_with_destructor(
+        py,
+        Exported(array),
+        Some(CString::new("arrow_array").unwrap()),
+        |_, _| {},
+    )?;
+    Ok(py
+        .import(intern!(py, "pyarrow"))?
+        .getattr(intern!(py, "RecordBatch"))?
+        .call_method1(intern!(py, "_import_from_c_capsule"), (schema, array))?
+        .into_py(py))
+}
+
+fn primitive_format(dtype: &str) -> PyResult<&'static str> {
+    Ok(match dtype {
+        "i1" => "c",
+        "u1" => "C",
+        "i2" => "s",
+        "u2" => "S",
+        "i4" => "i",
+        "u4" => "I",
+        "i8" => "l",
+        "u8" => "L",
+        _ => return Err(PyValueError::new_err(format!("Unsupported dtype {dtype}"))),
+    })
+}
+
+fn primitive_column<'a>(
+    rows: impl Iterator<Item = &'a [u8]>,
+    row_count: usize,
+    width: usize,
+) -> ArrowArray {
+    let mut values = AlignedBuffer::zeroed(row_count * width);
+    for (dest, field) in values.as_mut_bytes().chunks_exact_mut(width).zip(rows) {
+        dest.copy_from_slice(field);
+    }
+    ArrowArray::new(row_count, vec![values], Vec::new())
+}
+
+/// Creates a string column from null-padded fields. Falls back to a binary column if
+/// any field isn't valid UTF-8.
+fn string_column<'a>(
+    rows: impl Iterator<Item = &'a [u8]>,
+    row_count: usize,
+    width: usize,
+) -> PyResult<(&'static str, ArrowArray)> {
+    if i32::try_from(row_count * width).is_err() {
+        return Err(PyValueError::new_err(
+            "Too many records to decode into a single RecordBatch",
+        ));
+    }
+    let mut offsets = AlignedBuffer::zeroed((row_count + 1) * mem::size_of::<i32>());
+    let mut values = AlignedBuffer::zeroed(row_count * width);
+    let mut is_utf8 = true;
+    let mut len = 0;
+    let values_bytes = values.as_mut_bytes();
+    for (dest, field) in offsets
+        .as_mut_bytes()
+        .chunks_exact_mut(mem::size_of::<i32>())
+        .skip(1)
+        .zip(rows)
+    {
+        let field = field.split(|&b| b == 0).next().unwrap_or_default();
+        is_utf8 &= std::str::from_utf8(field).is_ok();
+        values_bytes[len..len + field.len()].copy_from_slice(field);
+        len += field.len();
+        dest.copy_from_slice(&(len as i32).to_ne_bytes());
+    }
+    let format = if is_utf8 { "u" } else { "z" };
+    Ok((
+        format,
+        ArrowArray::new(row_count, vec![offsets, values], Vec::new()),
+    ))
+}
+
+/// A zero-initialized buffer aligned to 8 bytes, the alignment of the largest
+/// primitive column type.
+struct AlignedBuffer(Vec<u64>);
+
+impl AlignedBuffer {
+    fn zeroed(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(mem::size_of::<u64>())])
+    }
+
+    fn as_mut_bytes(&mut self) -> &mut [u8] {
+        // Safety: `u8` has no alignment requirements and every element is initialized.
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.0.as_mut_ptr().cast::<u8>(),
+                self.0.len() * mem::size_of::<u64>(),
+            )
+        }
+    }
+}
+
+/// Wrapper for passing C data interface structs to `PyCapsule`, which requires `Send`.
+#[repr(transparent)]
+struct Exported<T>(T);
+
+// Safety: the exported structs own all the memory they point to.
+unsafe impl<T> Send for Exported<T> {}
+
+/// The `ArrowSchema` struct of the C data interface.
+#[repr(C)]
+struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+struct SchemaPrivateData {
+    format: CString,
+    name: CString,
+    children: Vec<*mut ArrowSchema>,
+}
+
+impl ArrowSchema {
+    fn new(format: &str, name: &str, children: Vec<ArrowSchema>) -> Self {
+        let mut private_data = Box::new(SchemaPrivateData {
+            format: CString::new(format).expect("format doesn't contain null bytes"),
+            name: CString::new(name).expect("field names don't contain null bytes"),
+            children: children
+                .into_iter()
+                .map(|child| Box::into_raw(Box::new(child)))
+                .collect(),
+        });
+        Self {
+            format: private_data.format.as_ptr(),
+            name: private_data.name.as_ptr(),
+            metadata: ptr::null(),
+            flags: 0,
+            n_children: private_data.children.len() as i64,
+            children: private_data.children.as_mut_ptr(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_schema),
+            private_data: Box::into_raw(private_data).cast(),
+        }
+    }
+}
+
+impl Drop for ArrowSchema {
+    fn drop(&mut self) {
+        // `release` is cleared once the consumer has moved or released the struct
+        if let Some(release) = self.release {
+            // Safety: `release` was set by `ArrowSchema::new()`
+            unsafe { release(self) };
+        }
+    }
+}
+
+unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+    let schema = &mut *schema;
+    let private_data = Box::from_raw(schema.private_data.cast::<SchemaPrivateData>());
+    for &child in private_data.children.iter() {
+        drop(Box::from_raw(child));
+    }
+    schema.release = None;
+}
+
+/// The `ArrowArray` struct of the C data interface.
+#[repr(C)]
+struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+struct ArrayPrivateData {
+    // Owns the memory in `buffer_ptrs`
+    _buffers: Vec<AlignedBuffer>,
+    buffer_ptrs: Vec<*const c_void>,
+    children: Vec<*mut ArrowArray>,
+}
+
+impl ArrowArray {
+    /// Creates an array without nulls. `buffers` doesn't include the validity
+    /// buffer.
+    fn new(length: usize, buffers: Vec<AlignedBuffer>, children: Vec<ArrowArray>) -> Self {
+        let buffer_ptrs = std::iter::once(ptr::null())
+            .chain(buffers.iter().map(|buffer| buffer.0.as_ptr().cast()))
+            .collect();
+        let mut private_data = Box::new(ArrayPrivateData {
+            _buffers: buffers,
+            buffer_ptrs,
+            children: children
+                .into_iter()
+                .map(|child| Box::into_raw(Box::new(child)))
+                .collect(),
+        });
+        Self {
+            length: length as i64,
+            null_count: 0,
+            offset: 0,
+            n_buffers: private_data.buffer_ptrs.len() as i64,
+            n_children: private_data.children.len() as i64,
+            buffers: private_data.buffer_ptrs.as_mut_ptr(),
+            children: private_data.children.as_mut_ptr(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_array),
+            private_data: Box::into_raw(private_data).cast(),
+        }
+    }
+}
+
+impl Drop for ArrowArray {
+    fn drop(&mut self) {
+        // `release` is cleared once the consumer has moved or released the struct
+        if let Some(release) = self.release {
+            // Safety: `release` was set by `ArrowArray::new()`
+            unsafe { release(self) };
+        }
+    }
+}
+
+unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+    let array = &mut *array;
+    let private_data = Box::from_raw(array.private_data.cast::<ArrayPrivateData>());
+    for &child in private_data.children.iter() {
+        drop(Box::from_raw(child));
+    }
+    array.release = None;
+}
@@ -1,20 +1,72 @@
-use std::io::{self, Write};
+use std::{
+    ffi::{c_int, c_void},
+    io::{self, Write},
+    mem,
+    ops::Range,
+    ptr,
+};
 
-use pyo3::prelude::*;
+use pyo3::{
+    buffer::PyBuffer,
+    exceptions::{PyBufferError, PyTypeError, PyValueError},
+    ffi, intern,
+    prelude::*,
+    types::{PyBool, PyByteArray, PyDict, PyList, PyMemoryView},
+    PyClass,
+};
 
 use dbn::{
     decode::dbn::{MetadataDecoder, RecordDecoder},
-    python::to_val_err,
-    rtype_ts_out_dispatch, HasRType, Record, VersionUpgradePolicy,
+    python::{to_dbn_err, to_val_err},
+    rtype_ts_out_dispatch, schema_method_dispatch, HasRType, Metadata, RType, Record, RecordHeader,
+    RecordRef, Schema, VersionUpgradePolicy,
 };
 
+use crate::arrow;
+
 #[pyclass(module = "databento_dbn", name = "DBNDecoder")]
 pub struct DbnDecoder {
     buffer: io::Cursor<Vec<u8>>,
+    // position of the first byte in `buffer` that hasn't been decoded
+    start: usize,
+    // maximum number of undecoded bytes `buffer` can hold
+    max_buffer_size: Option<usize>,
     has_decoded_metadata: bool,
     ts_out: bool,
+    schema: Option<Schema>,
     input_version: u8,
     upgrade_policy: VersionUpgradePolicy,
+    // number of active buffer protocol exports, e.g. memoryviews, of `buffer`
+    exports: usize,
+}
+
+// Records decoded into an owned buffer so they can be converted to Python objects
+// after the GIL is reacquired.
+struct FramedRecords {
+    data: Vec<u8>,
+    // the end offset of each record in `data`
+    ends: Vec<usize>,
+    // the buffer position after the last complete record
+    read_position: usize,
+}
+
+impl FramedRecords {
+    fn iter(&self) -> impl Iterator<Item = RecordRef<'_>> + '_ {
+        let starts = std::iter::once(0).chain(self.ends.iter().copied());
+        // Safety: `data` only contains complete records copied from `RecordRef`s
+        starts
+            .zip(self.ends.iter())
+            .map(|(start, &end)| unsafe { RecordRef::new(&self.data[start..end]) })
+    }
+}
+
+// Ranges of contiguous records in the decoder buffer.
+struct RecordRuns {
+    ranges: Vec<Range<usize>>,
+    // the total length of `ranges`
+    len: usize,
+    // the buffer position after the last complete record
+    read_position: usize,
 }
 
 #[pymethods]
@@ -25,107 +77,548 @@ impl DbnDecoder {
         ts_out: Option<bool>,
         input_version: Option<u8>,
         upgrade_policy: Option<VersionUpgradePolicy>,
+        max_buffer_size: Option<usize>,
     ) -> Self {
         Self {
             buffer: io::Cursor::default(),
+            start: 0,
+            max_buffer_size,
             has_decoded_metadata: !has_metadata.unwrap_or(true),
             ts_out: ts_out.unwrap_or_default(),
+            schema: None,
             input_version: input_version.unwrap_or(dbn::DBN_VERSION),
             upgrade_policy: upgrade_policy.unwrap_or_default(),
+            exports: 0,
         }
     }
 
-    fn write(&mut self, bytes: &[u8]) -> PyResult<()> {
-        self.buffer.write_all(bytes).map_err(to_val_err)
+    /// Accepts any object implementing the buffer protocol, e.g. `bytes`, `bytearray`,
+    /// and `memoryview`.
+    #[pyo3(name = "write")]
+    fn py_write(&mut self, py: Python<'_>, bytes: PyBuffer<u8>) -> PyResult<()> {
+        if bytes.is_c_contiguous() {
+            // Safety: the buffer is contiguous and `bytes` keeps it alive and
+            // unresized until it's dropped
+            let slice = unsafe {
+                std::slice::from_raw_parts(bytes.buf_ptr() as *const u8, bytes.len_bytes())
+            };
+            self.write(slice)
+        } else {
+            self.write(&bytes.to_vec(py)?)
+        }
     }
 
-    fn buffer(&self) -> &[u8] {
-        self.buffer.get_ref().as_slice()
+    /// Returns a read-only `memoryview` of the data that has been written but not yet
+    /// decoded. The decoder can't be written to or decode while the `memoryview`
+    /// is alive.
+    fn buffer(slf: &PyCell<Self>) -> PyResult<&PyMemoryView> {
+        PyMemoryView::from(slf)
     }
 
-    fn decode(&mut self) -> PyResult<Vec<PyObject>> {
+    unsafe fn __getbuffer__(
+        mut slf: PyRefMut<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("DBNDecoder buffer is not writable"));
+        }
+        let data = &slf.buffer.get_ref()[slf.start..];
+        let owner = slf.as_ptr();
+        ffi::Py_INCREF(owner);
+        (*view).obj = owner;
+        (*view).buf = data.as_ptr() as *mut c_void;
+        (*view).len = data.len() as isize;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+            b"B\0".as_ptr() as *mut _
+        } else {
+            ptr::null_mut()
+        };
+        (*view).ndim = 1;
+        (*view).shape = if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+            &mut (*view).len
+        } else {
+            ptr::null_mut()
+        };
+        (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+            &mut (*view).itemsize
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = ptr::null_mut();
+        slf.exports += 1;
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(mut slf: PyRefMut<'_, Self>, _view: *mut ffi::Py_buffer) {
+        slf.exports -= 1;
+    }
+
+    fn decode(&mut self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
         let mut recs = Vec::new();
+        self.decode_with(py, |_, obj| {
+            recs.push(obj);
+            Ok(())
+        })?;
+        Ok(recs)
+    }
+
+    /// Decodes the buffered records like `decode()`, but returns a `dict` grouping them
+    /// into lists by their rtype name, e.g. `"mbo"` or `"instrument-def"`. The
+    /// metadata, if decoded, is under the `"metadata"` key.
+    fn decode_by_schema(&mut self, py: Python<'_>) -> PyResult<Py<PyDict>> {
+        let groups = PyDict::new(py);
+        self.decode_with(py, |rtype, obj| {
+            let key = match rtype {
+                None => "metadata",
+                Some(rtype) => RType::try_from(rtype).map_err(to_val_err)?.as_str(),
+            };
+            if rtype.is_none() {
+                groups.set_item(key, obj)
+            } else if let Some(group) = groups.get_item(key)? {
+                group.downcast::<PyList>()?.append(obj)
+            } else {
+                groups.set_item(key, PyList::new(py, [obj]))
+            }
+        })?;
+        Ok(groups.into())
+    }
+
+    /// Calls `callback` with each decoded record, including the metadata, without
+    /// accumulating them. Decoding stops early if `callback` returns `False`, leaving
+    /// the remaining records in the buffer. Returns the number of records passed to
+    /// `callback`.
+    fn process(&mut self, py: Python<'_>, callback: &PyAny) -> PyResult<usize> {
+        if !callback.is_callable() {
+            return Err(PyTypeError::new_err("callback must be callable"));
+        }
+        self.check_not_exported()?;
+        let orig_position = self.buffer.position();
+        self.buffer.set_position(self.start as u64);
+        let metadata = if self.has_decoded_metadata {
+            None
+        } else {
+            let Some(metadata) = self.decode_metadata(orig_position)? else {
+                return Ok(0);
+            };
+            Some(metadata)
+        };
+        let mut read_position = self.buffer.position() as usize;
+        let mut count = 0;
+        let res = self.process_records(py, callback, metadata, &mut read_position, &mut count);
+        if read_position == self.start {
+            self.buffer.set_position(orig_position);
+        } else {
+            self.shift_buffer(read_position);
+        }
+        res.map(|_| count)
+    }
+
+    fn decode_ndarray(&mut self, py: Python<'_>, schema: Option<Schema>) -> PyResult<PyObject> {
+        let (schema, has_metadata, orig_position) = self.prepare_columnar(schema)?;
+        schema_method_dispatch!(
+            schema,
+            self,
+            decode_ndarray_for,
+            py,
+            has_metadata,
+            orig_position
+        )
+    }
+
+    fn decode_arrow(&mut self, py: Python<'_>, schema: Option<Schema>) -> PyResult<PyObject> {
+        let (schema, has_metadata, orig_position) = self.prepare_columnar(schema)?;
+        schema_method_dispatch!(
+            schema,
+            self,
+            decode_arrow_for,
+            py,
+            has_metadata,
+            orig_position
+        )
+    }
+}
+
+impl DbnDecoder {
+    fn write(&mut self, bytes: &[u8]) -> PyResult<()> {
+        self.check_not_exported()?;
+        let inner_buf = self.buffer.get_ref();
+        let undecoded_len = inner_buf.len() - self.start;
+        if let Some(max_buffer_size) = self.max_buffer_size {
+            if undecoded_len + bytes.len() > max_buffer_size {
+                return Err(PyValueError::new_err(format!(
+                    "Writing {} bytes would exceed the max_buffer_size of {max_buffer_size} bytes with {undecoded_len} bytes not yet decoded",
+                    bytes.len()
+                )));
+            }
+        }
+        // reclaim the space of decoded data instead of growing the buffer
+        if self.start > 0 && inner_buf.len() + bytes.len() > inner_buf.capacity() {
+            self.compact_buffer();
+        }
+        self.buffer.write_all(bytes).map_err(to_val_err)
+    }
+
+    // Calls `push` with the rtype and Python object of each decoded record, or `None`
+    // and the metadata. Records are decoded with the GIL released and it's only
+    // reacquired to create the Python objects.
+    fn decode_with(
+        &mut self,
+        py: Python<'_>,
+        mut push: impl FnMut(Option<u8>, PyObject) -> PyResult<()>,
+    ) -> PyResult<()> {
+        fn to_py<R: Clone + HasRType + IntoPy<Py<PyAny>>>(rec: &R, py: Python) -> PyObject {
+            rec.clone().into_py(py)
+        }
+
+        self.check_not_exported()?;
         let orig_position = self.buffer.position();
-        self.buffer.set_position(0);
+        self.buffer.set_position(self.start as u64);
+        let mut has_decoded = false;
         if !self.has_decoded_metadata {
-            match MetadataDecoder::new(&mut self.buffer).decode() {
-                Ok(mut metadata) => {
-                    self.input_version = metadata.version;
-                    self.ts_out = metadata.ts_out;
-                    metadata.upgrade(self.upgrade_policy);
-                    Python::with_gil(|py| recs.push(metadata.into_py(py)));
-                    self.has_decoded_metadata = true;
+            let Some(metadata) = self.decode_metadata(orig_position)? else {
+                return Ok(());
+            };
+            push(None, metadata.into_py(py))?;
+            has_decoded = true;
+        }
+        let framed = py
+            .allow_threads(|| self.frame_records(|_| Ok(true)))
+            .map_err(|e| {
+                self.buffer.set_position(orig_position);
+                to_dbn_err(e)
+            })?;
+        for rec in framed.iter() {
+            // Safety: It's safe to cast to `WithTsOut` because we're passing in the `ts_out`
+            // from the metadata header.
+            let Ok(obj) = (unsafe { rtype_ts_out_dispatch!(rec, self.ts_out, to_py, py) }) else {
+                self.buffer.set_position(orig_position);
+                return Err(to_dbn_err(format!(
+                    "Invalid rtype {} found in record",
+                    rec.header().rtype,
+                )));
+            };
+            if let Err(e) = push(Some(rec.header().rtype), obj) {
+                self.buffer.set_position(orig_position);
+                return Err(e);
+            }
+            has_decoded = true;
+        }
+        if has_decoded {
+            self.shift_buffer(framed.read_position);
+        } else {
+            self.buffer.set_position(orig_position);
+        }
+        Ok(())
+    }
+
+    // Decodes records from the current position of the buffer into an owned buffer.
+    // Doesn't require the GIL. `filter` determines which records are kept.
+    fn frame_records(
+        &mut self,
+        mut filter: impl FnMut(&RecordRef) -> dbn::Result<bool>,
+    ) -> dbn::Result<FramedRecords> {
+        let mut framed = FramedRecords {
+            data: Vec::new(),
+            ends: Vec::new(),
+            read_position: self.buffer.position() as usize,
+        };
+        let mut decoder = RecordDecoder::with_version(
+            &mut self.buffer,
+            self.input_version,
+            self.upgrade_policy,
+            self.ts_out,
+        )?;
+        while let Some(rec) = decoder.decode_ref()? {
+            if filter(&rec)? {
+                framed.data.extend_from_slice(rec.as_ref());
+                framed.ends.push(framed.data.len());
+            }
+            // keep track of position after last _successful_ decoding to
+            // ensure buffer is left in correct state in the case where one
+            // or more successful decodings is followed by a partial one, i.e.
+            // `decode_record_ref` returning `Ok(None)`
+            framed.read_position = decoder.get_ref().position() as usize - decoder.buffer().len();
+        }
+        Ok(framed)
+    }
+
+    // Finds the runs of contiguous records of type `R` from the current position of the
+    // buffer so each run can be copied with a single `memcpy`. Unlike `frame_records`,
+    // this doesn't apply any version upgrade.
+    fn find_record_runs<R: HasRType>(&self, rec_size: usize) -> dbn::Result<RecordRuns> {
+        let buffer = self.buffer.get_ref();
+        let mut pos = self.buffer.position() as usize;
+        let mut runs = RecordRuns {
+            ranges: Vec::new(),
+            len: 0,
+            read_position: pos,
+        };
+        while let Some(&length_words) = buffer.get(pos) {
+            let length = length_words as usize * RecordHeader::LENGTH_MULTIPLIER;
+            if length < mem::size_of::<RecordHeader>() {
+                return Err(dbn::Error::decode(format!(
+                    "invalid record with length {length} shorter than header"
+                )));
+            }
+            // partial record
+            if pos + length > buffer.len() {
+                break;
+            }
+            let rtype = buffer[pos + 1];
+            if R::has_rtype(rtype) {
+                if length != rec_size {
+                    return Err(dbn::Error::decode(format!(
+                        "Record with rtype {rtype} has length {length}, expected {rec_size}"
+                    )));
                 }
-                Err(err) => {
-                    self.buffer.set_position(orig_position);
-                    // haven't read enough data for metadata
-                    if matches!(err, dbn::Error::Io { ref source, .. } if source.kind() == std::io::ErrorKind::UnexpectedEof)
-                    {
-                        return Ok(Vec::new());
-                    }
-                    return Err(to_val_err(err));
+                match runs.ranges.last_mut() {
+                    Some(run) if run.end == pos => run.end += length,
+                    _ => runs.ranges.push(pos..pos + length),
                 }
+                runs.len += length;
+            }
+            pos += length;
+            runs.read_position = pos;
+        }
+        Ok(runs)
+    }
+
+    // The buffer can't be modified while Python holds a view into it
+    fn check_not_exported(&self) -> PyResult<()> {
+        if self.exports > 0 {
+            return Err(PyBufferError::new_err(
+                "Can't modify the DBNDecoder buffer while it's exported, e.g. to a memoryview",
+            ));
+        }
+        Ok(())
+    }
+
+    // Decodes the metadata if necessary and determines which schema to decode.
+    fn prepare_columnar(&mut self, schema: Option<Schema>) -> PyResult<(Schema, bool, u64)> {
+        self.check_not_exported()?;
+        let orig_position = self.buffer.position();
+        self.buffer.set_position(self.start as u64);
+        let has_metadata =
+            self.has_decoded_metadata || self.decode_metadata(orig_position)?.is_some();
+        let Some(schema) = schema.or(self.schema) else {
+            if has_metadata {
+                // don't decode the metadata a second time
+                self.shift_buffer(self.buffer.position() as usize);
+            }
+            return Err(PyValueError::new_err(
+                "A schema must be specified when decoding mixed schema DBN to a columnar format",
+            ));
+        };
+        Ok((schema, has_metadata, orig_position))
+    }
+
+    // `read_position` is only updated after `callback` returns so a record whose
+    // callback raised an exception isn't dropped from the buffer. The metadata is
+    // always consumed because decoding it updates the decoder's state.
+    fn process_records(
+        &mut self,
+        py: Python<'_>,
+        callback: &PyAny,
+        metadata: Option<Metadata>,
+        read_position: &mut usize,
+        count: &mut usize,
+    ) -> PyResult<()> {
+        fn should_continue(res: &PyAny) -> bool {
+            res.downcast::<PyBool>().map_or(true, |res| res.is_true())
+        }
+        fn to_py<R: Clone + HasRType + IntoPy<Py<PyAny>>>(rec: &R, py: Python) -> PyObject {
+            rec.clone().into_py(py)
+        }
+
+        if let Some(metadata) = metadata {
+            let res = callback.call1((metadata.into_py(py),))?;
+            *count += 1;
+            if !should_continue(res) {
+                return Ok(());
             }
         }
-        let mut read_position = self.buffer.position() as usize;
         let mut decoder = RecordDecoder::with_version(
             &mut self.buffer,
             self.input_version,
             self.upgrade_policy,
             self.ts_out,
         )
-        .map_err(to_val_err)?;
-        Python::with_gil(|py| -> PyResult<()> {
-            while let Some(rec) = decoder.decode_ref().map_err(to_val_err)? {
-                // Bug in clippy generates an error here. trivial_copy feature isn't enabled,
-                // but clippy thinks these records are `Copy`
-                fn push_rec<R: Clone + HasRType + IntoPy<Py<PyAny>>>(
-                    rec: &R,
-                    py: Python,
-                    recs: &mut Vec<Py<PyAny>>,
-                ) {
-                    recs.push(rec.clone().into_py(py))
-                }
+        .map_err(to_dbn_err)?;
+        while let Some(rec) = decoder.decode_ref().map_err(to_dbn_err)? {
+            // Safety: It's safe to cast to `WithTsOut` because we're passing in the `ts_out`
+            // from the metadata header.
+            let Ok(obj) = (unsafe { rtype_ts_out_dispatch!(rec, self.ts_out, to_py, py) }) else {
+                return Err(to_dbn_err(format!(
+                    "Invalid rtype {} found in record",
+                    rec.header().rtype,
+                )));
+            };
+            let res = callback.call1((obj,))?;
+            *read_position = decoder.get_ref().position() as usize - decoder.buffer().len();
+            *count += 1;
+            if !should_continue(res) {
+                break;
+            }
+        }
+        Ok(())
+    }
 
-                // Safety: It's safe to cast to `WithTsOut` because we're passing in the `ts_out`
-                // from the metadata header.
-                if unsafe { rtype_ts_out_dispatch!(rec, self.ts_out, push_rec, py, &mut recs) }
-                    .is_err()
+    // returns `None` if more data is required to decode the metadata
+    fn decode_metadata(&mut self, orig_position: u64) -> PyResult<Option<Metadata>> {
+        match MetadataDecoder::new(&mut self.buffer).decode() {
+            Ok(mut metadata) => {
+                self.input_version = metadata.version;
+                self.ts_out = metadata.ts_out;
+                self.schema = metadata.schema;
+                metadata.upgrade(self.upgrade_policy);
+                self.has_decoded_metadata = true;
+                Ok(Some(metadata))
+            }
+            Err(err) => {
+                self.buffer.set_position(orig_position);
+                // haven't read enough data for metadata
+                if matches!(err, dbn::Error::Io { ref source, .. } if source.kind() == std::io::ErrorKind::UnexpectedEof)
                 {
-                    return Err(to_val_err(format!(
-                        "Invalid rtype {} found in record",
-                        rec.header().rtype,
-                    )));
+                    return Ok(None);
                 }
-                // keep track of position after last _successful_ decoding to
-                // ensure buffer is left in correct state in the case where one
-                // or more successful decodings is followed by a partial one, i.e.
-                // `decode_record_ref` returning `Ok(None)`
-                read_position = decoder.get_ref().position() as usize;
+                Err(to_dbn_err(err))
             }
-            Ok(())
-        })
-        .map_err(|e| {
-            self.buffer.set_position(orig_position);
-            e
-        })?;
-        if recs.is_empty() {
-            self.buffer.set_position(orig_position);
-        } else {
-            self.shift_buffer(read_position);
         }
-        Ok(recs)
     }
-}
 
-impl DbnDecoder {
+    fn decode_ndarray_for<R: HasRType + PyClass>(
+        &mut self,
+        py: Python<'_>,
+        has_metadata: bool,
+        orig_position: u64,
+    ) -> PyResult<PyObject> {
+        let (fields, rec_size, data) =
+            self.decode_record_data::<R>(py, has_metadata, orig_position)?;
+        let (names, formats): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+        let dtype_desc = PyDict::new(py);
+        dtype_desc.set_item(intern!(py, "names"), names)?;
+        dtype_desc.set_item(intern!(py, "formats"), formats)?;
+        dtype_desc.set_item(intern!(py, "itemsize"), rec_size)?;
+        let numpy = py.import(intern!(py, "numpy"))?;
+        let dtype = numpy.call_method1(intern!(py, "dtype"), (dtype_desc,))?;
+        Ok(numpy
+            .call_method1(intern!(py, "frombuffer"), (data, dtype))?
+            .into_py(py))
+    }
+
+    fn decode_arrow_for<R: HasRType + PyClass>(
+        &mut self,
+        py: Python<'_>,
+        has_metadata: bool,
+        orig_position: u64,
+    ) -> PyResult<PyObject> {
+        let (fields, rec_size, data) =
+            self.decode_record_data::<R>(py, has_metadata, orig_position)?;
+        let hidden_fields: Vec<String> = py
+            .get_type::<R>()
+            .getattr(intern!(py, "_hidden_fields"))?
+            .extract()?;
+        // Safety: `data` was just created and isn't accessible to Python code, which
+        // is the only way it could be resized
+        let data = unsafe { data.as_bytes() };
+        arrow::record_batch(py, data, rec_size, &fields, &hidden_fields)
+    }
+
+    /// Decodes the records of type `R` into a contiguous buffer. Returns the dtype
+    /// fields of the records, the size of each record, and the buffer.
+    fn decode_record_data<'py, R: HasRType + PyClass>(
+        &mut self,
+        py: Python<'py>,
+        has_metadata: bool,
+        orig_position: u64,
+    ) -> PyResult<(Vec<(String, String)>, usize, &'py PyByteArray)> {
+        let mut fields: Vec<(String, String)> = py
+            .get_type::<R>()
+            .getattr(intern!(py, "_dtypes"))?
+            .extract()?;
+        let mut rec_size = std::mem::size_of::<R>();
+        if self.ts_out {
+            fields.push(("ts_out".to_owned(), "u8".to_owned()));
+            rec_size += std::mem::size_of::<u64>();
+        }
+        let data = if !has_metadata {
+            PyByteArray::new(py, &[])
+        } else if self.input_version == dbn::DBN_VERSION
+            || self.upgrade_policy == VersionUpgradePolicy::AsIs
+        {
+            // Records are decoded as-is, so they can be copied directly from the buffer
+            let runs = py
+                .allow_threads(|| self.find_record_runs::<R>(rec_size))
+                .map_err(|e| {
+                    self.buffer.set_position(orig_position);
+                    to_dbn_err(e)
+                })?;
+            let buffer = self.buffer.get_ref();
+            let data = PyByteArray::new_with(py, runs.len, |bytes| {
+                let mut offset = 0;
+                for run in runs.ranges.iter() {
+                    bytes[offset..offset + run.len()].copy_from_slice(&buffer[run.clone()]);
+                    offset += run.len();
+                }
+                Ok(())
+            })?;
+            self.shift_buffer(runs.read_position);
+            data
+        } else {
+            let framed = py
+                .allow_threads(|| {
+                    self.frame_records(|rec| {
+                        if !rec.has::<R>() {
+                            return Ok(false);
+                        }
+                        if rec.record_size() != rec_size {
+                            return Err(dbn::Error::decode(format!(
+                                "Record with rtype {} has length {}, expected {rec_size}",
+                                rec.header().rtype,
+                                rec.record_size(),
+                            )));
+                        }
+                        Ok(true)
+                    })
+                })
+                .map_err(|e| {
+                    self.buffer.set_position(orig_position);
+                    to_dbn_err(e)
+                })?;
+            self.shift_buffer(framed.read_position);
+            PyByteArray::new(py, &framed.data)
+        };
+        Ok((fields, rec_size, data))
+    }
+
+    // Marks the data before `read_position` as decoded. Decoded data is only removed
+    // from the buffer once it's at least as long as the undecoded data, so the
+    // undecoded data isn't moved on every call, which would be quadratic for a
+    // buffer that's written to faster than it's decoded.
     fn shift_buffer(&mut self, read_position: usize) {
+        self.start = read_position;
+        let length = self.buffer.get_ref().len();
+        if self.start >= length - self.start {
+            self.compact_buffer();
+        } else {
+            self.buffer.set_position(length as u64);
+        }
+    }
+
+    // Removes the decoded data from the buffer.
+    fn compact_buffer(&mut self) {
         let inner_buf = self.buffer.get_mut();
-        let length = inner_buf.len();
-        let new_length = length - read_position;
-        inner_buf.drain(..read_position);
-        debug_assert_eq!(inner_buf.len(), new_length);
-        self.buffer.set_position(new_length as u64);
+        inner_buf.drain(..self.start);
+        self.start = 0;
+        let length = inner_buf.len() as u64;
+        self.buffer.set_position(length);
     }
 }
 
@@ -135,18 +628,19 @@ mod tests {
         datasets::XNAS_ITCH,
         encode::{dbn::Encoder, EncodeRecord},
         enums::{rtype, SType, Schema},
-        record::{ErrorMsg, OhlcvMsg, RecordHeader},
+        record::{ErrorMsg, OhlcvMsg, RecordHeader, WithTsOut},
         MetadataBuilder,
     };
     use pyo3::{py_run, types::PyString};
+    use rstest::rstest;
 
     use super::*;
-    use crate::tests::setup;
+    use crate::tests::{setup, setup_package};
 
     #[test]
     fn test_partial_metadata_and_records() {
         setup();
-        let mut target = DbnDecoder::new(None, None, None, None);
+        let mut target = DbnDecoder::new(None, None, None, None, None);
         let buffer = Vec::new();
         let mut encoder = Encoder::new(
             buffer,
@@ -161,10 +655,11 @@ mod tests {
         .unwrap();
         let metadata_split = encoder.get_ref().len() / 2;
         target.write(&encoder.get_ref()[..metadata_split]).unwrap();
-        assert!(target.decode().unwrap().is_empty());
+        assert!(Python::with_gil(|py| target.decode(py)).unwrap().is_empty());
         target.write(&encoder.get_ref()[metadata_split..]).unwrap();
         let metadata_pos = encoder.get_ref().len();
-        assert!(matches!(target.decode(), Ok(recs) if recs.len() == 1));
+        let res = Python::with_gil(|py| target.decode(py));
+        assert!(matches!(res, Ok(recs) if recs.len() == 1));
         assert!(target.has_decoded_metadata);
         let rec = ErrorMsg::new(1680708278000000000, "Python", true);
         encoder.encode_record(&rec).unwrap();
@@ -175,11 +670,11 @@ mod tests {
             assert_eq!(target.buffer.get_ref().len(), i + 1 - metadata_pos);
             // wrote last byte
             if i == record_pos - 1 {
-                let res = target.decode();
+                let res = Python::with_gil(|py| target.decode(py));
                 assert_eq!(record_pos - metadata_pos, std::mem::size_of_val(&rec));
                 assert!(matches!(res, Ok(recs) if recs.len() == 1));
             } else {
-                let res = target.decode();
+                let res = Python::with_gil(|py| target.decode(py));
                 assert!(matches!(res, Ok(recs) if recs.is_empty()));
             }
         }
@@ -188,7 +683,7 @@ mod tests {
     #[test]
     fn test_full_with_partial_record() {
         setup();
-        let mut decoder = DbnDecoder::new(None, None, None, None);
+        let mut decoder = DbnDecoder::new(None, None, None, None, None);
         let buffer = Vec::new();
         let mut encoder = Encoder::new(
             buffer,
@@ -203,7 +698,8 @@ mod tests {
         .unwrap();
         decoder.write(encoder.get_ref().as_slice()).unwrap();
         let metadata_pos = encoder.get_ref().len();
-        assert!(matches!(decoder.decode(), Ok(recs) if recs.len() == 1));
+        let res = Python::with_gil(|py| decoder.decode(py));
+        assert!(matches!(res, Ok(recs) if recs.len() == 1));
         assert!(decoder.has_decoded_metadata);
         let rec1 = ErrorMsg::new(1680708278000000000, "Python", true);
         let rec2 = OhlcvMsg {
@@ -223,14 +719,93 @@ mod tests {
             .write(&encoder.get_ref()[metadata_pos..rec1_pos + 4])
             .unwrap();
         // Read first record
-        let res1 = decoder.decode();
+        let res1 = Python::with_gil(|py| decoder.decode(py));
         assert!(matches!(res1, Ok(recs) if recs.len() == 1));
         // Write rest of second record
         decoder.write(&encoder.get_ref()[rec1_pos + 4..]).unwrap();
-        let res2 = decoder.decode();
+        let res2 = Python::with_gil(|py| decoder.decode(py));
         assert!(matches!(res2, Ok(recs) if recs.len() == 1));
     }
 
+    #[test]
+    fn test_dbn_decoder_compacts_buffer() {
+        setup();
+        let mut target = DbnDecoder::new(Some(false), None, None, None, Some(400));
+        let ohlcv = OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(rtype::OHLCV_1S, 1, 1, 1681228173000000000),
+            open: 100,
+            high: 200,
+            low: 50,
+            close: 150,
+            volume: 1000,
+        };
+        let error = ErrorMsg::new(1680708278000000000, "Python", true);
+        let ohlcv_bytes = ohlcv.as_ref();
+        let error_bytes = error.as_ref();
+        target.write(ohlcv_bytes).unwrap();
+        target.write(&error_bytes[..100]).unwrap();
+        Python::with_gil(|py| {
+            assert_eq!(target.decode(py).unwrap().len(), 1);
+            // The partial record is longer than the decoded one, so it isn't moved
+            assert_eq!(target.start, ohlcv_bytes.len());
+            assert_eq!(target.buffer.get_ref().len(), ohlcv_bytes.len() + 100);
+            // Only undecoded data counts toward `max_buffer_size`
+            target.write(&error_bytes[100..]).unwrap();
+            assert!(target.write(&[0; 100]).is_err());
+            assert_eq!(target.decode(py).unwrap().len(), 1);
+            assert_eq!(target.start, 0);
+            assert!(target.buffer.get_ref().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_dbn_decoder_ts_out() {
+        setup();
+        const TS_OUT: u64 = 1680708278000000001;
+        let mut encoder = Encoder::new(
+            Vec::new(),
+            &MetadataBuilder::new()
+                .dataset(XNAS_ITCH.to_owned())
+                .schema(None)
+                .stype_in(None)
+                .stype_out(SType::InstrumentId)
+                .start(0)
+                .ts_out(true)
+                .build(),
+        )
+        .unwrap();
+        let metadata_len = encoder.get_ref().len();
+        let rec = WithTsOut::new(ErrorMsg::new(1680708278000000000, "Python", true), TS_OUT);
+        encoder.encode_record(&rec).unwrap();
+        encoder.encode_record(&rec).unwrap();
+        // with metadata
+        let mut target = DbnDecoder::new(None, None, None, None, None);
+        target.write(encoder.get_ref()).unwrap();
+        let recs = Python::with_gil(|py| target.decode(py)).unwrap();
+        assert_eq!(recs.len(), 3);
+        assert!(target.buffer.get_ref().is_empty());
+        // without metadata
+        let mut no_metadata_target = DbnDecoder::new(Some(false), Some(true), None, None, None);
+        no_metadata_target
+            .write(&encoder.get_ref()[metadata_len..])
+            .unwrap();
+        let no_metadata_recs = Python::with_gil(|py| no_metadata_target.decode(py)).unwrap();
+        assert_eq!(no_metadata_recs.len(), 2);
+        Python::with_gil(|py| {
+            for rec in recs[1..].iter().chain(no_metadata_recs.iter()) {
+                let rec = rec.as_ref(py);
+                assert_eq!(
+                    rec.getattr("ts_out").unwrap().extract::<u64>().unwrap(),
+                    TS_OUT
+                );
+                assert_eq!(
+                    rec.getattr("ts_event").unwrap().extract::<u64>().unwrap(),
+                    1680708278000000000
+                );
+            }
+        });
+    }
+
     #[test]
     fn test_dbn_decoder() {
         setup();
@@ -259,12 +834,180 @@ for record in records[1:]:
         });
     }
 
+    #[rstest]
+    #[case::imbalance("imbalance", "ImbalanceMsg")]
+    #[case::statistics("statistics", "StatMsg")]
+    fn test_dbn_decoder_record_types(#[case] schema: &str, #[case] class_name: &str) {
+        setup();
+        Python::with_gil(|py| {
+            let path = PyString::new(
+                py,
+                &format!(
+                    "{}/../tests/data/test_data.{schema}.dbn",
+                    env!("CARGO_MANIFEST_DIR")
+                ),
+            );
+            let class_name = PyString::new(py, class_name);
+            py_run!(
+                py,
+                path class_name,
+                r#"import _lib
+
+decoder = _lib.DBNDecoder()
+with open(path, 'rb') as fin:
+    decoder.write(fin.read())
+records = decoder.decode()
+assert len(records) > 1
+for record in records[1:]:
+    assert isinstance(record, getattr(_lib, class_name))"#
+            )
+        });
+    }
+
+    #[test]
+    fn test_dbn_decoder_process() {
+        setup();
+        Python::with_gil(|py| {
+            let path = PyString::new(
+                py,
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../tests/data/test_data.mbo.dbn"
+                ),
+            );
+            py_run!(
+                py,
+                path,
+                r#"from _lib import DBNDecoder, Metadata, MBOMsg
+
+decoder = DBNDecoder()
+with open(path, 'rb') as fin:
+    decoder.write(fin.read())
+records = []
+def stop_after_first(record):
+    records.append(record)
+    return not isinstance(record, MBOMsg)
+assert decoder.process(stop_after_first) == 2
+assert isinstance(records[0], Metadata)
+assert isinstance(records[1], MBOMsg)
+assert decoder.process(records.append) == 1
+assert len(records) == 3
+assert decoder.process(records.append) == 0
+assert decoder.buffer() == b"""#
+            )
+        });
+    }
+
+    #[test]
+    fn test_dbn_decoder_decode_by_schema() {
+        setup();
+        Python::with_gil(|py| {
+            let data_dir = PyString::new(py, concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/data"));
+            py_run!(
+                py,
+                data_dir,
+                r#"from _lib import DBNDecoder, InstrumentDefMsg, Metadata
+
+mbo_decoder = DBNDecoder()
+with open(f"{data_dir}/test_data.mbo.dbn", "rb") as fin:
+    mbo_decoder.write(fin.read())
+mbo_records = mbo_decoder.decode()[1:]
+decoder = DBNDecoder()
+with open(f"{data_dir}/test_data.definition.dbn", "rb") as fin:
+    decoder.write(fin.read())
+for record in mbo_records:
+    decoder.write(bytes(record))
+groups = decoder.decode_by_schema()
+assert sorted(groups.keys()) == ["instrument-def", "mbo", "metadata"]
+assert isinstance(groups["metadata"], Metadata)
+assert len(groups["instrument-def"]) > 0
+assert all(isinstance(rec, InstrumentDefMsg) for rec in groups["instrument-def"])
+assert groups["mbo"] == mbo_records
+assert decoder.decode_by_schema() == {}"#
+            )
+        });
+    }
+
+    #[test]
+    fn test_dbn_decoder_threads() {
+        setup();
+        Python::with_gil(|py| {
+            let path = PyString::new(
+                py,
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../tests/data/test_data.mbo.dbn"
+                ),
+            );
+            py_run!(
+                py,
+                path,
+                r#"from concurrent.futures import ThreadPoolExecutor
+from _lib import DBNDecoder, Schema
+
+with open(path, "rb") as fin:
+    data = fin.read()
+
+def decode(_):
+    decoder = DBNDecoder()
+    decoder.write(data)
+    records = decoder.decode()
+    array_decoder = DBNDecoder()
+    array_decoder.write(data)
+    return records, array_decoder.decode_ndarray(Schema.MBO)
+
+with ThreadPoolExecutor(max_workers=4) as executor:
+    results = list(executor.map(decode, range(8)))
+for records, array in results:
+    assert records == results[0][0]
+    assert len(array) == len(records) - 1"#
+            )
+        });
+    }
+
+    #[test]
+    fn test_dbn_decoder_buffer_protocol() {
+        setup();
+        Python::with_gil(|py| {
+            let path = PyString::new(
+                py,
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../tests/data/test_data.mbo.dbn"
+                ),
+            );
+            py_run!(
+                py,
+                path,
+                r#"from _lib import DBNDecoder
+
+with open(path, 'rb') as fin:
+    data = fin.read()
+decoder = DBNDecoder()
+decoder.write(memoryview(data)[:100])
+decoder.write(bytearray(data[100:]))
+view = decoder.buffer()
+assert isinstance(view, memoryview)
+assert view.readonly
+assert view == data
+try:
+    decoder.decode()
+    assert False, "expected BufferError"
+except BufferError:
+    pass
+view.release()
+assert len(decoder.decode()) == 3
+assert decoder.buffer().nbytes == 0"#
+            );
+        });
+    }
+
     #[test]
     fn test_dbn_decoder_decoding_error() {
         setup();
         Python::with_gil(|py| {
             py.run(
-                r#"from _lib import DBNDecoder, Metadata, Schema, SType
+                r#"from _lib import DBNDecoder, DBNError, Metadata, Schema, SType
 
 metadata = Metadata(
     dataset="GLBX.MDP3",
@@ -286,8 +1029,9 @@ try:
     records = decoder.decode()
     # If this code is called, the test will fail
     assert False
-except Exception as ex:
+except DBNError as ex:
     assert "Invalid rtype" in str(ex)
+assert issubclass(DBNError, ValueError)
 "#,
                 None,
                 None,
@@ -315,4 +1059,223 @@ assert records[0] == record
         })
         .unwrap();
     }
+
+    #[test]
+    fn test_dbn_decoder_decode_ndarray() {
+        setup();
+        Python::with_gil(|py| {
+            if py.import("numpy").is_err() {
+                return;
+            }
+            let path = PyString::new(
+                py,
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../tests/data/test_data.mbo.dbn"
+                ),
+            );
+            py_run!(
+                py,
+                path,
+                r#"from _lib import DBNDecoder, MBOMsg
+
+decoder = DBNDecoder()
+with open(path, 'rb') as fin:
+    data = fin.read()
+decoder.write(data[:-4])
+array = decoder.decode_ndarray()
+assert len(array) == 1
+assert array.dtype.itemsize == MBOMsg.size_hint
+assert array.dtype.names == tuple(name for name, _ in MBOMsg._dtypes)
+decoder.write(data[-4:])
+array = decoder.decode_ndarray()
+assert len(array) == 1
+assert array[0]["rtype"] == 0xA0
+assert array[0]["action"] in (b"A", b"C", b"M", b"R", b"T", b"F")
+assert len(decoder.buffer()) == 0"#
+            )
+        });
+    }
+
+    #[test]
+    fn test_to_df() {
+        setup();
+        Python::with_gil(|py| {
+            if py.import("pandas").is_err() {
+                return;
+            }
+            setup_package(py);
+            let path = PyString::new(
+                py,
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../tests/data/test_data.mbo.dbn"
+                ),
+            );
+            py_run!(
+                py,
+                path,
+                r#"import pandas as pd
+from databento_dbn import DBNDecoder, FIXED_PRICE_SCALE, MBOMsg, Schema, to_df
+
+with open(path, 'rb') as fin:
+    data = fin.read()
+df = to_df(data)
+assert len(df) == 2
+assert df.index.name == "ts_recv"
+assert list(df.columns) == [field for field in MBOMsg._ordered_fields if field != "ts_recv"]
+assert df.index[0] == pd.Timestamp(1609160400000704060, unit="ns", tz="UTC")
+assert df["ts_event"].iloc[1] == pd.Timestamp(1609160400000431665, unit="ns", tz="UTC")
+assert df["action"].iloc[0] in ("A", "C", "M", "R", "T", "F")
+raw = to_df(data, schema=Schema.MBO, pretty_px=False, pretty_ts=False)
+assert list(raw.index) == [1609160400000704060, 1609160400000711344]
+assert list(raw["price"] / FIXED_PRICE_SCALE) == list(df["price"])
+decoder = DBNDecoder()
+decoder.write(data)
+records = decoder.decode()[1:]
+pd.testing.assert_frame_equal(to_df(records), df)
+try:
+    to_df([])
+    assert False, "expected ValueError"
+except ValueError:
+    pass"#
+            )
+        });
+    }
+
+    #[test]
+    fn test_async_dbn_decoder() {
+        setup();
+        Python::with_gil(|py| {
+            setup_package(py);
+            let path = PyString::new(
+                py,
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../tests/data/test_data.mbo.dbn"
+                ),
+            );
+            py_run!(
+                py,
+                path,
+                r#"import asyncio
+from databento_dbn import AsyncDBNDecoder, DBNDecoder, DBNError, MBOMsg, Metadata
+
+with open(path, 'rb') as fin:
+    data = fin.read()
+decoder = DBNDecoder()
+decoder.write(data)
+expected = decoder.decode()
+
+async def chunks(data, size):
+    for i in range(0, len(data), size):
+        await asyncio.sleep(0)
+        yield data[i:i + size]
+
+async def collect(source, **kwargs):
+    return [rec async for rec in AsyncDBNDecoder(source, **kwargs)]
+
+async def from_stream_reader(data):
+    reader = asyncio.StreamReader()
+    reader.feed_data(data)
+    reader.feed_eof()
+    return await collect(reader, chunk_size=7)
+
+records = asyncio.run(collect(chunks(data, 13)))
+assert records == expected
+assert isinstance(records[0], Metadata)
+assert [type(rec) for rec in records[1:]] == [MBOMsg, MBOMsg]
+assert records[1].ts_recv == 1609160400000704060
+assert records[2].ts_event == 1609160400000431665
+assert asyncio.run(from_stream_reader(data)) == expected
+try:
+    asyncio.run(collect(chunks(data[:-4], 13)))
+    assert False, "expected DBNError"
+except DBNError as exc:
+    assert "incomplete record" in str(exc)"#
+            )
+        });
+    }
+
+    #[test]
+    fn test_dbn_decoder_decode_ndarray_skips_other_rtypes() {
+        setup();
+        Python::with_gil(|py| {
+            if py.import("numpy").is_err() {
+                return;
+            }
+            let mut encoder = Encoder::new(
+                Vec::new(),
+                &MetadataBuilder::new()
+                    .dataset(XNAS_ITCH.to_owned())
+                    .schema(Some(Schema::Ohlcv1S))
+                    .stype_in(Some(SType::RawSymbol))
+                    .stype_out(SType::InstrumentId)
+                    .start(0)
+                    .build(),
+            )
+            .unwrap();
+            let ohlcv = |close| OhlcvMsg {
+                hd: RecordHeader::new::<OhlcvMsg>(rtype::OHLCV_1S, 1, 1, 1681228173000000000),
+                open: 100,
+                high: 200,
+                low: 50,
+                close,
+                volume: 1000,
+            };
+            encoder.encode_record(&ohlcv(1)).unwrap();
+            encoder.encode_record(&ohlcv(2)).unwrap();
+            encoder
+                .encode_record(&ErrorMsg::new(1680708278000000000, "Python", true))
+                .unwrap();
+            encoder.encode_record(&ohlcv(3)).unwrap();
+            let mut decoder = DbnDecoder::new(None, None, None, None, None);
+            decoder.write(encoder.get_ref()).unwrap();
+            let array = decoder.decode_ndarray(py, None).unwrap();
+            py_run!(py, array, r#"assert list(array["close"]) == [1, 2, 3]"#);
+            assert!(decoder.buffer.get_ref().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_dbn_decoder_decode_arrow() {
+        setup();
+        Python::with_gil(|py| {
+            if py.import("pyarrow").is_err() {
+                return;
+            }
+            let path = PyString::new(
+                py,
+                concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/../tests/data/test_data.trades.dbn"
+                ),
+            );
+            py_run!(
+                py,
+                path,
+                r#"import pyarrow as pa
+from _lib import DBNDecoder, TradeMsg
+
+with open(path, 'rb') as fin:
+    data = fin.read()
+decoder = DBNDecoder()
+decoder.write(data)
+records = decoder.decode()[1:]
+decoder = DBNDecoder()
+decoder.write(data)
+batch = decoder.decode_arrow()
+assert batch.num_rows == 2
+assert "length" not in batch.schema.names
+assert batch.schema.names == [name for name, _ in TradeMsg._dtypes if name != "length"]
+assert batch.schema.field("ts_event").type == pa.uint64()
+assert batch.schema.field("price").type == pa.int64()
+assert batch.schema.field("action").type == pa.string()
+assert batch.column("ts_event").to_pylist() == [rec.ts_event for rec in records]
+assert batch.column("price").to_pylist() == [rec.price for rec in records]
+assert batch.column("action").to_pylist() == [rec.action for rec in records]
+assert batch.column("side").to_pylist() == [rec.side for rec in records]"#
+            )
+        });
+    }
 }
@@ -6,13 +6,13 @@ use std::{
 use dbn::{
     encode::{
         dbn::{Encoder as DbnEncoder, MetadataEncoder},
-        DbnEncodable, DynWriter, EncodeDbn,
+        DbnEncodable, DynWriter, EncodeRecord,
     },
     enums::{Compression, Schema},
     python::to_val_err,
     record::{
-        ImbalanceMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, StatMsg, TbboMsg,
-        TradeMsg,
+        BboMsg, CbboMsg, ImbalanceMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg,
+        StatMsg, StatusMsg, TbboMsg, TradeMsg,
     },
     Metadata,
 };
@@ -49,19 +49,21 @@ pub fn update_encoded_metadata(
 
 /// Encodes the given data in the DBN encoding and writes it to `file`.
 ///
-/// `records` is a list of record objects.
+/// `records` is an iterable of record objects of the type corresponding to the
+/// schema in `metadata`. Records are encoded directly from their underlying Rust
+/// structs as they're iterated.
 ///
 /// # Errors
 /// This function returns an error if any of the enum arguments cannot be converted to
 /// their Rust equivalents. It will also return an error if there's an issue writing
-/// the encoded to bytes or an expected field is missing from one of the dicts.
+/// the encoded to bytes or one of the records is of the wrong type.
 #[pyfunction]
 pub fn write_dbn_file(
     py: Python<'_>,
     file: PyFileLike,
     compression: Compression,
     metadata: &Metadata,
-    records: Vec<&PyAny>,
+    records: &PyAny,
 ) -> PyResult<()> {
     PyErr::warn(
         py,
@@ -72,39 +74,39 @@ pub fn write_dbn_file(
     let writer = DynWriter::new(file, compression).map_err(to_val_err)?;
     let encoder = DbnEncoder::new(writer, metadata).map_err(to_val_err)?;
     match metadata.schema {
-        Some(Schema::Mbo) => encode_pyrecs::<MboMsg>(encoder, &records),
-        Some(Schema::Mbp1) => encode_pyrecs::<Mbp1Msg>(encoder, &records),
-        Some(Schema::Mbp10) => encode_pyrecs::<Mbp10Msg>(encoder, &records),
-        Some(Schema::Tbbo) => encode_pyrecs::<TbboMsg>(encoder, &records),
-        Some(Schema::Trades) => encode_pyrecs::<TradeMsg>(encoder, &records),
+        Some(Schema::Mbo) => encode_pyrecs::<MboMsg>(encoder, records),
+        Some(Schema::Mbp1) => encode_pyrecs::<Mbp1Msg>(encoder, records),
+        Some(Schema::Mbp10) => encode_pyrecs::<Mbp10Msg>(encoder, records),
+        Some(Schema::Tbbo) => encode_pyrecs::<TbboMsg>(encoder, records),
+        Some(Schema::Trades) => encode_pyrecs::<TradeMsg>(encoder, records),
         Some(Schema::Ohlcv1S)
         | Some(Schema::Ohlcv1M)
         | Some(Schema::Ohlcv1H)
         | Some(Schema::Ohlcv1D)
-        | Some(Schema::OhlcvEod) => encode_pyrecs::<OhlcvMsg>(encoder, &records),
-        Some(Schema::Definition) => encode_pyrecs::<InstrumentDefMsg>(encoder, &records),
-        Some(Schema::Imbalance) => encode_pyrecs::<ImbalanceMsg>(encoder, &records),
-        Some(Schema::Statistics) => encode_pyrecs::<StatMsg>(encoder, &records),
-        Some(Schema::Status) | None => Err(PyValueError::new_err(
+        | Some(Schema::OhlcvEod) => encode_pyrecs::<OhlcvMsg>(encoder, records),
+        Some(Schema::Bbo1S) | Some(Schema::Bbo1M) => encode_pyrecs::<BboMsg>(encoder, records),
+        Some(Schema::Cbbo) | Some(Schema::Cbbo1S) | Some(Schema::Cbbo1M) | Some(Schema::Tcbbo) => {
+            encode_pyrecs::<CbboMsg>(encoder, records)
+        }
+        Some(Schema::Definition) => encode_pyrecs::<InstrumentDefMsg>(encoder, records),
+        Some(Schema::Imbalance) => encode_pyrecs::<ImbalanceMsg>(encoder, records),
+        Some(Schema::Statistics) => encode_pyrecs::<StatMsg>(encoder, records),
+        Some(Schema::Status) => encode_pyrecs::<StatusMsg>(encoder, records),
+        None => Err(PyValueError::new_err(
             "Unsupported schema type for writing DBN files",
         )),
     }
 }
 
-fn encode_pyrecs<T: Clone + DbnEncodable + PyClass>(
+fn encode_pyrecs<T: DbnEncodable + PyClass>(
     mut encoder: DbnEncoder<DynWriter<PyFileLike>>,
-    records: &[&PyAny],
+    records: &PyAny,
 ) -> PyResult<()> {
-    encoder
-        .encode_records(
-            records
-                .iter()
-                .map(|obj| obj.extract())
-                .collect::<PyResult<Vec<T>>>()?
-                .iter()
-                .as_slice(),
-        )
-        .map_err(to_val_err)
+    for obj in records.iter()? {
+        let rec: PyRef<T> = obj?.extract()?;
+        encoder.encode_record(&*rec).map_err(to_val_err)?;
+    }
+    encoder.flush().map_err(to_val_err)
 }
 
 /// A Python object that implements the Python file interface.
@@ -220,6 +222,7 @@ pub mod tests {
         decode::{dbn::Decoder as DbnDecoder, DbnMetadata, DecodeRecord},
         SType, TbboMsg,
     };
+    use pyo3::types::PyList;
 
     use super::*;
 
@@ -271,6 +274,67 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_with_bytes_io() {
+        crate::tests::setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"import io
+import warnings
+from _lib import Compression, DBNDecoder, Metadata, Schema, SType, TradeMsg, update_encoded_metadata, write_dbn_file
+
+metadata = Metadata("GLBX.MDP3", 1, SType.INSTRUMENT_ID, schema=Schema.TRADES, stype_in=SType.RAW_SYMBOL)
+record = TradeMsg(1, 2, 3, 4, 5, ord("T"), ord("A"), 0, 0, 6, 7, 8)
+file = io.BytesIO()
+with warnings.catch_warnings():
+    warnings.simplefilter("ignore", DeprecationWarning)
+    write_dbn_file(file, Compression.NONE, metadata, [record])
+update_encoded_metadata(file, 10, 20, 30)
+decoder = DBNDecoder()
+decoder.write(file.getvalue())
+records = decoder.decode()
+assert records[0].start == 10
+assert records[0].end == 20
+assert records[0].limit == 30
+assert records[1:] == [record]
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_write_dbn_file_iterable() {
+        crate::tests::setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"import io
+import warnings
+from _lib import Compression, DBNDecoder, MBOMsg, Metadata, Schema, SType, TradeMsg, write_dbn_file
+
+warnings.simplefilter("ignore", DeprecationWarning)
+metadata = Metadata("GLBX.MDP3", 1, SType.INSTRUMENT_ID, schema=Schema.TRADES)
+records = [TradeMsg(1, 2, i, 4, 5, ord("T"), ord("A"), 0, 0, 6, 7, 8) for i in range(3)]
+file = io.BytesIO()
+write_dbn_file(file, Compression.NONE, metadata, (rec for rec in records))
+decoder = DBNDecoder()
+decoder.write(file.getvalue())
+assert decoder.decode()[1:] == records
+try:
+    write_dbn_file(io.BytesIO(), Compression.NONE, metadata, [MBOMsg(1, 2, 3, 4, 5, 6, 7, ord("A"), ord("B"), 8, 9, 10)])
+    assert False, "expected TypeError"
+except TypeError:
+    pass
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
     const DATASET: &str = GLBX_MDP3;
     const STYPE: SType = SType::InstrumentId;
 
@@ -289,7 +353,7 @@ pub mod tests {
                 .unwrap();
                 let rs_recs = decoder.decode_records::<$record_type>().unwrap();
                 let output_buf = Python::with_gil(|py| -> PyResult<_> {
-                    // Convert JSON objects to Python `dict`s
+                    // Convert to Python record objects
                     let recs: Vec<_> = rs_recs
                         .iter()
                         .map(|rs_rec| rs_rec.clone().into_py(py))
@@ -310,7 +374,7 @@ pub mod tests {
                         mock_file.extract(py).unwrap(),
                         Compression::ZStd,
                         &metadata,
-                        recs.iter().map(|r| r.as_ref(py)).collect(),
+                        PyList::new(py, recs),
                     )
                     .unwrap();
 
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use dbn::{
+    compat::{SymbolMappingMsgV1, SymbolMappingRec},
+    python::{py_to_time_date, to_val_err},
+    Metadata, Record, SymbolMappingMsg, TsSymbolMap,
+};
+use pyo3::{
+    intern,
+    prelude::*,
+    types::{timezone_utc, PyDate, PyDateTime},
+};
+use time::{OffsetDateTime, Time};
+
+/// Maps instrument IDs to human-readable symbols over time.
+#[pyclass(module = "databento_dbn")]
+#[derive(Debug, Default)]
+pub struct InstrumentMap(TsSymbolMap);
+
+#[pymethods]
+impl InstrumentMap {
+    #[new]
+    fn new(metadata: Option<PyRef<Metadata>>) -> PyResult<Self> {
+        let mut res = Self::default();
+        if let Some(metadata) = metadata {
+            res.insert_metadata(metadata)?;
+        }
+        Ok(res)
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Inserts the symbol mappings from `metadata`, replacing any existing mappings
+    /// for the same instrument and date.
+    fn insert_metadata(&mut self, metadata: PyRef<Metadata>) -> PyResult<()> {
+        let symbol_map = TsSymbolMap::from_metadata(&metadata).map_err(to_val_err)?;
+        self.0.inner_mut().extend(symbol_map.inner().clone());
+        Ok(())
+    }
+
+    /// Inserts the mapping from a `SymbolMappingMsg` or `SymbolMappingMsgV1`. Mappings
+    /// without an interval apply to the date of the record's `ts_event`.
+    fn insert_symbol_mapping_msg(&mut self, msg: &PyAny) -> PyResult<()> {
+        if let Ok(msg) = msg.extract::<PyRef<SymbolMappingMsg>>() {
+            self.insert_symbol_mapping(&*msg)
+        } else {
+            self.insert_symbol_mapping(&*msg.extract::<PyRef<SymbolMappingMsgV1>>()?)
+        }
+    }
+
+    /// Returns the symbol for `instrument_id` on the UTC date of `ts`, which can be a
+    /// `date`, `datetime`, or UNIX nanosecond timestamp. Naive datetimes are assumed to
+    /// be in UTC. Returns `None` if there's no mapping.
+    fn resolve(&self, instrument_id: u32, ts: &PyAny) -> PyResult<Option<String>> {
+        let date = extract_utc_date(ts)?;
+        Ok(self.0.get(date, instrument_id).cloned())
+    }
+
+    /// Removes all mappings.
+    fn clear(&mut self) {
+        self.0.inner_mut().clear();
+    }
+}
+
+impl InstrumentMap {
+    fn insert_symbol_mapping<S: SymbolMappingRec>(&mut self, msg: &S) -> PyResult<()> {
+        let symbol = msg.stype_out_symbol().map_err(to_val_err)?;
+        let (start_date, end_date) = match (msg.start_ts(), msg.end_ts()) {
+            (Some(start_ts), Some(end_ts)) => (start_ts.date(), exclusive_end_date(end_ts)),
+            _ => {
+                let ts_event =
+                    OffsetDateTime::from_unix_timestamp_nanos(msg.header().ts_event as i128)
+                        .map_err(to_val_err)?;
+                (ts_event.date(), exclusive_end_date(ts_event))
+            }
+        };
+        self.0
+            .insert(
+                msg.header().instrument_id,
+                start_date,
+                end_date,
+                Arc::new(symbol.to_owned()),
+            )
+            .map_err(to_val_err)
+    }
+}
+
+// `TsSymbolMap` intervals are by date with an exclusive end date, so partial days
+// need to be rounded up
+fn exclusive_end_date(end_ts: OffsetDateTime) -> time::Date {
+    if end_ts.time() == Time::MIDNIGHT {
+        end_ts.date()
+    } else {
+        end_ts.date().next_day().unwrap_or(time::Date::MAX)
+    }
+}
+
+fn extract_utc_date(ts: &PyAny) -> PyResult<time::Date> {
+    let py = ts.py();
+    if let Ok(ts) = ts.extract::<u64>() {
+        return OffsetDateTime::from_unix_timestamp_nanos(ts as i128)
+            .map(OffsetDateTime::date)
+            .map_err(to_val_err);
+    }
+    // `datetime` is a subclass of `date` so it must be checked first
+    let date = if let Ok(datetime) = ts.downcast::<PyDateTime>() {
+        let datetime = if datetime.getattr(intern!(py, "tzinfo"))?.is_none() {
+            datetime.as_ref()
+        } else {
+            datetime.call_method1(intern!(py, "astimezone"), (timezone_utc(py),))?
+        };
+        datetime.call_method0(intern!(py, "date"))?
+    } else {
+        ts
+    };
+    py_to_time_date(date.downcast::<PyDate>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::py_run;
+
+    use super::*;
+    use crate::tests::setup;
+
+    #[test]
+    fn test_instrument_map() {
+        setup();
+        Python::with_gil(|py| {
+            py_run!(
+                py,
+                r#"import datetime as dt
+from types import SimpleNamespace
+from _lib import InstrumentMap, Metadata, SType, SymbolMappingMsg
+
+metadata = Metadata(
+    dataset="XNAS.ITCH",
+    start=0,
+    stype_in=SType.RAW_SYMBOL,
+    stype_out=SType.INSTRUMENT_ID,
+    mappings=[
+        SimpleNamespace(
+            raw_symbol="AAPL",
+            intervals=[
+                SimpleNamespace(
+                    start_date=dt.date(2023, 7, 1),
+                    end_date=dt.date(2023, 7, 3),
+                    symbol="32",
+                ),
+            ],
+        ),
+    ],
+)
+instrument_map = InstrumentMap(metadata)
+assert instrument_map.resolve(32, dt.date(2023, 7, 1)) == "AAPL"
+assert instrument_map.resolve(32, dt.datetime(2023, 7, 2, 23, 59)) == "AAPL"
+assert instrument_map.resolve(32, dt.date(2023, 7, 3)) is None
+assert instrument_map.resolve(33, dt.date(2023, 7, 1)) is None
+# 2023-07-03T00:00:00Z
+assert instrument_map.resolve(32, 1_688_342_400_000_000_000) is None
+tz = dt.timezone(dt.timedelta(hours=-4))
+assert instrument_map.resolve(32, dt.datetime(2023, 7, 2, 21, tzinfo=tz)) is None
+msg = SymbolMappingMsg(1, 33, 1_688_342_400_000_000_000, SType.RAW_SYMBOL, "MSFT", SType.RAW_SYMBOL, "MSFT", 1_688_342_400_000_000_000, 1_688_378_400_000_000_000)
+instrument_map.insert_symbol_mapping_msg(msg)
+assert instrument_map.resolve(33, dt.date(2023, 7, 3)) == "MSFT"
+instrument_map.clear()
+assert len(instrument_map) == 0"#
+            );
+        });
+    }
+}
@@ -4,25 +4,29 @@ use pyo3::{prelude::*, wrap_pyfunction, PyClass};
 
 use dbn::{
     compat::{ErrorMsgV1, InstrumentDefMsgV1, SymbolMappingMsgV1, SystemMsgV1},
-    enums::{Compression, Encoding, SType, Schema},
+    enums::{Action, Compression, Encoding, SType, Schema, Side},
     flags,
-    python::EnumIterator,
+    python::{DBNError, EnumIterator},
     record::{
-        BidAskPair, ErrorMsg, ImbalanceMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg,
-        RecordHeader, StatMsg, StatusMsg, SymbolMappingMsg, SystemMsg, TradeMsg,
+        BboMsg, BidAskPair, CbboMsg, ConsolidatedBidAskPair, ErrorMsg, ImbalanceMsg,
+        InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, RecordHeader, StatMsg, StatusMsg,
+        SymbolMappingMsg, SystemMsg, TradeMsg,
     },
     Metadata, RType, VersionUpgradePolicy, FIXED_PRICE_SCALE, UNDEF_ORDER_SIZE, UNDEF_PRICE,
     UNDEF_STAT_QUANTITY, UNDEF_TIMESTAMP,
 };
 
+mod arrow;
 mod dbn_decoder;
 mod encode;
+mod instrument_map;
+mod pretty;
 mod transcoder;
 
 /// A Python module wrapping dbn functions
 #[pymodule] // The name of the function must match `lib.name` in `Cargo.toml`
 #[pyo3(name = "_lib")]
-fn databento_dbn(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+fn databento_dbn(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     fn checked_add_class<T: PyClass>(m: &PyModule) -> PyResult<()> {
         // ensure a module was specified, otherwise it defaults to builtins
         assert_eq!(T::MODULE.unwrap(), "databento_dbn");
@@ -31,10 +35,17 @@ fn databento_dbn(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     // all functions exposed to Python need to be added here
     m.add_wrapped(wrap_pyfunction!(encode::update_encoded_metadata))?;
     m.add_wrapped(wrap_pyfunction!(encode::write_dbn_file))?;
+    m.add_wrapped(wrap_pyfunction!(dbn::python::from_bytes))?;
+    m.add_wrapped(wrap_pyfunction!(pretty::fmt_px))?;
+    m.add_wrapped(wrap_pyfunction!(pretty::fmt_ts))?;
+    m.add_wrapped(wrap_pyfunction!(pretty::parse_px))?;
+    m.add_wrapped(wrap_pyfunction!(pretty::parse_ts))?;
     checked_add_class::<dbn_decoder::DbnDecoder>(m)?;
     checked_add_class::<transcoder::Transcoder>(m)?;
+    checked_add_class::<instrument_map::InstrumentMap>(m)?;
     checked_add_class::<Metadata>(m)?;
     checked_add_class::<EnumIterator>(m)?;
+    m.add("DBNError", py.get_type::<DBNError>())?;
     // Records
     checked_add_class::<RecordHeader>(m)?;
     checked_add_class::<MboMsg>(m)?;
@@ -43,6 +54,9 @@ fn databento_dbn(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     checked_add_class::<Mbp1Msg>(m)?;
     checked_add_class::<Mbp10Msg>(m)?;
     checked_add_class::<OhlcvMsg>(m)?;
+    checked_add_class::<BboMsg>(m)?;
+    checked_add_class::<ConsolidatedBidAskPair>(m)?;
+    checked_add_class::<CbboMsg>(m)?;
     checked_add_class::<ImbalanceMsg>(m)?;
     checked_add_class::<StatusMsg>(m)?;
     checked_add_class::<InstrumentDefMsg>(m)?;
@@ -55,11 +69,13 @@ fn databento_dbn(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     checked_add_class::<SystemMsgV1>(m)?;
     checked_add_class::<StatMsg>(m)?;
     // PyClass enums
+    checked_add_class::<Action>(m)?;
     checked_add_class::<Compression>(m)?;
     checked_add_class::<Encoding>(m)?;
     checked_add_class::<RType>(m)?;
     checked_add_class::<SType>(m)?;
     checked_add_class::<Schema>(m)?;
+    checked_add_class::<Side>(m)?;
     checked_add_class::<VersionUpgradePolicy>(m)?;
     // constants
     m.add("FIXED_PRICE_SCALE", FIXED_PRICE_SCALE)?;
@@ -93,6 +109,22 @@ mod tests {
         pyo3::prepare_freethreaded_python();
     }
 
+    /// Makes the pure-Python modules of the `databento_dbn` package importable by
+    /// registering the embedded native module as `databento_dbn._lib`.
+    pub fn setup_package(py: Python<'_>) {
+        let python_source = concat!(env!("CARGO_MANIFEST_DIR"), "/python");
+        pyo3::py_run!(
+            py,
+            python_source,
+            r#"import sys
+import _lib
+
+if python_source not in sys.path:
+    sys.path.insert(0, python_source)
+sys.modules["databento_dbn._lib"] = _lib"#
+        );
+    }
+
     #[test]
     fn test_metadata_identity() {
         // initialize interpreter
@@ -145,6 +177,301 @@ try:
     assert False
 except Exception:
     pass
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_record_asdict() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"from _lib import BidAskPair, MBP1Msg
+
+record = MBP1Msg(1, 2, 3, 4, 5, ord("A"), ord("B"), 0, 0, 6, 7, 8, BidAskPair(bid_px=9))
+as_dict = record.asdict()
+assert list(as_dict.keys()) == MBP1Msg._ordered_fields
+assert as_dict["price"] == 4
+assert as_dict["side"] == "B"
+assert as_dict["bid_px_00"] == 9
+assert dict(record) == as_dict
+record.ts_out = 10
+assert record.asdict()["ts_out"] == 10
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_pickle() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"import pickle
+from _lib import BidAskPair, Metadata, MBP10Msg, Schema, SType, TradeMsg
+
+record = TradeMsg(1, 2, 3, 4, 5, ord("T"), ord("A"), 0, 0, 6, 7, 8)
+record.ts_out = 9
+unpickled = pickle.loads(pickle.dumps(record))
+assert unpickled == record
+assert unpickled.ts_out == 9
+record = MBP10Msg(1, 2, 3, 4, 5, ord("T"), ord("A"), 0, 0, 6, 7, 8, [BidAskPair(1, 2)] * 10)
+assert pickle.loads(pickle.dumps(record)) == record
+level = BidAskPair(1, 2, 3, 4, 5, 6)
+assert pickle.loads(pickle.dumps(level)) == level
+metadata = Metadata(
+    dataset="GLBX.MDP3",
+    schema=Schema.MBO,
+    start=1,
+    stype_in=SType.RAW_SYMBOL,
+    stype_out=SType.INSTRUMENT_ID,
+    end=2,
+    symbols=["ESH4"],
+    partial=[],
+    not_found=[],
+    mappings=[]
+)
+assert pickle.loads(pickle.dumps(metadata)) == metadata
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_pretty_undef() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"import math
+from _lib import TradeMsg, UNDEF_PRICE, UNDEF_TIMESTAMP
+
+record = TradeMsg(1, 2, 3, UNDEF_PRICE, 5, ord("T"), ord("A"), 0, UNDEF_TIMESTAMP, 0, 0)
+assert math.isnan(record.pretty_price)
+assert record.pretty_ts_recv is None
+record.price = 1_500_000_000
+assert record.pretty_price == 1.5
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_metadata_setters() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"from _lib import Metadata, SType
+
+metadata = Metadata(dataset="XNAS.ITCH", start=1, stype_out=SType.INSTRUMENT_ID)
+assert metadata.symbols == []
+assert metadata.end is None
+metadata.start = 10
+metadata.end = 20
+metadata.limit = 5
+metadata.symbols = ["AAPL", "MSFT"]
+metadata = Metadata.decode(metadata.encode())
+assert metadata.start == 10
+assert metadata.end == 20
+assert metadata.limit == 5
+assert metadata.symbols == ["AAPL", "MSFT"]
+metadata.limit = None
+assert metadata.limit is None
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_metadata_datetime_and_enum_args() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"import datetime as dt
+from _lib import Metadata, Schema, SType
+
+metadata = Metadata(
+    dataset="XNAS.ITCH",
+    start=dt.datetime(2023, 7, 1, tzinfo=dt.timezone.utc),
+    end=dt.datetime(2023, 7, 1, 20, 0, 0, 1),
+    stype_out="instrument_id",
+    schema=Schema.TRADES,
+    stype_in="RAW_SYMBOL",
+)
+assert metadata.start == 1_688_169_600_000_000_000
+assert metadata.end == 1_688_241_600_000_001_000
+assert metadata.stype_out == SType.INSTRUMENT_ID
+assert metadata.stype_in == SType.RAW_SYMBOL
+assert metadata.schema == Schema.TRADES
+metadata.start = dt.datetime(2023, 7, 1, 1, tzinfo=dt.timezone(dt.timedelta(hours=1)))
+assert metadata.start == 1_688_169_600_000_000_000
+try:
+    Metadata(dataset="XNAS.ITCH", start=0, stype_out=SType.RAW_SYMBOL, schema="tbd")
+    assert False, "expected ValueError"
+except ValueError as exc:
+    assert "invalid `schema`" in str(exc)
+try:
+    Metadata(dataset="XNAS.ITCH", start="yesterday", stype_out=SType.RAW_SYMBOL)
+    assert False, "expected TypeError"
+except TypeError as exc:
+    assert "invalid `start`" in str(exc)
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_record_header_and_rtype_name() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"from _lib import ErrorMsg, MBOMsg, RecordHeader
+
+record = MBOMsg(1, 2, 3, 4, 5, 6, 7, ord("A"), ord("B"), 8, 9, 10)
+header = record.header
+assert isinstance(header, RecordHeader)
+assert header.length * 4 == record.record_size
+assert header.rtype == record.rtype
+assert header.publisher_id == 1
+assert header.instrument_id == 2
+assert header.ts_event == 3
+assert header.rtype_name == "mbo"
+assert record.rtype_name == "mbo"
+assert ErrorMsg(0, "test").rtype_name == "error"
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_record_kwargs_constructors() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"from _lib import ImbalanceMsg, InstrumentDefMsg, MBOMsg, StatMsg, TradeMsg, UNDEF_PRICE, UNDEF_TIMESTAMP
+
+mbo = MBOMsg(publisher_id=1, instrument_id=2, ts_event=3, price=4, side=ord("B"))
+assert mbo.price == 4
+assert mbo.side == "B"
+assert mbo.size == 2**32 - 1
+assert mbo.ts_recv == UNDEF_TIMESTAMP
+assert mbo == MBOMsg(1, 2, 3, price=4, side=ord("B"))
+trade = TradeMsg(1, 2, 3, size=10)
+assert trade.price == UNDEF_PRICE
+assert trade.size == 10
+imbalance = ImbalanceMsg(1, 2, 3, upper_collar=5)
+assert imbalance.upper_collar == 5
+assert imbalance.lower_collar == UNDEF_PRICE
+stat = StatMsg(1, 2, 3, stat_type=4)
+assert stat.quantity == 2**31 - 1
+definition = InstrumentDefMsg(1, 2, 3, raw_symbol="ESZ4")
+assert definition.raw_symbol == "ESZ4"
+assert definition.display_factor == UNDEF_PRICE
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_side_and_action_enums() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"from _lib import Action, MBOMsg, Side
+
+record = MBOMsg(1, 2, 3, 4, 5, 6, 7, ord("A"), ord("B"), 8, 9, 10)
+assert record.side == Side.BID
+assert record.side == "B"
+assert record.action == Action.ADD
+assert Side.from_str("ask") == Side.ASK
+assert Action("R") == Action.CLEAR
+assert str(Side.NONE) == "N"
+assert len(list(Action.variants())) == 6
+record = MBOMsg(1, 2, 3, 4, 5, 6, 7, ord("A"), 0, 8, 9, 10)
+assert record.side == "\0"
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_record_hash_and_eq() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"from _lib import BidAskPair, ErrorMsg, TradeMsg
+
+record = TradeMsg(1, 2, 3, 4, 5, ord("T"), ord("A"), 0, 0, 6, 7, 8)
+same = TradeMsg(1, 2, 3, 4, 5, ord("T"), ord("A"), 0, 0, 6, 7, 8)
+different = TradeMsg(1, 2, 3, 4, 5, ord("T"), ord("B"), 0, 0, 6, 7, 8)
+assert record == same
+assert record != different
+assert hash(record) == hash(same)
+assert len({record, same, different}) == 2
+assert record != ErrorMsg(0, "error")
+assert {BidAskPair(1, 2): "level"}[BidAskPair(1, 2)] == "level"
+assert "sequence: 8" in repr(record)
+"#,
+                None,
+                None,
+            )
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_record_to_and_from_bytes() {
+        setup();
+        Python::with_gil(|py| {
+            py.run(
+                r#"from _lib import ErrorMsg, MBOMsg, TradeMsg, from_bytes
+
+record = TradeMsg(1, 2, 3, 4, 5, ord("T"), ord("A"), 0, 0, 6, 7, 8)
+assert record.to_bytes() == bytes(record)
+decoded = from_bytes(record.to_bytes())
+assert isinstance(decoded, TradeMsg)
+assert decoded == record
+assert not hasattr(decoded, "ts_out")
+record.ts_out = 9
+data = record.to_bytes()
+assert len(data) == len(bytes(record)) + 8
+assert data[0] * 4 == len(data)
+decoded = from_bytes(data)
+assert decoded.ts_out == 9
+assert decoded.price == record.price
+assert isinstance(from_bytes(bytes(ErrorMsg(1, "error"))), ErrorMsg)
+try:
+    from_bytes(bytes(MBOMsg(1, 2, 3, 4, 5, 6, 7, ord("A"), ord("B"), 8, 9, 10))[:16])
+    assert False, "expected ValueError"
+except ValueError:
+    pass
 "#,
                 None,
                 None,
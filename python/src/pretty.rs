@@ -0,0 +1,58 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Converts a fixed-precision price to a decimal string, the same as the DBN CLI.
+#[pyfunction]
+pub fn fmt_px(px: i64) -> String {
+    dbn::pretty::fmt_px(px)
+}
+
+/// Converts a nanosecond UNIX timestamp to an ISO 8601 string with nanosecond
+/// precision, the same as the DBN CLI.
+#[pyfunction]
+pub fn fmt_ts(ts: u64) -> String {
+    dbn::pretty::fmt_ts(ts)
+}
+
+/// Parses a decimal price string, e.g. from `fmt_px`, into a fixed-precision price.
+#[pyfunction]
+pub fn parse_px(px: &str) -> PyResult<i64> {
+    dbn::pretty::parse_px(px).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Parses a timestamp string, e.g. from `fmt_ts`, into a nanosecond UNIX timestamp.
+#[pyfunction]
+pub fn parse_ts(ts: &str) -> PyResult<u64> {
+    dbn::pretty::parse_ts(ts).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::py_run;
+
+    use super::*;
+    use crate::tests::setup;
+
+    #[test]
+    fn test_fmt_and_parse() {
+        setup();
+        Python::with_gil(|py| {
+            py_run!(
+                py,
+                r#"from _lib import UNDEF_PRICE, fmt_px, fmt_ts, parse_px, parse_ts
+
+assert fmt_px(32_500_000_000) == "32.500000000"
+assert fmt_px(UNDEF_PRICE) == "UNDEF_PRICE"
+assert parse_px("32.5") == 32_500_000_000
+assert parse_px(fmt_px(-100_000)) == -100_000
+assert fmt_ts(1_622_838_300_000_000_000) == "2021-06-04T20:25:00.000000000Z"
+assert parse_ts("2021-06-04T20:25:00.000000000Z") == 1_622_838_300_000_000_000
+assert parse_ts(fmt_ts(0)) == 0
+try:
+    parse_px("abc")
+    assert False, "expected ValueError"
+except ValueError:
+    pass"#
+            );
+        });
+    }
+}
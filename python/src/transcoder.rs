@@ -16,7 +16,11 @@ use dbn::{
     Compression, Encoding, PitSymbolMap, RType, Record, RecordRef, Schema, SymbolIndex,
     TsSymbolMap, VersionUpgradePolicy,
 };
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyDate};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyBytes, PyDate},
+};
 
 use crate::encode::PyFileLike;
 
@@ -29,7 +33,7 @@ pub type PySymbolIntervalMap<'py> = HashMap<u32, Vec<(&'py PyDate, &'py PyDate,
 impl Transcoder {
     #[new]
     fn new(
-        file: PyFileLike,
+        file: Option<PyFileLike>,
         encoding: Encoding,
         compression: Compression,
         pretty_px: Option<bool>,
@@ -114,6 +118,11 @@ impl Transcoder {
     fn buffer(&self) -> &[u8] {
         self.0.buffer()
     }
+
+    fn read<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        let output = self.0.read()?;
+        Ok(PyBytes::new(py, &output))
+    }
 }
 
 trait Transcode {
@@ -122,12 +131,13 @@ trait Transcode {
     fn flush(&mut self) -> PyResult<()>;
 
     fn buffer(&self) -> &[u8];
+
+    fn read(&mut self) -> PyResult<Vec<u8>>;
 }
 
 struct Inner<const E: u8> {
     buffer: io::Cursor<Vec<u8>>,
-    // wrap in buffered writer to minimize calls to Python
-    output: DynWriter<'static, BufWriter<PyFileLike>>,
+    output: DynWriter<'static, Output>,
     use_pretty_px: bool,
     use_pretty_ts: bool,
     map_symbols: bool,
@@ -154,11 +164,20 @@ impl<const E: u8> Transcode for Inner<E> {
     fn buffer(&self) -> &[u8] {
         self.buffer.get_ref().as_slice()
     }
+
+    fn read(&mut self) -> PyResult<Vec<u8>> {
+        match self.output.get_mut() {
+            Output::File(_) => Err(PyValueError::new_err(
+                "Can only read transcoded output when no file was passed",
+            )),
+            Output::Buffer(buf) => Ok(std::mem::take(buf)),
+        }
+    }
 }
 
 impl<const OUTPUT_ENC: u8> Inner<OUTPUT_ENC> {
     fn new(
-        file: PyFileLike,
+        file: Option<PyFileLike>,
         compression: Compression,
         pretty_px: Option<bool>,
         pretty_ts: Option<bool>,
@@ -177,7 +196,12 @@ impl<const OUTPUT_ENC: u8> Inner<OUTPUT_ENC> {
         }
         Ok(Self {
             buffer: io::Cursor::default(),
-            output: DynWriter::new(BufWriter::new(file), compression).map_err(to_val_err)?,
+            output: DynWriter::new(
+                file.map(|file| Output::File(BufWriter::new(file)))
+                    .unwrap_or_default(),
+                compression,
+            )
+            .map_err(to_val_err)?,
             use_pretty_px: pretty_px.unwrap_or(true),
             use_pretty_ts: pretty_ts.unwrap_or(true),
             map_symbols: map_symbols.unwrap_or(true),
@@ -227,7 +251,7 @@ impl<const OUTPUT_ENC: u8> Inner<OUTPUT_ENC> {
                     // ensure buffer is left in correct state in the case where one
                     // or more successful decodings is followed by a partial one, i.e.
                     // `decode_record_ref` returning `Ok(None)`
-                    read_position = decoder.get_ref().position() as usize;
+                    read_position = decoder.get_ref().position() as usize - decoder.buffer().len();
                 }
                 Ok(None) => {
                     break;
@@ -282,7 +306,7 @@ impl<const OUTPUT_ENC: u8> Inner<OUTPUT_ENC> {
                     // ensure buffer is left in correct state in the case where one
                     // or more successful decodings is followed by a partial one, i.e.
                     // `decode_record_ref` returning `Ok(None)`
-                    read_position = decoder.get_ref().position() as usize;
+                    read_position = decoder.get_ref().position() as usize - decoder.buffer().len();
                 }
                 Ok(None) => {
                     break;
@@ -325,7 +349,7 @@ impl<const OUTPUT_ENC: u8> Inner<OUTPUT_ENC> {
                     // ensure buffer is left in correct state in the case where one
                     // or more successful decodings is followed by a partial one, i.e.
                     // `decode_record_ref` returning `Ok(None)`
-                    read_position = decoder.get_ref().position() as usize;
+                    read_position = decoder.get_ref().position() as usize - decoder.buffer().len();
                 }
                 Ok(None) => {
                     break;
@@ -358,7 +382,9 @@ impl<const OUTPUT_ENC: u8> Inner<OUTPUT_ENC> {
                             .map_err(to_val_err)?;
                     // CSV or JSON
                     } else if self.map_symbols {
-                        if metadata.schema.is_some() {
+                        // Check for mappings rather than the schema because historical
+                        // data can also contain mixed schemas
+                        if !metadata.mappings.is_empty() || !self.symbol_map.is_empty() {
                             // historical
                             // only read from metadata mappings if symbol_map is unpopulated,
                             // i.e. no `symbol_map` was passed in
@@ -411,6 +437,43 @@ impl<const OUTPUT_ENC: u8> Inner<OUTPUT_ENC> {
     }
 }
 
+/// Where transcoded output is written: either a Python file-like object or an
+/// internal buffer drained with `read()`.
+enum Output {
+    // wrap in buffered writer to minimize calls to Python
+    File(BufWriter<PyFileLike>),
+    Buffer(Vec<u8>),
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self::Buffer(Vec::new())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::File(file) => file.write(buf),
+            Output::Buffer(buffer) => buffer.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Output::File(file) => file.write_all(buf),
+            Output::Buffer(buffer) => buffer.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::File(file) => file.flush(),
+            Output::Buffer(_) => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug)]
 enum SymbolMap {
     Historical(TsSymbolMap),
@@ -485,7 +548,7 @@ mod tests {
         let output_buf = file.inner();
         let mut target = Python::with_gil(|py| {
             Transcoder::new(
-                Py::new(py, file).unwrap().extract(py).unwrap(),
+                Some(Py::new(py, file).unwrap().extract(py).unwrap()),
                 Encoding::Json,
                 Compression::None,
                 None,
@@ -560,7 +623,7 @@ mod tests {
         let output_buf = file.inner();
         let mut transcoder = Python::with_gil(|py| {
             Transcoder::new(
-                Py::new(py, file).unwrap().extract(py).unwrap(),
+                Some(Py::new(py, file).unwrap().extract(py).unwrap()),
                 Encoding::Csv,
                 Compression::None,
                 None,
@@ -634,7 +697,7 @@ mod tests {
         let output_buf = file.inner();
         let mut transcoder = Python::with_gil(|py| {
             Transcoder::new(
-                Py::new(py, file).unwrap().extract(py).unwrap(),
+                Some(Py::new(py, file).unwrap().extract(py).unwrap()),
                 encoding,
                 Compression::None,
                 None,
@@ -760,7 +823,7 @@ mod tests {
         let output_buf = file.inner();
         let mut transcoder = Python::with_gil(|py| {
             Transcoder::new(
-                Py::new(py, file).unwrap().extract(py).unwrap(),
+                Some(Py::new(py, file).unwrap().extract(py).unwrap()),
                 encoding,
                 Compression::None,
                 None,
@@ -893,7 +956,7 @@ mod tests {
         let output_buf = file.inner();
         let mut transcoder = Python::with_gil(|py| {
             Transcoder::new(
-                Py::new(py, file).unwrap().extract(py).unwrap(),
+                Some(Py::new(py, file).unwrap().extract(py).unwrap()),
                 encoding,
                 Compression::None,
                 None,
@@ -923,4 +986,72 @@ mod tests {
             assert!(!lines[2].ends_with(','));
         }
     }
+
+    #[rstest]
+    #[case::csv(Encoding::Csv)]
+    #[case::json(Encoding::Json)]
+    fn test_read_without_file(#[case] encoding: Encoding) {
+        setup();
+
+        let mut input = Vec::new();
+        let mut input_file = std::fs::File::open(format!("{TEST_DATA_PATH}/test_data.trades.dbn"))
+            .map_err(|e| dbn::Error::io(e, "opening file"))
+            .unwrap();
+        input_file.read_to_end(&mut input).unwrap();
+        let mut transcoder = Transcoder::new(
+            None,
+            encoding,
+            Compression::None,
+            None,
+            None,
+            Some(false),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let metadata_len = input.len() - 2 * std::mem::size_of::<dbn::TradeMsg>();
+        transcoder.write(&input[..metadata_len + 4]).unwrap();
+        let first = Python::with_gil(|py| transcoder.read(py).unwrap().as_bytes().to_vec());
+        if encoding == Encoding::Csv {
+            // header only
+            assert_eq!(std::str::from_utf8(&first).unwrap().lines().count(), 1);
+        } else {
+            assert!(first.is_empty());
+        }
+        transcoder.write(&input[metadata_len + 4..]).unwrap();
+        transcoder.flush().unwrap();
+        let second = Python::with_gil(|py| transcoder.read(py).unwrap().as_bytes().to_vec());
+        assert_eq!(std::str::from_utf8(&second).unwrap().lines().count(), 2);
+        // output is drained by each read
+        let third = Python::with_gil(|py| transcoder.read(py).unwrap().as_bytes().to_vec());
+        assert!(third.is_empty());
+    }
+
+    #[test]
+    fn test_read_with_file_errors() {
+        setup();
+        let file = MockPyFile::new();
+        let mut transcoder = Python::with_gil(|py| {
+            Transcoder::new(
+                Some(Py::new(py, file).unwrap().extract(py).unwrap()),
+                Encoding::Json,
+                Compression::None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+        });
+        Python::with_gil(|py| assert!(transcoder.read(py).is_err()));
+    }
 }
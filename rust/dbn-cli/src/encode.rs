@@ -1,15 +1,29 @@
-use std::io;
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, Write},
+    iter,
+    num::{NonZeroU64, NonZeroUsize},
+    path::{Path, PathBuf},
+    thread,
+};
+
+use anyhow::anyhow;
 
 use dbn::{
+    aggregate::{TradeSummary, VwapAggregator},
     decode::{DbnMetadata, DecodeRecordRef},
     encode::{
-        json, DbnEncodable, DbnRecordEncoder, DynEncoder, DynWriter, EncodeDbn, EncodeRecordRef,
-        EncodeRecordTextExt,
+        json, DbnEncodable, DbnRecordEncoder, DynEncoder, DynWriter, EncodeDbn, EncodeRecord,
+        EncodeRecordRef, EncodeRecordTextExt,
     },
-    rtype_dispatch, Compression, Encoding, MetadataBuilder, SType, SymbolIndex,
+    pretty, rtype_dispatch,
+    validate::{SequenceChecker, SequenceGrouping},
+    Compression, Encoding, MboMsg, MetadataBuilder, PitSymbolMap, RecordRef, SType, SymbolIndex,
+    TradeMsg,
 };
 
-use crate::{infer_encoding, output_from_args, Args};
+use crate::{infer_encoding, output_from_args, Args, OUTPUT_BUFFER_SIZE, STDIN_SENTINEL};
 
 pub fn silence_broken_pipe(err: anyhow::Error) -> anyhow::Result<()> {
     // Handle broken pipe as a non-error.
@@ -26,6 +40,12 @@ pub fn encode_from_dbn<D>(mut decoder: D, args: &Args) -> anyhow::Result<()>
 where
     D: DecodeRecordRef + DbnMetadata,
 {
+    if let Some(interval) = args.vwap {
+        return encode_vwap_summary(decoder, interval, args);
+    }
+    if let Some(grouping) = args.check_sequence {
+        return check_sequence(decoder, grouping.into(), args);
+    }
     let writer = output_from_args(args)?;
     let (encoding, compression, delimiter) = infer_encoding(args)?;
     Ok(if args.should_output_metadata {
@@ -48,28 +68,159 @@ where
             .delimiter(delimiter)
             .all_pretty(args.should_pretty_print)
             .with_symbol(args.map_symbols)
+            .buffer_size(OUTPUT_BUFFER_SIZE)
             .build()?;
         if args.map_symbols {
-            let symbol_map = decoder.metadata().symbol_map()?;
             let ts_out = decoder.metadata().ts_out;
-            while let Some(rec) = decoder.decode_record_ref()? {
-                let sym = symbol_map.get_for_rec(&rec).map(String::as_str);
-                // Safety: ts_out is accurate because we get it from the metadata
-                unsafe {
-                    encoder.encode_ref_ts_out_with_sym(rec, ts_out, sym)?;
+            if decoder.metadata().mappings.is_empty() {
+                // Live data has no mappings in the metadata, instead they're sent as
+                // symbol mapping records
+                let mut symbol_map = PitSymbolMap::new();
+                while let Some(rec) = decoder.decode_record_ref()? {
+                    symbol_map.on_record(rec)?;
+                    encode_with_sym(&mut encoder, rec, ts_out, &symbol_map)?;
+                }
+            } else {
+                let symbol_map = decoder.metadata().symbol_map()?;
+                while let Some(rec) = decoder.decode_record_ref()? {
+                    encode_with_sym(&mut encoder, rec, ts_out, &symbol_map)?;
                 }
             }
-            Ok(())
+            encoder.flush()
         } else {
             encoder.encode_decoded(decoder)
         }
     }?)
 }
 
+/// Writes a CSV summary of the VWAP, volume, and trade count of each instrument per
+/// `interval` of the trades in `decoder`.
+fn encode_vwap_summary<D>(mut decoder: D, interval: NonZeroU64, args: &Args) -> anyhow::Result<()>
+where
+    D: DecodeRecordRef,
+{
+    let compression = if args.zstd {
+        Compression::ZStd
+    } else {
+        Compression::None
+    };
+    let delimiter = if args.tsv { '\t' } else { ',' };
+    let mut writer =
+        DynWriter::with_buffer_size(output_from_args(args)?, compression, OUTPUT_BUFFER_SIZE)?;
+    let io_err = |e| dbn::Error::io(e, "writing VWAP summary");
+    writeln!(
+        writer,
+        "{}",
+        [
+            "ts_start",
+            "ts_end",
+            "instrument_id",
+            "publisher_id",
+            "vwap",
+            "volume",
+            "trade_count"
+        ]
+        .join(&delimiter.to_string())
+    )
+    .map_err(io_err)?;
+    let mut aggregator = VwapAggregator::new(interval, 0);
+    loop {
+        let is_exhausted = match decoder.decode_record_ref()? {
+            Some(rec) => {
+                if let Some(trade) = rec.get::<TradeMsg>() {
+                    aggregator.push_trade(trade);
+                } else if let Some(mbo) = rec.get::<MboMsg>() {
+                    aggregator.push_mbo(mbo);
+                }
+                false
+            }
+            None => {
+                aggregator.finish();
+                true
+            }
+        };
+        while let Some(summary) = aggregator.pop() {
+            write_trade_summary(&mut writer, &summary, delimiter, args.should_pretty_print)
+                .map_err(io_err)?;
+        }
+        if is_exhausted {
+            break;
+        }
+    }
+    writer.flush().map_err(io_err)?;
+    Ok(())
+}
+
+fn write_trade_summary(
+    writer: &mut impl io::Write,
+    summary: &TradeSummary,
+    delimiter: char,
+    pretty: bool,
+) -> io::Result<()> {
+    let (ts_start, ts_end, vwap) = if pretty {
+        (
+            pretty::fmt_ts(summary.ts_start),
+            pretty::fmt_ts(summary.ts_end),
+            pretty::fmt_px(summary.vwap()),
+        )
+    } else {
+        (
+            summary.ts_start.to_string(),
+            summary.ts_end.to_string(),
+            summary.vwap().to_string(),
+        )
+    };
+    writeln!(
+        writer,
+        "{ts_start}{delimiter}{ts_end}{delimiter}{}{delimiter}{}{delimiter}{vwap}{delimiter}{}{delimiter}{}",
+        summary.instrument_id, summary.publisher_id, summary.volume, summary.trade_count
+    )
+}
+
+/// Writes a line for each sequence problem found in the records of `decoder` followed
+/// by a summary. Returns an error if any problems were found.
+fn check_sequence<D>(mut decoder: D, grouping: SequenceGrouping, args: &Args) -> anyhow::Result<()>
+where
+    D: DecodeRecordRef,
+{
+    let mut writer = io::BufWriter::with_capacity(OUTPUT_BUFFER_SIZE, output_from_args(args)?);
+    let io_err = |e| dbn::Error::io(e, "writing sequence report");
+    let mut checker = SequenceChecker::new(grouping);
+    while let Some(rec) = decoder.decode_record_ref()? {
+        for issue in checker.check(rec) {
+            writeln!(writer, "{issue}").map_err(io_err)?;
+        }
+    }
+    let report = checker.report();
+    writeln!(
+        writer,
+        "Checked {} records: {} gaps with {} missing sequence numbers, {} reorders, {} duplicates, {} ts_recv reorders",
+        report.record_count,
+        report.gap_count,
+        report.missing_count,
+        report.reorder_count,
+        report.duplicate_count,
+        report.ts_recv_reorder_count
+    )
+    .map_err(io_err)?;
+    writer.flush().map_err(io_err)?;
+    if report.is_valid() {
+        Ok(())
+    } else {
+        Err(anyhow!("Found problems with the sequence of records"))
+    }
+}
+
 pub fn encode_from_frag<D>(mut decoder: D, args: &Args) -> anyhow::Result<()>
 where
     D: DecodeRecordRef,
 {
+    if let Some(interval) = args.vwap {
+        return encode_vwap_summary(decoder, interval, args);
+    }
+    if let Some(grouping) = args.check_sequence {
+        return check_sequence(decoder, grouping.into(), args);
+    }
     let writer = output_from_args(args)?;
     let (encoding, compression, delimiter) = infer_encoding(args)?;
     if args.fragment {
@@ -95,6 +246,7 @@ where
     // Can't write header until we know the record type
     .write_header(false)
     .all_pretty(args.should_pretty_print)
+    .buffer_size(OUTPUT_BUFFER_SIZE)
     .build()?;
     let mut has_written_header = encoding != Encoding::Csv;
     fn write_header<T: DbnEncodable>(
@@ -113,14 +265,127 @@ where
     Ok(())
 }
 
+/// Converts the input and additional inputs of `args` concurrently, saving the result
+/// for each to `output_dir`. Errors are reported for each input that fails to
+/// convert.
+pub fn encode_to_dir(args: &Args, output_dir: &Path) -> anyhow::Result<()> {
+    let (encoding, compression, delimiter) = infer_encoding(args)?;
+    let inputs: Vec<&PathBuf> = iter::once(&args.input)
+        .chain(&args.additional_inputs)
+        .collect();
+    let output_path =
+        |input: &Path| output_dir.join(output_file_name(input, encoding, compression, delimiter));
+    let mut outputs = HashSet::new();
+    for input in inputs.iter() {
+        if input.as_os_str() == STDIN_SENTINEL {
+            return Err(anyhow!("Can't read from standard input with --output-dir"));
+        }
+        let output = output_path(input);
+        if output.exists()
+            && (!args.force || fs::canonicalize(&output).ok() == fs::canonicalize(input).ok())
+        {
+            return Err(anyhow!(
+                "Output file '{}' exists. Pass --force flag to overwrite the existing file.",
+                output.display()
+            ));
+        }
+        if !outputs.insert(output.clone()) {
+            return Err(anyhow!(
+                "Multiple inputs would be saved to output file '{}'",
+                output.display()
+            ));
+        }
+    }
+    let threads = args
+        .threads
+        .or_else(|| thread::available_parallelism().ok())
+        .unwrap_or(NonZeroUsize::MIN);
+    let results = dbn::transcode::parallel(
+        &inputs,
+        |input, metadata| {
+            let output = output_path(input);
+            let file = File::create(&output).map_err(|e| {
+                dbn::Error::io(e, format!("opening output file '{}'", output.display()))
+            })?;
+            DynEncoder::builder(file, encoding, compression, metadata)
+                .delimiter(delimiter)
+                .all_pretty(args.should_pretty_print)
+                .buffer_size(OUTPUT_BUFFER_SIZE)
+                .build()
+        },
+        threads,
+        args.upgrade_policy(),
+    );
+    let mut failure_count = 0;
+    for (input, res) in inputs.iter().zip(results) {
+        if let Err(err) = res {
+            eprintln!("Failed to convert '{}': {err}", input.display());
+            failure_count += 1;
+        }
+    }
+    if failure_count > 0 {
+        Err(anyhow!(
+            "Failed to convert {failure_count} of {} files",
+            inputs.len()
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the name of the input file with its DBN and compression extensions replaced
+/// with those of the output, e.g. `20240102.mbo.dbn.zst` becomes `20240102.mbo.csv`.
+fn output_file_name(
+    input: &Path,
+    encoding: Encoding,
+    compression: Compression,
+    delimiter: u8,
+) -> String {
+    let input_name = input
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let stem = input_name.strip_suffix(".zst").unwrap_or(&input_name);
+    let stem = stem
+        .strip_suffix(".dbn")
+        .or_else(|| stem.strip_suffix(".dbz"))
+        .unwrap_or(stem);
+    let extension = match encoding {
+        Encoding::Dbn => "dbn",
+        Encoding::Csv if delimiter == b'\t' => "tsv",
+        Encoding::Csv => "csv",
+        Encoding::Json => "json",
+    };
+    if compression == Compression::ZStd {
+        format!("{stem}.{extension}.zst")
+    } else {
+        format!("{stem}.{extension}")
+    }
+}
+
+fn encode_with_sym(
+    encoder: &mut DynEncoder<Box<dyn io::Write>>,
+    rec: RecordRef,
+    ts_out: bool,
+    symbol_map: &impl SymbolIndex,
+) -> dbn::Result<()> {
+    let sym = symbol_map.get_for_rec(&rec).map(String::as_str);
+    // Safety: ts_out is accurate because we get it from the metadata
+    unsafe { encoder.encode_ref_ts_out_with_sym(rec, ts_out, sym) }
+}
+
 fn encode_fragment<D: DecodeRecordRef>(
     mut decoder: D,
     writer: Box<dyn io::Write>,
     compression: Compression,
 ) -> dbn::Result<()> {
-    let mut encoder = DbnRecordEncoder::new(DynWriter::new(writer, compression)?);
+    let mut encoder = DbnRecordEncoder::new(DynWriter::with_buffer_size(
+        writer,
+        compression,
+        OUTPUT_BUFFER_SIZE,
+    )?);
     while let Some(record) = decoder.decode_record_ref()? {
         encoder.encode_record_ref(record)?;
     }
-    Ok(())
+    encoder.flush()
 }
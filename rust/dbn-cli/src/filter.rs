@@ -1,7 +1,9 @@
-use std::num::NonZeroU64;
+use std::num::{NonZeroU64, NonZeroUsize};
 
 use dbn::{
+    aggregate::{EmptyBuckets, OhlcvAggregator, OhlcvDecoder},
     decode::{DbnMetadata, DecodeRecordRef},
+    dedup::DedupDecoder,
     RType, Record, RecordRef, Schema,
 };
 
@@ -61,6 +63,194 @@ impl<D: DecodeRecordRef> DecodeRecordRef for SchemaFilter<D> {
     }
 }
 
+/// Filters records by the instrument ID in their header and their index timestamp,
+/// without decoding the rest of the record.
+#[derive(Debug)]
+pub struct HeaderFilter<D> {
+    decoder: D,
+    instrument_ids: Vec<u32>,
+    start: Option<u64>,
+    end: Option<u64>,
+}
+
+impl<D> HeaderFilter<D>
+where
+    D: DbnMetadata,
+{
+    pub fn new(
+        mut decoder: D,
+        instrument_ids: Vec<u32>,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> dbn::Result<Self> {
+        if start.is_some() || end.is_some() {
+            let metadata = decoder.metadata_mut();
+            let is_open_ended = metadata.end.is_none() && end.is_none();
+            let restrict_end = end
+                .and_then(NonZeroU64::new)
+                .or(metadata.end)
+                .unwrap_or(NonZeroU64::MAX);
+            metadata.restrict(start.unwrap_or(0), restrict_end)?;
+            if is_open_ended {
+                metadata.end = None;
+            }
+        }
+        Ok(Self::new_no_metadata(decoder, instrument_ids, start, end))
+    }
+}
+
+impl<D> HeaderFilter<D> {
+    pub fn new_no_metadata(
+        decoder: D,
+        instrument_ids: Vec<u32>,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Self {
+        Self {
+            decoder,
+            instrument_ids,
+            start,
+            end,
+        }
+    }
+
+    fn matches(&self, record: RecordRef) -> bool {
+        let index_ts = record.raw_index_ts();
+        (self.instrument_ids.is_empty()
+            || self.instrument_ids.contains(&record.header().instrument_id))
+            && self.start.map_or(true, |start| index_ts >= start)
+            && self.end.map_or(true, |end| index_ts < end)
+    }
+}
+
+impl<D: DbnMetadata> DbnMetadata for HeaderFilter<D> {
+    fn metadata(&self) -> &dbn::Metadata {
+        self.decoder.metadata()
+    }
+
+    fn metadata_mut(&mut self) -> &mut dbn::Metadata {
+        self.decoder.metadata_mut()
+    }
+}
+
+impl<D: DecodeRecordRef> DecodeRecordRef for HeaderFilter<D> {
+    fn decode_record_ref(&mut self) -> dbn::Result<Option<RecordRef>> {
+        while let Some(record) = self.decoder.decode_record_ref()? {
+            if self.matches(record) {
+                // Safe: casting reference to pointer so the pointer will always be valid.
+                // Getting around borrow checker limitation.
+                return Ok(Some(unsafe {
+                    RecordRef::unchecked_from_header(record.header())
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Skips duplicate records when a window is specified, otherwise passes through all
+/// records.
+#[derive(Debug)]
+pub enum DedupFilter<D> {
+    Passthrough(D),
+    Dedup(DedupDecoder<D>),
+}
+
+impl<D> DedupFilter<D> {
+    pub fn new(decoder: D, window: Option<NonZeroUsize>) -> Self {
+        match window {
+            Some(window) => Self::Dedup(DedupDecoder::new(decoder, window)),
+            None => Self::Passthrough(decoder),
+        }
+    }
+}
+
+impl<D: DbnMetadata> DbnMetadata for DedupFilter<D> {
+    fn metadata(&self) -> &dbn::Metadata {
+        match self {
+            Self::Passthrough(decoder) => decoder.metadata(),
+            Self::Dedup(decoder) => decoder.metadata(),
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut dbn::Metadata {
+        match self {
+            Self::Passthrough(decoder) => decoder.metadata_mut(),
+            Self::Dedup(decoder) => decoder.metadata_mut(),
+        }
+    }
+}
+
+impl<D: DecodeRecordRef> DecodeRecordRef for DedupFilter<D> {
+    fn decode_record_ref(&mut self) -> dbn::Result<Option<RecordRef>> {
+        match self {
+            Self::Passthrough(decoder) => decoder.decode_record_ref(),
+            Self::Dedup(decoder) => decoder.decode_record_ref(),
+        }
+    }
+}
+
+/// Aggregates trades into OHLCV bars when an interval is specified, otherwise passes
+/// through all records.
+#[derive(Debug)]
+pub enum BarFilter<D> {
+    Passthrough(D),
+    Bars(OhlcvDecoder<D>),
+}
+
+impl<D> BarFilter<D>
+where
+    D: DbnMetadata,
+{
+    pub fn new(decoder: D, interval: Option<NonZeroU64>) -> dbn::Result<Self> {
+        Ok(match interval {
+            Some(interval) => Self::Bars(OhlcvDecoder::new(decoder, Self::aggregator(interval)?)),
+            None => Self::Passthrough(decoder),
+        })
+    }
+}
+
+impl<D> BarFilter<D> {
+    pub fn new_no_metadata(decoder: D, interval: Option<NonZeroU64>) -> dbn::Result<Self> {
+        Ok(match interval {
+            Some(interval) => Self::Bars(OhlcvDecoder::new_no_metadata(
+                decoder,
+                Self::aggregator(interval)?,
+            )),
+            None => Self::Passthrough(decoder),
+        })
+    }
+
+    fn aggregator(interval: NonZeroU64) -> dbn::Result<OhlcvAggregator> {
+        OhlcvAggregator::new(interval, 0, EmptyBuckets::Skip)
+    }
+}
+
+impl<D: DbnMetadata> DbnMetadata for BarFilter<D> {
+    fn metadata(&self) -> &dbn::Metadata {
+        match self {
+            Self::Passthrough(decoder) => decoder.metadata(),
+            Self::Bars(decoder) => decoder.metadata(),
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut dbn::Metadata {
+        match self {
+            Self::Passthrough(decoder) => decoder.metadata_mut(),
+            Self::Bars(decoder) => decoder.metadata_mut(),
+        }
+    }
+}
+
+impl<D: DecodeRecordRef> DecodeRecordRef for BarFilter<D> {
+    fn decode_record_ref(&mut self) -> dbn::Result<Option<RecordRef>> {
+        match self {
+            Self::Passthrough(decoder) => decoder.decode_record_ref(),
+            Self::Bars(decoder) => decoder.decode_record_ref(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct LimitFilter<D> {
     decoder: D,
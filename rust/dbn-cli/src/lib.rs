@@ -1,7 +1,7 @@
 use std::{
     fs::File,
-    io::{self, BufWriter},
-    num::NonZeroU64,
+    io,
+    num::{NonZeroU64, NonZeroUsize},
     path::PathBuf,
 };
 
@@ -10,7 +10,7 @@ use clap::{ArgAction, Parser, ValueEnum};
 
 use dbn::{
     enums::{Compression, Encoding},
-    Schema, VersionUpgradePolicy,
+    validate, Schema, VersionUpgradePolicy,
 };
 
 pub mod encode;
@@ -28,6 +28,24 @@ pub enum OutputEncoding {
     DbnFragment,
 }
 
+/// How `--check-sequence` groups records into streams with their own sequence.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SequenceGrouping {
+    /// Each instrument of each publisher has its own sequence
+    Instrument,
+    /// Each channel of each publisher has its own sequence
+    Channel,
+}
+
+impl From<SequenceGrouping> for validate::SequenceGrouping {
+    fn from(grouping: SequenceGrouping) -> Self {
+        match grouping {
+            SequenceGrouping::Instrument => Self::Instrument,
+            SequenceGrouping::Channel => Self::Channel,
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 #[clap(version, about)]
 #[cfg_attr(test, derive(Default))]
@@ -37,6 +55,12 @@ pub struct Args {
         value_name = "FILE"
     )]
     pub input: PathBuf,
+    #[clap(
+        help = "Additional DBN files to convert concurrently with --output-dir or to merge with --merge",
+        value_name = "FILE",
+        requires = "multiple_inputs"
+    )]
+    pub additional_inputs: Vec<PathBuf>,
     #[clap(
         short,
         long,
@@ -44,6 +68,30 @@ pub struct Args {
         value_name = "FILE"
     )]
     pub output: Option<PathBuf>,
+    #[clap(
+        long = "output-dir",
+        value_name = "DIR",
+        group = "multiple_inputs",
+        conflicts_with_all = ["output", "should_output_metadata", "fragment", "input_fragment", "map_symbols", "limit", "schema_filter", "instrument_ids", "start", "end", "bars", "vwap", "check_sequence", "dedup"],
+        help = "Saves the result for each input FILE to DIR, named after the input with the extension of the output encoding. Multiple files are converted concurrently. Requires an explicit output encoding"
+    )]
+    pub output_dir: Option<PathBuf>,
+    #[clap(
+        long,
+        value_name = "NUM_THREADS",
+        requires = "output_dir",
+        help = "The maximum number of files to convert concurrently with --output-dir. Defaults to the number of available cores"
+    )]
+    pub threads: Option<NonZeroUsize>,
+    #[clap(
+        long,
+        action = ArgAction::SetTrue,
+        default_value = "false",
+        group = "multiple_inputs",
+        conflicts_with = "input_fragment",
+        help = "Merge the records of FILE and any additional files into a single output in timestamp order. The files must be from the same dataset"
+    )]
+    pub merge: bool,
     #[clap(
         short = 'J',
         long,
@@ -131,7 +179,7 @@ pub struct Args {
          action = ArgAction::SetTrue,
          default_value = "false",
          conflicts_with_all = ["input_fragment", "dbn", "fragment"],
-         help ="Use symbology mappings from the metadata to create a 'symbol' field mapping the intstrument ID to its requested symbol."
+         help ="Use symbology mappings from the metadata or symbol mapping records to create a 'symbol' field mapping the intstrument ID to its requested symbol."
     )]
     pub map_symbols: bool,
     #[clap(
@@ -141,6 +189,14 @@ pub struct Args {
         help = "Limit the number of records in the output to the specified number"
     )]
     pub limit: Option<NonZeroU64>,
+    #[clap(
+        long = "dedup",
+        value_name = "WINDOW",
+        num_args = 0..=1,
+        default_missing_value = "100000",
+        help = "Skip records identical to one of the last WINDOW distinct records, e.g. from overlapping captures. WINDOW defaults to 100000"
+    )]
+    pub dedup: Option<NonZeroUsize>,
     // Fragment arguments
     #[clap(
         long = "input-fragment",
@@ -174,6 +230,52 @@ pub struct Args {
         value_name = "SCHEMA"
     )]
     pub schema_filter: Option<Schema>,
+    #[clap(
+        long = "instrument-id",
+        value_name = "ID",
+        action = ArgAction::Append,
+        help = "Only encode records with this instrument ID. Can be passed multiple times to keep several instruments"
+    )]
+    pub instrument_ids: Vec<u32>,
+    #[clap(
+        long = "start",
+        value_name = "TIMESTAMP",
+        value_parser = parse_ts,
+        help = "Only encode records with an index timestamp at or after this time, either as nanoseconds since the UNIX epoch or like 2020-12-28T13:00:00.000000000Z"
+    )]
+    pub start: Option<u64>,
+    #[clap(
+        long = "end",
+        value_name = "TIMESTAMP",
+        value_parser = parse_ts,
+        help = "Only encode records with an index timestamp before this time, either as nanoseconds since the UNIX epoch or like 2020-12-28T13:00:00.000000000Z"
+    )]
+    pub end: Option<u64>,
+    #[clap(
+        long = "bars",
+        value_name = "INTERVAL",
+        value_parser = parse_bar_interval,
+        help = "Aggregate trades into OHLCV bars of this interval, like 30s, 5m, 4h, or 1d. Bars are aligned to UTC midnight and other records are ignored"
+    )]
+    pub bars: Option<NonZeroU64>,
+    #[clap(
+        long = "vwap",
+        value_name = "INTERVAL",
+        value_parser = parse_bar_interval,
+        conflicts_with_all = ["json", "dbn", "fragment", "should_output_metadata", "map_symbols", "bars"],
+        help = "Summarize the VWAP, volume, and trade count of each instrument over intervals of this length, like 30s, 5m, 4h, or 1d, as CSV. Summarizes trade and MBO records and ignores other records"
+    )]
+    pub vwap: Option<NonZeroU64>,
+    #[clap(
+        long = "check-sequence",
+        value_name = "GROUPING",
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "instrument",
+        conflicts_with_all = ["json", "csv", "tsv", "dbn", "fragment", "should_output_metadata", "map_symbols", "bars", "vwap"],
+        help = "Instead of transcoding, report gaps, reorders, and duplicates in the sequence numbers and decreases in the ts_recv of each instrument or channel. Fails if any are found"
+    )]
+    pub check_sequence: Option<SequenceGrouping>,
 }
 
 impl Args {
@@ -207,6 +309,31 @@ impl Args {
     }
 }
 
+fn parse_ts(s: &str) -> Result<u64, String> {
+    dbn::pretty::parse_ts(s).map_err(|e| e.to_string())
+}
+
+fn parse_bar_interval(s: &str) -> Result<NonZeroU64, String> {
+    const NANOS_PER_SEC: u64 = 1_000_000_000;
+    let err = || format!("invalid interval '{s}', expected a number followed by s, m, h, or d");
+    let unit_secs = match s.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 3_600,
+        Some('d') => 86_400,
+        _ => return Err(err()),
+    };
+    // The unit is a single byte
+    let secs = s[..s.len() - 1]
+        .parse::<u64>()
+        .map_err(|_| err())?
+        .saturating_mul(unit_secs);
+    if !(1..=86_400).contains(&secs) {
+        return Err(format!("interval '{s}' must be from 1s to 1d"));
+    }
+    Ok(NonZeroU64::new(secs * NANOS_PER_SEC).unwrap())
+}
+
 /// Infer the [`Encoding`], [`Compression`], and delimiter (CSV/TSV) from `args` if they
 /// aren't already explicitly set.
 pub fn infer_encoding(args: &Args) -> anyhow::Result<(Encoding, Compression, u8)> {
@@ -252,11 +379,18 @@ pub fn infer_encoding(args: &Args) -> anyhow::Result<(Encoding, Compression, u8)
     }
 }
 
-/// Returns a writeable object where the `dbn` output will be directed.
+/// The input path that denotes reading from standard input.
+pub const STDIN_SENTINEL: &str = "-";
+
+/// The size in bytes of the buffer for the `dbn` output.
+pub const OUTPUT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Returns a writeable object where the `dbn` output will be directed. The output is
+/// unbuffered, the encoders buffer it with [`OUTPUT_BUFFER_SIZE`].
 pub fn output_from_args(args: &Args) -> anyhow::Result<Box<dyn io::Write>> {
     if let Some(output) = &args.output {
         let output_file = open_output_file(output, args.force)?;
-        Ok(Box::new(BufWriter::new(output_file)))
+        Ok(Box::new(output_file))
     } else {
         Ok(Box::new(io::stdout().lock()))
     }
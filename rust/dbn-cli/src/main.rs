@@ -4,37 +4,82 @@ use std::{
 };
 
 use clap::Parser;
-use dbn::decode::{DbnMetadata, DbnRecordDecoder, DecodeRecordRef, DynDecoder};
+use dbn::{
+    decode::{DbnMetadata, DbnRecordDecoder, DecodeRecordRef, DynDecoder},
+    merge::MergeDecoder,
+};
 use dbn_cli::{
-    encode::{encode_from_dbn, encode_from_frag, silence_broken_pipe},
-    filter::{LimitFilter, SchemaFilter},
-    Args,
+    encode::{encode_from_dbn, encode_from_frag, encode_to_dir, silence_broken_pipe},
+    filter::{BarFilter, DedupFilter, HeaderFilter, LimitFilter, SchemaFilter},
+    Args, STDIN_SENTINEL,
 };
 
-const STDIN_SENTINEL: &str = "-";
-
 fn wrap_frag(args: &Args, reader: impl io::Read) -> anyhow::Result<impl DecodeRecordRef> {
     // assume no ts_out for fragments
     const TS_OUT: bool = false;
     Ok(LimitFilter::new_no_metadata(
-        SchemaFilter::new_no_metadata(
-            DbnRecordDecoder::with_version(
-                reader,
-                args.input_version(),
-                args.upgrade_policy(),
-                TS_OUT,
+        BarFilter::new_no_metadata(
+            HeaderFilter::new_no_metadata(
+                SchemaFilter::new_no_metadata(
+                    DedupFilter::new(
+                        DbnRecordDecoder::with_version(
+                            reader,
+                            args.input_version(),
+                            args.upgrade_policy(),
+                            TS_OUT,
+                        )?,
+                        args.dedup,
+                    ),
+                    args.schema_filter,
+                ),
+                args.instrument_ids.clone(),
+                args.start,
+                args.end,
+            )?,
+            args.bars,
+        )?,
+        args.limit,
+    ))
+}
+
+fn wrap<D: DecodeRecordRef + DbnMetadata>(
+    args: &Args,
+    decoder: D,
+) -> anyhow::Result<impl DecodeRecordRef + DbnMetadata> {
+    Ok(LimitFilter::new(
+        BarFilter::new(
+            HeaderFilter::new(
+                SchemaFilter::new(DedupFilter::new(decoder, args.dedup), args.schema_filter),
+                args.instrument_ids.clone(),
+                args.start,
+                args.end,
             )?,
-            args.schema_filter,
-        ),
+            args.bars,
+        )?,
         args.limit,
     ))
 }
 
-fn wrap<R: io::BufRead>(
+fn merge_inputs(
     args: &Args,
-    decoder: DynDecoder<'static, R>,
-) -> impl DecodeRecordRef + DbnMetadata {
-    LimitFilter::new(SchemaFilter::new(decoder, args.schema_filter), args.limit)
+) -> anyhow::Result<MergeDecoder<DynDecoder<'static, Box<dyn io::BufRead>>>> {
+    let decoders = std::iter::once(&args.input)
+        .chain(&args.additional_inputs)
+        .map(|input| {
+            let reader: Box<dyn io::BufRead> = if input.as_os_str() == STDIN_SENTINEL {
+                Box::new(io::stdin().lock())
+            } else {
+                Box::new(BufReader::new(File::open(input).map_err(|e| {
+                    dbn::Error::io(e, format!("opening {}", input.display()))
+                })?))
+            };
+            Ok(DynDecoder::inferred_with_buffer(
+                reader,
+                args.upgrade_policy(),
+            )?)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(MergeDecoder::new(decoders)?)
 }
 
 fn main() -> anyhow::Result<()> {
@@ -43,6 +88,12 @@ fn main() -> anyhow::Result<()> {
 
 fn main_impl() -> anyhow::Result<()> {
     let args = Args::parse();
+    if let Some(output_dir) = &args.output_dir {
+        return encode_to_dir(&args, output_dir);
+    }
+    if args.merge {
+        return encode_from_dbn(wrap(&args, merge_inputs(&args)?)?, &args);
+    }
     // DBN fragment
     if args.is_input_fragment {
         if args.input.as_os_str() == STDIN_SENTINEL {
@@ -78,7 +129,7 @@ fn main_impl() -> anyhow::Result<()> {
             wrap(
                 &args,
                 DynDecoder::inferred_with_buffer(io::stdin().lock(), args.upgrade_policy())?,
-            ),
+            )?,
             &args,
         )
     } else {
@@ -86,7 +137,7 @@ fn main_impl() -> anyhow::Result<()> {
             wrap(
                 &args,
                 DynDecoder::from_file(&args.input, args.upgrade_policy())?,
-            ),
+            )?,
             &args,
         )
     }
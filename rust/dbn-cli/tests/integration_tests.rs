@@ -5,7 +5,11 @@ use std::{
 };
 
 use assert_cmd::Command;
-use dbn::Schema;
+use dbn::{
+    decode::{DbnDecoder, DecodeRecord},
+    encode::{DbnEncoder, EncodeRecord},
+    rtype, MboMsg, MetadataBuilder, RecordHeader, SType, Schema, SymbolMappingMsg, UNDEF_TIMESTAMP,
+};
 use predicates::{
     boolean::PredicateBooleanExt,
     ord::eq,
@@ -630,6 +634,48 @@ fn map_symbols(#[case] output_flag: &str) {
     }
 }
 
+#[test]
+fn map_symbols_from_symbol_mapping_records() {
+    let metadata = MetadataBuilder::new()
+        .dataset("XNAS.ITCH".to_owned())
+        .schema(None)
+        .start(0)
+        .stype_in(Some(SType::RawSymbol))
+        .stype_out(SType::InstrumentId)
+        .build();
+    let mut input = Vec::new();
+    let mut encoder = DbnEncoder::new(&mut input, &metadata).unwrap();
+    encoder
+        .encode_record(
+            &SymbolMappingMsg::new(
+                5,
+                0,
+                SType::RawSymbol,
+                "AAPL",
+                SType::RawSymbol,
+                "AAPL",
+                UNDEF_TIMESTAMP,
+                UNDEF_TIMESTAMP,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    encoder
+        .encode_record(&MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 5, 0),
+            ..Default::default()
+        })
+        .unwrap();
+    drop(encoder);
+    cmd()
+        .args(["-", "--json", "--map-symbols"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(contains("\"symbol\":\"AAPL\"").count(2))
+        .stderr(is_empty());
+}
+
 #[rstest]
 #[case::dbn("--dbn")]
 #[case::fragment("--fragment")]
@@ -697,6 +743,114 @@ fn passing_dbn_version_conflicts_with_non_fragment_input(#[case] output_flag: &s
         ));
 }
 
+#[test]
+fn write_to_output_dir() {
+    let output_dir = tempdir().unwrap();
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst"),
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            &format!("{TEST_DATA_PATH}/test_data.mbp-1.dbn.zst"),
+            "--output-dir",
+            output_dir.path().to_str().unwrap(),
+            "--csv",
+            "--threads",
+            "2",
+        ])
+        .assert()
+        .success()
+        .stdout(is_empty());
+    for schema in ["mbo", "trades", "mbp-1"] {
+        let contents =
+            fs::read_to_string(output_dir.path().join(format!("test_data.{schema}.csv"))).unwrap();
+        assert_eq!(contents.lines().count(), 3, "{schema}");
+    }
+    // Doesn't overwrite without --force
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst"),
+            "--output-dir",
+            output_dir.path().to_str().unwrap(),
+            "--csv",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("Output file").and(contains("exists")));
+}
+
+#[test]
+fn output_dir_reports_failed_inputs() {
+    let output_dir = tempdir().unwrap();
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst"),
+            &format!("{TEST_DATA_PATH}/nonexistent.dbn"),
+            "--output-dir",
+            output_dir.path().to_str().unwrap(),
+            "--json",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("Failed to convert").and(contains("nonexistent.dbn")))
+        .stderr(contains("Failed to convert 1 of 2 files"));
+    assert!(output_dir.path().join("test_data.mbo.json").exists());
+}
+
+#[test]
+fn multiple_inputs_require_output_dir() {
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst"),
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            "--csv",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("--output-dir"));
+}
+
+#[test]
+fn merge_inputs() {
+    let output = cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            &format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst"),
+            "--merge",
+            "--json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.lines().count(), 4);
+    let ts_recvs: Vec<u64> = output
+        .lines()
+        .map(|line| {
+            let ts_recv = line.split("\"ts_recv\":\"").nth(1).unwrap();
+            ts_recv[..ts_recv.find('"').unwrap()].parse().unwrap()
+        })
+        .collect();
+    assert!(ts_recvs.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn merge_conflicts_with_output_dir() {
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst"),
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            "--merge",
+            "--output-dir",
+            "out",
+            "--csv",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("cannot be used with"));
+}
+
 #[test]
 fn help() {
     cmd()
@@ -714,3 +868,153 @@ fn version() {
         .success()
         .stdout(contains(env!("CARGO_PKG_VERSION")));
 }
+
+#[rstest]
+#[case::instrument_id(&["--instrument-id", "5482"], 2)]
+#[case::other_instrument_id(&["--instrument-id", "1", "--instrument-id", "2"], 0)]
+#[case::start(&["--start", "1609160400000710000"], 1)]
+#[case::end(&["--end", "2020-12-28T13:00:00.000710000Z"], 1)]
+// Filters on the index timestamp `ts_recv`, not `ts_event`
+#[case::ts_event_range(&["--start", "1609160400000429831", "--end", "1609160400000431666"], 0)]
+#[case::empty_range(&["--start", "1609160400000704061", "--end", "1609160400000711344"], 0)]
+fn header_filters(#[case] filter_args: &[&str], #[case] exp_count: usize) {
+    let output = cmd()
+        .args([&format!("{TEST_DATA_PATH}/test_data.mbo.dbn"), "--dbn"])
+        .args(filter_args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let input = fs::read(format!("{TEST_DATA_PATH}/test_data.mbo.dbn")).unwrap();
+    let expected = DbnDecoder::new(input.as_slice())
+        .unwrap()
+        .decode_records::<MboMsg>()
+        .unwrap();
+    let records = DbnDecoder::new(output.as_slice())
+        .unwrap()
+        .decode_records::<MboMsg>()
+        .unwrap();
+    assert_eq!(records.len(), exp_count);
+    // Filtered records are copied unchanged
+    for rec in records {
+        assert!(expected.contains(&rec));
+    }
+}
+
+#[test]
+fn aggregate_trades_into_bars() {
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            "--json",
+            "--bars",
+            "1d",
+        ])
+        .assert()
+        .success()
+        .stdout(contains(r#""rtype":35"#).and(contains(r#""volume":"#)))
+        .stdout(is_match(r"\A[^\n]*\n\z").unwrap())
+        .stderr(is_empty());
+}
+
+#[rstest]
+#[case::no_unit("5", "expected a number followed by")]
+#[case::bad_unit("5x", "expected a number followed by")]
+#[case::too_long("2d", "must be from 1s to 1d")]
+#[case::zero("0s", "must be from 1s to 1d")]
+fn invalid_bar_interval(#[case] interval: &str, #[case] exp_err: &str) {
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            "--json",
+            "--bars",
+            interval,
+        ])
+        .assert()
+        .failure()
+        .stderr(contains(exp_err));
+}
+
+#[test]
+fn summarize_vwap() {
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            "--vwap",
+            "1d",
+        ])
+        .assert()
+        .success()
+        .stdout(is_match(r"\Ats_start,ts_end,instrument_id,publisher_id,vwap,volume,trade_count\n\d+,\d+,\d+,\d+,\d+,\d+,2\n\z").unwrap())
+        .stderr(is_empty());
+}
+
+#[test]
+fn vwap_conflicts_with_bars() {
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.trades.dbn"),
+            "--vwap",
+            "1d",
+            "--bars",
+            "1d",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("cannot be used with"));
+}
+
+#[rstest]
+#[case::instrument(&[])]
+#[case::channel(&["channel"])]
+fn check_sequence(#[case] grouping: &[&str]) {
+    cmd()
+        .args([
+            &format!("{TEST_DATA_PATH}/test_data.mbo.dbn"),
+            "--check-sequence",
+        ])
+        .args(grouping)
+        .assert()
+        .success()
+        .stdout(is_match(r"\AChecked 2 records: 0 gaps with 0 missing sequence numbers, 0 reorders, 0 duplicates, 0 ts_recv reorders\n\z").unwrap())
+        .stderr(is_empty());
+}
+
+#[test]
+fn check_sequence_reports_problems() {
+    let fragment = cmd()
+        .args([&format!("{TEST_DATA_PATH}/test_data.mbo.dbn"), "--fragment"])
+        .output()
+        .unwrap()
+        .stdout;
+    // Repeat the records so the sequence decreases
+    let input = [fragment.as_slice(), fragment.as_slice()].concat();
+    cmd()
+        .args(["--input-fragment", "-", "--check-sequence"])
+        .write_stdin(input)
+        .assert()
+        .failure()
+        .stdout(contains(
+            "record 2 (publisher 1 instrument 5482): sequence decreased from 1170353 to 1170352",
+        ))
+        .stdout(contains("Checked 4 records:"))
+        .stderr(contains("Found problems with the sequence of records"));
+}
+
+#[test]
+fn dedup_overlapping_captures() {
+    let fragment = cmd()
+        .args([&format!("{TEST_DATA_PATH}/test_data.mbo.dbn"), "--fragment"])
+        .output()
+        .unwrap()
+        .stdout;
+    let input = [fragment.as_slice(), fragment.as_slice()].concat();
+    cmd()
+        .args(["--input-fragment", "-", "--csv", "--dedup"])
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(is_match(r"\A[^\n]*\n([^\n]*\n){2}\z").unwrap())
+        .stderr(is_empty());
+}
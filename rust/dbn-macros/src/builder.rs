@@ -0,0 +1,132 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Field, Type};
+
+use crate::{
+    dbn_attr::{find_dbn_serialize_attr, C_CHAR_ATTR},
+    utils::crate_name,
+};
+
+pub fn derive_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let DeriveInput { ident, data, .. } = parse_macro_input!(input as DeriveInput);
+    let Data::Struct(data_struct) = data else {
+        return syn::Error::new(ident.span(), "Can only derive RecordBuilder for structs")
+            .into_compile_error()
+            .into();
+    };
+    let syn::Fields::Named(fields) = data_struct.fields else {
+        return syn::Error::new(ident.span(), "Cannot derive RecordBuilder for tuple struct")
+            .into_compile_error()
+            .into();
+    };
+    let crate_name = crate_name();
+    let builder_ident = format_ident!("{ident}Builder");
+    let builder_doc = format!(
+        "A builder for [`{ident}`] that sets the header's `rtype` and `length` \
+        automatically."
+    );
+    let setters = fields
+        .named
+        .iter()
+        .filter(|f| {
+            let name = f.ident.as_ref().unwrap().to_string();
+            name != "hd" && !name.starts_with('_')
+        })
+        .map(|f| field_setter(f, &crate_name));
+
+    quote! {
+        #[doc = #builder_doc]
+        #[derive(Clone, Debug)]
+        pub struct #builder_ident {
+            rec: #ident,
+        }
+
+        impl From<#ident> for #builder_ident {
+            fn from(rec: #ident) -> Self {
+                Self { rec }
+            }
+        }
+
+        impl #builder_ident {
+            /// Sets the publisher ID assigned by Databento, which denotes the dataset
+            /// and venue.
+            pub fn publisher_id(mut self, publisher_id: u16) -> Self {
+                self.rec.hd.publisher_id = publisher_id;
+                self
+            }
+
+            /// Sets the numeric instrument ID.
+            pub fn instrument_id(mut self, instrument_id: u32) -> Self {
+                self.rec.hd.instrument_id = instrument_id;
+                self
+            }
+
+            /// Sets the matching-engine-received timestamp expressed as the number of
+            /// nanoseconds since the UNIX epoch.
+            pub fn ts_event(mut self, ts_event: u64) -> Self {
+                self.rec.hd.ts_event = ts_event;
+                self
+            }
+
+            #(#setters)*
+
+            /// Returns the built record.
+            pub fn build(self) -> #ident {
+                self.rec
+            }
+        }
+    }
+    .into()
+}
+
+fn field_setter(field: &Field, crate_name: &TokenStream) -> TokenStream {
+    let ident = field.ident.as_ref().unwrap();
+    let f_type = &field.ty;
+    let docs = field.attrs.iter().filter(|a| a.path().is_ident("doc"));
+    let is_c_char = match find_dbn_serialize_attr(field) {
+        Ok(attr) => matches!(attr, Some(id) if id == C_CHAR_ATTR),
+        Err(e) => return e.into_compile_error(),
+    };
+    if is_c_char {
+        quote! {
+            #(#docs)*
+            pub fn #ident(mut self, #ident: impl Into<char>) -> Self {
+                self.rec.#ident = #ident.into() as std::ffi::c_char;
+                self
+            }
+        }
+    } else if is_c_char_array(f_type) {
+        let errors_doc = format!(
+            " This function returns an error if `{ident}` is too long to fit in the \
+            field, including the null terminator, or contains a null byte."
+        );
+        quote! {
+            #(#docs)*
+            ///
+            /// # Errors
+            #[doc = #errors_doc]
+            pub fn #ident(mut self, #ident: &str) -> #crate_name::Result<Self> {
+                self.rec.#ident = #crate_name::record::str_to_c_chars(#ident)?;
+                Ok(self)
+            }
+        }
+    } else {
+        quote! {
+            #(#docs)*
+            pub fn #ident(mut self, #ident: #f_type) -> Self {
+                self.rec.#ident = #ident;
+                self
+            }
+        }
+    }
+}
+
+fn is_c_char_array(f_type: &Type) -> bool {
+    let Type::Array(array) = f_type else {
+        return false;
+    };
+    matches!(
+        array.elem.as_ref(),
+        Type::Path(path) if path.path.segments.last().is_some_and(|s| s.ident == "c_char")
+    )
+}
@@ -7,6 +7,7 @@ use proc_macro2::Ident;
 use syn::{parenthesized, spanned::Spanned, token, Field, FieldsNamed, Meta};
 
 pub const C_CHAR_ATTR: &str = "c_char";
+pub const DBN_RTYPE_ATTR: &str = "dbn_rtype";
 pub const FIXED_PRICE_ATTR: &str = "fixed_price";
 pub const FMT_BINARY: &str = "fmt_binary";
 pub const FMT_METHOD: &str = "fmt_method";
@@ -8,7 +8,7 @@ use syn::{
     ExprPath, ItemStruct, Token,
 };
 
-use crate::dbn_attr::{find_dbn_attr_args, INDEX_TS_ATTR};
+use crate::dbn_attr::{find_dbn_attr_args, DBN_RTYPE_ATTR, INDEX_TS_ATTR};
 
 pub fn attribute_macro_impl(
     attr: proc_macro::TokenStream,
@@ -23,15 +23,98 @@ pub fn attribute_macro_impl(
         .into_compile_error()
         .into();
     }
+    let input_struct = parse_macro_input!(input as ItemStruct);
+    let record_impls = record_impls(&input_struct, &args.args);
+    let impl_debug = crate::debug::record_debug_impl(&input_struct);
+    quote! (
+        #input_struct
+
+        #record_impls
+
+        #impl_debug
+    )
+    .into()
+}
+
+pub fn derive_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input_struct = parse_macro_input!(input as ItemStruct);
     let record_type = &input_struct.ident;
-    let raw_index_ts = get_raw_index_ts(&input_struct).unwrap_or_else(|e| e.into_compile_error());
-    let rtypes = args.args.iter();
+    let Some(rtype_attr) = input_struct
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident(DBN_RTYPE_ATTR))
+    else {
+        return syn::Error::new(
+            record_type.span(),
+            "Need to specify at least one rtype to match against with `#[dbn_rtype(...)]`",
+        )
+        .into_compile_error()
+        .into();
+    };
+    let args = match rtype_attr.parse_args::<Args>() {
+        Ok(args) if args.args.is_empty() => {
+            return syn::Error::new(
+                rtype_attr.span(),
+                "Need to specify at least one rtype to match against",
+            )
+            .into_compile_error()
+            .into();
+        }
+        Ok(args) => args,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let syn::Fields::Named(fields) = &input_struct.fields else {
+        return syn::Error::new(
+            record_type.span(),
+            "Cannot derive DbnRecord for tuple struct",
+        )
+        .into_compile_error()
+        .into();
+    };
     let crate_name = crate::utils::crate_name();
+    let record_impls = record_impls(&input_struct, &args.args);
     let impl_debug = crate::debug::record_debug_impl(&input_struct);
+    let impl_csv = crate::serialize::csv_serialize_impl(record_type, fields.clone());
+    let impl_json = crate::serialize::json_serialize_impl(record_type, fields.clone());
+    let size_error = format!(
+        "The size of `{record_type}` must be a multiple of 4 bytes and no more than 1020 bytes"
+    );
     quote! (
-        #input_struct
+        #record_impls
+
+        #impl_debug
+
+        #impl_csv
+
+        #impl_json
+
+        impl #record_type {
+            /// The size of the record in bytes.
+            pub const RECORD_SIZE: usize = ::std::mem::size_of::<#record_type>();
+            /// The value of the `length` field of the record's header, the size of the
+            /// record in 32-bit words.
+            pub const HEADER_LENGTH: u8 =
+                (Self::RECORD_SIZE / #crate_name::record::RecordHeader::LENGTH_MULTIPLIER) as u8;
+        }
+
+        const _: () = assert!(
+            #record_type::RECORD_SIZE % #crate_name::record::RecordHeader::LENGTH_MULTIPLIER == 0
+                && #record_type::RECORD_SIZE
+                    <= u8::MAX as usize * #crate_name::record::RecordHeader::LENGTH_MULTIPLIER,
+            #size_error
+        );
+    )
+    .into()
+}
 
+/// Generates the implementations of the record traits shared between `dbn_record`
+/// and `DbnRecord`.
+fn record_impls(input_struct: &ItemStruct, rtypes: &[ExprPath]) -> TokenStream {
+    let record_type = &input_struct.ident;
+    let raw_index_ts = get_raw_index_ts(input_struct).unwrap_or_else(|e| e.into_compile_error());
+    let rtypes = rtypes.iter();
+    let crate_name = crate::utils::crate_name();
+    quote! (
         impl #crate_name::record::Record for #record_type {
             fn header(&self) -> &#crate_name::record::RecordHeader {
                 &self.hd
@@ -68,10 +151,7 @@ pub fn attribute_macro_impl(
                 }
             }
         }
-
-        #impl_debug
     )
-    .into()
 }
 
 pub(crate) struct Args {
@@ -0,0 +1,98 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, ExprLit, Field, Lit, Meta};
+
+use crate::dbn_attr::{
+    find_dbn_serialize_attr, get_sorted_fields, is_hidden, C_CHAR_ATTR, FIXED_PRICE_ATTR,
+    UNIX_NANOS_ATTR,
+};
+
+pub fn derive_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let DeriveInput { ident, data, .. } = parse_macro_input!(input as DeriveInput);
+    let Data::Struct(data_struct) = data else {
+        return syn::Error::new(ident.span(), "Can only derive DbnJsonSchema for structs")
+            .into_compile_error()
+            .into();
+    };
+    let syn::Fields::Named(fields) = data_struct.fields else {
+        return syn::Error::new(ident.span(), "Cannot derive DbnJsonSchema for tuple struct")
+            .into_compile_error()
+            .into();
+    };
+    let fields = match get_sorted_fields(fields) {
+        Ok(fields) => fields,
+        Err(ts) => {
+            return ts.into_compile_error().into();
+        }
+    };
+    let properties = fields
+        .iter()
+        .filter(|f| !is_hidden(f))
+        .map(property_token_stream);
+
+    quote! {
+        impl ::schemars::JsonSchema for #ident {
+            fn schema_name() -> String {
+                stringify!(#ident).to_owned()
+            }
+
+            fn json_schema(gen: &mut ::schemars::gen::SchemaGenerator) -> ::schemars::schema::Schema {
+                let mut obj = crate::encode::json::schema::object_schema();
+                #(#properties)*
+                obj.into()
+            }
+        }
+    }
+    .into()
+}
+
+fn property_token_stream(field: &Field) -> TokenStream {
+    let ident = field.ident.as_ref().unwrap();
+    let f_type = &field.ty;
+    let description = field_description(field);
+    let schema = match find_dbn_serialize_attr(field) {
+        Ok(Some(id)) if id == C_CHAR_ATTR => quote! {
+            crate::encode::json::schema::c_char_schema()
+        },
+        Ok(Some(id)) if id == FIXED_PRICE_ATTR => quote! {
+            crate::encode::json::schema::px_schema()
+        },
+        Ok(Some(id)) if id == UNIX_NANOS_ATTR => quote! {
+            crate::encode::json::schema::ts_schema()
+        },
+        Ok(Some(id)) => {
+            return syn::Error::new(id.span(), format!("Invalid attr `{id}` passed to `#[dbn]`"))
+                .into_compile_error()
+        }
+        Ok(None) => quote! {
+            <#f_type as crate::encode::json::schema::JsonSchemaField>::field_schema(gen)
+        },
+        Err(e) => return e.into_compile_error(),
+    };
+    quote! {
+        crate::encode::json::schema::insert_property(
+            &mut obj,
+            stringify!(#ident),
+            #description,
+            #schema,
+        );
+    }
+}
+
+/// Joins the lines of the field's doc comment.
+fn field_description(field: &Field) -> String {
+    field
+        .attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(doc), ..
+                }) => Some(doc.value().trim().to_owned()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
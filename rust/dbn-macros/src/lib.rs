@@ -1,8 +1,10 @@
 use proc_macro::TokenStream;
 
+mod builder;
 mod dbn_attr;
 mod debug;
 mod has_rtype;
+mod json_schema;
 mod py_field_desc;
 mod serialize;
 mod utils;
@@ -58,6 +60,23 @@ pub fn derive_json_serialize(input: TokenStream) -> TokenStream {
     serialize::derive_json_macro_impl(input)
 }
 
+/// Derive macro for implementing `schemars::JsonSchema` with a schema matching the
+/// output of `JsonSerialize`.
+///
+/// Supports the following `dbn` attributes:
+/// - `c_char`: the field is a single-character string or `null`
+/// - `encode_order`: overrides the position of the field in the schema properties
+/// - `fixed_price`: the field is a fixed-precision price string or `null`
+/// - `skip`: the field isn't included in the schema
+/// - `unix_nanos`: the field is a UNIX nanosecond timestamp string or `null`
+///
+/// Note: fields beginning with `_` will automatically be skipped, e.g. `_reserved`
+/// isn't included in the schema.
+#[proc_macro_derive(DbnJsonSchema, attributes(dbn))]
+pub fn derive_dbn_json_schema(input: TokenStream) -> TokenStream {
+    json_schema::derive_impl(input)
+}
+
 /// Derive macro for field descriptions exposed to Python.
 ///
 /// Supports the following `dbn` attributes:
@@ -72,6 +91,22 @@ pub fn derive_py_field_desc(input: TokenStream) -> TokenStream {
     py_field_desc::derive_impl(input)
 }
 
+/// Derive macro for a `{Record}Builder` type with a setter for each field.
+///
+/// The builder starts from an existing record, so the header's `rtype` and `length`
+/// are preserved. Supports the following `dbn` attributes:
+/// - `c_char`: the setter accepts anything convertible to a `char`
+///
+/// Fixed-length `c_char` array fields have fallible setters that take a `&str`.
+///
+/// Note: `hd` and fields beginning with `_` don't have a setter, e.g. `_reserved`.
+/// Instead setters for the header's `publisher_id`, `instrument_id`, and `ts_event`
+/// are generated.
+#[proc_macro_derive(RecordBuilder, attributes(dbn))]
+pub fn derive_record_builder(input: TokenStream) -> TokenStream {
+    builder::derive_impl(input)
+}
+
 /// Attribute macro that acts like a derive macro for `Debug` (with customization),
 /// `Record`, `RecordMut`, `HasRType`, `PartialOrd`, and `AsRef<[u8]>`.
 ///
@@ -95,6 +130,27 @@ pub fn dbn_record(attr: TokenStream, input: TokenStream) -> TokenStream {
     has_rtype::attribute_macro_impl(attr, input)
 }
 
+/// Derive macro for user-defined DBN record types. Implements `Debug` (with
+/// customization), `Record`, `RecordMut`, `HasRType`, `PartialOrd`, `AsRef<[u8]>`,
+/// `CsvSerialize`, and `JsonSerialize`, and adds `RECORD_SIZE` and `HEADER_LENGTH`
+/// associated constants.
+///
+/// The struct must be `#[repr(C)]`, begin with a `hd: RecordHeader` field, and have a
+/// size that's a multiple of 4 bytes. The RTypes associated with the record are
+/// specified with a `#[dbn_rtype(...)]` attribute containing 1 or more paths to `u8`
+/// constants. Because the generated `PartialOrd` implementation requires `PartialEq`,
+/// the struct must also implement or derive `PartialEq`.
+///
+/// Supports the same `dbn` attributes as `dbn_record`, `CsvSerialize`, and
+/// `JsonSerialize`.
+///
+/// Note: the generated CSV serialization references the `csv` crate, so it must be a
+/// dependency of the crate deriving `DbnRecord`.
+#[proc_macro_derive(DbnRecord, attributes(dbn, dbn_rtype))]
+pub fn derive_dbn_record(input: TokenStream) -> TokenStream {
+    has_rtype::derive_impl(input)
+}
+
 /// Derive macro for Debug representations with the same extensions for DBN records
 /// as `dbn_record`.
 ///
@@ -1,6 +1,6 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Field};
+use syn::{parse_macro_input, Data, DeriveInput, Field, FieldsNamed};
 
 use crate::{
     dbn_attr::{
@@ -15,40 +15,7 @@ pub fn derive_csv_macro_impl(input: proc_macro::TokenStream) -> proc_macro::Toke
 
     if let Data::Struct(data_struct) = data {
         if let syn::Fields::Named(fields) = data_struct.fields {
-            let crate_name = crate_name();
-            let fields = match get_sorted_fields(fields) {
-                Ok(fields) => fields,
-                Err(ts) => {
-                    return ts.into_compile_error().into();
-                }
-            };
-            let serialize_header_iter = fields.iter().map(write_csv_header_token_stream);
-            let serialize_fields = fields
-                .iter()
-                .map(write_csv_field_token_stream)
-                .collect::<syn::Result<Vec<_>>>()
-                .unwrap_or_else(|e| vec![syn::Error::to_compile_error(&e)]);
-            return quote! {
-                impl #crate_name::encode::csv::serialize::CsvSerialize for #ident {
-                    fn serialize_header<W: ::std::io::Write>(writer: &mut ::csv::Writer<W>) -> ::csv::Result<()> {
-                        use #crate_name::encode::csv::serialize::WriteField;
-
-                        #(#serialize_header_iter)*
-                        Ok(())
-                    }
-
-                    fn serialize_to<W: ::std::io::Write, const PRETTY_PX: bool, const PRETTY_TS: bool>(
-                        &self,
-                        writer: &mut ::csv::Writer<W>
-                    ) -> ::csv::Result<()> {
-                        use #crate_name::encode::csv::serialize::WriteField;
-
-                        #(#serialize_fields)*
-                        Ok(())
-                    }
-                }
-            }
-            .into();
+            return csv_serialize_impl(&ident, fields).into();
         }
     }
     syn::Error::new(ident.span(), "Can only derive CsvSerialize for structs")
@@ -61,31 +28,7 @@ pub fn derive_json_macro_impl(input: proc_macro::TokenStream) -> proc_macro::Tok
 
     if let Data::Struct(data_struct) = data {
         if let syn::Fields::Named(fields) = data_struct.fields {
-            let crate_name = crate_name();
-            let fields = match get_sorted_fields(fields) {
-                Ok(fields) => fields,
-                Err(ts) => {
-                    return ts.into_compile_error().into();
-                }
-            };
-            let serialize_fields = fields
-                .iter()
-                .map(write_json_field_token_stream)
-                .collect::<syn::Result<Vec<_>>>()
-                .unwrap_or_else(|e| vec![syn::Error::to_compile_error(&e)]);
-            return quote! {
-                impl crate::encode::json::serialize::JsonSerialize for #ident {
-                    fn to_json<J: #crate_name::json_writer::JsonWriter, const PRETTY_PX: bool, const PRETTY_TS: bool>(
-                        &self,
-                        writer: &mut #crate_name::json_writer::JsonObjectWriter<J>,
-                    ) {
-                        use #crate_name::encode::json::serialize::WriteField;
-
-                        #(#serialize_fields)*
-                    }
-                }
-            }
-            .into();
+            return json_serialize_impl(&ident, fields).into();
         }
     }
     syn::Error::new(ident.span(), "Can only derive JsonSerialize for structs")
@@ -93,6 +36,69 @@ pub fn derive_json_macro_impl(input: proc_macro::TokenStream) -> proc_macro::Tok
         .into()
 }
 
+pub(crate) fn csv_serialize_impl(ident: &Ident, fields: FieldsNamed) -> TokenStream {
+    let crate_name = crate_name();
+    let fields = match get_sorted_fields(fields) {
+        Ok(fields) => fields,
+        Err(ts) => {
+            return ts.into_compile_error();
+        }
+    };
+    let serialize_header_iter = fields.iter().map(write_csv_header_token_stream);
+    let serialize_fields = fields
+        .iter()
+        .map(|f| write_csv_field_token_stream(f, &crate_name))
+        .collect::<syn::Result<Vec<_>>>()
+        .unwrap_or_else(|e| vec![syn::Error::to_compile_error(&e)]);
+    quote! {
+        impl #crate_name::encode::csv::serialize::CsvSerialize for #ident {
+            fn serialize_header<W: ::std::io::Write>(writer: &mut ::csv::Writer<W>) -> ::csv::Result<()> {
+                use #crate_name::encode::csv::serialize::WriteField;
+
+                #(#serialize_header_iter)*
+                Ok(())
+            }
+
+            fn serialize_to<W: ::std::io::Write, const PRETTY_PX: bool, const PRETTY_TS: bool>(
+                &self,
+                writer: &mut ::csv::Writer<W>
+            ) -> ::csv::Result<()> {
+                use #crate_name::encode::csv::serialize::WriteField;
+
+                #(#serialize_fields)*
+                Ok(())
+            }
+        }
+    }
+}
+
+pub(crate) fn json_serialize_impl(ident: &Ident, fields: FieldsNamed) -> TokenStream {
+    let crate_name = crate_name();
+    let fields = match get_sorted_fields(fields) {
+        Ok(fields) => fields,
+        Err(ts) => {
+            return ts.into_compile_error();
+        }
+    };
+    let serialize_fields = fields
+        .iter()
+        .map(|f| write_json_field_token_stream(f, &crate_name))
+        .collect::<syn::Result<Vec<_>>>()
+        .unwrap_or_else(|e| vec![syn::Error::to_compile_error(&e)]);
+    quote! {
+        impl #crate_name::encode::json::serialize::JsonSerialize for #ident {
+            fn to_json<J: #crate_name::json_writer::JsonWriter, const PRETTY_PX: bool, const PRETTY_TS: bool>(
+                &self,
+                writer: &mut #crate_name::json_writer::JsonObjectWriter<J>,
+            ) {
+                use #crate_name::encode::json::serialize::WriteField;
+
+                #(#serialize_fields)*
+            }
+        }
+    }
+}
+
 fn write_csv_header_token_stream(field: &Field) -> TokenStream {
     let ident = field.ident.as_ref().unwrap();
     let field_type = &field.ty;
@@ -105,7 +111,10 @@ fn write_csv_header_token_stream(field: &Field) -> TokenStream {
     }
 }
 
-fn write_csv_field_token_stream(field: &Field) -> syn::Result<TokenStream> {
+fn write_csv_field_token_stream(
+    field: &Field,
+    crate_name: &TokenStream,
+) -> syn::Result<TokenStream> {
     let ident = field.ident.as_ref().unwrap();
     // ignore dummy fields
     if is_hidden(field) {
@@ -114,15 +123,15 @@ fn write_csv_field_token_stream(field: &Field) -> syn::Result<TokenStream> {
     if let Some(dbn_attr_id) = find_dbn_serialize_attr(field)? {
         if dbn_attr_id == UNIX_NANOS_ATTR {
             Ok(quote! {
-                crate::encode::csv::serialize::write_ts_field::<_, PRETTY_TS>(writer, self.#ident)?;
+                #crate_name::encode::csv::serialize::write_ts_field::<_, PRETTY_TS>(writer, self.#ident)?;
             })
         } else if dbn_attr_id == FIXED_PRICE_ATTR {
             Ok(quote! {
-                crate::encode::csv::serialize::write_px_field::<_, PRETTY_PX>(writer, self.#ident)?;
+                #crate_name::encode::csv::serialize::write_px_field::<_, PRETTY_PX>(writer, self.#ident)?;
             })
         } else if dbn_attr_id == C_CHAR_ATTR {
             Ok(quote! {
-                crate::encode::csv::serialize::write_c_char_field(writer, self.#ident)?;
+                #crate_name::encode::csv::serialize::write_c_char_field(writer, self.#ident)?;
             })
         } else {
             Err(syn::Error::new(
@@ -137,7 +146,10 @@ fn write_csv_field_token_stream(field: &Field) -> syn::Result<TokenStream> {
     }
 }
 
-fn write_json_field_token_stream(field: &Field) -> syn::Result<TokenStream> {
+fn write_json_field_token_stream(
+    field: &Field,
+    crate_name: &TokenStream,
+) -> syn::Result<TokenStream> {
     let ident = field.ident.as_ref().unwrap();
     // ignore dummy fields
     if is_hidden(field) {
@@ -146,15 +158,15 @@ fn write_json_field_token_stream(field: &Field) -> syn::Result<TokenStream> {
     if let Some(dbn_attr_id) = find_dbn_serialize_attr(field)? {
         if dbn_attr_id == UNIX_NANOS_ATTR {
             Ok(quote! {
-                crate::encode::json::serialize::write_ts_field::<_, PRETTY_TS>(writer, stringify!(#ident), self.#ident);
+                #crate_name::encode::json::serialize::write_ts_field::<_, PRETTY_TS>(writer, stringify!(#ident), self.#ident);
             })
         } else if dbn_attr_id == FIXED_PRICE_ATTR {
             Ok(quote! {
-                crate::encode::json::serialize::write_px_field::<_, PRETTY_PX>(writer, stringify!(#ident), self.#ident);
+                #crate_name::encode::json::serialize::write_px_field::<_, PRETTY_PX>(writer, stringify!(#ident), self.#ident);
             })
         } else if dbn_attr_id == C_CHAR_ATTR {
             Ok(quote! {
-                crate::encode::json::serialize::write_c_char_field(writer, stringify!(#ident), self.#ident);
+                #crate_name::encode::json::serialize::write_c_char_field(writer, stringify!(#ident), self.#ident);
             })
         } else {
             Err(syn::Error::new(
@@ -171,8 +183,6 @@ fn write_json_field_token_stream(field: &Field) -> syn::Result<TokenStream> {
 
 #[cfg(test)]
 mod tests {
-    use syn::FieldsNamed;
-
     use super::*;
 
     #[test]
@@ -183,8 +193,10 @@ mod tests {
         });
         let fields = syn::parse2::<FieldsNamed>(input).unwrap();
         assert_eq!(fields.named.len(), 1);
-        let csv_generated = write_csv_field_token_stream(fields.named.first().unwrap()).unwrap();
-        let json_generated = write_json_field_token_stream(fields.named.first().unwrap()).unwrap();
+        let csv_generated =
+            write_csv_field_token_stream(fields.named.first().unwrap(), &quote!(crate)).unwrap();
+        let json_generated =
+            write_json_field_token_stream(fields.named.first().unwrap(), &quote!(crate)).unwrap();
         assert!(csv_generated.is_empty());
         assert!(json_generated.is_empty());
     }
@@ -196,8 +208,10 @@ mod tests {
         });
         let fields = syn::parse2::<FieldsNamed>(input).unwrap();
         assert_eq!(fields.named.len(), 1);
-        let csv_generated = write_csv_field_token_stream(fields.named.first().unwrap()).unwrap();
-        let json_generated = write_json_field_token_stream(fields.named.first().unwrap()).unwrap();
+        let csv_generated =
+            write_csv_field_token_stream(fields.named.first().unwrap(), &quote!(crate)).unwrap();
+        let json_generated =
+            write_json_field_token_stream(fields.named.first().unwrap(), &quote!(crate)).unwrap();
         assert!(csv_generated.is_empty());
         assert!(json_generated.is_empty());
     }
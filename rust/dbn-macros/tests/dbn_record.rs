@@ -0,0 +1,81 @@
+use std::ffi::c_char;
+
+use dbn::{
+    encode::{csv, json, EncodeRecord},
+    record::RecordHeader,
+    HasRType, Record, RecordRef,
+};
+use dbn_macros::DbnRecord;
+
+const CUSTOM: u8 = 0xF0;
+
+#[repr(C)]
+#[derive(Clone, PartialEq, DbnRecord)]
+#[dbn_rtype(CUSTOM)]
+struct CustomMsg {
+    hd: RecordHeader,
+    #[dbn(fixed_price)]
+    price: i64,
+    #[dbn(unix_nanos, index_ts)]
+    ts_recv: u64,
+    #[dbn(c_char)]
+    side: c_char,
+    _reserved: [u8; 7],
+}
+
+fn custom_msg() -> CustomMsg {
+    CustomMsg {
+        hd: RecordHeader::new::<CustomMsg>(CUSTOM, 1, 323, 1658441851000000000),
+        price: 5_500_000_000,
+        ts_recv: 1658441891000000000,
+        side: 'B' as c_char,
+        _reserved: [0; 7],
+    }
+}
+
+#[test]
+fn test_record_traits() {
+    let rec = custom_msg();
+    assert_eq!(CustomMsg::RECORD_SIZE, 40);
+    assert_eq!(CustomMsg::HEADER_LENGTH, 10);
+    assert_eq!(rec.header().record_size(), CustomMsg::RECORD_SIZE);
+    assert!(CustomMsg::has_rtype(CUSTOM));
+    assert!(!CustomMsg::has_rtype(0));
+    assert_eq!(rec.raw_index_ts(), rec.ts_recv);
+    assert_eq!(rec.as_ref().len(), CustomMsg::RECORD_SIZE);
+    let rec_ref = RecordRef::from(&rec);
+    assert_eq!(rec_ref.get::<CustomMsg>().unwrap().price, rec.price);
+    assert!(format!("{rec:?}").contains("price: 5.500000000"));
+}
+
+#[test]
+fn test_encode_csv() {
+    let mut buffer = Vec::new();
+    let mut encoder = csv::Encoder::builder(&mut buffer)
+        .use_pretty_px(true)
+        .build()
+        .unwrap();
+    encoder.encode_header::<CustomMsg>(false).unwrap();
+    encoder.encode_record(&custom_msg()).unwrap();
+    encoder.flush().unwrap();
+    drop(encoder);
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        "ts_event,rtype,publisher_id,instrument_id,price,ts_recv,side\n\
+        1658441851000000000,240,1,323,5.500000000,1658441891000000000,B\n"
+    );
+}
+
+#[test]
+fn test_encode_json() {
+    let mut buffer = Vec::new();
+    let mut encoder = json::Encoder::builder(&mut buffer).build();
+    encoder.encode_record(&custom_msg()).unwrap();
+    drop(encoder);
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        r#"{"hd":{"ts_event":"1658441851000000000","rtype":240,"publisher_id":1,"instrument_id":323},"price":"5500000000","ts_recv":"1658441891000000000","side":"B"}"#
+            .to_owned()
+            + "\n"
+    );
+}
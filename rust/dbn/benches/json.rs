@@ -0,0 +1,87 @@
+//! Compares encoding MBP-10 records as JSON by allocating a `String` for each record,
+//! as the JSON encoder did before reusing a buffer, with the current
+//! `JsonEncoder`. Run with:
+//!
+//! ```sh
+//! cargo bench -p dbn --bench json
+//! ```
+//!
+//! To compare against an older version of the encoder itself, run the benchmark with
+//! `--save-baseline before` on that version, then with `--baseline before`.
+
+use std::{
+    ffi::c_char,
+    io::{self, Write},
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dbn::{
+    encode::{json::serialize::to_json_string, EncodeDbn, JsonEncoder},
+    rtype, BidAskPair, Mbp10Msg, RecordHeader,
+};
+
+const RECORD_COUNT: u64 = 100_000;
+
+fn records() -> Vec<Mbp10Msg> {
+    (0..RECORD_COUNT)
+        .map(|i| Mbp10Msg {
+            hd: RecordHeader::new::<Mbp10Msg>(
+                rtype::MBP_10,
+                1,
+                (i % 100) as u32,
+                1_658_441_851_000_000_000 + i,
+            ),
+            price: 372_000_000_000_000 + i as i64,
+            size: 10,
+            action: 'A' as c_char,
+            side: 'B' as c_char,
+            depth: (i % 10) as u8,
+            ts_recv: 1_658_441_851_000_000_100 + i,
+            sequence: i as u32,
+            levels: std::array::from_fn(|level| BidAskPair {
+                bid_px: 372_000_000_000_000 - level as i64 * 250_000_000,
+                ask_px: 372_500_000_000_000 + level as i64 * 250_000_000,
+                bid_sz: 10,
+                ask_sz: 5,
+                bid_ct: 5,
+                ask_ct: 2,
+            }),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn encode_mbp10(c: &mut Criterion) {
+    let records = records();
+    let mut group = c.benchmark_group("encode_mbp10_json");
+    group.throughput(Throughput::Elements(RECORD_COUNT));
+    for pretty in [false, true] {
+        group.bench_with_input(
+            BenchmarkId::new("alloc_per_record", pretty),
+            &pretty,
+            |b, &pretty| {
+                b.iter(|| {
+                    let mut writer = io::sink();
+                    for rec in records.iter() {
+                        let json = to_json_string(rec, false, pretty, pretty);
+                        writer.write_all(json.as_bytes()).unwrap();
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("encoder", pretty),
+            &pretty,
+            |b, &pretty| {
+                b.iter(|| {
+                    let mut encoder = JsonEncoder::new(io::sink(), false, pretty, pretty);
+                    encoder.encode_records(&records).unwrap();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode_mbp10);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+//! Compares decoding an uncompressed DBN file through the `BufReader` of
+//! `DbnDecoder::from_file()` with decoding it through io_uring with
+//! `DbnDecoder::from_file_uring()`. Run with:
+//!
+//! ```sh
+//! cargo bench -p dbn --features io-uring --bench uring
+//! ```
+//!
+//! The file is in the page cache after the first iteration, so this compares the
+//! overhead of each path. The benefit of io_uring is in hiding the latency of cold
+//! reads, which requires dropping the page cache between iterations, e.g. with
+//! `echo 1 | sudo tee /proc/sys/vm/drop_caches`.
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{fs::File, io::BufWriter, path::PathBuf};
+
+    use criterion::{Criterion, Throughput};
+    use dbn::{
+        decode::{DbnDecoder, DecodeRecordRef},
+        encode::{DbnEncoder, EncodeRecord},
+        rtype, Dataset, MboMsg, MetadataBuilder, RecordHeader, SType, Schema,
+    };
+
+    const RECORD_COUNT: u64 = 1_000_000;
+
+    /// Writes a DBN file of `RECORD_COUNT` MBO records to a temporary path.
+    fn write_file() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("dbn_bench_uring_{}.dbn", std::process::id()));
+        let metadata = MetadataBuilder::new()
+            .dataset(Dataset::XnasItch.as_str().to_owned())
+            .schema(Some(Schema::Mbo))
+            .start(0)
+            .stype_in(Some(SType::InstrumentId))
+            .stype_out(SType::InstrumentId)
+            .build();
+        let file = BufWriter::new(File::create(&path).unwrap());
+        let mut encoder = DbnEncoder::new(file, &metadata).unwrap();
+        for i in 0..RECORD_COUNT {
+            encoder
+                .encode_record(&MboMsg {
+                    hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, (i % 100) as u32, i),
+                    order_id: i,
+                    ts_recv: i,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        encoder.flush().unwrap();
+        path
+    }
+
+    fn count_records(mut decoder: impl DecodeRecordRef) -> u64 {
+        let mut count = 0;
+        while decoder.decode_record_ref().unwrap().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    pub fn decode_file(c: &mut Criterion) {
+        let path = write_file();
+        let mut group = c.benchmark_group("decode_file");
+        group.throughput(Throughput::Bytes(std::fs::metadata(&path).unwrap().len()));
+        group.bench_function("buf_reader", |b| {
+            b.iter(|| {
+                let count = count_records(DbnDecoder::from_file(&path).unwrap());
+                assert_eq!(count, RECORD_COUNT);
+            })
+        });
+        group.bench_function("io_uring", |b| {
+            b.iter(|| {
+                let count = count_records(DbnDecoder::from_file_uring(&path).unwrap());
+                assert_eq!(count, RECORD_COUNT);
+            })
+        });
+        group.finish();
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[cfg(target_os = "linux")]
+criterion::criterion_group!(benches, linux::decode_file);
+#[cfg(target_os = "linux")]
+criterion::criterion_main!(benches);
+
+#[cfg(not(target_os = "linux"))]
+fn main() {}
@@ -0,0 +1,564 @@
+//! Aggregation of trades into OHLCV bars and per-interval VWAP and volume summaries
+//! of arbitrary intervals.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    num::NonZeroU64,
+};
+
+use crate::{
+    decode::{DbnMetadata, DecodeRecordRef},
+    enums::{Action, StatType},
+    rtype, MboMsg, Metadata, OhlcvMsg, RecordHeader, RecordRef, Schema, StatMsg, TradeMsg,
+    UNDEF_PRICE, UNDEF_TIMESTAMP,
+};
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+/// The intervals with their own OHLCV rtype, longest first.
+const STANDARD_INTERVALS: [(u64, u8, Schema); 4] = [
+    (86_400 * NANOS_PER_SEC, rtype::OHLCV_1D, Schema::Ohlcv1D),
+    (3_600 * NANOS_PER_SEC, rtype::OHLCV_1H, Schema::Ohlcv1H),
+    (60 * NANOS_PER_SEC, rtype::OHLCV_1M, Schema::Ohlcv1M),
+    (NANOS_PER_SEC, rtype::OHLCV_1S, Schema::Ohlcv1S),
+];
+
+/// How an [`OhlcvAggregator`] handles intervals in which an instrument had no trades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyBuckets {
+    /// Don't emit a bar, like the OHLCV schemas.
+    Skip,
+    /// Emit a bar with zero volume and all prices set to the previous close.
+    FillForward,
+}
+
+/// Aggregates [`TradeMsg`] records of any number of instruments into [`OhlcvMsg`]
+/// bars.
+///
+/// Bars are aligned to `origin`, so with an origin of a session open, no bar spans a
+/// session boundary. The `ts_event` of a bar is the start of its interval. Trades must
+/// be sorted by `ts_event`: a trade from before the current interval is added to the
+/// current bar.
+///
+/// Because DBN only has rtypes for 1-second, 1-minute, 1-hour, and 1-day bars, bars
+/// of other intervals have the rtype of the longest of those intervals that evenly
+/// divides the interval, e.g. 5-minute bars have the rtype of 1-minute bars.
+#[derive(Debug, Clone)]
+pub struct OhlcvAggregator {
+    interval: u64,
+    origin: u64,
+    empty_buckets: EmptyBuckets,
+    rtype: u8,
+    schema: Schema,
+    bucket_start: Option<u64>,
+    /// Open bars of the current interval.
+    bars: BTreeMap<u32, OhlcvMsg>,
+    /// The publisher and last close of each instrument for filling forward.
+    closes: BTreeMap<u32, (u16, i64)>,
+    completed: VecDeque<OhlcvMsg>,
+}
+
+impl OhlcvAggregator {
+    /// Creates a new [`OhlcvAggregator`] for bars of `interval` nanoseconds aligned to
+    /// the UNIX nanosecond timestamp `origin`.
+    ///
+    /// # Errors
+    /// This function returns an error if `interval` isn't a whole number of seconds
+    /// from 1 second to 1 day.
+    pub fn new(
+        interval: NonZeroU64,
+        origin: u64,
+        empty_buckets: EmptyBuckets,
+    ) -> crate::Result<Self> {
+        let interval = interval.get();
+        if interval % NANOS_PER_SEC != 0 || interval > STANDARD_INTERVALS[0].0 {
+            return Err(crate::Error::BadArgument {
+                param_name: "interval".to_owned(),
+                desc: "must be a whole number of seconds from 1 second to 1 day".to_owned(),
+            });
+        }
+        // 1 second divides every valid interval
+        let (_, rtype, schema) = STANDARD_INTERVALS
+            .into_iter()
+            .find(|(standard, _, _)| interval % standard == 0)
+            .unwrap();
+        Ok(Self {
+            interval,
+            origin: origin % interval,
+            empty_buckets,
+            rtype,
+            schema,
+            bucket_start: None,
+            bars: BTreeMap::new(),
+            closes: BTreeMap::new(),
+            completed: VecDeque::new(),
+        })
+    }
+
+    /// Returns the schema corresponding to the rtype of the bars.
+    pub fn schema(&self) -> Schema {
+        self.schema
+    }
+
+    /// Adds `trade` to the bar of its instrument. Bars of previous intervals are
+    /// completed once a trade from a later interval is pushed. Trades with an undefined
+    /// price or `ts_event` are ignored.
+    pub fn push(&mut self, trade: &TradeMsg) {
+        if trade.price == UNDEF_PRICE || trade.hd.ts_event == UNDEF_TIMESTAMP {
+            return;
+        }
+        let bucket_start = bucket_start_of(trade.hd.ts_event, self.interval, self.origin);
+        let current = match self.bucket_start {
+            Some(current) if bucket_start > current => {
+                self.complete_buckets(current, bucket_start);
+                bucket_start
+            }
+            Some(current) => current,
+            None => bucket_start,
+        };
+        self.bucket_start = Some(current);
+        let rtype = self.rtype;
+        self.bars
+            .entry(trade.hd.instrument_id)
+            .and_modify(|bar| {
+                bar.high = bar.high.max(trade.price);
+                bar.low = bar.low.min(trade.price);
+                bar.close = trade.price;
+                bar.volume += trade.size as u64;
+            })
+            .or_insert_with(|| OhlcvMsg {
+                hd: RecordHeader::new::<OhlcvMsg>(
+                    rtype,
+                    trade.hd.publisher_id,
+                    trade.hd.instrument_id,
+                    current,
+                ),
+                open: trade.price,
+                high: trade.price,
+                low: trade.price,
+                close: trade.price,
+                volume: trade.size as u64,
+            });
+    }
+
+    /// Completes the bars of the current interval. Call once the trades are
+    /// exhausted.
+    pub fn finish(&mut self) {
+        if let Some(current) = self.bucket_start.take() {
+            self.complete_buckets(current, current + self.interval);
+        }
+    }
+
+    /// Removes and returns the next completed bar or `None` if there are no completed
+    /// bars.
+    pub fn pop(&mut self) -> Option<OhlcvMsg> {
+        self.completed.pop_front()
+    }
+
+    /// Completes the bars of the interval starting at `current` and, when filling
+    /// forward, the empty intervals before `next`.
+    fn complete_buckets(&mut self, current: u64, next: u64) {
+        for (instrument_id, bar) in self.bars.iter() {
+            self.closes
+                .insert(*instrument_id, (bar.hd.publisher_id, bar.close));
+        }
+        let mut start = current;
+        while start < next {
+            for (&instrument_id, &(publisher_id, close)) in self.closes.iter() {
+                if let Some(bar) = self.bars.remove(&instrument_id) {
+                    self.completed.push_back(bar);
+                } else if self.empty_buckets == EmptyBuckets::FillForward {
+                    self.completed.push_back(OhlcvMsg {
+                        hd: RecordHeader::new::<OhlcvMsg>(
+                            self.rtype,
+                            publisher_id,
+                            instrument_id,
+                            start,
+                        ),
+                        open: close,
+                        high: close,
+                        low: close,
+                        close,
+                        volume: 0,
+                    });
+                }
+            }
+            if self.empty_buckets == EmptyBuckets::Skip {
+                break;
+            }
+            start += self.interval;
+        }
+    }
+}
+
+/// Returns the start of the interval containing `ts`, where `origin` is less than
+/// `interval`.
+fn bucket_start_of(ts: u64, interval: u64, origin: u64) -> u64 {
+    ((ts + interval - origin) / interval * interval + origin).saturating_sub(interval)
+}
+
+/// The VWAP, volume, and number of trades of an instrument over a single interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeSummary {
+    /// The publisher ID of the first trade in the interval.
+    pub publisher_id: u16,
+    /// The numeric instrument ID.
+    pub instrument_id: u32,
+    /// The start of the interval as a UNIX nanosecond timestamp.
+    pub ts_start: u64,
+    /// The end of the interval as a UNIX nanosecond timestamp.
+    pub ts_end: u64,
+    /// The sum of the price times the size of each trade, where the price is in
+    /// units of 1e-9.
+    pub notional: i128,
+    /// The total size of the trades.
+    pub volume: u64,
+    /// The number of trades.
+    pub trade_count: u64,
+}
+
+impl TradeSummary {
+    /// Returns the volume-weighted average price where every 1 unit corresponds to
+    /// 1e-9, i.e. 1/1,000,000,000 or 0.000000001. Returns [`UNDEF_PRICE`] if the
+    /// volume is zero.
+    pub fn vwap(&self) -> i64 {
+        if self.volume == 0 {
+            UNDEF_PRICE
+        } else {
+            (self.notional / self.volume as i128) as i64
+        }
+    }
+
+    /// Converts the summary to a [`StatMsg`] with a `stat_type` of
+    /// [`StatType::Vwap`]. `ts_ref` is the start of the interval and `ts_event` and
+    /// `ts_recv` are its end. The volume saturates at [`i32::MAX`] to fit in
+    /// `quantity`, and the trade count isn't included.
+    pub fn to_stat_msg(&self) -> StatMsg {
+        StatMsg {
+            hd: RecordHeader::new::<StatMsg>(
+                rtype::STATISTICS,
+                self.publisher_id,
+                self.instrument_id,
+                self.ts_end,
+            ),
+            ts_recv: self.ts_end,
+            ts_ref: self.ts_start,
+            price: self.vwap(),
+            quantity: self.volume.min(i32::MAX as u64) as i32,
+            stat_type: StatType::Vwap as u16,
+            ..Default::default()
+        }
+    }
+}
+
+/// Summarizes the trades of any number of instruments into a [`TradeSummary`] per
+/// instrument per interval from [`TradeMsg`] or [`MboMsg`] records.
+///
+/// Like [`OhlcvAggregator`], intervals are aligned to `origin`, trades must be sorted
+/// by `ts_event`, and no summary is emitted for an instrument without trades in an
+/// interval.
+#[derive(Debug, Clone)]
+pub struct VwapAggregator {
+    interval: u64,
+    origin: u64,
+    bucket_start: Option<u64>,
+    /// Open summaries of the current interval.
+    summaries: BTreeMap<u32, TradeSummary>,
+    completed: VecDeque<TradeSummary>,
+}
+
+impl VwapAggregator {
+    /// Creates a new [`VwapAggregator`] for intervals of `interval` nanoseconds aligned
+    /// to the UNIX nanosecond timestamp `origin`.
+    pub fn new(interval: NonZeroU64, origin: u64) -> Self {
+        let interval = interval.get();
+        Self {
+            interval,
+            origin: origin % interval,
+            bucket_start: None,
+            summaries: BTreeMap::new(),
+            completed: VecDeque::new(),
+        }
+    }
+
+    /// Adds `trade` to the summary of its instrument. Trades with an undefined price or
+    /// `ts_event` are ignored.
+    pub fn push_trade(&mut self, trade: &TradeMsg) {
+        self.push(&trade.hd, trade.price, trade.size);
+    }
+
+    /// Adds `mbo` to the summary of its instrument if it's a trade. Other actions,
+    /// including fills, are ignored so each trade is only counted once.
+    pub fn push_mbo(&mut self, mbo: &MboMsg) {
+        if matches!(mbo.action(), Ok(Action::Trade)) {
+            self.push(&mbo.hd, mbo.price, mbo.size);
+        }
+    }
+
+    /// Completes the summaries of the current interval. Call once the trades are
+    /// exhausted.
+    pub fn finish(&mut self) {
+        self.bucket_start = None;
+        self.completed
+            .extend(std::mem::take(&mut self.summaries).into_values());
+    }
+
+    /// Removes and returns the next completed summary or `None` if there are no
+    /// completed summaries.
+    pub fn pop(&mut self) -> Option<TradeSummary> {
+        self.completed.pop_front()
+    }
+
+    fn push(&mut self, hd: &RecordHeader, price: i64, size: u32) {
+        if price == UNDEF_PRICE || hd.ts_event == UNDEF_TIMESTAMP {
+            return;
+        }
+        let bucket_start = bucket_start_of(hd.ts_event, self.interval, self.origin);
+        let current = match self.bucket_start {
+            Some(current) if bucket_start > current => {
+                self.finish();
+                bucket_start
+            }
+            Some(current) => current,
+            None => bucket_start,
+        };
+        self.bucket_start = Some(current);
+        let summary = self
+            .summaries
+            .entry(hd.instrument_id)
+            .or_insert_with(|| TradeSummary {
+                publisher_id: hd.publisher_id,
+                instrument_id: hd.instrument_id,
+                ts_start: current,
+                ts_end: current + self.interval,
+                notional: 0,
+                volume: 0,
+                trade_count: 0,
+            });
+        summary.notional += price as i128 * size as i128;
+        summary.volume += size as u64;
+        summary.trade_count += 1;
+    }
+}
+
+/// Type for decoding [`OhlcvMsg`] bars aggregated from the [`TradeMsg`] records of
+/// another decoder. Records of other types are ignored.
+#[derive(Debug)]
+pub struct OhlcvDecoder<D> {
+    decoder: D,
+    aggregator: OhlcvAggregator,
+    bar: OhlcvMsg,
+    is_exhausted: bool,
+}
+
+impl<D> OhlcvDecoder<D>
+where
+    D: DbnMetadata,
+{
+    /// Creates a new [`OhlcvDecoder`] aggregating the trades of `decoder` with
+    /// `aggregator`. Updates the schema of the metadata to that of the bars.
+    pub fn new(mut decoder: D, aggregator: OhlcvAggregator) -> Self {
+        let metadata = decoder.metadata_mut();
+        metadata.schema = Some(aggregator.schema());
+        // Bars never have a `ts_out`
+        metadata.ts_out = false;
+        Self::new_no_metadata(decoder, aggregator)
+    }
+}
+
+impl<D> OhlcvDecoder<D> {
+    /// Creates a new [`OhlcvDecoder`] aggregating the trades of `decoder`, which
+    /// doesn't have metadata, with `aggregator`.
+    pub fn new_no_metadata(decoder: D, aggregator: OhlcvAggregator) -> Self {
+        Self {
+            decoder,
+            aggregator,
+            bar: OhlcvMsg::default_for_schema(Schema::Ohlcv1S),
+            is_exhausted: false,
+        }
+    }
+
+    /// Consumes the decoder and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.decoder
+    }
+}
+
+impl<D: DbnMetadata> DbnMetadata for OhlcvDecoder<D> {
+    fn metadata(&self) -> &Metadata {
+        self.decoder.metadata()
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        self.decoder.metadata_mut()
+    }
+}
+
+impl<D: DecodeRecordRef> DecodeRecordRef for OhlcvDecoder<D> {
+    fn decode_record_ref(&mut self) -> crate::Result<Option<RecordRef>> {
+        loop {
+            if let Some(bar) = self.aggregator.pop() {
+                self.bar = bar;
+                return Ok(Some(RecordRef::from(&self.bar)));
+            }
+            if self.is_exhausted {
+                return Ok(None);
+            }
+            match self.decoder.decode_record_ref()? {
+                Some(rec) => {
+                    if let Some(trade) = rec.get::<TradeMsg>() {
+                        self.aggregator.push(trade);
+                    }
+                }
+                None => {
+                    self.aggregator.finish();
+                    self.is_exhausted = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::raw::c_char;
+
+    use super::*;
+    use crate::decode::{dbn::Decoder, tests::TEST_DATA_PATH};
+
+    const MINUTE: u64 = 60 * NANOS_PER_SEC;
+
+    fn trade(instrument_id: u32, ts_event: u64, price: i64, size: u32) -> TradeMsg {
+        TradeMsg {
+            hd: RecordHeader::new::<TradeMsg>(rtype::MBP_0, 1, instrument_id, ts_event),
+            price,
+            size,
+            ..Default::default()
+        }
+    }
+
+    fn drain(target: &mut OhlcvAggregator) -> Vec<OhlcvMsg> {
+        std::iter::from_fn(|| target.pop()).collect()
+    }
+
+    #[test]
+    fn test_invalid_interval() {
+        for interval in [1, NANOS_PER_SEC + 1, 2 * 86_400 * NANOS_PER_SEC] {
+            assert!(matches!(
+                OhlcvAggregator::new(NonZeroU64::new(interval).unwrap(), 0, EmptyBuckets::Skip),
+                Err(crate::Error::BadArgument { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let mut target =
+            OhlcvAggregator::new(NonZeroU64::new(5 * MINUTE).unwrap(), 0, EmptyBuckets::Skip)
+                .unwrap();
+        assert_eq!(target.schema(), Schema::Ohlcv1M);
+        target.push(&trade(1, MINUTE, 100, 1));
+        target.push(&trade(2, MINUTE, 50, 4));
+        target.push(&trade(1, 2 * MINUTE, 105, 2));
+        target.push(&trade(1, 3 * MINUTE, 95, 3));
+        assert!(target.pop().is_none());
+        // Skips the empty intervals
+        target.push(&trade(1, 17 * MINUTE, 101, 1));
+        let bars = drain(&mut target);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].hd.rtype, rtype::OHLCV_1M);
+        assert_eq!(bars[0].hd.instrument_id, 1);
+        assert_eq!(bars[0].hd.ts_event, 0);
+        assert_eq!(
+            (bars[0].open, bars[0].high, bars[0].low, bars[0].close),
+            (100, 105, 95, 95)
+        );
+        assert_eq!(bars[0].volume, 6);
+        assert_eq!(bars[1].hd.instrument_id, 2);
+        target.finish();
+        let bars = drain(&mut target);
+        assert_eq!(bars.len(), 1);
+        assert_eq!(bars[0].hd.ts_event, 15 * MINUTE);
+    }
+
+    #[test]
+    fn test_fill_forward_with_origin() {
+        let mut target = OhlcvAggregator::new(
+            NonZeroU64::new(MINUTE).unwrap(),
+            30 * NANOS_PER_SEC,
+            EmptyBuckets::FillForward,
+        )
+        .unwrap();
+        target.push(&trade(1, 45 * NANOS_PER_SEC, 100, 1));
+        target.push(&trade(1, 100 * NANOS_PER_SEC, 101, 1));
+        target.push(&trade(1, 215 * NANOS_PER_SEC, 102, 1));
+        target.finish();
+        let bars = drain(&mut target);
+        let starts: Vec<_> = bars
+            .iter()
+            .map(|bar| bar.hd.ts_event / NANOS_PER_SEC)
+            .collect();
+        assert_eq!(starts, [30, 90, 150, 210]);
+        assert_eq!((bars[2].open, bars[2].close, bars[2].volume), (101, 101, 0));
+    }
+
+    #[test]
+    fn test_vwap() {
+        let mut target = VwapAggregator::new(NonZeroU64::new(MINUTE).unwrap(), 0);
+        target.push_trade(&trade(1, 10 * NANOS_PER_SEC, 100, 1));
+        target.push_trade(&trade(1, 20 * NANOS_PER_SEC, 110, 3));
+        target.push_trade(&trade(2, 30 * NANOS_PER_SEC, 50, 2));
+        target.push_trade(&trade(1, 30 * NANOS_PER_SEC, UNDEF_PRICE, 2));
+        let mut fill = MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, 70 * NANOS_PER_SEC),
+            price: 120,
+            size: 5,
+            action: Action::Fill as c_char,
+            ..Default::default()
+        };
+        target.push_mbo(&fill);
+        assert!(target.pop().is_none());
+        fill.action = Action::Trade as c_char;
+        target.push_mbo(&fill);
+        let summary = target.pop().unwrap();
+        assert_eq!(summary.instrument_id, 1);
+        assert_eq!((summary.ts_start, summary.ts_end), (0, MINUTE));
+        assert_eq!(summary.vwap(), 107);
+        assert_eq!((summary.volume, summary.trade_count), (4, 2));
+        let stat = summary.to_stat_msg();
+        assert_eq!(stat.stat_type, StatType::Vwap as u16);
+        assert_eq!((stat.price, stat.quantity), (107, 4));
+        assert_eq!((stat.ts_ref, stat.hd.ts_event), (0, MINUTE));
+        assert_eq!(target.pop().unwrap().instrument_id, 2);
+        assert!(target.pop().is_none());
+        target.finish();
+        let summary = target.pop().unwrap();
+        assert_eq!(summary.ts_start, MINUTE);
+        assert_eq!((summary.vwap(), summary.trade_count), (120, 1));
+    }
+
+    #[test]
+    fn test_decoder() {
+        let decoder =
+            Decoder::from_zstd_file(format!("{TEST_DATA_PATH}/test_data.trades.dbn.zst")).unwrap();
+        let mut target = OhlcvDecoder::new(
+            decoder,
+            OhlcvAggregator::new(
+                NonZeroU64::new(86_400 * NANOS_PER_SEC).unwrap(),
+                0,
+                EmptyBuckets::Skip,
+            )
+            .unwrap(),
+        );
+        assert_eq!(target.metadata().schema, Some(Schema::Ohlcv1D));
+        let bar = target
+            .decode_record_ref()
+            .unwrap()
+            .unwrap()
+            .get::<OhlcvMsg>()
+            .unwrap()
+            .clone();
+        assert_eq!(bar.hd.ts_event % (86_400 * NANOS_PER_SEC), 0);
+        assert!(bar.volume > 0);
+        assert!(target.decode_record_ref().unwrap().is_none());
+    }
+}
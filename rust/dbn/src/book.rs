@@ -0,0 +1,791 @@
+//! Building limit order books from market-by-order (MBO) data and deriving
+//! market-by-price (MBP) records from them, and consolidating the top of book of
+//! multiple publishers.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::c_char,
+    marker::PhantomData,
+    num::NonZeroU64,
+};
+
+use crate::{
+    flags, rtype, Action, BidAskPair, CbboMsg, ConsolidatedBidAskPair, HasRType, MboMsg, Mbp10Msg,
+    Mbp1Msg, Record, RecordHeader, Schema, Side, UNDEF_PRICE,
+};
+
+/// The aggregated orders at a single price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceLevel {
+    /// The price of the level.
+    pub price: i64,
+    /// The total size of the orders at the level.
+    pub size: u32,
+    /// The number of orders at the level.
+    pub count: u32,
+}
+
+/// An order resting in an [`InstrumentBook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Order {
+    /// The side of the order.
+    pub side: Side,
+    /// The price of the order.
+    pub price: i64,
+    /// The remaining size of the order.
+    pub size: u32,
+}
+
+/// The limit order books of multiple instruments built from [`MboMsg`] records.
+///
+/// Following DBN's MBO semantics, [`Action::Trade`] and [`Action::Fill`] records
+/// don't change the book: the resulting change in resting orders is sent as a
+/// separate [`Action::Cancel`] or [`Action::Modify`] record.
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    books: HashMap<u32, InstrumentBook>,
+}
+
+impl OrderBook {
+    /// Creates a new empty [`OrderBook`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the book of the instrument of `mbo`.
+    ///
+    /// # Errors
+    /// This function returns an error if `mbo` has an invalid action or side.
+    pub fn apply(&mut self, mbo: &MboMsg) -> crate::Result<()> {
+        self.books
+            .entry(mbo.hd.instrument_id)
+            .or_default()
+            .apply(mbo)
+    }
+
+    /// Returns the book of the instrument with `instrument_id` or `None` if no records
+    /// for that instrument have been applied.
+    pub fn book(&self, instrument_id: u32) -> Option<&InstrumentBook> {
+        self.books.get(&instrument_id)
+    }
+
+    /// Returns an iterator over the instrument IDs and their books.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &InstrumentBook)> {
+        self.books
+            .iter()
+            .map(|(instrument_id, book)| (*instrument_id, book))
+    }
+
+    /// Removes the books of all instruments.
+    pub fn clear(&mut self) {
+        self.books.clear();
+    }
+}
+
+/// The limit order book of a single instrument.
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentBook {
+    orders: HashMap<u64, Order>,
+    bids: BTreeMap<i64, PriceLevel>,
+    asks: BTreeMap<i64, PriceLevel>,
+}
+
+impl InstrumentBook {
+    /// Creates a new empty [`InstrumentBook`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the book from `mbo`. Cancels and modifications of unknown orders,
+    /// such as when the data starts mid-session, are treated as no-ops and
+    /// additions respectively.
+    ///
+    /// # Errors
+    /// This function returns an error if `mbo` has an invalid action or side.
+    pub fn apply(&mut self, mbo: &MboMsg) -> crate::Result<()> {
+        let action = mbo.action()?;
+        match action {
+            Action::Clear => {
+                self.clear();
+                return Ok(());
+            }
+            Action::Trade | Action::Fill => return Ok(()),
+            Action::Add | Action::Cancel | Action::Modify => (),
+        }
+        let side = mbo.side()?;
+        if side == Side::None {
+            return Ok(());
+        }
+        if mbo.flags & flags::TOB != 0 {
+            // A top-of-book record replaces the whole side with a single level
+            self.clear_side(side);
+            if action != Action::Cancel && mbo.price != UNDEF_PRICE {
+                self.levels_mut(side).insert(
+                    mbo.price,
+                    PriceLevel {
+                        price: mbo.price,
+                        size: mbo.size,
+                        count: 1,
+                    },
+                );
+            }
+            return Ok(());
+        }
+        match action {
+            Action::Add => self.add(mbo.order_id, side, mbo.price, mbo.size),
+            Action::Cancel => self.cancel(mbo.order_id, mbo.size),
+            Action::Modify => self.modify(mbo.order_id, side, mbo.price, mbo.size),
+            _ => unreachable!("handled above"),
+        }
+        Ok(())
+    }
+
+    /// Returns the best bid level or `None` if there are no bids.
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids.values().next_back().copied()
+    }
+
+    /// Returns the best ask level or `None` if there are no asks.
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks.values().next().copied()
+    }
+
+    /// Returns the best bid and offer. The price of an empty side is [`UNDEF_PRICE`].
+    pub fn bbo(&self) -> BidAskPair {
+        self.depth(1).pop().unwrap_or_default()
+    }
+
+    /// Returns a snapshot of the top `levels` price levels of each side, best first,
+    /// in the same format as an MBP record. Levels beyond the depth of a side have a
+    /// price of [`UNDEF_PRICE`].
+    pub fn depth(&self, levels: usize) -> Vec<BidAskPair> {
+        let mut bids = self.bids();
+        let mut asks = self.asks();
+        (0..levels)
+            .map(|_| {
+                let mut pair = BidAskPair::default();
+                if let Some(bid) = bids.next() {
+                    pair.bid_px = bid.price;
+                    pair.bid_sz = bid.size;
+                    pair.bid_ct = bid.count;
+                }
+                if let Some(ask) = asks.next() {
+                    pair.ask_px = ask.price;
+                    pair.ask_sz = ask.size;
+                    pair.ask_ct = ask.count;
+                }
+                pair
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the bid levels from best to worst.
+    pub fn bids(&self) -> impl Iterator<Item = PriceLevel> + '_ {
+        self.bids.values().rev().copied()
+    }
+
+    /// Returns an iterator over the ask levels from best to worst.
+    pub fn asks(&self) -> impl Iterator<Item = PriceLevel> + '_ {
+        self.asks.values().copied()
+    }
+
+    /// Returns the resting order with `order_id` or `None` if there's no such order.
+    pub fn order(&self, order_id: u64) -> Option<&Order> {
+        self.orders.get(&order_id)
+    }
+
+    /// Removes all orders from the book.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    fn levels_mut(&mut self, side: Side) -> &mut BTreeMap<i64, PriceLevel> {
+        if side == Side::Bid {
+            &mut self.bids
+        } else {
+            &mut self.asks
+        }
+    }
+
+    fn clear_side(&mut self, side: Side) {
+        self.orders.retain(|_, order| order.side != side);
+        self.levels_mut(side).clear();
+    }
+
+    fn add(&mut self, order_id: u64, side: Side, price: i64, size: u32) {
+        if let Some(prev) = self.orders.insert(order_id, Order { side, price, size }) {
+            self.remove_from_level(&prev, prev.size, true);
+        }
+        let level = self.levels_mut(side).entry(price).or_insert(PriceLevel {
+            price,
+            size: 0,
+            count: 0,
+        });
+        level.size += size;
+        level.count += 1;
+    }
+
+    fn cancel(&mut self, order_id: u64, size: u32) {
+        let Some(order) = self.orders.get_mut(&order_id) else {
+            return;
+        };
+        let size = size.min(order.size);
+        order.size -= size;
+        let order = *order;
+        let is_removed = order.size == 0;
+        if is_removed {
+            self.orders.remove(&order_id);
+        }
+        self.remove_from_level(&order, size, is_removed);
+    }
+
+    fn modify(&mut self, order_id: u64, side: Side, price: i64, size: u32) {
+        match self.orders.get(&order_id).copied() {
+            Some(order) if order.side == side && order.price == price => {
+                let level = self
+                    .levels_mut(side)
+                    .get_mut(&price)
+                    .expect("level of resting order");
+                level.size = level.size - order.size + size;
+                self.orders.get_mut(&order_id).unwrap().size = size;
+            }
+            // A change in price moves the order to a different level
+            _ => self.add(order_id, side, price, size),
+        }
+    }
+
+    /// Removes `size` of `order` from its level and, if `is_removed`, the order
+    /// itself, dropping the level once it's empty.
+    fn remove_from_level(&mut self, order: &Order, size: u32, is_removed: bool) {
+        let levels = self.levels_mut(order.side);
+        let Some(level) = levels.get_mut(&order.price) else {
+            return;
+        };
+        level.size -= size;
+        if is_removed {
+            level.count -= 1;
+            if level.count == 0 {
+                levels.remove(&order.price);
+            }
+        }
+    }
+}
+
+/// An MBP record type that can be derived from an [`InstrumentBook`].
+pub trait MbpRecord: HasRType {
+    /// The number of levels of the book in the record.
+    const DEPTH: usize;
+
+    /// Creates a record with the top levels of `book` and the event fields of `mbo`.
+    /// `depth` is the level of the book that changed.
+    fn from_book(mbo: &MboMsg, book: &InstrumentBook, ts_recv: u64, depth: u8) -> Self;
+}
+
+macro_rules! impl_mbp_record {
+    ($ty:ident, $rtype:expr, $depth:literal) => {
+        impl MbpRecord for $ty {
+            const DEPTH: usize = $depth;
+
+            fn from_book(mbo: &MboMsg, book: &InstrumentBook, ts_recv: u64, depth: u8) -> Self {
+                Self {
+                    hd: RecordHeader::new::<Self>(
+                        $rtype,
+                        mbo.hd.publisher_id,
+                        mbo.hd.instrument_id,
+                        mbo.hd.ts_event,
+                    ),
+                    price: mbo.price,
+                    size: mbo.size,
+                    action: mbo.action,
+                    side: mbo.side,
+                    flags: mbo.flags,
+                    depth,
+                    ts_recv,
+                    ts_in_delta: mbo.ts_in_delta,
+                    sequence: mbo.sequence,
+                    levels: book.depth($depth).try_into().unwrap(),
+                }
+            }
+        }
+    };
+}
+
+impl_mbp_record!(Mbp1Msg, rtype::MBP_1, 1);
+impl_mbp_record!(Mbp10Msg, rtype::MBP_10, 10);
+
+/// When an [`MbpTransformer`] emits records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampling {
+    /// Emit a record for every trade and every change to the levels of the book
+    /// included in the record.
+    OnChange,
+    /// Emit a snapshot of the book of an instrument at the end of every interval of
+    /// the given number of nanoseconds in which the instrument had a record. The
+    /// event fields of a snapshot are those of the last MBO record before it.
+    Interval(NonZeroU64),
+}
+
+/// Type for deriving MBP records like [`Mbp1Msg`] and [`Mbp10Msg`] from a stream of
+/// [`MboMsg`] records by maintaining an [`OrderBook`].
+#[derive(Debug, Clone)]
+pub struct MbpTransformer<T> {
+    book: OrderBook,
+    sampling: Sampling,
+    last_records: HashMap<u32, MboMsg>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: MbpRecord> MbpTransformer<T> {
+    /// Creates a new [`MbpTransformer`] that emits records according to `sampling`.
+    pub fn new(sampling: Sampling) -> Self {
+        Self {
+            book: OrderBook::new(),
+            sampling,
+            last_records: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the order book built so far.
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    /// Applies `mbo` to the order book and returns an MBP record if one is due.
+    ///
+    /// With [`Sampling::Interval`], the returned snapshot is of the book before `mbo`
+    /// was applied.
+    ///
+    /// # Errors
+    /// This function returns an error if `mbo` has an invalid action or side.
+    pub fn apply(&mut self, mbo: &MboMsg) -> crate::Result<Option<T>> {
+        let instrument_id = mbo.hd.instrument_id;
+        match self.sampling {
+            Sampling::OnChange => {
+                let before = self
+                    .book
+                    .book(instrument_id)
+                    .map(|book| book.depth(T::DEPTH))
+                    .unwrap_or_else(|| vec![BidAskPair::default(); T::DEPTH]);
+                self.book.apply(mbo)?;
+                // `apply()` always creates the book of the instrument
+                let book = self.book.book(instrument_id).unwrap();
+                let after = book.depth(T::DEPTH);
+                if mbo.action()? == Action::Trade {
+                    return Ok(Some(T::from_book(mbo, book, mbo.ts_recv, 0)));
+                }
+                Ok(before
+                    .iter()
+                    .zip(after.iter())
+                    .position(|(before, after)| before != after)
+                    .map(|depth| T::from_book(mbo, book, mbo.ts_recv, depth as u8)))
+            }
+            Sampling::Interval(interval) => {
+                let interval = interval.get();
+                let sample = match (
+                    self.last_records.get(&instrument_id),
+                    self.book.book(instrument_id),
+                ) {
+                    (Some(last), Some(book))
+                        if mbo.ts_recv / interval != last.ts_recv / interval =>
+                    {
+                        Some(Self::sample(last, book, interval))
+                    }
+                    _ => None,
+                };
+                self.book.apply(mbo)?;
+                self.last_records.insert(instrument_id, mbo.clone());
+                Ok(sample)
+            }
+        }
+    }
+
+    /// Returns the snapshots of the last interval of each instrument that haven't been
+    /// emitted yet with [`Sampling::Interval`]. Call once the MBO stream is exhausted.
+    pub fn finish(&mut self) -> Vec<T> {
+        let Sampling::Interval(interval) = self.sampling else {
+            return Vec::new();
+        };
+        let mut samples: Vec<T> = self
+            .last_records
+            .drain()
+            .filter_map(|(instrument_id, last)| {
+                self.book
+                    .book(instrument_id)
+                    .map(|book| Self::sample(&last, book, interval.get()))
+            })
+            .collect();
+        samples.sort_by_key(|rec| rec.header().instrument_id);
+        samples
+    }
+
+    fn sample(last: &MboMsg, book: &InstrumentBook, interval: u64) -> T {
+        let ts_recv = (last.ts_recv / interval + 1) * interval;
+        T::from_book(last, book, ts_recv, 0)
+    }
+}
+
+/// Type for consolidating the top of book of [`Mbp1Msg`] records from multiple
+/// publishers into [`CbboMsg`] records, tracking which publisher is at the inside of
+/// each side.
+///
+/// The quote of each publisher remains part of the consolidated top of book until the
+/// publisher sends another record for the instrument. When several publishers share
+/// the best price, the one with the largest size is at the inside, with ties going to
+/// the lowest publisher ID. Records from different publishers must use the same
+/// instrument IDs for the same instrument.
+#[derive(Debug, Clone, Default)]
+pub struct CbboTransformer {
+    instruments: HashMap<u32, ConsolidatedQuotes>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ConsolidatedQuotes {
+    /// The latest top of book of each publisher.
+    quotes: BTreeMap<u16, BidAskPair>,
+    cbbo: ConsolidatedBidAskPair,
+    /// The price, size, and side of the last trade from any publisher.
+    last_trade: Option<(i64, u32, c_char)>,
+}
+
+impl CbboTransformer {
+    /// Creates a new [`CbboTransformer`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the consolidated top of book of `instrument_id` or `None` if no records
+    /// have been applied for it.
+    pub fn cbbo(&self, instrument_id: u32) -> Option<&ConsolidatedBidAskPair> {
+        self.instruments
+            .get(&instrument_id)
+            .map(|quotes| &quotes.cbbo)
+    }
+
+    /// Updates the quote of the publisher of `mbp` and returns a [`CbboMsg`] if the
+    /// consolidated top of book changed or `mbp` is a trade.
+    ///
+    /// The price, size, and side of the returned record are those of the last trade of
+    /// the instrument from any publisher, while the other fields are copied from `mbp`.
+    pub fn apply(&mut self, mbp: &Mbp1Msg) -> Option<CbboMsg> {
+        let quotes = self.instruments.entry(mbp.hd.instrument_id).or_default();
+        quotes
+            .quotes
+            .insert(mbp.hd.publisher_id, mbp.levels[0].clone());
+        let is_trade = matches!(mbp.action(), Ok(Action::Trade));
+        if is_trade {
+            quotes.last_trade = Some((mbp.price, mbp.size, mbp.side));
+        }
+        let cbbo = consolidate(&quotes.quotes);
+        if cbbo == quotes.cbbo && !is_trade {
+            return None;
+        }
+        quotes.cbbo = cbbo.clone();
+        let mut res = CbboMsg::default_for_schema(Schema::Cbbo);
+        res.hd = RecordHeader::new::<CbboMsg>(
+            rtype::CBBO,
+            mbp.hd.publisher_id,
+            mbp.hd.instrument_id,
+            mbp.hd.ts_event,
+        );
+        if let Some((price, size, side)) = quotes.last_trade {
+            res.price = price;
+            res.size = size;
+            res.side = side;
+        }
+        res.flags = mbp.flags;
+        res.ts_recv = mbp.ts_recv;
+        res.sequence = mbp.sequence;
+        res.levels = [cbbo];
+        Some(res)
+    }
+
+    /// Removes the quotes of all instruments.
+    pub fn clear(&mut self) {
+        self.instruments.clear();
+    }
+}
+
+fn consolidate(quotes: &BTreeMap<u16, BidAskPair>) -> ConsolidatedBidAskPair {
+    let mut cbbo = ConsolidatedBidAskPair::default();
+    // Iterating in order of publisher ID and only replacing a better quote gives ties to
+    // the lowest publisher ID
+    for (&publisher_id, quote) in quotes.iter() {
+        if quote.bid_px != UNDEF_PRICE
+            && (cbbo.bid_px == UNDEF_PRICE
+                || (quote.bid_px, quote.bid_sz) > (cbbo.bid_px, cbbo.bid_sz))
+        {
+            cbbo.bid_px = quote.bid_px;
+            cbbo.bid_sz = quote.bid_sz;
+            cbbo.bid_pb = publisher_id;
+        }
+        if quote.ask_px != UNDEF_PRICE
+            && (cbbo.ask_px == UNDEF_PRICE
+                || quote.ask_px < cbbo.ask_px
+                || (quote.ask_px == cbbo.ask_px && quote.ask_sz > cbbo.ask_sz))
+        {
+            cbbo.ask_px = quote.ask_px;
+            cbbo.ask_sz = quote.ask_sz;
+            cbbo.ask_pb = publisher_id;
+        }
+    }
+    cbbo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTRUMENT_ID: u32 = 5482;
+
+    fn mbo(action: Action, side: Side, order_id: u64, price: i64, size: u32) -> MboMsg {
+        MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, INSTRUMENT_ID, 0),
+            order_id,
+            price,
+            size,
+            action: u8::from(action) as c_char,
+            side: u8::from(side) as c_char,
+            ..Default::default()
+        }
+    }
+
+    fn build(records: &[MboMsg]) -> OrderBook {
+        let mut target = OrderBook::new();
+        for rec in records {
+            target.apply(rec).unwrap();
+        }
+        target
+    }
+
+    #[test]
+    fn test_add_and_bbo() {
+        let target = build(&[
+            mbo(Action::Add, Side::Bid, 1, 100, 5),
+            mbo(Action::Add, Side::Bid, 2, 101, 3),
+            mbo(Action::Add, Side::Bid, 3, 101, 2),
+            mbo(Action::Add, Side::Ask, 4, 103, 7),
+            mbo(Action::Add, Side::Ask, 5, 102, 1),
+        ]);
+        let book = target.book(INSTRUMENT_ID).unwrap();
+        assert_eq!(
+            book.best_bid(),
+            Some(PriceLevel {
+                price: 101,
+                size: 5,
+                count: 2
+            })
+        );
+        assert_eq!(book.best_ask().unwrap().price, 102);
+        let depth = book.depth(3);
+        assert_eq!(depth.len(), 3);
+        assert_eq!((depth[1].bid_px, depth[1].ask_px), (100, 103));
+        assert_eq!(
+            (depth[2].bid_px, depth[2].ask_px),
+            (UNDEF_PRICE, UNDEF_PRICE)
+        );
+        assert_eq!(book.bbo(), depth[0]);
+        assert!(target.book(INSTRUMENT_ID + 1).is_none());
+    }
+
+    #[test]
+    fn test_cancel_and_modify() {
+        let target = build(&[
+            mbo(Action::Add, Side::Bid, 1, 100, 5),
+            mbo(Action::Add, Side::Bid, 2, 100, 3),
+            // Partial cancel
+            mbo(Action::Cancel, Side::Bid, 1, 100, 2),
+            // Full cancel removes the order
+            mbo(Action::Cancel, Side::Bid, 2, 100, 3),
+            // Trades and fills don't change the book
+            mbo(Action::Trade, Side::Ask, 0, 100, 3),
+            mbo(Action::Fill, Side::Bid, 1, 100, 3),
+            // Cancel of unknown order is ignored
+            mbo(Action::Cancel, Side::Bid, 9, 100, 3),
+            mbo(Action::Add, Side::Ask, 3, 105, 4),
+            mbo(Action::Modify, Side::Ask, 3, 105, 6),
+            mbo(Action::Modify, Side::Bid, 1, 99, 3),
+        ]);
+        let book = target.book(INSTRUMENT_ID).unwrap();
+        assert_eq!(book.bids().count(), 1);
+        assert_eq!(
+            book.best_bid(),
+            Some(PriceLevel {
+                price: 99,
+                size: 3,
+                count: 1
+            })
+        );
+        assert_eq!(
+            book.best_ask(),
+            Some(PriceLevel {
+                price: 105,
+                size: 6,
+                count: 1
+            })
+        );
+        assert!(book.order(2).is_none());
+        assert_eq!(book.order(1).unwrap().price, 99);
+    }
+
+    #[test]
+    fn test_clear_and_tob() {
+        let mut target = build(&[
+            mbo(Action::Add, Side::Bid, 1, 100, 5),
+            mbo(Action::Add, Side::Ask, 2, 102, 5),
+            mbo(Action::Clear, Side::None, 0, UNDEF_PRICE, 0),
+        ]);
+        let book = target.book(INSTRUMENT_ID).unwrap();
+        assert!(book.best_bid().is_none());
+        assert!(book.best_ask().is_none());
+
+        let mut tob = mbo(Action::Add, Side::Bid, 0, 98, 10);
+        tob.flags = flags::TOB;
+        target
+            .apply(&mbo(Action::Add, Side::Bid, 1, 100, 5))
+            .unwrap();
+        target.apply(&tob).unwrap();
+        let book = target.book(INSTRUMENT_ID).unwrap();
+        assert_eq!(
+            book.bids().collect::<Vec<_>>(),
+            [PriceLevel {
+                price: 98,
+                size: 10,
+                count: 1
+            }]
+        );
+        assert!(book.order(1).is_none());
+    }
+
+    #[test]
+    fn test_mbp_on_change() {
+        let mut mbp1 = MbpTransformer::<Mbp1Msg>::new(Sampling::OnChange);
+        let mut mbp10 = MbpTransformer::<Mbp10Msg>::new(Sampling::OnChange);
+        let mut apply = |rec: &MboMsg| {
+            (
+                mbp1.apply(rec).unwrap().map(|rec| rec.depth),
+                mbp10.apply(rec).unwrap().map(|rec| rec.depth),
+            )
+        };
+        assert_eq!(
+            apply(&mbo(Action::Add, Side::Bid, 1, 100, 5)),
+            (Some(0), Some(0))
+        );
+        // Below the top of the book
+        assert_eq!(
+            apply(&mbo(Action::Add, Side::Bid, 2, 99, 5)),
+            (None, Some(1))
+        );
+        // Trades are always emitted
+        assert_eq!(
+            apply(&mbo(Action::Trade, Side::Ask, 0, 100, 1)),
+            (Some(0), Some(0))
+        );
+        assert_eq!(
+            apply(&mbo(Action::Cancel, Side::Bid, 1, 100, 5)),
+            (Some(0), Some(0))
+        );
+
+        let mut rec = mbo(Action::Add, Side::Ask, 3, 102, 4);
+        rec.ts_recv = 123;
+        let res = mbp10.apply(&rec).unwrap().unwrap();
+        assert_eq!(res.hd.rtype, rtype::MBP_10);
+        assert_eq!(res.hd.instrument_id, INSTRUMENT_ID);
+        assert_eq!((res.price, res.size, res.ts_recv), (102, 4, 123));
+        assert_eq!((res.levels[0].bid_px, res.levels[0].ask_px), (99, 102));
+        assert_eq!(res.levels[1].bid_px, UNDEF_PRICE);
+    }
+
+    #[test]
+    fn test_mbp_interval() {
+        let mut target =
+            MbpTransformer::<Mbp1Msg>::new(Sampling::Interval(NonZeroU64::new(10).unwrap()));
+        let rec = |ts_recv, rec: MboMsg| MboMsg { ts_recv, ..rec };
+        assert!(target
+            .apply(&rec(1, mbo(Action::Add, Side::Bid, 1, 100, 5)))
+            .unwrap()
+            .is_none());
+        assert!(target
+            .apply(&rec(2, mbo(Action::Add, Side::Ask, 2, 102, 5)))
+            .unwrap()
+            .is_none());
+        let res = target
+            .apply(&rec(25, mbo(Action::Cancel, Side::Bid, 1, 100, 5)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.ts_recv, 10);
+        assert_eq!((res.levels[0].bid_px, res.levels[0].ask_px), (100, 102));
+        let res = target.finish();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].ts_recv, 30);
+        assert_eq!(
+            (res[0].levels[0].bid_px, res[0].levels[0].ask_px),
+            (UNDEF_PRICE, 102)
+        );
+        assert!(target.finish().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_action() {
+        let mut rec = mbo(Action::Add, Side::Bid, 1, 100, 5);
+        rec.action = b'X' as c_char;
+        assert!(OrderBook::new().apply(&rec).is_err());
+    }
+
+    #[test]
+    fn test_cbbo() {
+        let quote = |publisher_id, bid_px, bid_sz, ask_px, ask_sz| Mbp1Msg {
+            hd: RecordHeader::new::<Mbp1Msg>(rtype::MBP_1, publisher_id, INSTRUMENT_ID, 0),
+            action: u8::from(Action::Add) as c_char,
+            levels: [BidAskPair {
+                bid_px,
+                ask_px,
+                bid_sz,
+                ask_sz,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let inside = |rec: Option<CbboMsg>| {
+            rec.map(|rec| {
+                let level = &rec.levels[0];
+                (level.bid_px, level.bid_pb, level.ask_px, level.ask_pb)
+            })
+        };
+        let mut target = CbboTransformer::new();
+        assert_eq!(
+            inside(target.apply(&quote(1, 100, 5, 103, 5))),
+            Some((100, 1, 103, 1))
+        );
+        assert_eq!(
+            inside(target.apply(&quote(2, 101, 1, 104, 5))),
+            Some((101, 2, 103, 1))
+        );
+        // No change to the consolidated top of book
+        assert!(target.apply(&quote(2, 101, 1, 105, 5)).is_none());
+        // The largest size at the best price is at the inside
+        assert_eq!(
+            inside(target.apply(&quote(3, UNDEF_PRICE, 0, 103, 9))),
+            Some((101, 2, 103, 3))
+        );
+        assert_eq!(target.cbbo(INSTRUMENT_ID).unwrap().ask_sz, 9);
+
+        let mut trade = quote(2, 101, 1, 105, 5);
+        trade.action = u8::from(Action::Trade) as c_char;
+        trade.price = 101;
+        trade.size = 2;
+        trade.side = u8::from(Side::Ask) as c_char;
+        trade.ts_recv = 123;
+        let res = target.apply(&trade).unwrap();
+        assert_eq!(res.hd.rtype, rtype::CBBO);
+        assert_eq!((res.price, res.size, res.ts_recv), (101, 2, 123));
+        // The publisher with the inside ask leaving it
+        let res = target.apply(&quote(3, UNDEF_PRICE, 0, 106, 9)).unwrap();
+        assert_eq!((res.price, res.size), (101, 2));
+        assert_eq!(inside(Some(res)), Some((101, 2, 103, 1)));
+        target.clear();
+        assert!(target.cbbo(INSTRUMENT_ID).is_none());
+    }
+}
@@ -0,0 +1,185 @@
+//! Field-level comparison of records, e.g. for investigating discrepancies between
+//! two captures of the same data.
+
+use std::fmt;
+
+use crate::{
+    encode::{csv::Encoder, DbnEncodable, EncodeRecord},
+    rtype_dispatch, RecordRef,
+};
+
+/// A field whose value differs between two records.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldDiff {
+    /// The name of the field. Nested fields are flattened like in CSV, e.g.
+    /// `bid_px_00` for the bid price of the top level.
+    pub field: String,
+    /// The value of the field in the left record or `None` if the left record doesn't
+    /// have the field.
+    pub left: Option<String>,
+    /// The value of the field in the right record or `None` if the right record
+    /// doesn't have the field.
+    pub right: Option<String>,
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} != {}",
+            self.field,
+            self.left.as_deref().unwrap_or("<missing>"),
+            self.right.as_deref().unwrap_or("<missing>")
+        )
+    }
+}
+
+/// Compares `left` and `right` field by field and returns the fields that differ, in
+/// encoding order. Values are formatted as in CSV without pretty prices or
+/// timestamps, so the comparison is exact.
+///
+/// Records of different types are compared by field name, so fields only present in
+/// one of the records are reported with `None` for the other record.
+///
+/// # Errors
+/// This function returns an error if either record has an unknown rtype.
+pub fn diff_records(left: RecordRef, right: RecordRef) -> crate::Result<Vec<FieldDiff>> {
+    let left_fields = rtype_dispatch!(left, record_fields)??;
+    let right_fields = rtype_dispatch!(right, record_fields)??;
+    let mut diffs = Vec::new();
+    for (field, left_val) in left_fields.iter() {
+        let right_val = right_fields
+            .iter()
+            .find_map(|(f, val)| (f == field).then_some(val));
+        if right_val != Some(left_val) {
+            diffs.push(FieldDiff {
+                field: field.clone(),
+                left: Some(left_val.clone()),
+                right: right_val.cloned(),
+            });
+        }
+    }
+    for (field, right_val) in right_fields {
+        if !left_fields.iter().any(|(f, _)| *f == field) {
+            diffs.push(FieldDiff {
+                field,
+                left: None,
+                right: Some(right_val),
+            });
+        }
+    }
+    Ok(diffs)
+}
+
+/// Returns the name and value of each field of `rec`.
+fn record_fields<R: DbnEncodable>(rec: &R) -> crate::Result<Vec<(String, String)>> {
+    let mut encoder = Encoder::new(Vec::new(), false, false);
+    encoder.encode_header::<R>(false)?;
+    encoder.encode_record(rec)?;
+    encoder.flush()?;
+    let mut reader = csv::Reader::from_reader(encoder.get_ref().as_slice());
+    let header = reader.headers()?.clone();
+    let values = reader.records().next().transpose()?.unwrap_or_default();
+    Ok(header
+        .iter()
+        .zip(values.iter())
+        .map(|(field, val)| (field.to_owned(), val.to_owned()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_char;
+
+    use super::*;
+    use crate::{
+        enums::rtype,
+        record::{BidAskPair, MboMsg, Mbp1Msg, RecordHeader, TradeMsg},
+    };
+
+    const MBO: MboMsg = MboMsg {
+        hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 323, 1658441851000000000),
+        order_id: 17,
+        price: 372_000_000_000_000,
+        size: 10,
+        flags: 0,
+        channel_id: 1,
+        action: 'A' as c_char,
+        side: 'B' as c_char,
+        ts_recv: 1658441891000000000,
+        ts_in_delta: 160,
+        sequence: 1067,
+    };
+
+    #[test]
+    fn test_diff_identical() {
+        assert!(diff_records(RecordRef::from(&MBO), RecordRef::from(&MBO))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_diff_same_type() {
+        let mut other = MBO;
+        other.size = 11;
+        other.side = 'A' as c_char;
+        let diffs = diff_records(RecordRef::from(&MBO), RecordRef::from(&other)).unwrap();
+        assert_eq!(
+            diffs,
+            vec![
+                FieldDiff {
+                    field: "side".to_owned(),
+                    left: Some("B".to_owned()),
+                    right: Some("A".to_owned()),
+                },
+                FieldDiff {
+                    field: "size".to_owned(),
+                    left: Some("10".to_owned()),
+                    right: Some("11".to_owned()),
+                },
+            ]
+        );
+        assert_eq!(diffs[1].to_string(), "size: 10 != 11");
+    }
+
+    #[test]
+    fn test_diff_levels() {
+        let mbp1 = Mbp1Msg {
+            hd: RecordHeader::new::<Mbp1Msg>(rtype::MBP_1, 1, 323, 0),
+            levels: [BidAskPair {
+                bid_px: 100,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut other = mbp1.clone();
+        other.levels[0].bid_px = 101;
+        let diffs = diff_records(RecordRef::from(&mbp1), RecordRef::from(&other)).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].field, "bid_px_00");
+    }
+
+    #[test]
+    fn test_diff_different_types() {
+        let trade = TradeMsg {
+            hd: RecordHeader::new::<TradeMsg>(rtype::MBP_0, 1, 323, 1658441851000000000),
+            price: MBO.price,
+            size: MBO.size,
+            action: 'T' as c_char,
+            side: MBO.side,
+            flags: MBO.flags,
+            depth: 0,
+            ts_recv: MBO.ts_recv,
+            ts_in_delta: MBO.ts_in_delta,
+            sequence: MBO.sequence,
+        };
+        let diffs = diff_records(RecordRef::from(&MBO), RecordRef::from(&trade)).unwrap();
+        let fields: Vec<_> = diffs.iter().map(|diff| diff.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["rtype", "action", "channel_id", "order_id", "depth"]
+        );
+        assert!(diffs[2].right.is_none());
+        assert!(diffs[4].left.is_none());
+    }
+}
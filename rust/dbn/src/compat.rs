@@ -1,5 +1,5 @@
 //! Compatibility shims for different DBN versions.
-use std::os::raw::c_char;
+use std::{mem, os::raw::c_char};
 
 use crate::{
     macros::{dbn_record, CsvSerialize, JsonSerialize},
@@ -88,8 +88,14 @@ where
 {
     if ts_out {
         let rec = transmute_record_bytes::<WithTsOut<T>>(input).unwrap();
-        let upgraded = WithTsOut::new(U::from(&rec.rec), rec.ts_out);
-        std::ptr::copy_nonoverlapping(&upgraded, compat_buffer.as_mut_ptr().cast(), 1);
+        // Write the upgraded record and `ts_out` to the buffer separately instead of
+        // moving them through a temporary `WithTsOut`
+        let mut upgraded = U::from(&rec.rec);
+        upgraded.header_mut().length =
+            (mem::size_of::<WithTsOut<U>>() / RecordHeader::LENGTH_MULTIPLIER) as u8;
+        let output = compat_buffer.as_mut_ptr();
+        std::ptr::write_unaligned(output.cast::<U>(), upgraded);
+        std::ptr::write_unaligned(output.add(mem::size_of::<U>()).cast::<u64>(), rec.ts_out);
     } else {
         let upgraded = U::from(transmute_record_bytes::<T>(input).unwrap());
         std::ptr::copy_nonoverlapping(&upgraded, compat_buffer.as_mut_ptr().cast(), 1);
@@ -103,6 +109,7 @@ where
 #[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -344,6 +351,7 @@ pub struct InstrumentDefMsgV1 {
 #[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -368,6 +376,7 @@ pub struct ErrorMsgV1 {
 #[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -407,6 +416,7 @@ pub struct SymbolMappingMsgV1 {
 #[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -642,7 +652,7 @@ mod tests {
     use time::OffsetDateTime;
     use type_layout::{Field, TypeLayout};
 
-    use crate::{Mbp1Msg, Record, MAX_RECORD_LEN};
+    use crate::{record::str_to_c_chars, Mbp1Msg, Record, MAX_RECORD_LEN};
 
     use super::*;
 
@@ -720,6 +730,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn upgrade_instrument_def_ts_out() {
+        let ts_out = OffsetDateTime::now_utc().unix_timestamp_nanos() as u64;
+        let orig = WithTsOut::new(
+            InstrumentDefMsgV1 {
+                hd: RecordHeader::new::<InstrumentDefMsgV1>(rtype::INSTRUMENT_DEF, 1, 2, 3),
+                strike_price: 4_500_000_000_000,
+                raw_symbol: str_to_c_chars("ESZ4").unwrap(),
+                ..Default::default()
+            },
+            ts_out,
+        );
+        let mut compat_buffer = [0; MAX_RECORD_LEN];
+        let res = unsafe {
+            decode_record_ref(
+                1,
+                VersionUpgradePolicy::Upgrade,
+                true,
+                &mut compat_buffer,
+                orig.as_ref(),
+            )
+        };
+        let upgraded_len = mem::size_of::<WithTsOut<InstrumentDefMsgV2>>();
+        assert_eq!(res.record_size(), upgraded_len);
+        // used compat buffer
+        assert!(std::ptr::addr_eq(res.header(), compat_buffer.as_ptr()));
+        // `compat_buffer` isn't necessarily aligned, so read the fields unaligned
+        let upgraded = unsafe {
+            std::ptr::read_unaligned(compat_buffer.as_ptr().cast::<InstrumentDefMsgV2>())
+        };
+        assert_eq!(
+            upgraded.hd.length as usize * RecordHeader::LENGTH_MULTIPLIER,
+            upgraded_len
+        );
+        assert_eq!(upgraded.hd.ts_event, 3);
+        assert_eq!(upgraded.strike_price, orig.rec.strike_price);
+        assert_eq!(upgraded.raw_symbol().unwrap(), "ESZ4");
+        let ts_out_offset = mem::size_of::<InstrumentDefMsgV2>();
+        let upgraded_ts_out = u64::from_ne_bytes(
+            compat_buffer[ts_out_offset..ts_out_offset + mem::size_of::<u64>()]
+                .try_into()
+                .unwrap(),
+        );
+        assert_eq!(upgraded_ts_out, ts_out);
+    }
+
     #[test]
     fn upgrade_mbp1_ts_out() -> crate::Result<()> {
         let rec = Mbp1Msg {
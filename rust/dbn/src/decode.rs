@@ -493,7 +493,7 @@ impl FromLittleEndianSlice for u16 {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::io::Read;
 
     use crate::enums::VersionUpgradePolicy;
@@ -7,9 +7,18 @@ pub fn starts_with_prefix(bytes: &[u8]) -> bool {
     bytes.len() > DBN_PREFIX_LEN && &bytes[..DBN_PREFIX_LEN] == DBN_PREFIX
 }
 
+mod sections;
+mod seekable;
 mod sync;
+pub use sections::SectionDecoder;
+pub use seekable::SeekableDecoder;
 pub(crate) use sync::decode_iso8601;
-pub use sync::{Decoder, MetadataDecoder, RecordDecoder};
+pub use sync::{Decoder, LazyMetadata, MetadataDecoder, RecordBatch, RecordDecoder};
+
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+mod uring;
+#[cfg(all(feature = "io-uring", target_os = "linux"))]
+pub use uring::UringReader;
 
 #[cfg(feature = "async")]
 mod r#async;
@@ -468,6 +468,55 @@ where
     /// `tokio::select!` statement and another branch completes first, the metadata
     /// may have been partially read, corrupting the stream.
     pub async fn decode(&mut self) -> Result<Metadata> {
+        let (version, length) = self.decode_prelude().await?;
+        let mut metadata_buffer = vec![0u8; length as usize];
+        self.reader
+            .read_exact(&mut metadata_buffer)
+            .await
+            .map_err(|e| crate::Error::io(e, "reading fixed metadata"))?;
+        super::MetadataDecoder::<std::fs::File>::decode_metadata_fields(version, metadata_buffer)
+    }
+
+    /// Decodes and returns only the fixed-length fields of a DBN [`Metadata`],
+    /// skipping over the variable-length symbol sections. The `symbols`, `partial`,
+    /// `not_found`, and `mappings` of the returned metadata are always empty.
+    ///
+    /// # Errors
+    /// This function will return an error if it is unable to parse the metadata or the
+    /// input is encoded in a newer version of DBN.
+    ///
+    /// # Cancel safety
+    /// This method is not cancellation safe. If this method is used in a
+    /// `tokio::select!` statement and another branch completes first, the metadata
+    /// may have been partially read, corrupting the stream.
+    pub async fn decode_prefix(&mut self) -> Result<Metadata> {
+        let (version, length) = self.decode_prelude().await?;
+        let mut metadata_buffer = [0u8; METADATA_FIXED_LEN];
+        self.reader
+            .read_exact(&mut metadata_buffer)
+            .await
+            .map_err(|e| crate::Error::io(e, "reading fixed metadata"))?;
+        let (metadata, _) = super::MetadataDecoder::<std::fs::File>::decode_fixed_metadata_fields(
+            version,
+            &metadata_buffer,
+        )?;
+        let skip_len = length as u64 - METADATA_FIXED_LEN as u64;
+        let skipped = io::copy(
+            &mut io::AsyncReadExt::take(&mut self.reader, skip_len),
+            &mut io::sink(),
+        )
+        .await
+        .map_err(|e| crate::Error::io(e, "skipping variable-length metadata"))?;
+        if skipped != skip_len {
+            return Err(crate::Error::io(
+                std::io::Error::from(std::io::ErrorKind::UnexpectedEof),
+                "skipping variable-length metadata",
+            ));
+        }
+        Ok(metadata)
+    }
+
+    async fn decode_prelude(&mut self) -> Result<(u8, u32)> {
         let mut prelude_buffer = [0u8; 8];
         self.reader
             .read_exact(&mut prelude_buffer)
@@ -486,13 +535,7 @@ where
                 "invalid DBN metadata. Metadata length shorter than fixed length.",
             ));
         }
-
-        let mut metadata_buffer = vec![0u8; length as usize];
-        self.reader
-            .read_exact(&mut metadata_buffer)
-            .await
-            .map_err(|e| crate::Error::io(e, "reading fixed metadata"))?;
-        super::MetadataDecoder::<std::fs::File>::decode_metadata_fields(version, metadata_buffer)
+        Ok((version, length))
     }
 
     /// Returns a mutable reference to the inner reader.
@@ -0,0 +1,228 @@
+use std::{io, mem};
+
+use super::{MetadataDecoder, DBN_PREFIX, DBN_PREFIX_LEN};
+use crate::{
+    compat,
+    decode::{DecodeRecordRef, VersionUpgradePolicy},
+    error::silence_eof_error,
+    Metadata, RecordHeader, RecordRef,
+};
+
+/// Type for decoding files containing multiple concatenated DBN streams, called
+/// sections, e.g. an archive consolidating several datasets. Each section has its own
+/// [`Metadata`] and mappings.
+///
+/// Such a file can be created by encoding each section with a separate
+/// [`Encoder`](crate::encode::dbn::Encoder) to the same writer. Sections are
+/// delimited by the DBN prefix at a record boundary, which can't be mistaken for the
+/// start of a record with a known rtype.
+pub struct SectionDecoder<R>
+where
+    R: io::Read,
+{
+    reader: R,
+    upgrade_policy: VersionUpgradePolicy,
+    state: SectionState,
+    metadata: Option<Metadata>,
+    version: u8,
+    ts_out: bool,
+    read_buffer: Vec<u8>,
+    compat_buffer: [u8; crate::MAX_RECORD_LEN],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SectionState {
+    /// No section has been decoded yet.
+    Start,
+    /// Decoding the records of a section.
+    Records,
+    /// The prefix of the next section has been read.
+    NextSection,
+    /// The input is exhausted.
+    Done,
+}
+
+impl<R> SectionDecoder<R>
+where
+    R: io::Read,
+{
+    /// Creates a new [`SectionDecoder`] from `reader`. Call
+    /// [`next_section()`](Self::next_section) to decode the metadata of the first
+    /// section. Records from previous DBN versions are decoded according to
+    /// `upgrade_policy`.
+    pub fn new(reader: R, upgrade_policy: VersionUpgradePolicy) -> Self {
+        Self {
+            reader,
+            upgrade_policy,
+            state: SectionState::Start,
+            metadata: None,
+            version: crate::DBN_VERSION,
+            ts_out: false,
+            read_buffer: vec![0],
+            compat_buffer: [0; crate::MAX_RECORD_LEN],
+        }
+    }
+
+    /// Advances to the next section, skipping any remaining records of the current
+    /// section, and returns its metadata. Returns `Ok(None)` once all sections have
+    /// been decoded.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to read from the underlying reader
+    /// or to decode the metadata of the next section.
+    pub fn next_section(&mut self) -> crate::Result<Option<&Metadata>> {
+        while self.decode_record_ref()?.is_some() {}
+        let mut metadata = match self.state {
+            SectionState::Start => MetadataDecoder::new(&mut self.reader).decode()?,
+            SectionState::NextSection => {
+                MetadataDecoder::new(io::Read::chain(DBN_PREFIX, &mut self.reader)).decode()?
+            }
+            SectionState::Records | SectionState::Done => {
+                self.state = SectionState::Done;
+                self.metadata = None;
+                return Ok(None);
+            }
+        };
+        self.version = metadata.version;
+        self.ts_out = metadata.ts_out;
+        metadata.upgrade(self.upgrade_policy);
+        self.state = SectionState::Records;
+        let metadata = &*self.metadata.insert(metadata);
+        Ok(Some(metadata))
+    }
+
+    /// Returns the metadata of the current section, or `None` if
+    /// [`next_section()`](Self::next_section) hasn't been called yet or all sections
+    /// have been decoded.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the decoder and returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> DecodeRecordRef for SectionDecoder<R>
+where
+    R: io::Read,
+{
+    /// Tries to decode the next record of the current section. Returns `Ok(None)` at
+    /// the end of the section.
+    ///
+    /// # Errors
+    /// This function returns an error if the underlying reader returns an
+    /// error of a kind other than `io::ErrorKind::UnexpectedEof` upon reading.
+    /// It will also return an error if it encounters an invalid record.
+    fn decode_record_ref(&mut self) -> crate::Result<Option<RecordRef>> {
+        if self.state != SectionState::Records {
+            return Ok(None);
+        }
+        let io_err = |e| crate::Error::io(e, "decoding record reference");
+        if let Err(err) = self.reader.read_exact(&mut self.read_buffer[..1]) {
+            self.state = SectionState::Done;
+            return silence_eof_error(err).map_err(io_err);
+        }
+        let mut pos = 1;
+        if self.read_buffer[0] == DBN_PREFIX[0] {
+            self.read_buffer.resize(DBN_PREFIX_LEN, 0);
+            if let Err(err) = self.reader.read_exact(&mut self.read_buffer[1..]) {
+                self.state = SectionState::Done;
+                return silence_eof_error(err).map_err(io_err);
+            }
+            if self.read_buffer == DBN_PREFIX {
+                self.state = SectionState::NextSection;
+                return Ok(None);
+            }
+            pos = DBN_PREFIX_LEN;
+        }
+        let length = self.read_buffer[0] as usize * RecordHeader::LENGTH_MULTIPLIER;
+        if length < mem::size_of::<RecordHeader>() {
+            return Err(crate::Error::decode(format!(
+                "invalid record with length {length} shorter than header"
+            )));
+        }
+        if length > self.read_buffer.len() {
+            self.read_buffer.resize(length, 0);
+        }
+        if let Err(err) = self.reader.read_exact(&mut self.read_buffer[pos..length]) {
+            self.state = SectionState::Done;
+            return silence_eof_error(err).map_err(io_err);
+        }
+        // Safety: `read_buffer` is resized to contain at least `length` bytes.
+        Ok(Some(unsafe {
+            compat::decode_record_ref(
+                self.version,
+                self.upgrade_policy,
+                self.ts_out,
+                &mut self.compat_buffer,
+                &self.read_buffer,
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        datasets::{GLBX_MDP3, XNAS_ITCH},
+        encode::{dbn::Encoder, EncodeRecord},
+        rtype, MboMsg, MetadataBuilder, SType, Schema, TradeMsg,
+    };
+
+    fn metadata(dataset: &str, schema: Schema) -> Metadata {
+        MetadataBuilder::new()
+            .dataset(dataset.to_owned())
+            .schema(Some(schema))
+            .start(0)
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .build()
+    }
+
+    #[test]
+    fn test_decode_sections() {
+        let mut buffer = Vec::new();
+        let mbo = MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 5482, 0),
+            ..Default::default()
+        };
+        let mut encoder = Encoder::new(&mut buffer, &metadata(GLBX_MDP3, Schema::Mbo)).unwrap();
+        encoder.encode_record(&mbo).unwrap();
+        encoder.encode_record(&mbo).unwrap();
+        // Empty section
+        Encoder::new(&mut buffer, &metadata(GLBX_MDP3, Schema::Trades)).unwrap();
+        let mut encoder = Encoder::new(&mut buffer, &metadata(XNAS_ITCH, Schema::Trades)).unwrap();
+        encoder.encode_record(&TradeMsg::default()).unwrap();
+
+        let mut target = SectionDecoder::new(buffer.as_slice(), VersionUpgradePolicy::AsIs);
+        assert!(target.metadata().is_none());
+        assert_eq!(target.next_section().unwrap().unwrap().dataset, GLBX_MDP3);
+        // Only decode one of the records
+        assert_eq!(
+            target.decode_record_ref().unwrap().unwrap().get::<MboMsg>(),
+            Some(&mbo)
+        );
+        assert_eq!(
+            target.next_section().unwrap().unwrap().schema,
+            Some(Schema::Trades)
+        );
+        assert!(target.decode_record_ref().unwrap().is_none());
+        assert_eq!(target.next_section().unwrap().unwrap().dataset, XNAS_ITCH);
+        assert!(target
+            .decode_record_ref()
+            .unwrap()
+            .unwrap()
+            .has::<TradeMsg>());
+        assert!(target.decode_record_ref().unwrap().is_none());
+        assert!(target.next_section().unwrap().is_none());
+        assert!(target.metadata().is_none());
+    }
+}
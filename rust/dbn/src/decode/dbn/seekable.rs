@@ -0,0 +1,421 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read, SeekFrom},
+    mem,
+    num::NonZeroUsize,
+    panic, thread, vec,
+};
+
+use super::MetadataDecoder;
+use crate::{
+    compat,
+    decode::{
+        zstd::{decode_seek_table, SeekTableEntry},
+        DbnMetadata, DecodeRecordRef, VersionUpgradePolicy,
+    },
+    Metadata, RecordHeader, RecordRef,
+};
+
+/// The maximum capacity allocated for a decompressed frame before decompressing it.
+/// Larger frames grow their buffer as they're decompressed, so a corrupt or malicious
+/// seek table can't cause a large allocation.
+const MAX_PREALLOCATED_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Type for decoding DBN in the Zstandard seekable format, such as written by
+/// [`SeekableEncoder`](crate::encode::dbn::SeekableEncoder). Frames are decompressed
+/// `threads` at a time across threads, while records are returned in order.
+///
+/// Frames don't need to contain whole records: the metadata and records may span
+/// multiple frames, as with files compressed by other seekable Zstandard tools.
+///
+/// Memory usage is bounded by `threads` decompressed frames.
+pub struct SeekableDecoder<R>
+where
+    R: io::Read + io::Seek,
+{
+    reader: R,
+    threads: usize,
+    upgrade_policy: VersionUpgradePolicy,
+    metadata: Metadata,
+    version: u8,
+    ts_out: bool,
+    frames: vec::IntoIter<SeekTableEntry>,
+    decompressed: VecDeque<Vec<u8>>,
+    frame: Vec<u8>,
+    pos: usize,
+    compat_buffer: [u8; crate::MAX_RECORD_LEN],
+}
+
+impl<R> SeekableDecoder<R>
+where
+    R: io::Read + io::Seek,
+{
+    /// Creates a new [`SeekableDecoder`] from `reader` that decompresses up to
+    /// `threads` frames concurrently. Records from previous DBN versions are decoded
+    /// according to `upgrade_policy`.
+    ///
+    /// # Errors
+    /// This function returns an error if `reader` doesn't contain a valid seek table,
+    /// or it fails to decompress the frames containing the metadata or to decode it.
+    pub fn new(
+        mut reader: R,
+        threads: NonZeroUsize,
+        upgrade_policy: VersionUpgradePolicy,
+    ) -> crate::Result<Self> {
+        let frames = decode_seek_table(&mut reader)?;
+        // `decode_seek_table` leaves `reader` at the start of the seek table, which
+        // bounds the sizes of the frames
+        let table_pos = reader
+            .stream_position()
+            .map_err(|e| crate::Error::io(e, "getting position of seek table"))?;
+        let compressed_len: u64 = frames
+            .iter()
+            .map(|entry| u64::from(entry.compressed_size))
+            .sum();
+        if compressed_len > table_pos {
+            return Err(crate::Error::decode(format!(
+                "seek table frame sizes total {compressed_len} bytes, more than the {table_pos} bytes before it"
+            )));
+        }
+        reader
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| crate::Error::io(e, "seeking to first frame"))?;
+        let mut frames = frames.into_iter();
+        let mut frame = Vec::new();
+        // The metadata may span multiple frames
+        while metadata_len(&frame).map_or(true, |len| frame.len() < len) {
+            let Some(entry) = frames.next() else {
+                break;
+            };
+            frame.append(&mut decompress(&read_frame(&mut reader, &entry)?, &entry)?);
+        }
+        if frame.is_empty() {
+            return Err(crate::Error::decode("seek table contains no frames"));
+        }
+        let mut remaining = frame.as_slice();
+        let mut metadata = MetadataDecoder::new(&mut remaining).decode()?;
+        let pos = frame.len() - remaining.len();
+        let version = metadata.version;
+        let ts_out = metadata.ts_out;
+        metadata.upgrade(upgrade_policy);
+        Ok(Self {
+            reader,
+            threads: threads.get(),
+            upgrade_policy,
+            metadata,
+            version,
+            ts_out,
+            frames,
+            decompressed: VecDeque::new(),
+            frame,
+            pos,
+            compat_buffer: [0; crate::MAX_RECORD_LEN],
+        })
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the decoder and returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Replaces the current frame with the next decompressed frame, keeping any
+    /// partial record at the end of the current frame. Returns `false` if there are
+    /// no more frames.
+    fn next_frame(&mut self) -> crate::Result<bool> {
+        if self.decompressed.is_empty() {
+            self.decompress_frames()?;
+        }
+        let Some(mut frame) = self.decompressed.pop_front() else {
+            return Ok(false);
+        };
+        if self.pos < self.frame.len() {
+            let mut partial = self.frame.split_off(self.pos);
+            partial.append(&mut frame);
+            frame = partial;
+        }
+        self.frame = frame;
+        self.pos = 0;
+        Ok(true)
+    }
+
+    /// Reads the next `threads` frames and decompresses them concurrently.
+    fn decompress_frames(&mut self) -> crate::Result<()> {
+        let mut compressed = Vec::with_capacity(self.threads);
+        for entry in self.frames.by_ref().take(self.threads) {
+            let frame = read_frame(&mut self.reader, &entry)?;
+            compressed.push((frame, entry));
+        }
+        if compressed.len() == 1 {
+            let (frame, entry) = &compressed[0];
+            self.decompressed.push_back(decompress(frame, entry)?);
+            return Ok(());
+        }
+        let decompressed: Vec<crate::Result<Vec<u8>>> = thread::scope(|scope| {
+            let workers: Vec<_> = compressed
+                .iter()
+                .map(|(frame, entry)| scope.spawn(move || decompress(frame, entry)))
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|err| panic::resume_unwind(err))
+                })
+                .collect()
+        });
+        for frame in decompressed {
+            self.decompressed.push_back(frame?);
+        }
+        Ok(())
+    }
+}
+
+impl<R> DecodeRecordRef for SeekableDecoder<R>
+where
+    R: io::Read + io::Seek,
+{
+    /// Tries to decode the next record. Returns `Ok(None)` once all frames have been
+    /// decoded. Like [`RecordDecoder`](super::RecordDecoder), a partial record at the
+    /// end of the last frame is ignored.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to read or decompress a frame, or it
+    /// encounters an invalid record.
+    fn decode_record_ref(&mut self) -> crate::Result<Option<RecordRef>> {
+        let length = loop {
+            if let Some(&length) = self.frame.get(self.pos) {
+                let length = length as usize * RecordHeader::LENGTH_MULTIPLIER;
+                if length < mem::size_of::<RecordHeader>() {
+                    return Err(crate::Error::decode(format!(
+                        "invalid record with length {length} shorter than header"
+                    )));
+                }
+                if self.pos + length <= self.frame.len() {
+                    break length;
+                }
+            }
+            // The record continues in the next frame
+            if !self.next_frame()? {
+                return Ok(None);
+            }
+        };
+        let record = &self.frame[self.pos..self.pos + length];
+        self.pos += length;
+        // Safety: `record` contains exactly `length` bytes.
+        Ok(Some(unsafe {
+            compat::decode_record_ref(
+                self.version,
+                self.upgrade_policy,
+                self.ts_out,
+                &mut self.compat_buffer,
+                record,
+            )
+        }))
+    }
+}
+
+impl<R> DbnMetadata for SeekableDecoder<R>
+where
+    R: io::Read + io::Seek,
+{
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+fn read_frame(reader: &mut impl io::Read, entry: &SeekTableEntry) -> crate::Result<Vec<u8>> {
+    let mut frame = vec![0; entry.compressed_size as usize];
+    reader
+        .read_exact(&mut frame)
+        .map_err(|e| crate::Error::io(e, "reading frame"))?;
+    Ok(frame)
+}
+
+/// Returns the length of the encoded metadata at the start of `buffer`, or `None` if
+/// `buffer` is too short to contain the length.
+fn metadata_len(buffer: &[u8]) -> Option<usize> {
+    // After the DBN prefix and version
+    let len_bytes = buffer.get(4..8)?;
+    Some(8 + u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize)
+}
+
+fn decompress(frame: &[u8], entry: &SeekTableEntry) -> crate::Result<Vec<u8>> {
+    let expected_len = entry.decompressed_size as usize;
+    let mut decompressed = Vec::with_capacity(expected_len.min(MAX_PREALLOCATED_FRAME_LEN));
+    // Reading one byte more than expected detects a frame that's longer than its seek
+    // table entry without decompressing all of it
+    zstd::stream::read::Decoder::with_buffer(frame)
+        .map_err(|e| crate::Error::io(e, "creating Zstandard decoder"))?
+        .take(u64::from(entry.decompressed_size) + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| crate::Error::io(e, "decompressing frame"))?;
+    if decompressed.len() != expected_len {
+        return Err(crate::Error::decode(format!(
+            "decompressed frame length {} doesn't match seek table length {}",
+            decompressed.len(),
+            entry.decompressed_size
+        )));
+    }
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        datasets::GLBX_MDP3,
+        decode::{zstd::encode_seek_table, DynDecoder},
+        encode::{
+            dbn::{Encoder, SeekableEncoder},
+            EncodeDbn,
+        },
+        rtype, MboMsg, MetadataBuilder, SType, Schema,
+    };
+
+    fn metadata() -> Metadata {
+        MetadataBuilder::new()
+            .dataset(GLBX_MDP3.to_owned())
+            .schema(Some(Schema::Mbo))
+            .start(0)
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .build()
+    }
+
+    fn records() -> Vec<MboMsg> {
+        (0..100)
+            .map(|i| MboMsg {
+                hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 5482, i),
+                order_id: i,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Compresses `dbn` into frames of `frame_len` decompressed bytes regardless of
+    /// the boundaries of the metadata and records, followed by a seek table.
+    fn compress_in_chunks(dbn: &[u8], frame_len: usize) -> (Vec<u8>, Vec<SeekTableEntry>) {
+        let mut buffer = Vec::new();
+        let mut entries = Vec::new();
+        for chunk in dbn.chunks(frame_len) {
+            let frame = zstd::bulk::compress(chunk, 0).unwrap();
+            entries.push(SeekTableEntry {
+                compressed_size: frame.len() as u32,
+                decompressed_size: chunk.len() as u32,
+            });
+            buffer.extend(frame);
+        }
+        (buffer, entries)
+    }
+
+    #[rstest]
+    #[case::one_thread(1)]
+    #[case::many_threads(4)]
+    fn test_seekable_round_trip(#[case] threads: usize) {
+        let metadata = metadata();
+        let records = records();
+        let mut encoder =
+            SeekableEncoder::new(Vec::new(), &metadata, NonZeroUsize::new(7).unwrap()).unwrap();
+        encoder.encode_records(&records).unwrap();
+        let buffer = encoder.finish().unwrap();
+
+        let mut target = SeekableDecoder::new(
+            io::Cursor::new(buffer.as_slice()),
+            NonZeroUsize::new(threads).unwrap(),
+            VersionUpgradePolicy::AsIs,
+        )
+        .unwrap();
+        assert_eq!(*target.metadata(), metadata);
+        for exp in records.iter() {
+            assert_eq!(
+                target.decode_record_ref().unwrap().unwrap().get::<MboMsg>(),
+                Some(exp)
+            );
+        }
+        assert!(target.decode_record_ref().unwrap().is_none());
+
+        // Still readable by the regular Zstandard decoder
+        let mut decoder =
+            DynDecoder::inferred_with_buffer(buffer.as_slice(), VersionUpgradePolicy::AsIs)
+                .unwrap();
+        assert_eq!(*decoder.metadata(), metadata);
+        let mut count = 0;
+        while decoder.decode_record_ref().unwrap().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, records.len());
+    }
+
+    #[rstest]
+    #[case::one_thread(1)]
+    #[case::many_threads(3)]
+    fn test_frames_not_record_aligned(#[case] threads: usize) {
+        let metadata = metadata();
+        let records = records();
+        let mut dbn = Vec::new();
+        Encoder::new(&mut dbn, &metadata)
+            .unwrap()
+            .encode_records(&records)
+            .unwrap();
+        // Shorter than the metadata and not a divisor of the record length
+        let (mut buffer, entries) = compress_in_chunks(&dbn, 37);
+        encode_seek_table(&mut buffer, &entries);
+
+        let mut target = SeekableDecoder::new(
+            io::Cursor::new(buffer.as_slice()),
+            NonZeroUsize::new(threads).unwrap(),
+            VersionUpgradePolicy::AsIs,
+        )
+        .unwrap();
+        assert_eq!(*target.metadata(), metadata);
+        for exp in records.iter() {
+            assert_eq!(
+                target.decode_record_ref().unwrap().unwrap().get::<MboMsg>(),
+                Some(exp)
+            );
+        }
+        assert!(target.decode_record_ref().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_invalid_seek_table_sizes() {
+        let mut dbn = Vec::new();
+        Encoder::new(&mut dbn, &metadata())
+            .unwrap()
+            .encode_records(&records())
+            .unwrap();
+        let (frames, entries) = compress_in_chunks(&dbn, 1024);
+        let decode = |entries: &[SeekTableEntry]| {
+            let mut buffer = frames.clone();
+            encode_seek_table(&mut buffer, entries);
+            SeekableDecoder::new(
+                io::Cursor::new(buffer),
+                NonZeroUsize::new(1).unwrap(),
+                VersionUpgradePolicy::AsIs,
+            )
+            .map(|_| ())
+        };
+        assert!(decode(&entries).is_ok());
+        let mut too_long = entries.clone();
+        too_long[0].decompressed_size = u32::MAX;
+        assert!(matches!(decode(&too_long), Err(crate::Error::Decode(_))));
+        let mut too_short = entries.clone();
+        too_short[0].decompressed_size -= 1;
+        assert!(matches!(decode(&too_short), Err(crate::Error::Decode(_))));
+        let mut past_end = entries;
+        past_end[0].compressed_size = u32::MAX;
+        assert!(matches!(decode(&past_end), Err(crate::Error::Decode(_))));
+    }
+}
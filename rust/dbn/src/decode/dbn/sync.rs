@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{self, BufReader},
     mem,
@@ -14,9 +15,9 @@ use crate::{
         private::BufferSlice, DbnMetadata, DecodeRecord, DecodeRecordRef, DecodeStream,
         FromLittleEndianSlice, StreamIterDecoder, VersionUpgradePolicy,
     },
-    error::silence_eof_error,
     HasRType, MappingInterval, Metadata, Record, RecordHeader, RecordRef, SType, Schema,
-    SymbolMapping, DBN_VERSION, METADATA_FIXED_LEN, NULL_SCHEMA, NULL_STYPE, UNDEF_TIMESTAMP,
+    SymbolMapping, DBN_VERSION, METADATA_EXT_TAG_CUSTOM, METADATA_EXT_TAG_END, METADATA_FIXED_LEN,
+    NULL_SCHEMA, NULL_STYPE, UNDEF_TIMESTAMP,
 };
 
 /// Type for decoding files and streams in Databento Binary Encoding (DBN), both metadata and records.
@@ -69,6 +70,9 @@ where
     }
 
     /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading directly from the inner reader will skip records, because the decoder
+    /// reads ahead in blocks.
     pub fn get_mut(&mut self) -> &mut R {
         self.decoder.get_mut()
     }
@@ -78,8 +82,10 @@ where
         self.decoder.get_ref()
     }
 
-    /// Consumes the decoder and returns the inner reader.
-    pub fn into_inner(self) -> R {
+    /// Consumes the decoder and returns the inner reader and the data read ahead from
+    /// it that hasn't been decoded yet. The undecoded data precedes the remaining data
+    /// of the reader.
+    pub fn into_inner(self) -> (R, Vec<u8>) {
         self.decoder.into_inner()
     }
 
@@ -88,6 +94,17 @@ where
         self.metadata.upgrade(upgrade_policy);
         self.decoder.set_upgrade_policy(upgrade_policy);
     }
+
+    /// Tries to decode a batch of up to `max_records` records. See
+    /// [`RecordDecoder::decode_batch()`] for details.
+    ///
+    /// # Errors
+    /// This function returns an error if the underlying reader returns an
+    /// error of a kind other than `io::ErrorKind::UnexpectedEof` upon reading.
+    /// It will also return an error if it encounters an invalid record.
+    pub fn decode_batch(&mut self, max_records: usize) -> crate::Result<RecordBatch> {
+        self.decoder.decode_batch(max_records)
+    }
 }
 
 impl<'a, R> Decoder<zstd::stream::Decoder<'a, BufReader<R>>>
@@ -205,14 +222,36 @@ where
     }
 }
 
-/// A DBN decoder of records
+/// The number of bytes [`RecordDecoder`] tries to read from its reader at a time.
+const READ_BUFFER_LEN: usize = 64 * 1024;
+
+/// A DBN decoder of records.
+///
+/// Records are framed from an internal buffer that's filled in large blocks, rather than
+/// reading each record with separate calls, which minimizes the number of reads from
+/// the inner reader. Unlike [`BufReader`], a read isn't retried to fill the whole
+/// block, so decoding a live stream never waits for more data than the next record.
 pub struct RecordDecoder<R> {
     /// For future use with reading different DBN versions.
     version: u8,
     upgrade_policy: VersionUpgradePolicy,
     ts_out: bool,
     reader: R,
+    /// Bytes read from `reader`. `read_buffer[pos..filled]` haven't been decoded yet.
     read_buffer: Vec<u8>,
+    /// The start of the last decoded record in `read_buffer`.
+    record_start: usize,
+    pos: usize,
+    filled: usize,
+    /// The end of the current run of buffered records with the same length and rtype.
+    #[cfg(feature = "unchecked")]
+    run_end: usize,
+    #[cfg(feature = "unchecked")]
+    run_length: usize,
+    /// The offsets of the records of the last batch.
+    batch_offsets: Vec<usize>,
+    /// The records of the last batch when they're upgraded from a previous version.
+    batch_buffer: Vec<u8>,
     compat_buffer: [u8; crate::MAX_RECORD_LEN],
 }
 
@@ -249,8 +288,16 @@ where
             upgrade_policy,
             reader,
             ts_out,
-            // `read_buffer` should have capacity for reading `length`
-            read_buffer: vec![0],
+            read_buffer: vec![0; READ_BUFFER_LEN],
+            record_start: 0,
+            pos: 0,
+            filled: 0,
+            #[cfg(feature = "unchecked")]
+            run_end: 0,
+            #[cfg(feature = "unchecked")]
+            run_length: 0,
+            batch_offsets: Vec::new(),
+            batch_buffer: Vec::new(),
             compat_buffer: [0; crate::MAX_RECORD_LEN],
         })
     }
@@ -280,6 +327,9 @@ where
     }
 
     /// Returns a mutable reference to the inner reader.
+    ///
+    /// Reading directly from the inner reader will skip records, because the decoder
+    /// reads ahead in blocks.
     pub fn get_mut(&mut self) -> &mut R {
         &mut self.reader
     }
@@ -289,9 +339,20 @@ where
         &self.reader
     }
 
-    /// Consumes the decoder and returns the inner reader.
-    pub fn into_inner(self) -> R {
-        self.reader
+    /// Consumes the decoder and returns the inner reader and the data read ahead from
+    /// it that hasn't been decoded yet. The undecoded data precedes the remaining data
+    /// of the reader.
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        let mut read_buffer = self.read_buffer;
+        read_buffer.copy_within(self.pos..self.filled, 0);
+        read_buffer.truncate(self.filled - self.pos);
+        (self.reader, read_buffer)
+    }
+
+    /// Returns the data read from the inner reader that hasn't been decoded yet. This
+    /// can be used to determine how far the decoder has decoded into the inner reader.
+    pub fn buffer(&self) -> &[u8] {
+        &self.read_buffer[self.pos..self.filled]
     }
 
     /// Tries to decode the next record of type `T`. Returns `Ok(None)` if
@@ -328,33 +389,192 @@ where
     /// error of a kind other than `io::ErrorKind::UnexpectedEof` upon reading.
     /// It will also return an error if it encounters an invalid record.
     pub fn decode_ref(&mut self) -> crate::Result<Option<RecordRef>> {
-        let io_err = |e| crate::Error::io(e, "decoding record reference");
-        if let Err(err) = self.reader.read_exact(&mut self.read_buffer[..1]) {
-            return silence_eof_error(err).map_err(io_err);
+        if !self.fill_buffer(1)? {
+            return Ok(None);
         }
-        let length = self.read_buffer[0] as usize * RecordHeader::LENGTH_MULTIPLIER;
+        let length = self.read_buffer[self.pos] as usize * RecordHeader::LENGTH_MULTIPLIER;
         if length < mem::size_of::<RecordHeader>() {
             return Err(crate::Error::decode(format!(
                 "invalid record with length {length} shorter than header"
             )));
         }
-        if length > self.read_buffer.len() {
-            self.read_buffer.resize(length, 0);
-        }
-        if let Err(err) = self.reader.read_exact(&mut self.read_buffer[1..length]) {
-            return silence_eof_error(err).map_err(io_err);
+        if !self.fill_buffer(length)? {
+            return Ok(None);
         }
-        // Safety: `read_buffer` is resized to contain at least `length` bytes.
+        self.record_start = self.pos;
+        self.pos += length;
+        // Safety: `fill_buffer` ensures the slice contains exactly `length` bytes.
         Ok(Some(unsafe {
             compat::decode_record_ref(
                 self.version,
                 self.upgrade_policy,
                 self.ts_out,
                 &mut self.compat_buffer,
-                &self.read_buffer,
+                &self.read_buffer[self.record_start..self.pos],
             )
         }))
     }
+
+    /// Tries to decode a generic reference a record like [`decode_ref()`](Self::decode_ref),
+    /// but only validates the first record of each run of records with the same
+    /// length and rtype. The rest of the run that's already buffered is returned
+    /// without any further checks. Requires the `unchecked` feature.
+    ///
+    /// This is only intended for trusted input, such as files written by the
+    /// application itself. Records that are upgraded from a previous DBN version are
+    /// always fully validated.
+    ///
+    /// # Errors
+    /// This function returns an error if the underlying reader returns an
+    /// error of a kind other than `io::ErrorKind::UnexpectedEof` upon reading.
+    /// It will also return an error if the first record of a run is invalid.
+    #[cfg(feature = "unchecked")]
+    pub fn decode_ref_unchecked(&mut self) -> crate::Result<Option<RecordRef>> {
+        if self.pos < self.run_end {
+            self.record_start = self.pos;
+            self.pos += self.run_length;
+            // Safety: the run only contains complete aligned records of `run_length`
+            // bytes.
+            return Ok(Some(unsafe {
+                RecordRef::new(&self.read_buffer[self.record_start..self.pos])
+            }));
+        }
+        if self.version < DBN_VERSION && self.upgrade_policy == VersionUpgradePolicy::Upgrade {
+            return self.decode_ref();
+        }
+        if self.decode_ref()?.is_none() {
+            return Ok(None);
+        }
+        let run_length = self.pos - self.record_start;
+        let run_header = &self.read_buffer[self.record_start..self.record_start + 2];
+        let mut run_end = self.pos;
+        if run_length % mem::align_of::<RecordHeader>() == 0 {
+            while run_end + run_length <= self.filled
+                && self.read_buffer[run_end..run_end + 2] == *run_header
+            {
+                run_end += run_length;
+            }
+        }
+        self.run_length = run_length;
+        self.run_end = run_end;
+        // Safety: `decode_ref` validated the record.
+        Ok(Some(unsafe {
+            RecordRef::new(&self.read_buffer[self.record_start..self.pos])
+        }))
+    }
+
+    /// Tries to decode a batch of up to `max_records` records, returning a view of the
+    /// records in the decoder's internal buffer. Returns an empty batch if the reader
+    /// is exhausted.
+    ///
+    /// Only the first record of the batch may be read from the inner reader, the rest
+    /// are the records that are already buffered. The batch therefore never waits for
+    /// more data than one record and can contain fewer than `max_records` records
+    /// even if the reader isn't exhausted.
+    ///
+    /// # Errors
+    /// This function returns an error if the underlying reader returns an
+    /// error of a kind other than `io::ErrorKind::UnexpectedEof` upon reading.
+    /// It will also return an error if it encounters an invalid record.
+    pub fn decode_batch(&mut self, max_records: usize) -> crate::Result<RecordBatch> {
+        // Upgraded records are copied out of `compat_buffer`
+        let is_upgrading =
+            self.version < DBN_VERSION && self.upgrade_policy == VersionUpgradePolicy::Upgrade;
+        let mut offsets = mem::take(&mut self.batch_offsets);
+        let mut upgraded = mem::take(&mut self.batch_buffer);
+        offsets.clear();
+        upgraded.clear();
+        let mut start = None;
+        while offsets.len() < max_records && (offsets.is_empty() || self.has_buffered_record()) {
+            let Some(rec) = self.decode_ref()? else {
+                break;
+            };
+            if is_upgrading {
+                offsets.push(upgraded.len());
+                upgraded.extend_from_slice(rec.as_ref());
+            } else {
+                let start = *start.get_or_insert(self.record_start);
+                offsets.push(self.record_start - start);
+            }
+        }
+        self.batch_offsets = offsets;
+        self.batch_buffer = upgraded;
+        let buffer = if is_upgrading {
+            self.batch_buffer.as_slice()
+        } else {
+            &self.read_buffer[start.unwrap_or(self.pos)..self.pos]
+        };
+        Ok(RecordBatch {
+            buffer,
+            offsets: &self.batch_offsets,
+        })
+    }
+
+    /// Tries to decode the next record and appends it to `buffer`, returning the
+    /// length of the record in bytes. Returns `Ok(None)` if the reader is exhausted.
+    ///
+    /// Unlike with [`decode_ref()`](Self::decode_ref), the decoded records remain valid
+    /// after subsequent calls, so a batch of records can be decoded into a `buffer`
+    /// that's cleared and reused for the next batch without allocating for each
+    /// record. The records in `buffer` can be decoded again with a `RecordDecoder` over
+    /// `buffer.as_slice()`.
+    ///
+    /// # Errors
+    /// This function returns an error if the underlying reader returns an
+    /// error of a kind other than `io::ErrorKind::UnexpectedEof` upon reading.
+    /// It will also return an error if it encounters an invalid record.
+    pub fn decode_ref_into(&mut self, buffer: &mut Vec<u8>) -> crate::Result<Option<usize>> {
+        let Some(rec_ref) = self.decode_ref()? else {
+            return Ok(None);
+        };
+        let bytes = rec_ref.as_ref();
+        buffer.extend_from_slice(bytes);
+        Ok(Some(bytes.len()))
+    }
+
+    /// Returns `true` if the next record is completely buffered and aligned.
+    fn has_buffered_record(&self) -> bool {
+        let buffered = self.filled - self.pos;
+        self.pos % mem::align_of::<RecordHeader>() == 0
+            && buffered > 0
+            && buffered >= self.read_buffer[self.pos] as usize * RecordHeader::LENGTH_MULTIPLIER
+    }
+
+    /// Reads from the inner reader until at least `len` undecoded bytes are buffered.
+    /// Returns `Ok(false)` if the reader is exhausted first, leaving the bytes read so
+    /// far in the buffer.
+    fn fill_buffer(&mut self, len: usize) -> crate::Result<bool> {
+        let buffered = self.filled - self.pos;
+        let is_aligned = self.pos % mem::align_of::<RecordHeader>() == 0;
+        if buffered >= len && is_aligned {
+            return Ok(true);
+        }
+        // Move the undecoded bytes to the front of the buffer to make room and so the
+        // next record is aligned
+        if !is_aligned || self.pos + len > self.read_buffer.len() {
+            self.read_buffer.copy_within(self.pos..self.filled, 0);
+            self.record_start = 0;
+            self.pos = 0;
+            self.filled = buffered;
+            #[cfg(feature = "unchecked")]
+            {
+                self.run_end = 0;
+            }
+        }
+        if len > self.read_buffer.len() {
+            self.read_buffer.resize(len, 0);
+        }
+        while self.filled - self.pos < len {
+            match self.reader.read(&mut self.read_buffer[self.filled..]) {
+                Ok(0) => return Ok(false),
+                Ok(n) => self.filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => (),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+                Err(err) => return Err(crate::Error::io(err, "decoding record reference")),
+            }
+        }
+        Ok(true)
+    }
 }
 
 impl<R> DecodeRecordRef for RecordDecoder<R>
@@ -371,7 +591,49 @@ where
     R: io::Read,
 {
     fn buffer_slice(&self) -> &[u8] {
-        self.read_buffer.as_slice()
+        &self.read_buffer[self.record_start..]
+    }
+}
+
+/// A batch of records returned by [`RecordDecoder::decode_batch()`] that borrows
+/// the decoder's internal buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordBatch<'a> {
+    buffer: &'a [u8],
+    offsets: &'a [usize],
+}
+
+impl<'a> RecordBatch<'a> {
+    /// Returns the number of records in the batch.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if the batch contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the contiguous bytes of all the records in the batch.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.buffer
+    }
+
+    /// Returns a reference to the record at `index` or `None` if `index` is out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<RecordRef<'a>> {
+        let offset = *self.offsets.get(index)?;
+        // Safety: every offset is the start of a complete aligned record.
+        Some(unsafe { RecordRef::new(&self.buffer[offset..]) })
+    }
+
+    /// Returns an iterator over references to the records in the batch.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = RecordRef<'a>> + 'a {
+        let buffer = self.buffer;
+        self.offsets
+            .iter()
+            // Safety: every offset is the start of a complete aligned record.
+            .map(move |&offset| unsafe { RecordRef::new(&buffer[offset..]) })
     }
 }
 
@@ -399,6 +661,70 @@ where
     /// # Errors
     /// This function will return an error if it is unable to parse the metadata.
     pub fn decode(&mut self) -> crate::Result<Metadata> {
+        let (version, length) = self.decode_prelude()?;
+        let mut metadata_buffer = vec![0u8; length as usize];
+        self.reader
+            .read_exact(&mut metadata_buffer)
+            .map_err(|e| crate::Error::io(e, "reading fixed metadata"))?;
+        Self::decode_metadata_fields(version, metadata_buffer)
+    }
+
+    /// Decodes and returns only the fixed-length fields of a DBN [`Metadata`],
+    /// skipping over the variable-length symbol sections. This is faster than
+    /// [`decode()`](Self::decode) for large metadata when the symbols aren't needed.
+    ///
+    /// The `symbols`, `partial`, `not_found`, and `mappings` of the returned metadata
+    /// are always empty. The reader is left positioned at the start of the records.
+    ///
+    /// # Errors
+    /// This function will return an error if it is unable to parse the metadata.
+    pub fn decode_prefix(&mut self) -> crate::Result<Metadata> {
+        let (version, length) = self.decode_prelude()?;
+        let mut metadata_buffer = [0u8; METADATA_FIXED_LEN];
+        self.reader
+            .read_exact(&mut metadata_buffer)
+            .map_err(|e| crate::Error::io(e, "reading fixed metadata"))?;
+        let (metadata, _) = Self::decode_fixed_metadata_fields(version, &metadata_buffer)?;
+        let skip_len = length as u64 - METADATA_FIXED_LEN as u64;
+        let skipped = io::copy(
+            &mut io::Read::take(&mut self.reader, skip_len),
+            &mut io::sink(),
+        )
+        .map_err(|e| crate::Error::io(e, "skipping variable-length metadata"))?;
+        if skipped != skip_len {
+            return Err(crate::Error::io(
+                io::Error::from(io::ErrorKind::UnexpectedEof),
+                "skipping variable-length metadata",
+            ));
+        }
+        Ok(metadata)
+    }
+
+    /// Decodes the fixed-length fields of a DBN [`Metadata`] and defers parsing the
+    /// variable-length symbol sections until they're first accessed through
+    /// [`LazyMetadata::metadata()`]. This is faster than [`decode()`](Self::decode)
+    /// when only the fixed-length fields are needed for most inputs.
+    ///
+    /// # Errors
+    /// This function will return an error if it is unable to read the metadata or
+    /// parse its fixed-length fields.
+    pub fn decode_lazy(&mut self) -> crate::Result<LazyMetadata> {
+        let (version, length) = self.decode_prelude()?;
+        let mut metadata_buffer = vec![0u8; length as usize];
+        self.reader
+            .read_exact(&mut metadata_buffer)
+            .map_err(|e| crate::Error::io(e, "reading fixed metadata"))?;
+        let (metadata, pos) = Self::decode_fixed_metadata_fields(version, &metadata_buffer)?;
+        Ok(LazyMetadata {
+            metadata,
+            buffer: Some(metadata_buffer),
+            pos,
+        })
+    }
+
+    /// Decodes the prelude of the metadata and returns the DBN version and the length
+    /// of the rest of the metadata.
+    fn decode_prelude(&mut self) -> crate::Result<(u8, u32)> {
         let mut prelude_buffer = [0u8; 8];
         self.reader
             .read_exact(&mut prelude_buffer)
@@ -416,14 +742,44 @@ where
                 "invalid DBN metadata. Metadata length shorter than fixed length.",
             ));
         }
-        let mut metadata_buffer = vec![0u8; length as usize];
-        self.reader
-            .read_exact(&mut metadata_buffer)
-            .map_err(|e| crate::Error::io(e, "reading fixed metadata"))?;
-        Self::decode_metadata_fields(version, metadata_buffer)
+        Ok((version, length))
     }
 
     pub(super) fn decode_metadata_fields(version: u8, buffer: Vec<u8>) -> crate::Result<Metadata> {
+        let (mut metadata, pos) = Self::decode_fixed_metadata_fields(version, &buffer)?;
+        Self::decode_variable_metadata_fields(&mut metadata, &buffer, pos)?;
+        Ok(metadata)
+    }
+
+    /// Decodes the variable-length sections of the metadata in `buffer` starting at
+    /// `pos` into `metadata`.
+    fn decode_variable_metadata_fields(
+        metadata: &mut Metadata,
+        buffer: &[u8],
+        mut pos: usize,
+    ) -> crate::Result<()> {
+        let schema_definition_length = u32::from_le_slice(&buffer[pos..]);
+        if schema_definition_length != 0 {
+            return Err(crate::Error::decode(
+                "this version of dbn can't parse schema definitions",
+            ));
+        }
+        pos += Self::U32_SIZE + (schema_definition_length as usize);
+        let symbol_cstr_len = metadata.symbol_cstr_len;
+        metadata.symbols = Self::decode_repeated_symbol_cstr(symbol_cstr_len, buffer, &mut pos)?;
+        metadata.partial = Self::decode_repeated_symbol_cstr(symbol_cstr_len, buffer, &mut pos)?;
+        metadata.not_found = Self::decode_repeated_symbol_cstr(symbol_cstr_len, buffer, &mut pos)?;
+        metadata.mappings = Self::decode_symbol_mappings(symbol_cstr_len, buffer, &mut pos)?;
+        metadata.custom = Self::decode_custom(buffer, &mut pos)?;
+        Ok(())
+    }
+
+    /// Decodes the first [`METADATA_FIXED_LEN`] bytes of `buffer` and returns the
+    /// metadata without any symbols and the position after the fixed-length fields.
+    pub(super) fn decode_fixed_metadata_fields(
+        version: u8,
+        buffer: &[u8],
+    ) -> crate::Result<(Metadata, usize)> {
         const U64_SIZE: usize = mem::size_of::<u64>();
         let mut pos = 0;
         let dataset = std::str::from_utf8(&buffer[pos..pos + crate::METADATA_DATASET_CSTR_LEN])
@@ -479,41 +835,31 @@ where
         } else {
             crate::METADATA_RESERVED_LEN
         };
-        let schema_definition_length = u32::from_le_slice(&buffer[pos..]);
-        if schema_definition_length != 0 {
-            return Err(crate::Error::decode(
-                "this version of dbn can't parse schema definitions",
-            ));
-        }
-        pos += Self::U32_SIZE + (schema_definition_length as usize);
-        let symbols =
-            Self::decode_repeated_symbol_cstr(symbol_cstr_len, buffer.as_slice(), &mut pos)?;
-        let partial =
-            Self::decode_repeated_symbol_cstr(symbol_cstr_len, buffer.as_slice(), &mut pos)?;
-        let not_found =
-            Self::decode_repeated_symbol_cstr(symbol_cstr_len, buffer.as_slice(), &mut pos)?;
-        let mappings = Self::decode_symbol_mappings(symbol_cstr_len, buffer.as_slice(), &mut pos)?;
-
-        Ok(Metadata {
-            version,
-            dataset,
-            schema,
-            stype_in,
-            stype_out,
-            start,
-            end: if end == UNDEF_TIMESTAMP {
-                None
-            } else {
-                NonZeroU64::new(end)
+
+        Ok((
+            Metadata {
+                version,
+                dataset,
+                schema,
+                stype_in,
+                stype_out,
+                start,
+                end: if end == UNDEF_TIMESTAMP {
+                    None
+                } else {
+                    NonZeroU64::new(end)
+                },
+                limit,
+                ts_out,
+                symbol_cstr_len,
+                symbols: Vec::new(),
+                partial: Vec::new(),
+                not_found: Vec::new(),
+                mappings: Vec::new(),
+                custom: BTreeMap::new(),
             },
-            limit,
-            ts_out,
-            symbol_cstr_len,
-            symbols,
-            partial,
-            not_found,
-            mappings,
-        })
+            pos,
+        ))
     }
 
     fn decode_repeated_symbol_cstr(
@@ -621,6 +967,50 @@ where
         })
     }
 
+    /// Decodes the optional extension section after the symbol mappings. Entries with
+    /// unknown tags are skipped and the section ends at the end tag, which also matches
+    /// any zero padding, or the end of the buffer.
+    fn decode_custom(buffer: &[u8], pos: &mut usize) -> crate::Result<BTreeMap<String, String>> {
+        const U16_SIZE: usize = mem::size_of::<u16>();
+        let mut res = BTreeMap::new();
+        while *pos + U16_SIZE <= buffer.len() {
+            let tag = u16::from_le_slice(&buffer[*pos..]);
+            if tag == METADATA_EXT_TAG_END {
+                break;
+            }
+            if *pos + U16_SIZE + Self::U32_SIZE > buffer.len() {
+                return Err(crate::Error::decode(
+                    "unexpected end of metadata buffer in extension header",
+                ));
+            }
+            let length = u32::from_le_slice(&buffer[*pos + U16_SIZE..]) as usize;
+            *pos += U16_SIZE + Self::U32_SIZE;
+            if *pos + length > buffer.len() {
+                return Err(crate::Error::decode(format!(
+                    "unexpected end of metadata buffer in extension with tag {tag}"
+                )));
+            }
+            let value = &buffer[*pos..*pos + length];
+            *pos += length;
+            if tag != METADATA_EXT_TAG_CUSTOM {
+                continue;
+            }
+            if value.len() < U16_SIZE {
+                return Err(crate::Error::decode("invalid custom metadata property"));
+            }
+            let key_len = u16::from_le_slice(value) as usize;
+            if U16_SIZE + key_len > value.len() {
+                return Err(crate::Error::decode("invalid custom metadata property"));
+            }
+            let key = std::str::from_utf8(&value[U16_SIZE..U16_SIZE + key_len])
+                .map_err(|e| crate::Error::utf8(e, "decoding custom metadata key"))?;
+            let value = std::str::from_utf8(&value[U16_SIZE + key_len..])
+                .map_err(|e| crate::Error::utf8(e, "decoding custom metadata value"))?;
+            res.insert(key.to_owned(), value.to_owned());
+        }
+        Ok(res)
+    }
+
     fn decode_symbol(
         symbol_cstr_len: usize,
         buffer: &[u8],
@@ -646,6 +1036,52 @@ where
     }
 }
 
+/// DBN [`Metadata`] whose variable-length symbol sections are only parsed when first
+/// accessed. Created with [`MetadataDecoder::decode_lazy()`].
+#[derive(Debug, Clone)]
+pub struct LazyMetadata {
+    metadata: Metadata,
+    /// The raw metadata until its variable-length sections are parsed.
+    buffer: Option<Vec<u8>>,
+    /// The position of the variable-length sections in `buffer`.
+    pos: usize,
+}
+
+impl LazyMetadata {
+    /// Returns the metadata without parsing the variable-length sections. Until
+    /// [`metadata()`](Self::metadata) is called, `symbols`, `partial`, `not_found`,
+    /// `mappings`, and `custom` are empty.
+    pub fn prefix(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Returns the full metadata, parsing the variable-length sections on the first
+    /// call.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to parse the variable-length sections.
+    pub fn metadata(&mut self) -> crate::Result<&Metadata> {
+        if let Some(buffer) = &self.buffer {
+            MetadataDecoder::<io::Empty>::decode_variable_metadata_fields(
+                &mut self.metadata,
+                buffer,
+                self.pos,
+            )?;
+            self.buffer = None;
+        }
+        Ok(&self.metadata)
+    }
+
+    /// Consumes the lazy metadata and returns the full metadata.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to parse the variable-length sections.
+    pub fn into_metadata(mut self) -> crate::Result<Metadata> {
+        self.metadata()?;
+        Ok(self.metadata)
+    }
+}
+
 pub(crate) fn decode_iso8601(raw: u32) -> Result<time::Date, String> {
     let year = raw / 10_000;
     let remaining = raw % 10_000;
@@ -662,7 +1098,7 @@ pub(crate) fn decode_iso8601(raw: u32) -> Result<time::Date, String> {
 
 #[cfg(test)]
 mod tests {
-    use std::fs::File;
+    use std::{fs::File, io::Read};
 
     use rstest::rstest;
 
@@ -998,6 +1434,167 @@ mod tests {
         assert!(decoder.decode_record_ref().unwrap().is_none());
     }
 
+    /// A reader that returns at most `max_read` bytes per read and counts the reads.
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        max_read: usize,
+        read_count: usize,
+    }
+
+    impl io::Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.read_count += 1;
+            let len = buf.len().min(self.max_read).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    #[rstest]
+    #[case::whole_blocks(usize::MAX)]
+    #[case::partial_records(7)]
+    fn test_decode_ref_reads_in_blocks(#[case] max_read: usize) {
+        let records: Vec<_> = (0..2000)
+            .map(|i| MboMsg {
+                hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, i),
+                order_id: i,
+                ..Default::default()
+            })
+            .collect();
+        let mut buffer = Vec::new();
+        let mut encoder = DbnRecordEncoder::new(&mut buffer);
+        encoder.encode_records(&records).unwrap();
+        // Truncated record at the end
+        buffer.extend_from_slice(&records[0].as_ref()[..10]);
+
+        let mut target = RecordDecoder::new(ChunkedReader {
+            data: &buffer,
+            max_read,
+            read_count: 0,
+        });
+        for exp in records.iter() {
+            assert_eq!(target.decode::<MboMsg>().unwrap(), Some(exp));
+        }
+        assert!(target.decode_ref().unwrap().is_none());
+        assert_eq!(target.buffer().len(), 10);
+        if max_read == usize::MAX {
+            let exp_reads = buffer.len().div_ceil(READ_BUFFER_LEN) + 1;
+            assert!(target.get_ref().read_count <= exp_reads);
+        }
+    }
+
+    #[cfg(feature = "unchecked")]
+    #[rstest]
+    #[case::whole_blocks(usize::MAX)]
+    #[case::partial_records(7)]
+    fn test_decode_ref_unchecked(#[case] max_read: usize) {
+        let mut buffer = Vec::new();
+        let mut encoder = DbnRecordEncoder::new(&mut buffer);
+        for i in 0..3000 {
+            // Runs of different lengths with the same rtype
+            if i % 1000 < 500 {
+                encoder
+                    .encode_record(&MboMsg {
+                        hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, i),
+                        ..Default::default()
+                    })
+                    .unwrap();
+            } else {
+                encoder
+                    .encode_record(&TradeMsg {
+                        hd: RecordHeader::new::<TradeMsg>(rtype::MBO, 1, 1, i),
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+        }
+        let mut expected = RecordDecoder::new(buffer.as_slice());
+        let mut target = RecordDecoder::new(ChunkedReader {
+            data: &buffer,
+            max_read,
+            read_count: 0,
+        });
+        let mut i = 0;
+        while let Some(exp) = expected.decode_ref().unwrap() {
+            // Mixing in checked decoding shouldn't affect the runs
+            let rec = if i % 7 == 0 {
+                target.decode_ref().unwrap().unwrap()
+            } else {
+                target.decode_ref_unchecked().unwrap().unwrap()
+            };
+            assert_eq!(rec, exp);
+            i += 1;
+        }
+        assert_eq!(i, 3000);
+        assert!(target.decode_ref_unchecked().unwrap().is_none());
+    }
+
+    #[rstest]
+    #[case::whole_blocks(usize::MAX)]
+    #[case::partial_records(7)]
+    fn test_decode_batch(#[case] max_read: usize) {
+        let mut buffer = Vec::new();
+        let mut encoder = DbnRecordEncoder::new(&mut buffer);
+        for i in 0..3000 {
+            if i % 3 == 0 {
+                encoder
+                    .encode_record(&ErrorMsg::new(i, "batch", true))
+                    .unwrap();
+            } else {
+                encoder
+                    .encode_record(&MboMsg {
+                        hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, i),
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+        }
+        let mut expected = RecordDecoder::new(buffer.as_slice());
+        let mut target = RecordDecoder::new(ChunkedReader {
+            data: &buffer,
+            max_read,
+            read_count: 0,
+        });
+        let mut count = 0;
+        loop {
+            let batch = target.decode_batch(100).unwrap();
+            if batch.is_empty() {
+                break;
+            }
+            assert!(batch.len() <= 100);
+            assert_eq!(batch.iter().len(), batch.len());
+            let mut byte_count = 0;
+            for (i, rec) in batch.iter().enumerate() {
+                assert_eq!(batch.get(i), Some(rec));
+                assert_eq!(rec, expected.decode_ref().unwrap().unwrap());
+                byte_count += rec.record_size();
+            }
+            assert_eq!(batch.as_bytes().len(), byte_count);
+            count += batch.len();
+        }
+        assert_eq!(count, 3000);
+        assert!(expected.decode_ref().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decode_batch_upgrade() -> crate::Result<()> {
+        let path = format!("{TEST_DATA_PATH}/test_data.definition.v1.dbn");
+        let mut expected = Decoder::from_file(&path)?;
+        expected.set_upgrade_policy(VersionUpgradePolicy::Upgrade);
+        let mut target = Decoder::from_file(&path)?;
+        target.set_upgrade_policy(VersionUpgradePolicy::Upgrade);
+        let batch = target.decode_batch(10)?;
+        assert_eq!(batch.len(), 2);
+        for rec in batch.iter() {
+            let exp = expected.decode_record_ref()?.unwrap();
+            assert_eq!(rec, exp);
+            assert!(rec.get::<InstrumentDefMsg>().is_some());
+        }
+        assert!(target.decode_batch(10)?.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_decode_record_0_length() {
         let buf = vec![0];
@@ -1052,6 +1649,111 @@ mod tests {
         assert_eq!(count, 8);
     }
 
+    #[rstest]
+    #[case::v1("test_data.mbo.v1.dbn")]
+    #[case::v2("test_data.mbo.dbn")]
+    fn test_decode_prefix(#[case] file_name: &str) -> crate::Result<()> {
+        let path = format!("{TEST_DATA_PATH}/{file_name}");
+        let mut expected = MetadataDecoder::new(File::open(&path).unwrap()).decode()?;
+        assert!(!expected.symbols.is_empty());
+        expected.symbols.clear();
+        expected.partial.clear();
+        expected.not_found.clear();
+        expected.mappings.clear();
+        let mut target = MetadataDecoder::new(File::open(&path).unwrap());
+        assert_eq!(target.decode_prefix()?, expected);
+        // Should be positioned at the first record
+        let mut record_decoder = RecordDecoder::new(target.into_inner());
+        assert!(record_decoder.decode::<MboMsg>()?.is_some());
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::v1("test_data.mbo.v1.dbn")]
+    #[case::v2("test_data.mbo.dbn")]
+    fn test_decode_lazy(#[case] file_name: &str) -> crate::Result<()> {
+        let path = format!("{TEST_DATA_PATH}/{file_name}");
+        let expected = MetadataDecoder::new(File::open(&path).unwrap()).decode()?;
+        let expected_prefix = MetadataDecoder::new(File::open(&path).unwrap()).decode_prefix()?;
+        let mut target = MetadataDecoder::new(File::open(&path).unwrap());
+        let mut lazy = target.decode_lazy()?;
+        assert_eq!(*lazy.prefix(), expected_prefix);
+        assert_eq!(*lazy.metadata()?, expected);
+        assert_eq!(lazy.into_metadata()?, expected);
+        // Should be positioned at the first record
+        let mut record_decoder = RecordDecoder::new(target.into_inner());
+        assert!(record_decoder.decode::<MboMsg>()?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_inner_returns_undecoded() -> crate::Result<()> {
+        let path = format!("{TEST_DATA_PATH}/test_data.mbo.dbn");
+        let expected = Decoder::from_file(&path)?.decode_records::<MboMsg>()?;
+        let mut target = Decoder::from_file(&path)?;
+        assert_eq!(target.decode_record::<MboMsg>()?.unwrap(), &expected[0]);
+        let (reader, undecoded) = target.into_inner();
+        assert!(!undecoded.is_empty());
+        let mut record_decoder = RecordDecoder::new(undecoded.as_slice().chain(reader));
+        let mut records = Vec::new();
+        while let Some(rec) = record_decoder.decode::<MboMsg>()? {
+            records.push(rec.clone());
+        }
+        assert_eq!(records, expected[1..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_ref_into() -> crate::Result<()> {
+        let mut decoder = Decoder::from_file(format!("{TEST_DATA_PATH}/test_data.mbo.dbn"))?;
+        let expected = decoder.decode_records::<MboMsg>()?;
+        let mut target =
+            RecordDecoder::new(File::open(format!("{TEST_DATA_PATH}/test_data.mbo.dbn")).unwrap());
+        MetadataDecoder::new(target.get_mut()).decode()?;
+        let mut buffer = Vec::with_capacity(mem::size_of::<MboMsg>() * expected.len());
+        let capacity = buffer.capacity();
+        while let Some(len) = target.decode_ref_into(&mut buffer)? {
+            assert_eq!(len, mem::size_of::<MboMsg>());
+        }
+        assert_eq!(buffer.capacity(), capacity);
+        let mut res_decoder = RecordDecoder::new(buffer.as_slice());
+        for rec in expected {
+            assert_eq!(res_decoder.decode::<MboMsg>()?, Some(&rec));
+        }
+        assert!(res_decoder.decode_ref()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_skips_unknown_metadata_extensions() -> crate::Result<()> {
+        let metadata = MetadataBuilder::new()
+            .dataset(XNAS_ITCH.to_owned())
+            .schema(Some(Schema::Mbo))
+            .start(0)
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .custom(BTreeMap::from([(
+                "request_id".to_owned(),
+                "abc".to_owned(),
+            )]))
+            .build();
+        let mut buffer = Vec::new();
+        crate::encode::dbn::MetadataEncoder::new(&mut buffer).encode(&metadata)?;
+        // Insert an extension with an unknown tag before the custom property
+        let ext_pos = buffer.len()
+            - (mem::size_of::<u16>() + mem::size_of::<u32>()) * 2
+            - mem::size_of::<u16>()
+            - "request_id".len()
+            - "abc".len();
+        let unknown_ext = [0xFF, 0xFF, 3, 0, 0, 0, b'x', b'y', b'z'];
+        buffer.splice(ext_pos..ext_pos, unknown_ext);
+        let length = u32::from_le_slice(&buffer[4..]) + unknown_ext.len() as u32;
+        buffer[4..8].copy_from_slice(&length.to_le_bytes());
+        let res = MetadataDecoder::new(buffer.as_slice()).decode()?;
+        assert_eq!(res, metadata);
+        Ok(())
+    }
+
     #[test]
     fn test_decode_upgrade() -> crate::Result<()> {
         let decoder = Decoder::with_upgrade_policy(
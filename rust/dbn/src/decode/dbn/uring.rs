@@ -0,0 +1,298 @@
+use std::{
+    fs::File,
+    io, mem,
+    os::unix::{fs::FileExt, io::AsRawFd},
+    path::Path,
+};
+
+use io_uring::{opcode, types, IoUring};
+
+use super::Decoder;
+
+/// The length of each read issued to the kernel.
+const BLOCK_LEN: usize = 256 * 1024;
+/// The number of reads kept in flight.
+const QUEUE_DEPTH: usize = 4;
+
+/// A reader of files that keeps several reads of the upcoming blocks of the file in
+/// flight through io_uring, overlapping I/O latency with decoding. Requires the
+/// `io-uring` feature and Linux 5.6 or later.
+///
+/// This is most beneficial for files that aren't in the page cache, especially on
+/// storage with a high latency per request.
+pub struct UringReader {
+    file: File,
+    ring: IoUring,
+    blocks: Vec<Block>,
+    /// The index of the block being read from.
+    current: usize,
+    /// The position within the current block.
+    pos: usize,
+    /// The file offset of the next block to submit.
+    next_offset: u64,
+    in_flight: usize,
+    eof: bool,
+}
+
+struct Block {
+    buffer: Box<[u8]>,
+    offset: u64,
+    state: BlockState,
+}
+
+enum BlockState {
+    Pending,
+    Ready { len: usize },
+    Failed(io::Error),
+}
+
+impl UringReader {
+    /// Opens the file at `path` and starts reading its first blocks.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to open the file, or to set up or
+    /// submit to the io_uring.
+    pub fn open(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let file = File::open(path.as_ref()).map_err(|e| {
+            crate::Error::io(
+                e,
+                format!("opening file at path '{}'", path.as_ref().display()),
+            )
+        })?;
+        Self::new(file)
+    }
+
+    /// Creates a new [`UringReader`] that reads `file` from its start.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to set up or submit to the io_uring.
+    pub fn new(file: File) -> crate::Result<Self> {
+        let ring = IoUring::new(QUEUE_DEPTH as u32)
+            .map_err(|e| crate::Error::io(e, "setting up io_uring"))?;
+        let mut reader = Self {
+            file,
+            ring,
+            blocks: Vec::with_capacity(QUEUE_DEPTH),
+            current: 0,
+            pos: 0,
+            next_offset: 0,
+            in_flight: 0,
+            eof: false,
+        };
+        for idx in 0..QUEUE_DEPTH {
+            reader.blocks.push(Block {
+                buffer: vec![0; BLOCK_LEN].into_boxed_slice(),
+                offset: 0,
+                state: BlockState::Pending,
+            });
+            let offset = reader.next_offset;
+            reader.next_offset += BLOCK_LEN as u64;
+            reader
+                .submit(idx, offset)
+                .map_err(|e| crate::Error::io(e, "submitting read"))?;
+        }
+        Ok(reader)
+    }
+
+    /// Returns a reference to the underlying file.
+    pub fn get_ref(&self) -> &File {
+        &self.file
+    }
+
+    /// Submits a read of the block at `offset` of the file into the buffer of the
+    /// block at `idx`.
+    fn submit(&mut self, idx: usize, offset: u64) -> io::Result<()> {
+        let block = &mut self.blocks[idx];
+        block.offset = offset;
+        block.state = BlockState::Pending;
+        let entry = opcode::Read::new(
+            types::Fd(self.file.as_raw_fd()),
+            block.buffer.as_mut_ptr(),
+            BLOCK_LEN as u32,
+        )
+        .offset(offset)
+        .build()
+        .user_data(idx as u64);
+        // Safety: the buffer is owned by `self` and isn't moved, accessed, or freed
+        // until the read completes. At most `QUEUE_DEPTH` reads are in flight, so the
+        // submission queue can't be full.
+        unsafe {
+            self.ring
+                .submission()
+                .push(&entry)
+                .expect("submission queue is full");
+        }
+        self.in_flight += 1;
+        self.ring.submit()?;
+        Ok(())
+    }
+
+    /// Waits for the read of the block at `idx` to complete.
+    fn wait(&mut self, idx: usize) -> io::Result<()> {
+        while matches!(self.blocks[idx].state, BlockState::Pending) {
+            self.ring.submit_and_wait(1)?;
+            let completed: Vec<_> = self
+                .ring
+                .completion()
+                .map(|cqe| (cqe.user_data() as usize, cqe.result()))
+                .collect();
+            // Every completion must be handled, even after an error, so no block is
+            // left pending without a read in flight
+            let mut res = Ok(());
+            for (completed_idx, completed_res) in completed {
+                self.in_flight -= 1;
+                let complete_res = self.complete(completed_idx, completed_res);
+                if res.is_ok() {
+                    res = complete_res;
+                }
+            }
+            res?;
+        }
+        Ok(())
+    }
+
+    fn complete(&mut self, idx: usize, res: i32) -> io::Result<()> {
+        if res < 0 {
+            let err = io::Error::from_raw_os_error(-res);
+            if matches!(
+                err.kind(),
+                io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock
+            ) {
+                let offset = self.blocks[idx].offset;
+                return self.submit(idx, offset);
+            }
+            self.blocks[idx].state = BlockState::Failed(err);
+            return Ok(());
+        }
+        let block = &mut self.blocks[idx];
+        let mut len = res as usize;
+        // Fill a short read synchronously so each block other than the last is full
+        while len > 0 && len < BLOCK_LEN {
+            match self
+                .file
+                .read_at(&mut block.buffer[len..], block.offset + len as u64)
+            {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    block.state = BlockState::Failed(e);
+                    return Ok(());
+                }
+            }
+        }
+        block.state = BlockState::Ready { len };
+        Ok(())
+    }
+}
+
+impl io::Read for UringReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.eof || buf.is_empty() {
+                return Ok(0);
+            }
+            self.wait(self.current)?;
+            let len = match mem::replace(&mut self.blocks[self.current].state, BlockState::Pending)
+            {
+                BlockState::Ready { len } => len,
+                BlockState::Failed(err) => {
+                    // Retry the same block on the next read
+                    let offset = self.blocks[self.current].offset;
+                    self.submit(self.current, offset)?;
+                    return Err(err);
+                }
+                BlockState::Pending => unreachable!("waited for block to complete"),
+            };
+            let block = &mut self.blocks[self.current];
+            block.state = BlockState::Ready { len };
+            if self.pos < len {
+                let n = buf.len().min(len - self.pos);
+                buf[..n].copy_from_slice(&block.buffer[self.pos..self.pos + n]);
+                self.pos += n;
+                return Ok(n);
+            }
+            if len < BLOCK_LEN {
+                self.eof = true;
+                return Ok(0);
+            }
+            // Reuse the consumed block for the next block of the file
+            let offset = self.next_offset;
+            self.next_offset += BLOCK_LEN as u64;
+            self.submit(self.current, offset)?;
+            self.current = (self.current + 1) % QUEUE_DEPTH;
+            self.pos = 0;
+        }
+    }
+}
+
+impl Drop for UringReader {
+    fn drop(&mut self) {
+        // The kernel may still write to the buffers of reads in flight
+        while self.in_flight > 0 {
+            match self.ring.submit_and_wait(self.in_flight) {
+                Ok(_) => self.in_flight -= self.ring.completion().count(),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => {
+                    // Leak the buffers rather than risk a use after free
+                    for block in self.blocks.drain(..) {
+                        mem::forget(block.buffer);
+                    }
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Decoder<UringReader> {
+    /// Creates a DBN [`Decoder`] from the uncompressed file at `path` that reads ahead
+    /// through io_uring. Requires the `io-uring` feature.
+    ///
+    /// # Errors
+    /// This function will return an error if it is unable to read the file at `path` or
+    /// if it is unable to parse the metadata in the file.
+    pub fn from_file_uring(path: impl AsRef<Path>) -> crate::Result<Self> {
+        Self::new(UringReader::open(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::{
+        decode::{tests::TEST_DATA_PATH, DbnMetadata, DecodeRecordRef},
+        MboMsg,
+    };
+
+    #[test]
+    fn test_read_multiple_blocks() {
+        let path =
+            std::env::temp_dir().join(format!("dbn_test_uring_reader_{}.bin", std::process::id()));
+        let len = BLOCK_LEN * QUEUE_DEPTH * 2 + 123;
+        let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &data).unwrap();
+        let mut target = UringReader::open(&path).unwrap();
+        let mut res = Vec::new();
+        target.read_to_end(&mut res).unwrap();
+        assert!(res == data);
+        drop(target);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_decode_matches_buffered() {
+        let path = format!("{TEST_DATA_PATH}/test_data.mbo.dbn");
+        let mut exp_decoder = Decoder::from_file(&path).unwrap();
+        let mut target = Decoder::from_file_uring(&path).unwrap();
+        assert_eq!(target.metadata(), exp_decoder.metadata());
+        while let Some(exp) = exp_decoder.decode_record_ref().unwrap() {
+            let exp = exp.get::<MboMsg>().unwrap().clone();
+            let rec = target.decode_record_ref().unwrap().unwrap();
+            assert_eq!(rec.get::<MboMsg>(), Some(&exp));
+        }
+        assert!(target.decode_record_ref().unwrap().is_none());
+    }
+}
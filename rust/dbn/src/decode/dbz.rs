@@ -1,5 +1,6 @@
 //! Decoding of legacy DBZ files, a precursor to DBN.
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{self, BufReader, Read},
     mem,
@@ -302,6 +303,7 @@ impl MetadataDecoder {
             not_found,
             mappings,
             symbol_cstr_len: crate::compat::SYMBOL_CSTR_LEN_V1,
+            custom: BTreeMap::new(),
         })
     }
 
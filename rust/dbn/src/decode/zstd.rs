@@ -1,4 +1,7 @@
-use std::ops::Range;
+use std::{
+    io::{self, SeekFrom},
+    ops::Range,
+};
 
 use super::FromLittleEndianSlice;
 
@@ -6,6 +9,16 @@ use super::FromLittleEndianSlice;
 pub(crate) const ZSTD_SKIPPABLE_MAGIC_RANGE: Range<u32> = 0x184D2A50..0x184D2A60;
 /// Magic number for the beginning of a Zstandard frame.
 const ZSTD_MAGIC_NUMBER: u32 = 0xFD2FB528;
+/// Magic number of the skippable frame containing the seek table of the Zstandard
+/// seekable format.
+pub(crate) const SEEK_TABLE_SKIPPABLE_MAGIC: u32 = 0x184D2A5E;
+/// Magic number at the end of the seek table footer.
+pub(crate) const SEEK_TABLE_FOOTER_MAGIC: u32 = 0x8F92EAB1;
+/// The length in bytes of the seek table footer: the number of frames, the
+/// descriptor, and the magic number.
+pub(crate) const SEEK_TABLE_FOOTER_LEN: usize = 9;
+/// Flag in the seek table descriptor indicating each entry has a checksum.
+const SEEK_TABLE_CHECKSUM_FLAG: u8 = 0x80;
 
 pub fn starts_with_prefix(bytes: &[u8]) -> bool {
     if bytes.len() < 4 {
@@ -15,6 +28,85 @@ pub fn starts_with_prefix(bytes: &[u8]) -> bool {
     ZSTD_MAGIC_NUMBER == magic
 }
 
+/// An entry in the seek table of the Zstandard seekable format describing a single
+/// frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SeekTableEntry {
+    pub compressed_size: u32,
+    pub decompressed_size: u32,
+}
+
+/// Appends the seek table for frames described by `entries` in its skippable frame to
+/// `buffer`. Checksums are omitted.
+pub(crate) fn encode_seek_table(buffer: &mut Vec<u8>, entries: &[SeekTableEntry]) {
+    let table_len = entries.len() * 8 + SEEK_TABLE_FOOTER_LEN;
+    buffer.extend(SEEK_TABLE_SKIPPABLE_MAGIC.to_le_bytes());
+    buffer.extend((table_len as u32).to_le_bytes());
+    for entry in entries {
+        buffer.extend(entry.compressed_size.to_le_bytes());
+        buffer.extend(entry.decompressed_size.to_le_bytes());
+    }
+    buffer.extend((entries.len() as u32).to_le_bytes());
+    // Descriptor
+    buffer.push(0);
+    buffer.extend(SEEK_TABLE_FOOTER_MAGIC.to_le_bytes());
+}
+
+/// Reads the seek table from the end of `reader`, leaving `reader` positioned at the
+/// start of the seek table's skippable frame.
+///
+/// # Errors
+/// This function returns an error if it fails to seek or read from `reader` or the
+/// input doesn't end with a valid seek table.
+pub(crate) fn decode_seek_table<R: io::Read + io::Seek>(
+    reader: &mut R,
+) -> crate::Result<Vec<SeekTableEntry>> {
+    let io_err = |e| crate::Error::io(e, "reading seek table");
+    let mut footer = [0; SEEK_TABLE_FOOTER_LEN];
+    let footer_pos = reader
+        .seek(SeekFrom::End(-(SEEK_TABLE_FOOTER_LEN as i64)))
+        .map_err(io_err)?;
+    reader.read_exact(&mut footer).map_err(io_err)?;
+    if u32::from_le_slice(&footer[5..]) != SEEK_TABLE_FOOTER_MAGIC {
+        return Err(crate::Error::decode(
+            "input doesn't end with a Zstandard seek table",
+        ));
+    }
+    let frame_count = u32::from_le_slice(&footer[..4]) as usize;
+    let entry_len = if footer[4] & SEEK_TABLE_CHECKSUM_FLAG == 0 {
+        8
+    } else {
+        12
+    };
+    let table_len = frame_count * entry_len + SEEK_TABLE_FOOTER_LEN;
+    if (8 + table_len - SEEK_TABLE_FOOTER_LEN) as u64 > footer_pos {
+        return Err(crate::Error::decode(format!(
+            "Zstandard seek table with {frame_count} frames is longer than the input"
+        )));
+    }
+    // Skippable frame header of magic number and length
+    let mut table = vec![0; 8 + table_len - SEEK_TABLE_FOOTER_LEN];
+    reader
+        .seek(SeekFrom::End(-((8 + table_len) as i64)))
+        .map_err(io_err)?;
+    reader.read_exact(&mut table).map_err(io_err)?;
+    if u32::from_le_slice(&table[..4]) != SEEK_TABLE_SKIPPABLE_MAGIC
+        || u32::from_le_slice(&table[4..8]) as usize != table_len
+    {
+        return Err(crate::Error::decode("invalid Zstandard seek table header"));
+    }
+    reader
+        .seek(SeekFrom::End(-((8 + table_len) as i64)))
+        .map_err(io_err)?;
+    Ok(table[8..]
+        .chunks_exact(entry_len)
+        .map(|entry| SeekTableEntry {
+            compressed_size: u32::from_le_slice(&entry[..4]),
+            decompressed_size: u32::from_le_slice(&entry[4..8]),
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs::File, io::Read};
@@ -24,6 +116,34 @@ mod tests {
     use super::*;
     use crate::{decode::tests::TEST_DATA_PATH, Schema};
 
+    #[test]
+    fn test_seek_table_round_trip() {
+        let entries = [
+            SeekTableEntry {
+                compressed_size: 100,
+                decompressed_size: 400,
+            },
+            SeekTableEntry {
+                compressed_size: 30,
+                decompressed_size: 56,
+            },
+        ];
+        let mut buffer = vec![0; 130];
+        encode_seek_table(&mut buffer, &entries);
+        let mut cursor = io::Cursor::new(buffer);
+        assert_eq!(decode_seek_table(&mut cursor).unwrap(), entries);
+        assert_eq!(cursor.position(), 130);
+    }
+
+    #[test]
+    fn test_decode_seek_table_missing() {
+        let mut file = File::open(format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst")).unwrap();
+        assert!(matches!(
+            decode_seek_table(&mut file),
+            Err(crate::Error::Decode(_))
+        ));
+    }
+
     #[rstest]
     #[case::mbo(Schema::Mbo)]
     #[case::mbp1(Schema::Mbp1)]
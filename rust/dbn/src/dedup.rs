@@ -0,0 +1,243 @@
+//! Removing duplicate records from a stream, e.g. for stitching together overlapping
+//! captures from redundant feed handlers.
+
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    num::NonZeroUsize,
+};
+
+use crate::{
+    decode::{DbnMetadata, DecodeRecordRef},
+    Metadata, Record, RecordRef, RecordRefEnum,
+};
+
+/// The fields identifying a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct DedupKey {
+    instrument_id: u32,
+    sequence: u32,
+    ts_event: u64,
+    rtype: u8,
+}
+
+impl DedupKey {
+    fn new(record: RecordRef) -> Self {
+        let header = record.header();
+        let sequence = match RecordRefEnum::try_from(record) {
+            Ok(RecordRefEnum::Mbo(rec)) => rec.sequence,
+            Ok(RecordRefEnum::Trade(rec)) => rec.sequence,
+            Ok(RecordRefEnum::Mbp1(rec)) => rec.sequence,
+            Ok(RecordRefEnum::Mbp10(rec)) => rec.sequence,
+            Ok(RecordRefEnum::Bbo(rec)) => rec.sequence,
+            Ok(RecordRefEnum::Cbbo(rec)) => rec.sequence,
+            Ok(RecordRefEnum::Stat(rec)) => rec.sequence,
+            _ => 0,
+        };
+        Self {
+            instrument_id: header.instrument_id,
+            sequence,
+            ts_event: header.ts_event,
+            rtype: header.rtype,
+        }
+    }
+}
+
+/// Detects records that repeat one of the last `window` distinct records byte for
+/// byte.
+///
+/// Records are first matched by their instrument ID, `sequence`, `ts_event`, and
+/// rtype, treating records without a `sequence` field as if their sequence were 0,
+/// and only the records sharing those fields are compared in full. Distinct records
+/// with the same fields, such as the trade, fill, and cancel MBO records from a single
+/// venue message, are therefore not treated as duplicates.
+#[derive(Debug, Clone)]
+pub struct Deduplicator {
+    window: usize,
+    /// The bytes of the records in the window by key, from oldest to newest.
+    seen: HashMap<DedupKey, Vec<Box<[u8]>>>,
+    /// The keys of the records in the window from oldest to newest.
+    order: VecDeque<DedupKey>,
+}
+
+impl Deduplicator {
+    /// Creates a new [`Deduplicator`] that remembers the last `window` distinct
+    /// records. The window should cover the largest overlap between the inputs.
+    pub fn new(window: NonZeroUsize) -> Self {
+        Self {
+            window: window.get(),
+            seen: HashMap::with_capacity(window.get()),
+            order: VecDeque::with_capacity(window.get()),
+        }
+    }
+
+    /// Returns `true` if `record` is a duplicate of a record in the window, otherwise
+    /// adds it to the window.
+    pub fn is_duplicate(&mut self, record: RecordRef) -> bool {
+        let key = DedupKey::new(record);
+        let bytes = record.as_ref();
+        let candidates = self.seen.entry(key).or_default();
+        if candidates.iter().any(|candidate| **candidate == *bytes) {
+            return true;
+        }
+        candidates.push(Box::from(bytes));
+        if self.order.len() == self.window {
+            // The window is full so there's always an oldest key
+            let oldest = self.order.pop_front().unwrap();
+            if let Entry::Occupied(mut entry) = self.seen.entry(oldest) {
+                // Records of each key are pushed in order, so the first is the oldest
+                entry.get_mut().remove(0);
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
+        }
+        self.order.push_back(key);
+        false
+    }
+}
+
+/// Type for decoding the records of another decoder without duplicates, as detected
+/// by a [`Deduplicator`].
+#[derive(Debug)]
+pub struct DedupDecoder<D> {
+    decoder: D,
+    deduplicator: Deduplicator,
+    duplicate_count: u64,
+}
+
+impl<D> DedupDecoder<D> {
+    /// Creates a new [`DedupDecoder`] that skips records of `decoder` repeating one of
+    /// the last `window` distinct records.
+    pub fn new(decoder: D, window: NonZeroUsize) -> Self {
+        Self {
+            decoder,
+            deduplicator: Deduplicator::new(window),
+            duplicate_count: 0,
+        }
+    }
+
+    /// Returns the number of duplicate records skipped so far.
+    pub fn duplicate_count(&self) -> u64 {
+        self.duplicate_count
+    }
+
+    /// Consumes the decoder and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.decoder
+    }
+}
+
+impl<D: DbnMetadata> DbnMetadata for DedupDecoder<D> {
+    fn metadata(&self) -> &Metadata {
+        self.decoder.metadata()
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        self.decoder.metadata_mut()
+    }
+}
+
+impl<D: DecodeRecordRef> DecodeRecordRef for DedupDecoder<D> {
+    fn decode_record_ref(&mut self) -> crate::Result<Option<RecordRef>> {
+        while let Some(record) = self.decoder.decode_record_ref()? {
+            if !self.deduplicator.is_duplicate(record) {
+                // Safe: casting reference to pointer so the pointer will always be valid.
+                // Getting around borrow checker limitation.
+                return Ok(Some(unsafe {
+                    RecordRef::unchecked_from_header(record.header())
+                }));
+            }
+            self.duplicate_count += 1;
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_char;
+
+    use super::*;
+    use crate::{
+        decode::dbn::RecordDecoder,
+        encode::{DbnRecordEncoder, EncodeRecord},
+        enums::{Action, Side},
+        rtype, MboMsg, OhlcvMsg, RecordHeader,
+    };
+
+    fn mbo(instrument_id: u32, sequence: u32, ts_event: u64) -> MboMsg {
+        MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, instrument_id, ts_event),
+            sequence,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_window() {
+        let mut target = Deduplicator::new(NonZeroUsize::new(2).unwrap());
+        let mut is_dup = |rec: &MboMsg| target.is_duplicate(RecordRef::from(rec));
+        assert!(!is_dup(&mbo(1, 1, 10)));
+        assert!(!is_dup(&mbo(1, 2, 10)));
+        assert!(is_dup(&mbo(1, 1, 10)));
+        // Each field is part of the key
+        assert!(!is_dup(&mbo(2, 2, 10)));
+        assert!(!is_dup(&mbo(2, 2, 11)));
+        // Evicted from the window
+        assert!(!is_dup(&mbo(1, 1, 10)));
+        let bar = OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(rtype::OHLCV_1S, 1, 1, 10),
+            open: 1,
+            high: 1,
+            low: 1,
+            close: 1,
+            volume: 1,
+        };
+        assert!(!target.is_duplicate(RecordRef::from(&bar)));
+        assert!(target.is_duplicate(RecordRef::from(&bar)));
+    }
+
+    #[test]
+    fn test_distinct_records_with_same_key() {
+        let mut target = Deduplicator::new(NonZeroUsize::new(10).unwrap());
+        // Trade, fill, and cancel from a single venue message
+        let event = [
+            (Action::Trade, Side::Ask, 0),
+            (Action::Fill, Side::Bid, 5),
+            (Action::Cancel, Side::Bid, 5),
+        ]
+        .map(|(action, side, order_id)| MboMsg {
+            order_id,
+            action: action as c_char,
+            side: side as c_char,
+            ..mbo(1, 1, 10)
+        });
+        for rec in event.iter() {
+            assert!(!target.is_duplicate(RecordRef::from(rec)));
+        }
+        // Repeated by an overlapping capture
+        for rec in event.iter() {
+            assert!(target.is_duplicate(RecordRef::from(rec)));
+        }
+    }
+
+    #[test]
+    fn test_decoder() {
+        let mut buffer = Vec::new();
+        let mut encoder = DbnRecordEncoder::new(&mut buffer);
+        // Two overlapping captures
+        for (sequence, ts_event) in [(1, 10), (2, 20), (3, 30), (2, 20), (3, 30), (4, 40)] {
+            encoder.encode_record(&mbo(1, sequence, ts_event)).unwrap();
+        }
+        let mut target = DedupDecoder::new(
+            RecordDecoder::new(buffer.as_slice()),
+            NonZeroUsize::new(10).unwrap(),
+        );
+        let mut sequences = Vec::new();
+        while let Some(rec) = target.decode_record_ref().unwrap() {
+            sequences.push(rec.get::<MboMsg>().unwrap().sequence);
+        }
+        assert_eq!(sequences, [1, 2, 3, 4]);
+        assert_eq!(target.duplicate_count(), 2);
+    }
+}
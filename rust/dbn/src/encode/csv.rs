@@ -1,6 +1,7 @@
 //! Encoding of DBN records into comma-separated values (CSV).
 
-pub(crate) mod serialize;
+#[doc(hidden)]
+pub mod serialize;
 mod sync;
 
 pub use sync::{Encoder, EncoderBuilder};
@@ -4,8 +4,10 @@ use csv::Writer;
 
 use crate::{
     enums::{SecurityUpdateAction, UserDefinedInstrument},
-    pretty::{fmt_px, fmt_ts},
-    record::{c_chars_to_str, BidAskPair, HasRType, RecordHeader, WithTsOut},
+    pretty::{PxBuffer, TsBuffer},
+    record::{
+        c_chars_to_str, BidAskPair, ConsolidatedBidAskPair, HasRType, RecordHeader, WithTsOut,
+    },
     UNDEF_PRICE, UNDEF_TIMESTAMP,
 };
 
@@ -88,6 +90,32 @@ impl<const N: usize> WriteField for [BidAskPair; N] {
         Ok(())
     }
 }
+
+impl<const N: usize> WriteField for [ConsolidatedBidAskPair; N] {
+    fn write_header<W: io::Write>(csv_writer: &mut Writer<W>, _name: &str) -> csv::Result<()> {
+        for i in 0..N {
+            for f in ["bid_px", "ask_px", "bid_sz", "ask_sz", "bid_pb", "ask_pb"] {
+                csv_writer.write_field(&format!("{f}_{i:02}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_field<W: io::Write, const PRETTY_PX: bool, const PRETTY_TS: bool>(
+        &self,
+        writer: &mut csv::Writer<W>,
+    ) -> csv::Result<()> {
+        for level in self.iter() {
+            write_px_field::<W, PRETTY_PX>(writer, level.bid_px)?;
+            write_px_field::<W, PRETTY_PX>(writer, level.ask_px)?;
+            level.bid_sz.write_field::<W, false, false>(writer)?;
+            level.ask_sz.write_field::<W, false, false>(writer)?;
+            level.bid_pb.write_field::<W, false, false>(writer)?;
+            level.ask_pb.write_field::<W, false, false>(writer)?;
+        }
+        Ok(())
+    }
+}
 macro_rules! impl_write_field_for {
         ($($ty:ident),+) => {
             $(
@@ -111,7 +139,7 @@ impl WriteField for bool {
         &self,
         writer: &mut Writer<W>,
     ) -> csv::Result<()> {
-        writer.write_field(self.to_string())
+        writer.write_field(if *self { "true" } else { "false" })
     }
 }
 
@@ -150,7 +178,7 @@ pub fn write_px_field<W: io::Write, const PRETTY_PX: bool>(
         if px == UNDEF_PRICE {
             csv_writer.write_field("")
         } else {
-            csv_writer.write_field(fmt_px(px))
+            csv_writer.write_field(PxBuffer::new().format(px))
         }
     } else {
         csv_writer.write_field(itoa::Buffer::new().format(px))
@@ -164,7 +192,7 @@ pub fn write_ts_field<W: io::Write, const PRETTY_TS: bool>(
     if PRETTY_TS {
         match ts {
             0 | UNDEF_TIMESTAMP => csv_writer.write_field(""),
-            ts => csv_writer.write_field(fmt_ts(ts)),
+            ts => csv_writer.write_field(TsBuffer::new().format(ts)),
         }
     } else {
         csv_writer.write_field(itoa::Buffer::new().format(ts))
@@ -40,6 +40,7 @@ where
     ts_out: bool,
     with_symbol: bool,
     delimiter: u8,
+    buffer_size: Option<usize>,
 }
 
 impl<W> EncoderBuilder<W>
@@ -57,6 +58,7 @@ where
             ts_out: false,
             with_symbol: false,
             delimiter: b',',
+            buffer_size: None,
         }
     }
 
@@ -113,17 +115,27 @@ where
         self
     }
 
+    /// Sets the size in bytes of the internal buffer the output is written to before
+    /// being written to the underlying writer. The CSV output is always buffered, so
+    /// the size is at least [`Encoder::MIN_BUFFER_SIZE`]. Defaults to 8 KiB.
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = Some(buffer_size.max(Encoder::<W>::MIN_BUFFER_SIZE));
+        self
+    }
+
     /// Creates the new encoder with the previously specified settings and if
     /// `write_header` is `true`, encodes the header row.
     ///
     /// # Errors
     /// This function returns an error if it fails to write the header row.
     pub fn build(self) -> crate::Result<Encoder<W>> {
+        let mut writer_builder = csv::WriterBuilder::new();
+        writer_builder.has_headers(false).delimiter(self.delimiter);
+        if let Some(buffer_size) = self.buffer_size {
+            writer_builder.buffer_capacity(buffer_size);
+        }
         let mut encoder = Encoder {
-            writer: csv::WriterBuilder::new()
-                .has_headers(false)
-                .delimiter(self.delimiter)
-                .from_writer(self.writer),
+            writer: writer_builder.from_writer(self.writer),
             has_written_header: false,
             use_pretty_px: self.use_pretty_px,
             use_pretty_ts: self.use_pretty_ts,
@@ -145,6 +157,9 @@ impl<W> Encoder<W>
 where
     W: io::Write,
 {
+    /// The minimum size in bytes of the internal buffer.
+    pub const MIN_BUFFER_SIZE: usize = 64;
+
     /// Creates a builder for configuring an `Encoder` object.
     pub fn builder(writer: W) -> EncoderBuilder<W> {
         EncoderBuilder::new(writer)
@@ -901,4 +916,38 @@ mod tests {
         let orig_header = snd_line.split_once(",ts_out,symbol").unwrap().0;
         assert_eq!(fst_line, orig_header);
     }
+
+    #[test]
+    fn test_encode_small_buffer_size() {
+        let records: Vec<_> = (0..100)
+            .map(|i| MboMsg {
+                hd: RECORD_HEADER,
+                order_id: i,
+                price: 5500,
+                size: 3,
+                flags: 128,
+                channel_id: 14,
+                action: 'A' as c_char,
+                side: 'B' as c_char,
+                ts_recv: 1658441891000000000,
+                ts_in_delta: 22_000,
+                sequence: 1_002_375,
+            })
+            .collect();
+        let encode = |buffer_size: Option<usize>| {
+            let mut builder = Encoder::builder(Vec::new())
+                .use_pretty_px(true)
+                .use_pretty_ts(true)
+                .schema(Some(Schema::Mbo))
+                .unwrap();
+            if let Some(buffer_size) = buffer_size {
+                builder = builder.buffer_size(buffer_size);
+            }
+            let mut encoder = builder.build().unwrap();
+            encoder.encode_records(records.as_slice()).unwrap();
+            encoder.flush().unwrap();
+            encoder.get_ref().clone()
+        };
+        assert_eq!(encode(Some(0)), encode(None));
+    }
 }
@@ -1,5 +1,7 @@
 //! Encoding DBN records into DBN, Zstandard-compressed or not.
+mod seekable;
 mod sync;
+pub use seekable::SeekableEncoder;
 pub use sync::{Encoder, MetadataEncoder, RecordEncoder};
 
 #[cfg(feature = "async")]
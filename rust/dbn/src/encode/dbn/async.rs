@@ -287,6 +287,12 @@ where
             .await?;
         self.encode_symbol_mappings(metadata.symbol_cstr_len, &metadata.mappings)
             .await?;
+        let mut custom_buffer = Vec::new();
+        super::MetadataEncoder::new(&mut custom_buffer).encode_custom(&metadata.custom)?;
+        self.writer
+            .write_all(&custom_buffer)
+            .await
+            .map_err(|e| Error::io(e, "writing custom metadata"))?;
 
         Ok(())
     }
@@ -436,7 +442,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::mem;
+    use std::{collections::BTreeMap, mem};
 
     use super::*;
     use crate::{
@@ -502,6 +508,7 @@ mod tests {
                     ],
                 },
             ],
+            custom: BTreeMap::from([("generator".to_owned(), "dbn".to_owned())]),
         };
         let mut buffer = Vec::new();
         let mut target = MetadataEncoder::new(&mut buffer);
@@ -0,0 +1,144 @@
+use std::{io, num::NonZeroUsize};
+
+use super::MetadataEncoder;
+use crate::{
+    decode::zstd::{encode_seek_table, SeekTableEntry},
+    encode::{DbnEncodable, EncodeDbn, EncodeRecord, EncodeRecordRef, ZSTD_COMPRESSION_LEVEL},
+    record_ref::RecordRef,
+    Error, Metadata, Result,
+};
+
+/// Type for encoding DBN in the Zstandard seekable format: the metadata and every
+/// `records_per_frame` records are compressed into independent frames, followed by a
+/// seek table describing the frames. Such files can be decompressed in parallel with
+/// [`SeekableDecoder`](crate::decode::dbn::SeekableDecoder) and remain readable by
+/// any Zstandard decoder.
+///
+/// [`finish()`](Self::finish) must be called to compress the last frame and write
+/// the seek table.
+pub struct SeekableEncoder<W>
+where
+    W: io::Write,
+{
+    writer: W,
+    records_per_frame: usize,
+    frame_record_count: usize,
+    frame_buffer: Vec<u8>,
+    seek_table: Vec<SeekTableEntry>,
+}
+
+impl<W> SeekableEncoder<W>
+where
+    W: io::Write,
+{
+    /// Creates a new [`SeekableEncoder`] that will write to `writer` and compresses
+    /// every `records_per_frame` records into a separate frame. The metadata is
+    /// encoded into its own frame immediately.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to encode or write the metadata.
+    pub fn new(writer: W, metadata: &Metadata, records_per_frame: NonZeroUsize) -> Result<Self> {
+        let mut encoder = Self {
+            writer,
+            records_per_frame: records_per_frame.get(),
+            frame_record_count: 0,
+            frame_buffer: Vec::new(),
+            seek_table: Vec::new(),
+        };
+        MetadataEncoder::new(&mut encoder.frame_buffer).encode(metadata)?;
+        encoder.write_frame()?;
+        Ok(encoder)
+    }
+
+    /// Compresses the records of the current frame, writes the seek table, and
+    /// returns the inner writer.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to compress the last frame or to
+    /// write to the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        if !self.frame_buffer.is_empty() {
+            self.write_frame()?;
+        }
+        let mut seek_table = Vec::new();
+        encode_seek_table(&mut seek_table, &self.seek_table);
+        let io_err = |e| Error::io(e, "writing seek table");
+        self.writer.write_all(&seek_table).map_err(io_err)?;
+        self.writer.flush().map_err(io_err)?;
+        Ok(self.writer)
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    fn append(&mut self, bytes: &[u8]) -> Result<()> {
+        self.frame_buffer.extend_from_slice(bytes);
+        self.frame_record_count += 1;
+        if self.frame_record_count == self.records_per_frame {
+            self.write_frame()?;
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self) -> Result<()> {
+        let compressed = zstd::bulk::compress(&self.frame_buffer, ZSTD_COMPRESSION_LEVEL)
+            .map_err(|e| Error::io(e, "compressing frame"))?;
+        let to_u32 = |len: usize| {
+            u32::try_from(len).map_err(|_| {
+                Error::encode(format!(
+                    "frame of {len} bytes is too large for the seek table"
+                ))
+            })
+        };
+        let entry = SeekTableEntry {
+            compressed_size: to_u32(compressed.len())?,
+            decompressed_size: to_u32(self.frame_buffer.len())?,
+        };
+        self.writer
+            .write_all(&compressed)
+            .map_err(|e| Error::io(e, "writing frame"))?;
+        self.seek_table.push(entry);
+        self.frame_buffer.clear();
+        self.frame_record_count = 0;
+        Ok(())
+    }
+}
+
+impl<W> EncodeRecord for SeekableEncoder<W>
+where
+    W: io::Write,
+{
+    fn encode_record<R: DbnEncodable>(&mut self, record: &R) -> Result<()> {
+        self.append(record.as_ref())
+    }
+
+    /// Flushes the underlying writer. Records of the current frame aren't written
+    /// until the frame is full or [`finish()`](SeekableEncoder::finish) is called.
+    fn flush(&mut self) -> Result<()> {
+        self.writer
+            .flush()
+            .map_err(|e| Error::io(e, "flushing output"))
+    }
+}
+
+impl<W> EncodeRecordRef for SeekableEncoder<W>
+where
+    W: io::Write,
+{
+    fn encode_record_ref(&mut self, record: RecordRef) -> Result<()> {
+        self.append(record.as_ref())
+    }
+
+    unsafe fn encode_record_ref_ts_out(&mut self, record: RecordRef, _ts_out: bool) -> Result<()> {
+        self.encode_record_ref(record)
+    }
+}
+
+impl<W> EncodeDbn for SeekableEncoder<W> where W: io::Write {}
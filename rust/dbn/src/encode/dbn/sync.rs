@@ -1,16 +1,18 @@
 use std::{
-    io::{self, SeekFrom},
+    collections::BTreeMap,
+    io::{self, IoSlice, SeekFrom},
     mem,
     num::NonZeroU64,
 };
 
 use crate::{
     compat::version_symbol_cstr_len,
+    decode::FromLittleEndianSlice,
     encode::{zstd_encoder, DbnEncodable, EncodeDbn, EncodeRecord, EncodeRecordRef},
     enums::Schema,
     record_ref::RecordRef,
-    Error, Metadata, Result, SymbolMapping, DBN_VERSION, NULL_LIMIT, NULL_RECORD_COUNT,
-    NULL_SCHEMA, NULL_STYPE, UNDEF_TIMESTAMP,
+    Error, Metadata, Result, SymbolMapping, DBN_VERSION, METADATA_EXT_TAG_CUSTOM,
+    METADATA_EXT_TAG_END, NULL_LIMIT, NULL_RECORD_COUNT, NULL_SCHEMA, NULL_STYPE, UNDEF_TIMESTAMP,
 };
 
 /// Type for encoding files and streams in Databento Binary Encoding (DBN).
@@ -97,7 +99,28 @@ where
     }
 }
 
-impl<W> EncodeDbn for Encoder<W> where W: io::Write {}
+impl<W> EncodeDbn for Encoder<W>
+where
+    W: io::Write,
+{
+    fn encode_records<R: DbnEncodable>(&mut self, records: &[R]) -> Result<()> {
+        self.record_encoder.encode_records(records)
+    }
+}
+
+impl<W> Encoder<W>
+where
+    W: io::Write,
+{
+    /// Encodes `records` with as few writes as possible. See
+    /// [`RecordEncoder::encode_record_refs()`].
+    ///
+    /// # Errors
+    /// This function returns an error if it's unable to write to the underlying writer.
+    pub fn encode_record_refs(&mut self, records: &[RecordRef]) -> Result<()> {
+        self.record_encoder.encode_record_refs(records)
+    }
+}
 
 /// Type for encoding [`Metadata`] into Databento Binary Encoding (DBN).
 pub struct MetadataEncoder<W>
@@ -105,6 +128,7 @@ where
     W: io::Write,
 {
     writer: W,
+    padding: u32,
 }
 
 impl<W> MetadataEncoder<W>
@@ -119,7 +143,16 @@ where
 
     /// Creates a new [`MetadataEncoder`] that will write to `writer`.
     pub fn new(writer: W) -> Self {
-        Self { writer }
+        Self { writer, padding: 0 }
+    }
+
+    /// Creates a new [`MetadataEncoder`] that will write to `writer` and reserve
+    /// `padding` bytes at the end of the metadata. The padding allows symbols and
+    /// mappings to later be added in place with
+    /// [`update_encoded_metadata()`](Self::update_encoded_metadata) without
+    /// rewriting the records that follow.
+    pub fn with_padding(writer: W, padding: u32) -> Self {
+        Self { writer, padding }
     }
 
     /// Encodes `metadata` into DBN.
@@ -127,6 +160,10 @@ where
     /// # Errors
     /// This function returns an error if it fails to write to the underlying writer.
     pub fn encode(&mut self, metadata: &Metadata) -> Result<()> {
+        self.encode_with_padding(metadata, self.padding)
+    }
+
+    fn encode_with_padding(&mut self, metadata: &Metadata, padding: u32) -> Result<()> {
         let metadata_err = |e| Error::io(e, "writing DBN metadata");
         self.writer.write_all(b"DBN").map_err(metadata_err)?;
         self.writer
@@ -134,7 +171,7 @@ where
             // greater than this version of the crate supports
             .write_all(&[metadata.version.clamp(1, DBN_VERSION)])
             .map_err(metadata_err)?;
-        let length = Self::calc_length(metadata);
+        let length = Self::calc_length(metadata) + padding;
         self.writer
             .write_all(length.to_le_bytes().as_slice())
             .map_err(metadata_err)?;
@@ -181,6 +218,12 @@ where
         self.encode_repeated_symbol_cstr(metadata.symbol_cstr_len, metadata.partial.as_slice())?;
         self.encode_repeated_symbol_cstr(metadata.symbol_cstr_len, metadata.not_found.as_slice())?;
         self.encode_symbol_mappings(metadata.symbol_cstr_len, metadata.mappings.as_slice())?;
+        self.encode_custom(&metadata.custom)?;
+        io::copy(
+            &mut io::Read::take(io::repeat(0), padding as u64),
+            &mut self.writer,
+        )
+        .map_err(metadata_err)?;
 
         Ok(())
     }
@@ -204,7 +247,22 @@ where
                         + mem::size_of::<u32>()
                         + m.intervals.len() * mapping_interval_len
                 })
-                .sum::<usize>()) as u32
+                .sum::<usize>()
+            + Self::calc_custom_length(&metadata.custom)) as u32
+    }
+
+    /// Returns the encoded length of the extension section containing `custom`, which
+    /// is omitted when there are no custom properties.
+    fn calc_custom_length(custom: &BTreeMap<String, String>) -> usize {
+        const EXT_HEADER_LEN: usize = mem::size_of::<u16>() + mem::size_of::<u32>();
+        if custom.is_empty() {
+            return 0;
+        }
+        custom
+            .iter()
+            .map(|(key, value)| EXT_HEADER_LEN + mem::size_of::<u16>() + key.len() + value.len())
+            .sum::<usize>()
+            + EXT_HEADER_LEN
     }
 
     fn encode_range_and_counts(
@@ -298,6 +356,46 @@ where
         Ok(())
     }
 
+    pub(super) fn encode_custom(&mut self, custom: &BTreeMap<String, String>) -> Result<()> {
+        if custom.is_empty() {
+            return Ok(());
+        }
+        let custom_err = |e| Error::io(e, "writing custom metadata");
+        for (key, value) in custom.iter() {
+            let key_len = u16::try_from(key.len()).map_err(|_| {
+                Error::encode(format!(
+                    "custom metadata key '{key}' is too long to be encoded in DBN"
+                ))
+            })?;
+            let entry_len = u32::try_from(mem::size_of::<u16>() + key.len() + value.len())
+                .map_err(|_| {
+                    Error::encode(format!(
+                        "custom metadata value for '{key}' is too long to be encoded in DBN"
+                    ))
+                })?;
+            self.writer
+                .write_all(&METADATA_EXT_TAG_CUSTOM.to_le_bytes())
+                .map_err(custom_err)?;
+            self.writer
+                .write_all(&entry_len.to_le_bytes())
+                .map_err(custom_err)?;
+            self.writer
+                .write_all(&key_len.to_le_bytes())
+                .map_err(custom_err)?;
+            self.writer.write_all(key.as_bytes()).map_err(custom_err)?;
+            self.writer
+                .write_all(value.as_bytes())
+                .map_err(custom_err)?;
+        }
+        self.writer
+            .write_all(&METADATA_EXT_TAG_END.to_le_bytes())
+            .map_err(custom_err)?;
+        self.writer
+            .write_all(&0u32.to_le_bytes())
+            .map_err(custom_err)?;
+        Ok(())
+    }
+
     fn encode_fixed_len_cstr(&mut self, symbol_cstr_len: usize, string: &str) -> Result<()> {
         if !string.is_ascii() {
             return Err(Error::Conversion {
@@ -359,6 +457,55 @@ where
     }
 }
 
+impl<W> MetadataEncoder<W>
+where
+    W: io::Read + io::Write + io::Seek,
+{
+    /// Replaces all of the metadata in an existing DBN buffer with `metadata`,
+    /// including the symbols and mappings, without moving the records that follow.
+    /// `metadata` must have the same version as the existing metadata.
+    ///
+    /// # Errors
+    /// This function returns an error if the version of `metadata` differs from the
+    /// existing metadata, the encoded `metadata` is larger than the existing metadata
+    /// and its padding, or it fails to seek, read the existing metadata prelude, or
+    /// write to the underlying writer.
+    pub fn update_encoded_metadata(&mut self, metadata: &Metadata) -> Result<()> {
+        self.writer
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| Error::io(e, "seeking to metadata prelude"))?;
+        let mut prelude_buffer = [0u8; 8];
+        self.writer
+            .read_exact(&mut prelude_buffer)
+            .map_err(|e| Error::io(e, "reading metadata prelude"))?;
+        let existing_version = prelude_buffer[3];
+        if metadata.version != existing_version {
+            return Err(Error::BadArgument {
+                param_name: "metadata".to_owned(),
+                desc: format!(
+                    "version {} doesn't match the existing metadata version {existing_version}",
+                    metadata.version
+                ),
+            });
+        }
+        let existing_length = u32::from_le_slice(&prelude_buffer[4..]);
+        let length = Self::calc_length(metadata);
+        if length > existing_length {
+            return Err(Error::encode(format!(
+                "updated metadata requires {length} bytes, but only {existing_length} bytes are available"
+            )));
+        }
+        self.writer
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| Error::io(e, "seeking to write position"))?;
+        self.encode_with_padding(metadata, existing_length - length)?;
+        self.writer
+            .seek(SeekFrom::End(0))
+            .map_err(|e| Error::io(e, "seeking back to end"))?;
+        Ok(())
+    }
+}
+
 /// Type for encoding Databento Binary Encoding (DBN) records (not metadata).
 pub struct RecordEncoder<W>
 where
@@ -385,6 +532,58 @@ where
     pub fn get_mut(&mut self) -> &mut W {
         &mut self.writer
     }
+
+    /// Encodes `records` with vectored writes, batching many records into each call
+    /// to the writer. This reduces the overhead of writing small records to an
+    /// unbuffered writer such as a pipe or socket.
+    ///
+    /// # Errors
+    /// This function returns an error if it's unable to write to the underlying writer.
+    pub fn encode_record_refs(&mut self, records: &[RecordRef]) -> Result<()> {
+        let slices: Vec<&[u8]> = records.iter().map(AsRef::as_ref).collect();
+        write_all_vectored(&mut self.writer, &slices)
+            .map_err(|e| Error::io(e, "serializing records"))
+    }
+}
+
+/// The maximum number of slices passed to a single call to `write_vectored`, the
+/// common value of `IOV_MAX`.
+const MAX_IO_SLICES: usize = 1024;
+
+/// Writes all of `slices` to `writer`, like the unstable `Write::write_all_vectored`.
+fn write_all_vectored(writer: &mut impl io::Write, slices: &[&[u8]]) -> io::Result<()> {
+    let mut idx = 0;
+    // The number of bytes of `slices[idx]` already written
+    let mut offset = 0;
+    let mut io_slices = Vec::with_capacity(slices.len().min(MAX_IO_SLICES));
+    while idx < slices.len() {
+        io_slices.clear();
+        io_slices.push(IoSlice::new(&slices[idx][offset..]));
+        io_slices.extend(
+            slices[idx + 1..]
+                .iter()
+                .take(MAX_IO_SLICES - 1)
+                .map(|slice| IoSlice::new(slice)),
+        );
+        let mut written = match writer.write_vectored(&io_slices) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ))
+            }
+            Ok(written) => written,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        while idx < slices.len() && written >= slices[idx].len() - offset {
+            written -= slices[idx].len() - offset;
+            idx += 1;
+            offset = 0;
+        }
+        offset += written;
+    }
+    Ok(())
 }
 
 impl<W> EncodeRecord for RecordEncoder<W>
@@ -430,6 +629,23 @@ where
     }
 }
 
+impl<W> EncodeDbn for RecordEncoder<W>
+where
+    W: io::Write,
+{
+    /// Encodes `records` with vectored writes, batching many records into each call
+    /// to the writer, then flushes the writer.
+    ///
+    /// # Errors
+    /// This function returns an error if it's unable to write to the underlying writer.
+    fn encode_records<R: DbnEncodable>(&mut self, records: &[R]) -> Result<()> {
+        let slices: Vec<&[u8]> = records.iter().map(AsRef::as_ref).collect();
+        write_all_vectored(&mut self.writer, &slices)
+            .map_err(|e| Error::io(e, "serializing records"))?;
+        self.flush()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{io::Seek, mem};
@@ -439,9 +655,12 @@ mod tests {
     use super::*;
     use crate::{
         datasets::{GLBX_MDP3, XNAS_ITCH},
-        decode::{dbn::MetadataDecoder, FromLittleEndianSlice},
-        enums::{SType, Schema},
-        MappingInterval, MetadataBuilder,
+        decode::{
+            dbn::{Decoder, MetadataDecoder},
+            DbnMetadata, DecodeRecord,
+        },
+        enums::{rtype, SType, Schema},
+        MappingInterval, MboMsg, MetadataBuilder, RecordHeader,
     };
 
     #[test]
@@ -499,6 +718,13 @@ mod tests {
                     ],
                 },
             ],
+            custom: BTreeMap::from([
+                ("generator".to_owned(), "dbn 0.17.0".to_owned()),
+                (
+                    "request_id".to_owned(),
+                    "GLBX-20220722-5DEFXVTMSM".to_owned(),
+                ),
+            ]),
         };
         let mut buffer = Vec::new();
         let mut target = MetadataEncoder::new(&mut buffer);
@@ -581,6 +807,7 @@ mod tests {
             partial: vec![],
             not_found: vec![],
             mappings: vec![],
+            custom: BTreeMap::new(),
         };
         let mut buffer = Vec::new();
         let mut target = MetadataEncoder::new(&mut buffer);
@@ -610,6 +837,63 @@ mod tests {
         assert_eq!(res.limit, new_limit);
     }
 
+    #[test]
+    fn test_update_encoded_metadata() {
+        let mut metadata = MetadataBuilder::new()
+            .dataset(XNAS_ITCH.to_owned())
+            .schema(Some(Schema::Mbo))
+            .start(1697240529000000000)
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .symbols(vec!["AAPL".to_owned()])
+            .build();
+        let mut cursor = io::Cursor::new(Vec::new());
+        MetadataEncoder::with_padding(&mut cursor, 2 * crate::SYMBOL_CSTR_LEN as u32)
+            .encode(&metadata)
+            .unwrap();
+        let record = MboMsg::default();
+        RecordEncoder::new(&mut cursor)
+            .encode_record(&record)
+            .unwrap();
+        metadata.symbols.push("MSFT".to_owned());
+        metadata.not_found.push("TSLA".to_owned());
+        let len_before = cursor.get_ref().len();
+        MetadataEncoder::new(&mut cursor)
+            .update_encoded_metadata(&metadata)
+            .unwrap();
+        assert_eq!(cursor.get_ref().len(), len_before);
+        let mut decoder = Decoder::new(cursor.get_ref().as_slice()).unwrap();
+        assert_eq!(*decoder.metadata(), metadata);
+        assert_eq!(decoder.decode_record::<MboMsg>().unwrap(), Some(&record));
+        // No padding left
+        metadata.partial.push("NVDA".to_owned());
+        assert!(MetadataEncoder::new(&mut cursor)
+            .update_encoded_metadata(&metadata)
+            .is_err());
+    }
+
+    #[test]
+    fn test_update_encoded_metadata_version_mismatch() {
+        let mut metadata = MetadataBuilder::new()
+            .dataset(XNAS_ITCH.to_owned())
+            .schema(Some(Schema::Mbo))
+            .start(1697240529000000000)
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .build();
+        let mut cursor = io::Cursor::new(Vec::new());
+        MetadataEncoder::with_padding(&mut cursor, 2 * crate::SYMBOL_CSTR_LEN as u32)
+            .encode(&metadata)
+            .unwrap();
+        let before = cursor.get_ref().clone();
+        metadata.version = 1;
+        let res = MetadataEncoder::new(&mut cursor).update_encoded_metadata(&metadata);
+        assert!(
+            matches!(res, Err(Error::BadArgument { ref param_name, .. }) if param_name == "metadata")
+        );
+        assert_eq!(*cursor.get_ref(), before);
+    }
+
     #[rstest]
     #[case(1)]
     #[case(2)]
@@ -617,9 +901,9 @@ mod tests {
         let metadata = MetadataBuilder::new()
             .version(version)
             .dataset(XNAS_ITCH.to_owned())
-            .schema(Some(Schema::Mbo))
+            .schema(None)
             .start(1697240529000000000)
-            .stype_in(Some(SType::RawSymbol))
+            .stype_in(None)
             .stype_out(SType::InstrumentId)
             .build();
         assert!(metadata.end.is_none());
@@ -627,8 +911,10 @@ mod tests {
         let mut buffer = Vec::new();
         MetadataEncoder::new(&mut buffer).encode(&metadata).unwrap();
         let decoded = MetadataDecoder::new(buffer.as_slice()).decode().unwrap();
+        assert!(decoded.schema.is_none());
         assert!(decoded.end.is_none());
         assert!(decoded.limit.is_none());
+        assert!(decoded.stype_in.is_none());
     }
 
     #[rstest]
@@ -651,4 +937,63 @@ mod tests {
         assert_eq!(calc_length as usize + 8, buffer.len());
         assert_eq!(MetadataEncoder::<Vec<u8>>::MIN_ENCODED_SIZE, buffer.len());
     }
+
+    /// Accepts at most `max_len` bytes per write, like a pipe or socket.
+    struct ShortWriter {
+        buffer: Vec<u8>,
+        max_len: usize,
+        write_count: usize,
+    }
+
+    impl io::Write for ShortWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.write_vectored(&[IoSlice::new(buf)])
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+            self.write_count += 1;
+            let mut written = 0;
+            for buf in bufs {
+                let len = buf.len().min(self.max_len - written);
+                self.buffer.extend_from_slice(&buf[..len]);
+                written += len;
+            }
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[rstest]
+    #[case::partial(50)]
+    #[case::all(usize::MAX)]
+    fn test_encode_records_vectored(#[case] max_len: usize) {
+        let records: Vec<_> = (0..10)
+            .map(|i| MboMsg {
+                hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 5482, i),
+                order_id: i,
+                ..Default::default()
+            })
+            .collect();
+        let expected: Vec<u8> = records
+            .iter()
+            .flat_map(|rec| rec.as_ref().iter().copied())
+            .collect();
+        let mut target = RecordEncoder::new(ShortWriter {
+            buffer: Vec::new(),
+            max_len,
+            write_count: 0,
+        });
+        target.encode_records(&records).unwrap();
+        assert_eq!(target.get_ref().buffer, expected);
+        if max_len == usize::MAX {
+            assert_eq!(target.get_ref().write_count, 1);
+        }
+        let refs: Vec<_> = records.iter().map(RecordRef::from).collect();
+        target.get_mut().buffer.clear();
+        target.encode_record_refs(&refs).unwrap();
+        assert_eq!(target.get_ref().buffer, expected);
+    }
 }
@@ -42,6 +42,7 @@ where
     use_pretty_ts: bool,
     with_symbol: bool,
     delimiter: u8,
+    buffer_size: usize,
 }
 
 impl<'m, W> DynEncoderBuilder<'m, W>
@@ -67,6 +68,7 @@ where
             use_pretty_ts: false,
             with_symbol: false,
             delimiter: b',',
+            buffer_size: 0,
         }
     }
 
@@ -121,6 +123,17 @@ where
         self
     }
 
+    /// Sets the size in bytes of the internal buffer for uncompressed output. Defaults
+    /// to 0, which disables the buffer and is appropriate when `writer` is already
+    /// buffered. Without a buffer, each record is written to `writer` separately.
+    ///
+    /// CSV output is always buffered by the CSV encoder, which uses this size when
+    /// non-zero. Zstd-compressed output is always buffered by the compressor.
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
     /// Creates the new encoder with the previously specified settings and if
     /// `write_header` is `true`, encodes the header row.
     ///
@@ -128,17 +141,27 @@ where
     /// This function returns an error if it fails to write the CSV header row or the
     /// DBN metadata.
     pub fn build<'a>(self) -> crate::Result<DynEncoder<'a, W>> {
-        let writer = DynWriter::new(self.writer, self.compression)?;
+        // The CSV encoder has its own buffer
+        let writer_buffer_size = if self.encoding == Encoding::Csv {
+            0
+        } else {
+            self.buffer_size
+        };
+        let writer =
+            DynWriter::with_buffer_size(self.writer, self.compression, writer_buffer_size)?;
         Ok(DynEncoder(match self.encoding {
             Encoding::Dbn => DynEncoderImpl::Dbn(DbnEncoder::new(writer, self.metadata)?),
             Encoding::Csv => {
-                let builder = CsvEncoder::builder(writer)
+                let mut builder = CsvEncoder::builder(writer)
                     .use_pretty_px(self.use_pretty_px)
                     .use_pretty_ts(self.use_pretty_ts)
                     .delimiter(self.delimiter)
                     .write_header(self.write_header)
                     .ts_out(self.metadata.ts_out)
                     .with_symbol(self.with_symbol);
+                if self.buffer_size > 0 {
+                    builder = builder.buffer_size(self.buffer_size);
+                }
                 DynEncoderImpl::Csv(if self.write_header {
                     builder.schema(self.metadata.schema)?.build()?
                 } else {
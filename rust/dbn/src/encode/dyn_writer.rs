@@ -14,6 +14,7 @@ where
     W: io::Write,
 {
     Uncompressed(W),
+    Buffered(io::BufWriter<W>),
     ZStd(zstd::stream::AutoFinishEncoder<'a, W>),
 }
 
@@ -26,8 +27,28 @@ where
     /// # Errors
     /// This function returns an error if it fails to initialize the Zstd compression.
     pub fn new(writer: W, compression: Compression) -> Result<Self> {
+        Self::with_buffer_size(writer, compression, 0)
+    }
+
+    /// Create a new instance of [`DynWriter`] which will wrap `writer` with
+    /// `compression` and buffer uncompressed output in an internal buffer of
+    /// `buffer_size` bytes. A `buffer_size` of 0 disables the buffer, e.g. when
+    /// `writer` is already buffered. Zstd-compressed output is always buffered by the
+    /// compressor.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to initialize the Zstd compression.
+    pub fn with_buffer_size(
+        writer: W,
+        compression: Compression,
+        buffer_size: usize,
+    ) -> Result<Self> {
         match compression {
-            Compression::None => Ok(Self(DynWriterImpl::Uncompressed(writer))),
+            Compression::None if buffer_size == 0 => Ok(Self(DynWriterImpl::Uncompressed(writer))),
+            Compression::None => Ok(Self(DynWriterImpl::Buffered(io::BufWriter::with_capacity(
+                buffer_size,
+                writer,
+            )))),
             Compression::ZStd => zstd_encoder(writer).map(|enc| Self(DynWriterImpl::ZStd(enc))),
         }
     }
@@ -36,6 +57,7 @@ where
     pub fn get_mut(&mut self) -> &mut W {
         match &mut self.0 {
             DynWriterImpl::Uncompressed(w) => w,
+            DynWriterImpl::Buffered(w) => w.get_mut(),
             DynWriterImpl::ZStd(enc) => enc.get_mut(),
         }
     }
@@ -48,6 +70,7 @@ where
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match &mut self.0 {
             DynWriterImpl::Uncompressed(writer) => writer.write(buf),
+            DynWriterImpl::Buffered(writer) => writer.write(buf),
             DynWriterImpl::ZStd(writer) => writer.write(buf),
         }
     }
@@ -55,6 +78,7 @@ where
     fn flush(&mut self) -> io::Result<()> {
         match &mut self.0 {
             DynWriterImpl::Uncompressed(writer) => writer.flush(),
+            DynWriterImpl::Buffered(writer) => writer.flush(),
             DynWriterImpl::ZStd(writer) => writer.flush(),
         }
     }
@@ -62,6 +86,7 @@ where
     fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
         match &mut self.0 {
             DynWriterImpl::Uncompressed(writer) => writer.write_vectored(bufs),
+            DynWriterImpl::Buffered(writer) => writer.write_vectored(bufs),
             DynWriterImpl::ZStd(writer) => writer.write_vectored(bufs),
         }
     }
@@ -69,6 +94,7 @@ where
     fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
         match &mut self.0 {
             DynWriterImpl::Uncompressed(writer) => writer.write_all(buf),
+            DynWriterImpl::Buffered(writer) => writer.write_all(buf),
             DynWriterImpl::ZStd(writer) => writer.write_all(buf),
         }
     }
@@ -76,6 +102,7 @@ where
     fn write_fmt(&mut self, fmt: std::fmt::Arguments<'_>) -> io::Result<()> {
         match &mut self.0 {
             DynWriterImpl::Uncompressed(writer) => writer.write_fmt(fmt),
+            DynWriterImpl::Buffered(writer) => writer.write_fmt(fmt),
             DynWriterImpl::ZStd(writer) => writer.write_fmt(fmt),
         }
     }
@@ -1,6 +1,9 @@
 //! Encoding of DBN records into newline-delimited JSON (ndjson).
 
-pub(crate) mod serialize;
+#[cfg(feature = "schemars")]
+pub mod schema;
+#[doc(hidden)]
+pub mod serialize;
 mod sync;
 pub use sync::{Encoder, EncoderBuilder};
 #[cfg(feature = "async")]
@@ -1,6 +1,6 @@
 use tokio::io;
 
-use super::serialize::to_json_string;
+use super::serialize::{to_json_string, write_json};
 use crate::{
     encode::DbnEncodable, record_ref::RecordRef, rtype_ts_out_async_method_dispatch, Error,
     Metadata, Result,
@@ -15,6 +15,8 @@ where
     should_pretty_print: bool,
     use_pretty_px: bool,
     use_pretty_ts: bool,
+    /// Reused across records to avoid allocating for each record.
+    buffer: String,
 }
 
 impl<W> Encoder<W>
@@ -35,6 +37,7 @@ where
             should_pretty_print,
             use_pretty_px,
             use_pretty_ts,
+            buffer: String::new(),
         }
     }
 
@@ -86,13 +89,15 @@ where
     /// record may have been partially written, but future calls will begin writing the
     /// encoded record from the beginning.
     pub async fn encode_record<R: DbnEncodable>(&mut self, record: &R) -> Result<()> {
-        let json = to_json_string(
+        self.buffer.clear();
+        write_json(
+            &mut self.buffer,
             record,
             self.should_pretty_print,
             self.use_pretty_px,
             self.use_pretty_ts,
         );
-        match self.writer.write_all(json.as_bytes()).await {
+        match self.writer.write_all(self.buffer.as_bytes()).await {
             Ok(()) => Ok(()),
             Err(e) => Err(Error::io(e, "writing record")),
         }
@@ -0,0 +1,428 @@
+//! Implementations of [`schemars::JsonSchema`] describing the output of the JSON
+//! encoder. Requires the `schemars` feature.
+//!
+//! 64-bit integers are encoded as strings to avoid a loss of precision, and with
+//! `pretty_px` and `pretty_ts`, undefined prices and timestamps are encoded as `null`.
+
+use std::ffi::c_char;
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{ArrayValidation, InstanceType, Schema, SchemaObject, SingleOrVec, StringValidation},
+    JsonSchema,
+};
+
+use crate::{
+    record::{BidAskPair, ConsolidatedBidAskPair, HasRType, RecordHeader, WithTsOut},
+    Metadata, SecurityUpdateAction, UserDefinedInstrument,
+};
+
+/// Describes how a field of a record is encoded in JSON.
+pub(crate) trait JsonSchemaField {
+    fn field_schema(gen: &mut SchemaGenerator) -> Schema;
+}
+
+macro_rules! impl_json_schema_field_for {
+    ($($ty:ident),+) => {
+        $(
+            impl JsonSchemaField for $ty {
+                fn field_schema(gen: &mut SchemaGenerator) -> Schema {
+                    gen.subschema_for::<$ty>()
+                }
+            }
+        )*
+    };
+}
+
+impl_json_schema_field_for! {i32, u32, i16, u16, i8, u8, bool}
+
+impl JsonSchemaField for i64 {
+    fn field_schema(_gen: &mut SchemaGenerator) -> Schema {
+        string_schema(None)
+    }
+}
+
+impl JsonSchemaField for u64 {
+    fn field_schema(_gen: &mut SchemaGenerator) -> Schema {
+        string_schema(None)
+    }
+}
+
+impl<const N: usize> JsonSchemaField for [c_char; N] {
+    fn field_schema(_gen: &mut SchemaGenerator) -> Schema {
+        // Excludes the null terminator
+        string_schema(Some(N.saturating_sub(1) as u32))
+    }
+}
+
+impl JsonSchemaField for SecurityUpdateAction {
+    fn field_schema(_gen: &mut SchemaGenerator) -> Schema {
+        char_schema()
+    }
+}
+
+impl JsonSchemaField for UserDefinedInstrument {
+    fn field_schema(_gen: &mut SchemaGenerator) -> Schema {
+        char_schema()
+    }
+}
+
+impl JsonSchemaField for RecordHeader {
+    fn field_schema(gen: &mut SchemaGenerator) -> Schema {
+        gen.subschema_for::<RecordHeader>()
+    }
+}
+
+impl<const N: usize> JsonSchemaField for [BidAskPair; N] {
+    fn field_schema(gen: &mut SchemaGenerator) -> Schema {
+        fixed_array_schema(gen.subschema_for::<BidAskPair>(), N)
+    }
+}
+
+impl<const N: usize> JsonSchemaField for [ConsolidatedBidAskPair; N] {
+    fn field_schema(gen: &mut SchemaGenerator) -> Schema {
+        fixed_array_schema(gen.subschema_for::<ConsolidatedBidAskPair>(), N)
+    }
+}
+
+impl<T: HasRType + JsonSchema> JsonSchema for WithTsOut<T> {
+    fn schema_name() -> String {
+        format!("WithTsOut_{}", T::schema_name())
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut obj = T::json_schema(gen).into_object();
+        insert_property(
+            &mut obj,
+            "ts_out",
+            "The live gateway send timestamp expressed as number of nanoseconds since the UNIX epoch.",
+            ts_schema(),
+        );
+        obj.into()
+    }
+}
+
+impl JsonSchema for Metadata {
+    fn schema_name() -> String {
+        "Metadata".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let nullable_string = nullable_schema(string_schema(None));
+        let string_array = array_schema(string_schema(None));
+        let mut interval = object_schema();
+        for (name, description) in [
+            ("start_date", "The UTC start date of interval (inclusive)."),
+            ("end_date", "The UTC end date of interval (exclusive)."),
+        ] {
+            insert_property(&mut interval, name, description, date_schema());
+        }
+        insert_property(
+            &mut interval,
+            "symbol",
+            "The resolved symbol for this interval.",
+            string_schema(None),
+        );
+        let mut mapping = object_schema();
+        insert_property(
+            &mut mapping,
+            "raw_symbol",
+            "The symbol assigned by publisher.",
+            string_schema(None),
+        );
+        insert_property(
+            &mut mapping,
+            "intervals",
+            "The mappings for different date ranges.",
+            array_schema(interval.into()),
+        );
+
+        let mut obj = object_schema();
+        for (name, description, schema) in [
+            (
+                "version",
+                "The DBN schema version number.",
+                gen.subschema_for::<u8>(),
+            ),
+            ("dataset", "The dataset code.", string_schema(None)),
+            (
+                "schema",
+                "The data record schema. `null` indicates the DBN stream may contain more than one record type.",
+                nullable_string.clone(),
+            ),
+            (
+                "start",
+                "The UNIX nanosecond timestamp of the query start, or the first record if the file was split.",
+                ts_schema(),
+            ),
+            (
+                "end",
+                "The UNIX nanosecond timestamp of the query end, or the last record if the file was split.",
+                ts_schema(),
+            ),
+            (
+                "limit",
+                "The optional maximum number of records for the query.",
+                nullable_string.clone(),
+            ),
+            (
+                "stype_in",
+                "The input symbology type to map from. `null` indicates a mix.",
+                nullable_string,
+            ),
+            (
+                "stype_out",
+                "The output symbology type to map to.",
+                string_schema(None),
+            ),
+            (
+                "ts_out",
+                "`true` if this store contains live data with send timestamps appended to each record.",
+                gen.subschema_for::<bool>(),
+            ),
+            (
+                "symbol_cstr_len",
+                "The length in bytes of fixed-length symbol strings, including a null terminator byte.",
+                gen.subschema_for::<u32>(),
+            ),
+            (
+                "symbols",
+                "The original query input symbols from the request.",
+                string_array.clone(),
+            ),
+            (
+                "partial",
+                "Symbols that did not resolve for at least one day in the query time range.",
+                string_array.clone(),
+            ),
+            (
+                "not_found",
+                "Symbols that did not resolve for any day in the query time range.",
+                string_array,
+            ),
+            (
+                "mappings",
+                "Symbol mappings containing a raw symbol and its mapping intervals.",
+                array_schema(mapping.into()),
+            ),
+        ] {
+            insert_property(&mut obj, name, description, schema);
+        }
+        let mut custom = object_schema();
+        custom.object().additional_properties = Some(Box::new(string_schema(None)));
+        insert_property(
+            &mut obj,
+            "custom",
+            "User-defined key-value properties. Omitted when empty.",
+            custom.into(),
+        );
+        obj.object().required.remove("custom");
+        obj.into()
+    }
+}
+
+/// Returns an empty JSON object schema.
+pub(crate) fn object_schema() -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    }
+}
+
+/// Adds a required property to an object schema.
+pub(crate) fn insert_property(
+    obj: &mut SchemaObject,
+    name: &str,
+    description: &str,
+    mut schema: Schema,
+) {
+    if let Schema::Object(prop) = &mut schema {
+        if !description.is_empty() && prop.reference.is_none() {
+            prop.metadata().description = Some(description.to_owned());
+        }
+    }
+    let validation = obj.object();
+    validation.properties.insert(name.to_owned(), schema);
+    validation.required.insert(name.to_owned());
+}
+
+/// Schema for a fixed-precision price: a string, or `null` for an undefined price
+/// with `pretty_px`.
+pub(crate) fn px_schema() -> Schema {
+    nullable_schema(string_schema(None))
+}
+
+/// Schema for a UNIX nanosecond timestamp: a string, or `null` for an undefined
+/// timestamp with `pretty_ts`.
+pub(crate) fn ts_schema() -> Schema {
+    nullable_schema(string_schema(None))
+}
+
+/// Schema for a `c_char` field: a single-character string, or `null` for a null byte.
+pub(crate) fn c_char_schema() -> Schema {
+    nullable_schema(char_schema())
+}
+
+fn char_schema() -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            min_length: Some(1),
+            max_length: Some(1),
+            pattern: None,
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn string_schema(max_length: Option<u32>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: max_length.map(|max_length| {
+            Box::new(StringValidation {
+                max_length: Some(max_length),
+                ..Default::default()
+            })
+        }),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Schema for a date: a `YYYYMMDD` integer, or a `YYYY-MM-DD` string with `pretty_ts`.
+fn date_schema() -> Schema {
+    SchemaObject {
+        instance_type: Some(SingleOrVec::Vec(vec![
+            InstanceType::Integer,
+            InstanceType::String,
+        ])),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn nullable_schema(schema: Schema) -> Schema {
+    let mut obj = schema.into_object();
+    if let Some(SingleOrVec::Single(instance_type)) = &obj.instance_type {
+        let instance_type = **instance_type;
+        obj.instance_type = Some(SingleOrVec::Vec(vec![instance_type, InstanceType::Null]));
+    }
+    obj.into()
+}
+
+fn array_schema(items: Schema) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(items.into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn fixed_array_schema(items: Schema, len: usize) -> Schema {
+    let mut obj = array_schema(items).into_object();
+    let array = obj.array();
+    array.min_items = Some(len as u32);
+    array.max_items = Some(len as u32);
+    obj.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::schema_for;
+
+    use super::*;
+    use crate::record::{InstrumentDefMsg, MboMsg, Mbp10Msg};
+
+    fn properties(schema: &SchemaObject) -> Vec<&str> {
+        schema
+            .object
+            .as_ref()
+            .unwrap()
+            .properties
+            .keys()
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn test_mbo_schema() {
+        let root = schema_for!(MboMsg);
+        let mut props = properties(&root.schema);
+        props.sort_unstable();
+        assert_eq!(
+            props,
+            [
+                "action",
+                "channel_id",
+                "flags",
+                "hd",
+                "order_id",
+                "price",
+                "sequence",
+                "side",
+                "size",
+                "ts_in_delta",
+                "ts_recv",
+            ]
+        );
+        let Schema::Object(hd) = &root.definitions["RecordHeader"] else {
+            panic!("Expected object schema");
+        };
+        let mut hd_props = properties(hd);
+        hd_props.sort_unstable();
+        assert_eq!(
+            hd_props,
+            ["instrument_id", "publisher_id", "rtype", "ts_event"]
+        );
+    }
+
+    #[test]
+    fn test_hidden_fields_excluded() {
+        let root = schema_for!(InstrumentDefMsg);
+        assert!(properties(&root.schema)
+            .iter()
+            .all(|prop| !prop.starts_with('_')));
+    }
+
+    #[test]
+    fn test_levels_schema() {
+        let root = schema_for!(Mbp10Msg);
+        let Schema::Object(levels) = &root.schema.object.as_ref().unwrap().properties["levels"]
+        else {
+            panic!("Expected object schema");
+        };
+        let array = levels.array.as_ref().unwrap();
+        assert_eq!(array.min_items, Some(10));
+        assert_eq!(array.max_items, Some(10));
+    }
+
+    #[test]
+    fn test_with_ts_out_schema() {
+        let root = schema_for!(WithTsOut<MboMsg>);
+        assert!(root
+            .schema
+            .object
+            .as_ref()
+            .unwrap()
+            .required
+            .contains("ts_out"));
+    }
+
+    #[test]
+    fn test_metadata_schema() {
+        let root = schema_for!(Metadata);
+        assert_eq!(properties(&root.schema).len(), 15);
+        assert!(!root
+            .schema
+            .object
+            .as_ref()
+            .unwrap()
+            .required
+            .contains("custom"));
+    }
+}
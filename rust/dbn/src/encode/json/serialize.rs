@@ -2,10 +2,10 @@ use std::ffi::c_char;
 
 use crate::{
     json_writer::{JsonObjectWriter, NULL},
-    pretty::{fmt_px, fmt_ts},
+    pretty::{PxBuffer, TsBuffer},
     record::c_chars_to_str,
-    BidAskPair, HasRType, Metadata, RecordHeader, SecurityUpdateAction, UserDefinedInstrument,
-    WithTsOut, UNDEF_PRICE, UNDEF_TIMESTAMP,
+    BidAskPair, ConsolidatedBidAskPair, HasRType, Metadata, RecordHeader, SecurityUpdateAction,
+    UserDefinedInstrument, WithTsOut, UNDEF_PRICE, UNDEF_TIMESTAMP,
 };
 
 /// Serializes `obj` to a JSON string.
@@ -16,39 +16,77 @@ pub fn to_json_string<T: JsonSerialize>(
     use_pretty_ts: bool,
 ) -> String {
     let mut res = String::new();
+    write_json(
+        &mut res,
+        obj,
+        should_pretty_print,
+        use_pretty_px,
+        use_pretty_ts,
+    );
+    res
+}
+
+/// Serializes `obj` to a JSON string with an optional `symbol`.
+pub fn to_json_string_with_sym<T: JsonSerialize>(
+    obj: &T,
+    should_pretty_print: bool,
+    use_pretty_px: bool,
+    use_pretty_ts: bool,
+    symbol: Option<&str>,
+) -> String {
+    let mut res = String::new();
+    write_json_with_sym(
+        &mut res,
+        obj,
+        should_pretty_print,
+        use_pretty_px,
+        use_pretty_ts,
+        symbol,
+    );
+    res
+}
+
+/// Serializes `obj` as a line of JSON, appending it to `buffer`. Reusing `buffer`
+/// across calls avoids allocating for each object.
+pub fn write_json<T: JsonSerialize>(
+    buffer: &mut String,
+    obj: &T,
+    should_pretty_print: bool,
+    use_pretty_px: bool,
+    use_pretty_ts: bool,
+) {
     if should_pretty_print {
-        let mut pretty = pretty_writer(&mut res);
+        let mut pretty = pretty_writer(buffer);
         let mut writer = JsonObjectWriter::new(&mut pretty);
         to_json_with_writer(obj, &mut writer, use_pretty_px, use_pretty_ts);
     } else {
-        let mut writer = JsonObjectWriter::new(&mut res);
+        let mut writer = JsonObjectWriter::new(&mut *buffer);
         to_json_with_writer(obj, &mut writer, use_pretty_px, use_pretty_ts);
     }
-    res.push('\n');
-    res
+    buffer.push('\n');
 }
 
-/// Serializes `obj` to a JSON string with an optional `symbol`.
-pub fn to_json_string_with_sym<T: JsonSerialize>(
+/// Serializes `obj` as a line of JSON with an optional `symbol`, appending it to
+/// `buffer`.
+pub fn write_json_with_sym<T: JsonSerialize>(
+    buffer: &mut String,
     obj: &T,
     should_pretty_print: bool,
     use_pretty_px: bool,
     use_pretty_ts: bool,
     symbol: Option<&str>,
-) -> String {
-    let mut res = String::new();
+) {
     if should_pretty_print {
-        let mut pretty = pretty_writer(&mut res);
+        let mut pretty = pretty_writer(buffer);
         let mut writer = JsonObjectWriter::new(&mut pretty);
         to_json_with_writer(obj, &mut writer, use_pretty_px, use_pretty_ts);
         writer.value("symbol", symbol);
     } else {
-        let mut writer = JsonObjectWriter::new(&mut res);
+        let mut writer = JsonObjectWriter::new(&mut *buffer);
         to_json_with_writer(obj, &mut writer, use_pretty_px, use_pretty_ts);
         writer.value("symbol", symbol);
     }
-    res.push('\n');
-    res
+    buffer.push('\n');
 }
 
 fn to_json_with_writer<T: JsonSerialize, J: crate::json_writer::JsonWriter>(
@@ -136,6 +174,13 @@ impl JsonSerialize for Metadata {
                 interval_writer.value("symbol", &interval.symbol);
             }
         }
+        drop(mappings_writer);
+        if !self.custom.is_empty() {
+            let mut custom_writer = writer.object("custom");
+            for (key, value) in self.custom.iter() {
+                custom_writer.value(key, value);
+            }
+        }
     }
 }
 
@@ -193,6 +238,29 @@ impl<const N: usize> WriteField for [BidAskPair; N] {
     }
 }
 
+impl<const N: usize> WriteField for [ConsolidatedBidAskPair; N] {
+    fn write_field<
+        J: crate::json_writer::JsonWriter,
+        const PRETTY_PX: bool,
+        const PRETTY_TS: bool,
+    >(
+        &self,
+        writer: &mut JsonObjectWriter<J>,
+        name: &str,
+    ) {
+        let mut arr_writer = writer.array(name);
+        for level in self.iter() {
+            let mut item_writer = arr_writer.object();
+            write_px_field::<J, PRETTY_PX>(&mut item_writer, "bid_px", level.bid_px);
+            write_px_field::<J, PRETTY_PX>(&mut item_writer, "ask_px", level.ask_px);
+            item_writer.value("bid_sz", level.bid_sz);
+            item_writer.value("ask_sz", level.ask_sz);
+            item_writer.value("bid_pb", level.bid_pb);
+            item_writer.value("ask_pb", level.ask_pb);
+        }
+    }
+}
+
 impl WriteField for i64 {
     fn write_field<
         J: crate::json_writer::JsonWriter,
@@ -305,7 +373,7 @@ pub fn write_px_field<J: crate::json_writer::JsonWriter, const PRETTY_PX: bool>(
         if px == UNDEF_PRICE {
             writer.value(key, NULL);
         } else {
-            writer.value(key, &fmt_px(px));
+            writer.value(key, PxBuffer::new().format(px));
         }
     } else {
         // Convert to string to avoid a loss of precision
@@ -321,7 +389,7 @@ pub fn write_ts_field<J: crate::json_writer::JsonWriter, const PRETTY_TS: bool>(
     if PRETTY_TS {
         match ts {
             0 | UNDEF_TIMESTAMP => writer.value(key, NULL),
-            ts => writer.value(key, &fmt_ts(ts)),
+            ts => writer.value(key, TsBuffer::new().format(ts)),
         };
     } else {
         // Convert to string to avoid a loss of precision
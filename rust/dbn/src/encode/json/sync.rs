@@ -1,6 +1,6 @@
 use std::io;
 
-use super::serialize::{to_json_string, to_json_string_with_sym};
+use super::serialize::{to_json_string, write_json, write_json_with_sym};
 use crate::{
     encode::{DbnEncodable, EncodeDbn, EncodeRecord, EncodeRecordRef, EncodeRecordTextExt},
     rtype_method_dispatch, rtype_ts_out_method_dispatch, Error, Metadata, Result,
@@ -15,6 +15,8 @@ where
     should_pretty_print: bool,
     use_pretty_px: bool,
     use_pretty_ts: bool,
+    /// Reused across records to avoid allocating for each record.
+    buffer: String,
 }
 
 /// Helper for constructing a JSON [`Encoder`].
@@ -96,6 +98,7 @@ where
             should_pretty_print,
             use_pretty_px,
             use_pretty_ts,
+            buffer: String::new(),
         }
     }
 
@@ -137,13 +140,15 @@ where
     W: io::Write,
 {
     fn encode_record<R: DbnEncodable>(&mut self, record: &R) -> Result<()> {
-        let json = to_json_string(
+        self.buffer.clear();
+        write_json(
+            &mut self.buffer,
             record,
             self.should_pretty_print,
             self.use_pretty_px,
             self.use_pretty_ts,
         );
-        match self.writer.write_all(json.as_bytes()) {
+        match self.writer.write_all(self.buffer.as_bytes()) {
             Ok(()) => Ok(()),
             Err(e) => Err(Error::io(e, "writing record")),
         }
@@ -184,14 +189,16 @@ where
         record: &R,
         symbol: Option<&str>,
     ) -> Result<()> {
-        let json = to_json_string_with_sym(
+        self.buffer.clear();
+        write_json_with_sym(
+            &mut self.buffer,
             record,
             self.should_pretty_print,
             self.use_pretty_px,
             self.use_pretty_ts,
             symbol,
         );
-        match self.writer.write_all(json.as_bytes()) {
+        match self.writer.write_all(self.buffer.as_bytes()) {
             Ok(()) => Ok(()),
             Err(e) => Err(Error::io(e, "writing record")),
         }
@@ -200,7 +207,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::{array, io::BufWriter, num::NonZeroU64, os::raw::c_char};
+    use std::{array, collections::BTreeMap, io::BufWriter, num::NonZeroU64, os::raw::c_char};
 
     use super::*;
     use crate::{
@@ -301,6 +308,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_buffer_cleared_between_records() {
+        let long = MboMsg {
+            hd: RECORD_HEADER,
+            order_id: 1_234_567_890_123,
+            price: 372_000_000_000_000,
+            size: 3_000_000,
+            action: 'A' as c_char,
+            side: 'B' as c_char,
+            ts_recv: 1658441891000000000,
+            ..Default::default()
+        };
+        let short = MboMsg {
+            hd: RECORD_HEADER,
+            order_id: 1,
+            price: 1,
+            size: 1,
+            action: 'C' as c_char,
+            side: 'A' as c_char,
+            ..Default::default()
+        };
+        for pretty in [false, true] {
+            let mut buffer = Vec::new();
+            let mut encoder = Encoder::new(&mut buffer, pretty, pretty, pretty);
+            encoder.encode_record(&long).unwrap();
+            encoder.encode_record(&short).unwrap();
+            encoder.encode_record_with_sym(&long, Some("AAPL")).unwrap();
+            encoder.encode_record_with_sym(&short, None).unwrap();
+            let mut expected = to_json_string(&long, pretty, pretty, pretty);
+            expected.push_str(&to_json_string(&short, pretty, pretty, pretty));
+            for (rec, symbol) in [(&long, Some("AAPL")), (&short, None)] {
+                let mut json = String::new();
+                write_json_with_sym(&mut json, rec, pretty, pretty, pretty, symbol);
+                expected.push_str(&json);
+            }
+            assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+        }
+    }
+
     #[test]
     fn test_mbp1_write_json() {
         let data = vec![Mbp1Msg {
@@ -637,6 +683,7 @@ mod tests {
                     symbol: "ESH2".to_owned(),
                 }],
             }],
+            custom: BTreeMap::new(),
         };
         let res = write_json_metadata_to_string(&metadata, false);
         assert_eq!(
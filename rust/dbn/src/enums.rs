@@ -16,6 +16,11 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 /// of the aggressor for trades.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
 #[repr(u8)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "databento_dbn", rename_all = "SCREAMING_SNAKE_CASE")
+)]
+#[cfg_attr(feature = "python", derive(strum::EnumIter))]
 pub enum Side {
     /// A sell order or sell aggressor in a trade.
     Ask = b'A',
@@ -32,13 +37,18 @@ impl From<Side> for char {
 }
 
 /// A tick action.
-/// 
+///
 /// This is used to indicate order life cycle, such as order cancelation and addition.  
 /// You can find examples here.  
 /// - https://databento.com/docs/examples/order-book/order-tracking  
 /// - https://databento.com/docs/examples/order-book/order-actions  
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
 #[repr(u8)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(module = "databento_dbn", rename_all = "SCREAMING_SNAKE_CASE")
+)]
+#[cfg_attr(feature = "python", derive(strum::EnumIter))]
 pub enum Action {
     /// An existing order was modified.
     Modify = b'M',
@@ -276,50 +286,86 @@ pub mod rtype {
         Statistics = 0x18,
         /// Denotes a market by order record.
         Mbo = 0xA0,
+        /// Denotes a consolidated best bid and offer record.
+        Cbbo = 0xB1,
+        /// Denotes a consolidated best bid and offer record subsampled on a one-second
+        /// interval.
+        Cbbo1S = 0xC0,
+        /// Denotes a consolidated best bid and offer record subsampled on a one-minute
+        /// interval.
+        Cbbo1M = 0xC1,
+        /// Denotes a consolidated best bid and offer trade record containing the
+        /// consolidated BBO before the trade.
+        Tcbbo = 0xC2,
+        /// Denotes a best bid and offer record subsampled on a one-second interval.
+        Bbo1S = 0xC3,
+        /// Denotes a best bid and offer record subsampled on a one-minute interval.
+        Bbo1M = 0xC4,
     }
 
     /// Denotes a market-by-price record with a book depth of 0 (used for the
     /// [`Trades`](super::Schema::Trades) schema).
-    pub const MBP_0: u8 = RType::Mbp0 as u8;
+    pub const MBP_0: u8 = 0x00;
     /// Denotes a market-by-price record with a book depth of 1 (also used for the
     /// [`Tbbo`](super::Schema::Tbbo) schema).
-    pub const MBP_1: u8 = RType::Mbp1 as u8;
+    pub const MBP_1: u8 = 0x01;
     /// Denotes a market-by-price record with a book depth of 10.
-    pub const MBP_10: u8 = RType::Mbp10 as u8;
+    pub const MBP_10: u8 = 0x0A;
     /// Denotes an open, high, low, close, and volume record at an unspecified cadence.
     #[deprecated(
         since = "0.3.3",
         note = "Separated into separate rtypes for each OHLCV schema."
     )]
-    pub const OHLCV_DEPRECATED: u8 = RType::OhlcvDeprecated as u8;
+    pub const OHLCV_DEPRECATED: u8 = 0x11;
     /// Denotes an open, high, low, close, and volume record at a 1-second cadence.
-    pub const OHLCV_1S: u8 = RType::Ohlcv1S as u8;
+    pub const OHLCV_1S: u8 = 0x20;
     /// Denotes an open, high, low, close, and volume record at a 1-minute cadence.
-    pub const OHLCV_1M: u8 = RType::Ohlcv1M as u8;
+    pub const OHLCV_1M: u8 = 0x21;
     /// Denotes an open, high, low, close, and volume record at an hourly cadence.
-    pub const OHLCV_1H: u8 = RType::Ohlcv1H as u8;
+    pub const OHLCV_1H: u8 = 0x22;
     /// Denotes an open, high, low, close, and volume record at a daily cadence based
     /// on the UTC date.
-    pub const OHLCV_1D: u8 = RType::Ohlcv1D as u8;
+    pub const OHLCV_1D: u8 = 0x23;
     /// Denotes an open, high, low, close, and volume record at a daily cadence
     /// based on the end of the trading session.
-    pub const OHLCV_EOD: u8 = RType::OhlcvEod as u8;
+    pub const OHLCV_EOD: u8 = 0x24;
     /// Denotes an exchange status record.
-    pub const STATUS: u8 = RType::Status as u8;
+    pub const STATUS: u8 = 0x12;
     /// Denotes an instrument definition record.
-    pub const INSTRUMENT_DEF: u8 = RType::InstrumentDef as u8;
+    pub const INSTRUMENT_DEF: u8 = 0x13;
     /// Denotes an order imbalance record.
-    pub const IMBALANCE: u8 = RType::Imbalance as u8;
+    pub const IMBALANCE: u8 = 0x14;
     /// Denotes an error from gateway.
-    pub const ERROR: u8 = RType::Error as u8;
+    pub const ERROR: u8 = 0x15;
     /// Denotes a symbol mapping record.
-    pub const SYMBOL_MAPPING: u8 = RType::SymbolMapping as u8;
+    pub const SYMBOL_MAPPING: u8 = 0x16;
     /// Denotes a non-error message from the gateway. Also used for heartbeats.
-    pub const SYSTEM: u8 = RType::System as u8;
+    pub const SYSTEM: u8 = 0x17;
     /// Denotes a statistics record from the publisher (not calculated by Databento).
-    pub const STATISTICS: u8 = RType::Statistics as u8;
+    pub const STATISTICS: u8 = 0x18;
     /// Denotes a market-by-order record.
-    pub const MBO: u8 = RType::Mbo as u8;
+    pub const MBO: u8 = 0xA0;
+    /// Denotes a consolidated best bid and offer record.
+    pub const CBBO: u8 = 0xB1;
+    /// Denotes a consolidated best bid and offer record subsampled on a one-second
+    /// interval.
+    pub const CBBO_1S: u8 = 0xC0;
+    /// Denotes a consolidated best bid and offer record subsampled on a one-minute
+    /// interval.
+    pub const CBBO_1M: u8 = 0xC1;
+    /// Denotes a consolidated best bid and offer trade record containing the
+    /// consolidated BBO before the trade.
+    pub const TCBBO: u8 = 0xC2;
+    /// Denotes a best bid and offer record subsampled on a one-second interval.
+    pub const BBO_1S: u8 = 0xC3;
+    /// Denotes a best bid and offer record subsampled on a one-minute interval.
+    pub const BBO_1M: u8 = 0xC4;
+    /// The first rtype of the range reserved for user-defined records, e.g. proprietary
+    /// records embedded in a DBN stream. Databento will never assign an rtype in this
+    /// range.
+    pub const USER_DEFINED_START: u8 = 0xF0;
+    /// The last rtype of the range reserved for user-defined records.
+    pub const USER_DEFINED_END: u8 = 0xFE;
 
     /// Get the corresponding `rtype` for the given `schema`.
     impl From<Schema> for RType {
@@ -338,10 +384,22 @@ pub mod rtype {
                 Schema::Statistics => RType::Statistics,
                 Schema::Status => RType::Status,
                 Schema::Imbalance => RType::Imbalance,
+                Schema::Cbbo => RType::Cbbo,
+                Schema::Cbbo1S => RType::Cbbo1S,
+                Schema::Cbbo1M => RType::Cbbo1M,
+                Schema::Tcbbo => RType::Tcbbo,
+                Schema::Bbo1S => RType::Bbo1S,
+                Schema::Bbo1M => RType::Bbo1M,
             }
         }
     }
 
+    /// Returns `true` if `rtype` is in the range reserved for user-defined records. See
+    /// [`CustomRecord`](crate::CustomRecord).
+    pub fn is_user_defined(rtype: u8) -> bool {
+        (USER_DEFINED_START..=USER_DEFINED_END).contains(&rtype)
+    }
+
     /// Tries to convert the given rtype to a [`Schema`].
     ///
     /// Returns `None` if there's no corresponding `Schema` for the given rtype or
@@ -361,6 +419,12 @@ pub mod rtype {
             IMBALANCE => Some(Schema::Imbalance),
             STATISTICS => Some(Schema::Statistics),
             MBO => Some(Schema::Mbo),
+            CBBO => Some(Schema::Cbbo),
+            CBBO_1S => Some(Schema::Cbbo1S),
+            CBBO_1M => Some(Schema::Cbbo1M),
+            TCBBO => Some(Schema::Tcbbo),
+            BBO_1S => Some(Schema::Bbo1S),
+            BBO_1M => Some(Schema::Bbo1M),
             _ => None,
         }
     }
@@ -387,6 +451,12 @@ pub mod rtype {
                 "system" => Ok(RType::System),
                 "statistics" => Ok(RType::Statistics),
                 "mbo" => Ok(RType::Mbo),
+                "cbbo" => Ok(RType::Cbbo),
+                "cbbo-1s" => Ok(RType::Cbbo1S),
+                "cbbo-1m" => Ok(RType::Cbbo1M),
+                "tcbbo" => Ok(RType::Tcbbo),
+                "bbo-1s" => Ok(RType::Bbo1S),
+                "bbo-1m" => Ok(RType::Bbo1M),
                 _ => Err(crate::Error::conversion::<Self>(s.to_owned())),
             }
         }
@@ -413,6 +483,12 @@ pub mod rtype {
                 RType::System => "system",
                 RType::Statistics => "statistics",
                 RType::Mbo => "mbo",
+                RType::Cbbo => "cbbo",
+                RType::Cbbo1S => "cbbo-1s",
+                RType::Cbbo1M => "cbbo-1m",
+                RType::Tcbbo => "tcbbo",
+                RType::Bbo1S => "bbo-1s",
+                RType::Bbo1M => "bbo-1m",
             }
         }
     }
@@ -470,10 +546,31 @@ pub enum Schema {
     /// trading session.
     #[pyo3(name = "OHLCV_EOD")]
     OhlcvEod = 13,
+    /// Consolidated best bid and offer.
+    #[pyo3(name = "CBBO")]
+    Cbbo = 14,
+    /// Consolidated best bid and offer subsampled at one-second intervals, in addition
+    /// to trades.
+    #[pyo3(name = "CBBO_1S")]
+    Cbbo1S = 15,
+    /// Consolidated best bid and offer subsampled at one-minute intervals, in addition
+    /// to trades.
+    #[pyo3(name = "CBBO_1M")]
+    Cbbo1M = 16,
+    /// All trade events with the consolidated best bid and offer (CBBO) immediately
+    /// **before** the effect of the trade.
+    #[pyo3(name = "TCBBO")]
+    Tcbbo = 17,
+    /// Best bid and offer subsampled at one-second intervals, in addition to trades.
+    #[pyo3(name = "BBO_1S")]
+    Bbo1S = 18,
+    /// Best bid and offer subsampled at one-minute intervals, in addition to trades.
+    #[pyo3(name = "BBO_1M")]
+    Bbo1M = 19,
 }
 
 /// The number of [`Schema`]s.
-pub const SCHEMA_COUNT: usize = 14;
+pub const SCHEMA_COUNT: usize = 20;
 
 impl std::str::FromStr for Schema {
     type Err = crate::Error;
@@ -494,6 +591,12 @@ impl std::str::FromStr for Schema {
             "statistics" => Ok(Schema::Statistics),
             "status" => Ok(Schema::Status),
             "imbalance" => Ok(Schema::Imbalance),
+            "cbbo" => Ok(Schema::Cbbo),
+            "cbbo-1s" => Ok(Schema::Cbbo1S),
+            "cbbo-1m" => Ok(Schema::Cbbo1M),
+            "tcbbo" => Ok(Schema::Tcbbo),
+            "bbo-1s" => Ok(Schema::Bbo1S),
+            "bbo-1m" => Ok(Schema::Bbo1M),
             _ => Err(crate::Error::conversion::<Self>(s.to_owned())),
         }
     }
@@ -523,6 +626,12 @@ impl Schema {
             Schema::Statistics => "statistics",
             Schema::Status => "status",
             Schema::Imbalance => "imbalance",
+            Schema::Cbbo => "cbbo",
+            Schema::Cbbo1S => "cbbo-1s",
+            Schema::Cbbo1M => "cbbo-1m",
+            Schema::Tcbbo => "tcbbo",
+            Schema::Bbo1S => "bbo-1s",
+            Schema::Bbo1M => "bbo-1m",
         }
     }
 }
@@ -968,9 +1077,41 @@ mod deserialize {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::{Serialize, Serializer};
+
+    use super::*;
+
+    impl Serialize for Compression {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl Serialize for SType {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl Serialize for Schema {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl Serialize for Encoding {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rtype::RType;
 
     #[test]
     fn validate_schema_count() {
@@ -978,4 +1119,35 @@ mod tests {
 
         assert_eq!(Schema::COUNT, SCHEMA_COUNT);
     }
+
+    #[test]
+    fn validate_rtype_consts() {
+        for (val, variant) in [
+            (rtype::MBP_0, RType::Mbp0),
+            (rtype::MBP_1, RType::Mbp1),
+            (rtype::MBP_10, RType::Mbp10),
+            (rtype::OHLCV_DEPRECATED, RType::OhlcvDeprecated),
+            (rtype::OHLCV_1S, RType::Ohlcv1S),
+            (rtype::OHLCV_1M, RType::Ohlcv1M),
+            (rtype::OHLCV_1H, RType::Ohlcv1H),
+            (rtype::OHLCV_1D, RType::Ohlcv1D),
+            (rtype::OHLCV_EOD, RType::OhlcvEod),
+            (rtype::STATUS, RType::Status),
+            (rtype::INSTRUMENT_DEF, RType::InstrumentDef),
+            (rtype::IMBALANCE, RType::Imbalance),
+            (rtype::ERROR, RType::Error),
+            (rtype::SYMBOL_MAPPING, RType::SymbolMapping),
+            (rtype::SYSTEM, RType::System),
+            (rtype::STATISTICS, RType::Statistics),
+            (rtype::MBO, RType::Mbo),
+            (rtype::CBBO, RType::Cbbo),
+            (rtype::CBBO_1S, RType::Cbbo1S),
+            (rtype::CBBO_1M, RType::Cbbo1M),
+            (rtype::TCBBO, RType::Tcbbo),
+            (rtype::BBO_1S, RType::Bbo1S),
+            (rtype::BBO_1M, RType::Bbo1M),
+        ] {
+            assert_eq!(val, variant as u8);
+        }
+    }
 }
@@ -0,0 +1,313 @@
+//! Analytics of auction imbalance records, grouping the [`ImbalanceMsg`] of each
+//! auction to track its paired and imbalance quantities and indicative prices over
+//! time.
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    num::NonZeroU64,
+    os::raw::c_char,
+};
+
+use crate::{enums::Side, ImbalanceMsg, UNDEF_PRICE};
+
+/// The state of an auction from a single [`ImbalanceMsg`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImbalanceSnapshot {
+    /// The capture-server-received timestamp expressed as the number of nanoseconds
+    /// since the UNIX epoch.
+    pub ts_recv: u64,
+    /// The price at which the imbalance shares are calculated.
+    pub ref_price: i64,
+    /// The indicative auction-clearing price for both cross and continuous orders.
+    pub cont_book_clr_price: i64,
+    /// The indicative auction-clearing price for cross orders only.
+    pub auct_interest_clr_price: i64,
+    /// The quantity of shares that are eligible to be matched at `ref_price`.
+    pub paired_qty: u32,
+    /// The quantity of shares that are not paired at `ref_price`.
+    pub total_imbalance_qty: u32,
+    /// The market side of the `total_imbalance_qty`. Invalid sides are mapped to
+    /// [`Side::None`].
+    pub side: Side,
+}
+
+impl ImbalanceSnapshot {
+    /// Returns the imbalance quantity as a positive number for a buy imbalance and a
+    /// negative number for a sell imbalance.
+    pub fn signed_imbalance_qty(&self) -> i64 {
+        match self.side {
+            Side::Bid => i64::from(self.total_imbalance_qty),
+            Side::Ask => -i64::from(self.total_imbalance_qty),
+            Side::None => 0,
+        }
+    }
+}
+
+impl From<&ImbalanceMsg> for ImbalanceSnapshot {
+    fn from(imbalance: &ImbalanceMsg) -> Self {
+        Self {
+            ts_recv: imbalance.ts_recv,
+            ref_price: imbalance.ref_price,
+            cont_book_clr_price: imbalance.cont_book_clr_price,
+            auct_interest_clr_price: imbalance.auct_interest_clr_price,
+            paired_qty: imbalance.paired_qty,
+            total_imbalance_qty: imbalance.total_imbalance_qty,
+            side: imbalance.side().unwrap_or(Side::None),
+        }
+    }
+}
+
+/// The imbalance records of a single auction of an instrument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Auction {
+    /// The publisher ID assigned by Databento.
+    pub publisher_id: u16,
+    /// The numeric instrument ID.
+    pub instrument_id: u32,
+    /// The venue-specific character code of the auction type.
+    pub auction_type: c_char,
+    /// The state of the auction from each imbalance record, in the order received.
+    pub snapshots: Vec<ImbalanceSnapshot>,
+}
+
+impl Auction {
+    /// Returns the `ts_recv` of the first imbalance record of the auction.
+    pub fn ts_start(&self) -> Option<u64> {
+        self.snapshots.first().map(|snapshot| snapshot.ts_recv)
+    }
+
+    /// Returns the `ts_recv` of the last imbalance record of the auction.
+    pub fn ts_end(&self) -> Option<u64> {
+        self.snapshots.last().map(|snapshot| snapshot.ts_recv)
+    }
+
+    /// Returns the last state of the auction before it was uncrossed.
+    pub fn final_snapshot(&self) -> Option<&ImbalanceSnapshot> {
+        self.snapshots.last()
+    }
+
+    /// Returns the state of the auction with the largest imbalance quantity. Ties go
+    /// to the earliest.
+    pub fn peak_imbalance(&self) -> Option<&ImbalanceSnapshot> {
+        self.snapshots
+            .iter()
+            .rev()
+            .max_by_key(|snapshot| snapshot.total_imbalance_qty)
+    }
+
+    /// Returns the `ts_recv` and indicative clearing price `cont_book_clr_price` of
+    /// each imbalance record with a defined price.
+    pub fn indicative_prices(&self) -> impl Iterator<Item = (u64, i64)> + '_ {
+        self.snapshots
+            .iter()
+            .filter(|snapshot| snapshot.cont_book_clr_price != UNDEF_PRICE)
+            .map(|snapshot| (snapshot.ts_recv, snapshot.cont_book_clr_price))
+    }
+
+    /// Returns the `ts_recv`, `paired_qty`, and signed imbalance quantity of each
+    /// imbalance record. See [`ImbalanceSnapshot::signed_imbalance_qty()`].
+    pub fn quantities(&self) -> impl Iterator<Item = (u64, u32, i64)> + '_ {
+        self.snapshots.iter().map(|snapshot| {
+            (
+                snapshot.ts_recv,
+                snapshot.paired_qty,
+                snapshot.signed_imbalance_qty(),
+            )
+        })
+    }
+}
+
+/// Groups imbalance records into [`Auction`]s.
+///
+/// An auction of an instrument ends when the next imbalance record of that instrument
+/// has a different auction type or is received more than `max_gap` nanoseconds after
+/// the previous one, so completed auctions are only returned from [`pop()`](Self::pop)
+/// once the next auction of the instrument begins or [`finish()`](Self::finish) is
+/// called.
+#[derive(Debug, Clone)]
+pub struct AuctionTracker {
+    max_gap: u64,
+    /// The auction in progress by publisher and instrument ID.
+    open: HashMap<(u16, u32), Auction>,
+    completed: VecDeque<Auction>,
+}
+
+impl AuctionTracker {
+    /// Creates a new [`AuctionTracker`] that ends an auction after a gap of more than
+    /// `max_gap` nanoseconds between its imbalance records, e.g. a few minutes.
+    pub fn new(max_gap: NonZeroU64) -> Self {
+        Self {
+            max_gap: max_gap.get(),
+            open: HashMap::new(),
+            completed: VecDeque::new(),
+        }
+    }
+
+    /// Adds an imbalance record to the auction in progress for its instrument.
+    pub fn push(&mut self, imbalance: &ImbalanceMsg) {
+        let key = (imbalance.hd.publisher_id, imbalance.hd.instrument_id);
+        let snapshot = ImbalanceSnapshot::from(imbalance);
+        if let Some(auction) = self.open.get_mut(&key) {
+            let ts_end = auction.ts_end().unwrap_or(snapshot.ts_recv);
+            let is_same_auction = auction.auction_type == imbalance.auction_type
+                && snapshot.ts_recv.saturating_sub(ts_end) <= self.max_gap;
+            if is_same_auction {
+                auction.snapshots.push(snapshot);
+                return;
+            }
+        }
+        let auction = Auction {
+            publisher_id: imbalance.hd.publisher_id,
+            instrument_id: imbalance.hd.instrument_id,
+            auction_type: imbalance.auction_type,
+            snapshots: vec![snapshot],
+        };
+        if let Some(previous) = self.open.insert(key, auction) {
+            self.completed.push_back(previous);
+        }
+    }
+
+    /// Ends all auctions in progress, e.g. at the end of the input. They're returned
+    /// from [`pop()`](Self::pop) in order of their first imbalance record.
+    pub fn finish(&mut self) {
+        let mut open: Vec<_> = self.open.drain().map(|(_, auction)| auction).collect();
+        open.sort_by_key(|auction| {
+            (
+                auction.ts_start(),
+                auction.publisher_id,
+                auction.instrument_id,
+            )
+        });
+        self.completed.extend(open);
+    }
+
+    /// Returns the next completed auction, if any.
+    pub fn pop(&mut self) -> Option<Auction> {
+        self.completed.pop_front()
+    }
+}
+
+/// Returns an iterator of the [`Auction`]s of `imbalances`. See [`AuctionTracker`] for
+/// how auctions are delimited.
+pub fn auctions<I>(imbalances: I, max_gap: NonZeroU64) -> Auctions<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Borrow<ImbalanceMsg>,
+{
+    Auctions {
+        imbalances: imbalances.into_iter(),
+        tracker: AuctionTracker::new(max_gap),
+        is_finished: false,
+    }
+}
+
+/// An iterator adapter grouping imbalance records into [`Auction`]s, created with
+/// [`auctions()`].
+#[derive(Debug, Clone)]
+pub struct Auctions<I> {
+    imbalances: I,
+    tracker: AuctionTracker,
+    is_finished: bool,
+}
+
+impl<I> Iterator for Auctions<I>
+where
+    I: Iterator,
+    I::Item: Borrow<ImbalanceMsg>,
+{
+    type Item = Auction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(auction) = self.tracker.pop() {
+                return Some(auction);
+            }
+            if self.is_finished {
+                return None;
+            }
+            match self.imbalances.next() {
+                Some(imbalance) => self.tracker.push(imbalance.borrow()),
+                None => {
+                    self.tracker.finish();
+                    self.is_finished = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rtype, RecordHeader};
+
+    const MINUTE: u64 = 60_000_000_000;
+
+    fn imbalance(
+        instrument_id: u32,
+        auction_type: u8,
+        ts_recv: u64,
+        price: i64,
+        paired_qty: u32,
+        imbalance_qty: u32,
+        side: Side,
+    ) -> ImbalanceMsg {
+        ImbalanceMsg {
+            hd: RecordHeader::new::<ImbalanceMsg>(rtype::IMBALANCE, 1, instrument_id, ts_recv),
+            ts_recv,
+            ref_price: price,
+            cont_book_clr_price: price,
+            paired_qty,
+            total_imbalance_qty: imbalance_qty,
+            auction_type: auction_type as c_char,
+            side: u8::from(side) as c_char,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_auctions() {
+        let records = [
+            imbalance(1, b'O', 0, 100, 10, 5, Side::Bid),
+            imbalance(2, b'O', 1, 50, 20, 0, Side::None),
+            imbalance(1, b'O', MINUTE, UNDEF_PRICE, 15, 8, Side::Ask),
+            imbalance(1, b'O', 2 * MINUTE, 102, 20, 8, Side::Bid),
+            // Different auction type
+            imbalance(1, b'H', 3 * MINUTE, 101, 5, 1, Side::Bid),
+            // Gap
+            imbalance(2, b'O', 10 * MINUTE, 51, 30, 2, Side::Ask),
+        ];
+        let target: Vec<_> = auctions(&records, NonZeroU64::new(5 * MINUTE).unwrap()).collect();
+        assert_eq!(target.len(), 4);
+        let opening = &target[0];
+        assert_eq!(opening.instrument_id, 1);
+        assert_eq!(opening.auction_type, b'O' as c_char);
+        assert_eq!(opening.ts_start(), Some(0));
+        assert_eq!(opening.ts_end(), Some(2 * MINUTE));
+        assert_eq!(
+            opening.indicative_prices().collect::<Vec<_>>(),
+            [(0, 100), (2 * MINUTE, 102)]
+        );
+        assert_eq!(
+            opening.quantities().collect::<Vec<_>>(),
+            [(0, 10, 5), (MINUTE, 15, -8), (2 * MINUTE, 20, 8)]
+        );
+        assert_eq!(opening.peak_imbalance().unwrap().ts_recv, MINUTE);
+        assert_eq!(opening.final_snapshot().unwrap().paired_qty, 20);
+        assert_eq!(target[1].instrument_id, 2);
+        assert_eq!(target[1].snapshots.len(), 1);
+        // Remaining auctions in order of their start
+        assert_eq!(target[2].auction_type, b'H' as c_char);
+        assert_eq!(target[3].instrument_id, 2);
+        assert_eq!(target[3].ts_start(), Some(10 * MINUTE));
+    }
+
+    #[test]
+    fn test_no_imbalances() {
+        assert_eq!(
+            auctions(Vec::<ImbalanceMsg>::new(), NonZeroU64::new(MINUTE).unwrap()).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! A compact sidecar index of uncompressed DBN files for seeking to a timestamp or
+//! instrument without scanning the file.
+//!
+//! The index is conventionally stored next to the DBN file with an additional `.idx`
+//! extension, e.g. `data.dbn.idx`. See [`sidecar_path()`].
+//!
+//! The index splits the records into blocks of a fixed number of records. For each
+//! block, it stores the byte offset of its first record, the latest index timestamp of
+//! its records, and a bloom filter of the instrument IDs of its records.
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek, Write},
+    mem,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    decode::dbn::{MetadataDecoder, RecordDecoder},
+    Record, RecordRef, VersionUpgradePolicy, UNDEF_TIMESTAMP,
+};
+
+/// The magic bytes at the start of every index.
+const INDEX_MAGIC: &[u8; 4] = b"DBNI";
+/// The current version of the index format.
+const INDEX_VERSION: u8 = 1;
+/// Magic, version, 3 reserved bytes, the interval, and the entry count.
+const INDEX_HEADER_LEN: usize = 20;
+/// The number of 64-bit words in the bloom filter of each block.
+const BLOOM_WORDS: usize = 4;
+const BLOOM_BITS: u64 = (BLOOM_WORDS * u64::BITS as usize) as u64;
+/// The offset, the last timestamp, and the bloom filter.
+const INDEX_ENTRY_LEN: usize = 2 * mem::size_of::<u64>() + BLOOM_WORDS * mem::size_of::<u64>();
+
+/// Returns the conventional path of the index of the DBN file at `dbn_path`, i.e. the
+/// same path with `.idx` appended.
+pub fn sidecar_path(dbn_path: impl AsRef<Path>) -> PathBuf {
+    let mut path = dbn_path.as_ref().as_os_str().to_owned();
+    path.push(".idx");
+    PathBuf::from(path)
+}
+
+/// The index entry of a single block of records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// The byte offset of the first record of the block from the start of the file.
+    pub offset: u64,
+    /// The latest index timestamp of the records in the block or [`UNDEF_TIMESTAMP`]
+    /// if no record in the block had a defined index timestamp.
+    pub last_ts: u64,
+    bloom: [u64; BLOOM_WORDS],
+}
+
+impl IndexEntry {
+    fn new(offset: u64) -> Self {
+        Self {
+            offset,
+            last_ts: UNDEF_TIMESTAMP,
+            bloom: [0; BLOOM_WORDS],
+        }
+    }
+
+    /// Returns `true` if the block may contain a record for `instrument_id`. Returns
+    /// `false` only if the block definitely contains no such record.
+    pub fn may_contain(&self, instrument_id: u32) -> bool {
+        bloom_bits(instrument_id)
+            .into_iter()
+            .all(|bit| self.bloom[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, record: RecordRef) {
+        for bit in bloom_bits(record.header().instrument_id) {
+            self.bloom[bit / 64] |= 1 << (bit % 64);
+        }
+        let index_ts = record.raw_index_ts();
+        if index_ts != UNDEF_TIMESTAMP {
+            self.last_ts = if self.last_ts == UNDEF_TIMESTAMP {
+                index_ts
+            } else {
+                self.last_ts.max(index_ts)
+            };
+        }
+    }
+}
+
+/// Returns the positions of the bits of the bloom filter set for `instrument_id`.
+fn bloom_bits(instrument_id: u32) -> [usize; 3] {
+    // The hash must be stable across platforms and releases because it's persisted
+    let hash = (instrument_id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    [56, 48, 40].map(|shift| ((hash >> shift) % BLOOM_BITS) as usize)
+}
+
+/// A sidecar index of an uncompressed DBN file. Create one with [`IndexBuilder`] or
+/// [`Index::build()`].
+///
+/// Seeking by timestamp assumes the records of the file are sorted by their index
+/// timestamp, as is the case for historical data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    interval: u32,
+    entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// Builds an index of the uncompressed DBN file at `path` with an entry for every
+    /// `interval` records.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to open the file, or to decode the
+    /// metadata or a record, which includes the file being compressed.
+    pub fn build(path: impl AsRef<Path>, interval: NonZeroU32) -> crate::Result<Self> {
+        let mut file = File::open(path.as_ref()).map_err(|e| {
+            crate::Error::io(
+                e,
+                format!("opening DBN file at path '{}'", path.as_ref().display()),
+            )
+        })?;
+        let metadata = MetadataDecoder::new(&mut file).decode_prefix()?;
+        let records_offset = file
+            .stream_position()
+            .map_err(|e| crate::Error::io(e, "getting position of records"))?;
+        let mut decoder = RecordDecoder::with_version(
+            io::BufReader::new(file),
+            metadata.version,
+            VersionUpgradePolicy::AsIs,
+            metadata.ts_out,
+        )?;
+        let mut builder = IndexBuilder::new(interval, records_offset);
+        while let Some(record) = decoder.decode_ref()? {
+            builder.push(record);
+        }
+        Ok(builder.finish())
+    }
+
+    /// Reads an index from the file at `path`.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to open or read the file, or the
+    /// file isn't a valid index.
+    pub fn from_file(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let file = File::open(path.as_ref()).map_err(|e| {
+            crate::Error::io(
+                e,
+                format!("opening index file at path '{}'", path.as_ref().display()),
+            )
+        })?;
+        Self::decode(io::BufReader::new(file))
+    }
+
+    /// Decodes an index from `reader`.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to read from `reader` or the input
+    /// isn't a valid index.
+    pub fn decode(mut reader: impl io::Read) -> crate::Result<Self> {
+        let mut header = [0; INDEX_HEADER_LEN];
+        reader
+            .read_exact(&mut header)
+            .map_err(|e| crate::Error::io(e, "reading index header"))?;
+        if &header[..INDEX_MAGIC.len()] != INDEX_MAGIC {
+            return Err(crate::Error::decode("invalid index magic"));
+        }
+        let version = header[4];
+        if version != INDEX_VERSION {
+            return Err(crate::Error::decode(format!(
+                "unsupported index version {version}"
+            )));
+        }
+        let interval = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if interval == 0 {
+            return Err(crate::Error::decode("invalid index interval of 0"));
+        }
+        let entry_count = u64::from_le_bytes(header[12..20].try_into().unwrap());
+        let mut buffer = Vec::new();
+        reader
+            .read_to_end(&mut buffer)
+            .map_err(|e| crate::Error::io(e, "reading index entries"))?;
+        if buffer.len() as u64 != entry_count.saturating_mul(INDEX_ENTRY_LEN as u64) {
+            return Err(crate::Error::decode(format!(
+                "index with {entry_count} entries has invalid length {}",
+                buffer.len() + INDEX_HEADER_LEN
+            )));
+        }
+        let entries = buffer
+            .chunks_exact(INDEX_ENTRY_LEN)
+            .map(|chunk| {
+                let mut words = chunk
+                    .chunks_exact(mem::size_of::<u64>())
+                    .map(|word| u64::from_le_bytes(word.try_into().unwrap()));
+                IndexEntry {
+                    offset: words.next().unwrap(),
+                    last_ts: words.next().unwrap(),
+                    bloom: [(); BLOOM_WORDS].map(|_| words.next().unwrap()),
+                }
+            })
+            .collect();
+        Ok(Self { interval, entries })
+    }
+
+    /// Writes the index to a new file at `path`, overwriting any existing file.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to create or write to the file.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        let file = File::create(path.as_ref()).map_err(|e| {
+            crate::Error::io(
+                e,
+                format!("creating index file at path '{}'", path.as_ref().display()),
+            )
+        })?;
+        let mut writer = io::BufWriter::new(file);
+        self.encode(&mut writer)?;
+        writer
+            .flush()
+            .map_err(|e| crate::Error::io(e, "flushing index"))
+    }
+
+    /// Encodes the index to `writer`.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to write to `writer`.
+    pub fn encode(&self, mut writer: impl io::Write) -> crate::Result<()> {
+        let mut header = [0; INDEX_HEADER_LEN];
+        header[..INDEX_MAGIC.len()].copy_from_slice(INDEX_MAGIC);
+        header[4] = INDEX_VERSION;
+        header[8..12].copy_from_slice(&self.interval.to_le_bytes());
+        header[12..20].copy_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        let io_err = |e| crate::Error::io(e, "writing index");
+        writer.write_all(&header).map_err(io_err)?;
+        for entry in self.entries.iter() {
+            for word in [entry.offset, entry.last_ts]
+                .iter()
+                .chain(entry.bloom.iter())
+            {
+                writer.write_all(&word.to_le_bytes()).map_err(io_err)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of records in each block.
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    /// Returns the entries of the index, one per block of records.
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// Returns the byte offset of the block containing the first record with an index
+    /// timestamp of at least `ts`, or `None` if all records are before `ts`. At most
+    /// [`interval()`](Self::interval) records need to be skipped after seeking to the
+    /// offset.
+    pub fn offset_for_ts(&self, ts: u64) -> Option<u64> {
+        let idx = self.entries.partition_point(|entry| entry.last_ts < ts);
+        self.entries.get(idx).map(|entry| entry.offset)
+    }
+
+    /// Returns an iterator over the entries of the blocks that may contain a record for
+    /// `instrument_id`.
+    pub fn blocks_for_instrument(&self, instrument_id: u32) -> impl Iterator<Item = &IndexEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.may_contain(instrument_id))
+    }
+}
+
+/// Type for building an [`Index`] by passing it the records of a DBN file in order.
+#[derive(Debug, Clone)]
+pub struct IndexBuilder {
+    interval: u32,
+    offset: u64,
+    block_record_count: u32,
+    entries: Vec<IndexEntry>,
+}
+
+impl IndexBuilder {
+    /// Creates a new [`IndexBuilder`] with an entry for every `interval` records.
+    /// `records_offset` is the byte offset of the first record from the start of the
+    /// file, i.e. the length of the encoded metadata.
+    pub fn new(interval: NonZeroU32, records_offset: u64) -> Self {
+        Self {
+            interval: interval.get(),
+            offset: records_offset,
+            block_record_count: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds the next record of the file to the index. `record` must be as encoded in
+    /// the file, i.e. not upgraded from a previous DBN version.
+    pub fn push(&mut self, record: RecordRef) {
+        if self.block_record_count == 0 {
+            self.entries.push(IndexEntry::new(self.offset));
+        }
+        // An entry is always pushed for the first record of a block
+        self.entries.last_mut().unwrap().insert(record);
+        self.offset += record.record_size() as u64;
+        self.block_record_count += 1;
+        if self.block_record_count == self.interval {
+            self.block_record_count = 0;
+        }
+    }
+
+    /// Returns the built [`Index`].
+    pub fn finish(self) -> Index {
+        Index {
+            interval: self.interval,
+            entries: self.entries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode::tests::TEST_DATA_PATH, rtype, MboMsg, RecordHeader};
+
+    const MBO_PATH: &str = "test_data.mbo.dbn";
+
+    #[test]
+    fn test_build_and_seek() {
+        let path = format!("{TEST_DATA_PATH}/{MBO_PATH}");
+        let target = Index::build(&path, NonZeroU32::new(1).unwrap()).unwrap();
+        assert_eq!(target.entries().len(), 2);
+        let second = target.entries()[1].clone();
+        assert_eq!(target.offset_for_ts(0), Some(target.entries()[0].offset));
+        assert_eq!(target.offset_for_ts(second.last_ts), Some(second.offset));
+        assert_eq!(target.offset_for_ts(second.last_ts + 1), None);
+
+        let mut file = File::open(&path).unwrap();
+        let metadata = MetadataDecoder::new(&mut file).decode().unwrap();
+        file.seek(io::SeekFrom::Start(second.offset)).unwrap();
+        let mut decoder = RecordDecoder::with_version(
+            file,
+            metadata.version,
+            VersionUpgradePolicy::AsIs,
+            metadata.ts_out,
+        )
+        .unwrap();
+        let rec = decoder.decode_ref().unwrap().unwrap();
+        assert_eq!(rec.raw_index_ts(), second.last_ts);
+        assert!(decoder.decode_ref().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut builder = IndexBuilder::new(NonZeroU32::new(2).unwrap(), 0);
+        for (instrument_id, ts) in [(1, 10), (2, 20), (3, 30)] {
+            let rec = MboMsg {
+                hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, instrument_id, ts),
+                ts_recv: ts,
+                ..Default::default()
+            };
+            builder.push(RecordRef::from(&rec));
+        }
+        let target = builder.finish();
+        assert_eq!(target.entries().len(), 2);
+        assert_eq!(target.entries()[0].last_ts, 20);
+        assert_eq!(
+            target.entries()[1].offset,
+            2 * mem::size_of::<MboMsg>() as u64
+        );
+        assert!(target.entries()[0].may_contain(1));
+        assert!(target.entries()[0].may_contain(2));
+        assert_eq!(target.blocks_for_instrument(3).count(), 1);
+        assert_eq!(target.offset_for_ts(15), Some(0));
+        assert_eq!(target.offset_for_ts(21), Some(target.entries()[1].offset));
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let target = Index::build(
+            format!("{TEST_DATA_PATH}/{MBO_PATH}"),
+            NonZeroU32::new(5).unwrap(),
+        )
+        .unwrap();
+        let mut buffer = Vec::new();
+        target.encode(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), INDEX_HEADER_LEN + INDEX_ENTRY_LEN);
+        assert_eq!(Index::decode(buffer.as_slice()).unwrap(), target);
+
+        buffer[0] = b'X';
+        assert!(matches!(
+            Index::decode(buffer.as_slice()),
+            Err(crate::Error::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn test_sidecar_path() {
+        assert_eq!(
+            sidecar_path("data/test.dbn"),
+            PathBuf::from("data/test.dbn.idx")
+        );
+    }
+}
@@ -27,7 +27,11 @@
 //!
 //! # Feature flags
 //! - `async`: enables async decoding and encoding
+//! - `io-uring`: enables decoding files with reads ahead through io_uring on Linux
+//! - `live`: enables a blocking [client](crate::live) for the Databento live gateway
 //! - `python`: enables `pyo3` bindings
+//! - `schemars`: enables generating JSON Schemas for records and [`Metadata`] that
+//!   describe the output of the [JSON encoder](crate::encode::json)
 //! - `serde`: enables deriving `serde` traits for types
 //! - `trivial_copy`: enables deriving the `Copy` trait for records
 
@@ -37,22 +41,38 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(clippy::missing_errors_doc)]
 
+pub mod aggregate;
+pub mod book;
+pub mod compare;
 pub mod compat;
 pub mod decode;
+pub mod dedup;
 pub mod encode;
 pub mod enums;
 pub mod error;
-mod json_writer;
+pub mod imbalance;
+pub mod index;
+#[doc(hidden)]
+pub mod json_writer;
+#[cfg(feature = "live")]
+pub mod live;
 pub mod macros;
+pub mod merge;
 pub mod metadata;
 pub mod pretty;
 pub mod publishers;
 #[cfg(feature = "python")]
 pub mod python;
 pub mod record;
+pub mod record_arena;
 mod record_enum;
 pub mod record_ref;
+pub mod replay;
+pub mod scan;
+pub mod split;
 pub mod symbol_map;
+pub mod transcode;
+pub mod validate;
 
 pub use crate::{
     enums::{
@@ -61,15 +81,20 @@ pub use crate::{
         TradingEvent, TriState, UserDefinedInstrument, VersionUpgradePolicy,
     },
     error::{Error, Result},
-    metadata::{MappingInterval, Metadata, MetadataBuilder, SymbolMapping},
+    metadata::{
+        MappingInterval, MappingWarning, Metadata, MetadataBuilder, MetadataDiff, SymbolMapping,
+        SymbolsDiff,
+    },
     publishers::{Dataset, Publisher, Venue},
     record::{
-        BidAskPair, ErrorMsg, HasRType, ImbalanceMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg,
-        OhlcvMsg, Record, RecordHeader, RecordMut, StatMsg, StatusMsg, SymbolMappingMsg, SystemMsg,
-        TbboMsg, TradeMsg, WithTsOut,
+        Bbo1MMsg, Bbo1SMsg, BboMsg, BidAskPair, Cbbo1MMsg, Cbbo1SMsg, CbboMsg,
+        ConsolidatedBidAskPair, CustomRecord, ErrorMsg, HasRType, ImbalanceMsg, InstrumentDefMsg,
+        MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, Record, RecordHeader, RecordMut, SortKey, StatMsg,
+        StatusMsg, SymbolMappingMsg, SystemMsg, TbboMsg, TcbboMsg, TradeMsg, WithTsOut,
     },
+    record_arena::RecordArena,
     record_enum::{RecordEnum, RecordRefEnum},
-    record_ref::RecordRef,
+    record_ref::{split_records, RecordRef, RecordRefMut},
     symbol_map::{PitSymbolMap, SymbolIndex, TsSymbolMap},
 };
 
@@ -82,6 +107,11 @@ const METADATA_DATASET_CSTR_LEN: usize = 16;
 const METADATA_RESERVED_LEN: usize = 53;
 /// Excludes magic string, version, and length.
 const METADATA_FIXED_LEN: usize = 100;
+/// Tag ending the optional extension section after the symbol mappings. Each entry in
+/// the section is a `u16` tag, a `u32` length, and a value of that length.
+const METADATA_EXT_TAG_END: u16 = 0;
+/// Tag of a [`Metadata::custom`] property: a `u16` key length, the key, and the value.
+const METADATA_EXT_TAG_CUSTOM: u16 = 1;
 const NULL_LIMIT: u64 = 0;
 const NULL_RECORD_COUNT: u64 = u64::MAX;
 const NULL_SCHEMA: u16 = u16::MAX;
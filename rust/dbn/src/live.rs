@@ -0,0 +1,348 @@
+//! A blocking client for the Databento Live Subscription Gateway (LSG) that speaks the
+//! raw DBN protocol. Requires the `live` feature.
+//!
+//! After a plain-text handshake of authentication, subscriptions, and starting the
+//! session, the gateway sends a DBN stream, which is decoded with a DBN
+//! [`Decoder`].
+//!
+//! # Example
+//! ```no_run
+//! use dbn::{
+//!     decode::DecodeRecordRef,
+//!     live::{Client, Subscription},
+//!     SType, Schema,
+//! };
+//!
+//! # fn main() -> dbn::Result<()> {
+//! let mut client = Client::connect("db-YOUR_API_KEY_HERE_0000000000", "XNAS.ITCH", false)?;
+//! client.subscribe(&Subscription::new(Schema::Trades, SType::RawSymbol, ["AAPL"]))?;
+//! let mut decoder = client.start()?;
+//! while let Some(rec) = decoder.decode_record_ref()? {
+//!     println!("{rec:?}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+    net::TcpStream,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{decode::dbn::Decoder, SType, Schema};
+
+/// The default port of the gateway.
+pub const DEFAULT_PORT: u16 = 13_000;
+/// The number of characters at the end of an API key that identify it.
+const BUCKET_ID_LEN: usize = 5;
+
+/// Returns the address of the gateway for `dataset`, e.g.
+/// `xnas-itch.lsg.databento.com:13000` for `XNAS.ITCH`.
+pub fn gateway_addr(dataset: &str) -> String {
+    format!(
+        "{}.lsg.databento.com:{DEFAULT_PORT}",
+        dataset.replace('.', "-").to_lowercase()
+    )
+}
+
+/// A subscription to the records of a single schema for a set of symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subscription {
+    /// The schema of the records.
+    pub schema: Schema,
+    /// The symbology type of `symbols`.
+    pub stype_in: SType,
+    /// The symbols to subscribe to.
+    pub symbols: Vec<String>,
+    /// The optional UNIX nanosecond timestamp to replay the data from for intraday
+    /// replay.
+    pub start: Option<u64>,
+}
+
+impl Subscription {
+    /// Creates a new [`Subscription`] to the `schema` records of `symbols` of
+    /// symbology type `stype_in`.
+    pub fn new(
+        schema: Schema,
+        stype_in: SType,
+        symbols: impl IntoIterator<Item = impl ToString>,
+    ) -> Self {
+        Self {
+            schema,
+            stype_in,
+            symbols: symbols.into_iter().map(|s| s.to_string()).collect(),
+            start: None,
+        }
+    }
+}
+
+/// A blocking client for a live session with the gateway over `S`, by default a
+/// [`TcpStream`]. Any other bidirectional stream, such as a TLS stream, can be used
+/// with [`Client::authenticate()`].
+#[derive(Debug)]
+pub struct Client<S = TcpStream> {
+    stream: io::BufReader<S>,
+    session_id: String,
+}
+
+impl Client<TcpStream> {
+    /// Connects to the gateway of `dataset` over TCP and authenticates with `key`. With
+    /// `ts_out`, the gateway appends its send timestamp to each record.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to connect, `key` is invalid, or the
+    /// gateway rejects the authentication.
+    pub fn connect(key: &str, dataset: &str, ts_out: bool) -> crate::Result<Self> {
+        let addr = gateway_addr(dataset);
+        let stream = TcpStream::connect(&addr)
+            .map_err(|e| crate::Error::io(e, format!("connecting to gateway at {addr}")))?;
+        Self::authenticate(stream, key, dataset, ts_out)
+    }
+}
+
+impl<S> Client<S>
+where
+    S: io::Read + io::Write,
+{
+    /// Performs the authentication handshake for `dataset` with `key` over `stream`,
+    /// which must be connected to the gateway. With `ts_out`, the gateway appends its
+    /// send timestamp to each record.
+    ///
+    /// # Errors
+    /// This function returns an error if `key` is invalid, it fails to read from or
+    /// write to `stream`, or the gateway rejects the authentication.
+    pub fn authenticate(stream: S, key: &str, dataset: &str, ts_out: bool) -> crate::Result<Self> {
+        if key.len() < BUCKET_ID_LEN || !key.is_ascii() {
+            return Err(crate::Error::BadArgument {
+                param_name: "key".to_owned(),
+                desc: format!("must be an ASCII API key of at least {BUCKET_ID_LEN} characters"),
+            });
+        }
+        let mut stream = io::BufReader::new(stream);
+        // The gateway first sends its version
+        read_line(&mut stream)?;
+        let challenge_line = read_line(&mut stream)?;
+        let Some(challenge) = challenge_line.strip_prefix("cram=") else {
+            return Err(crate::Error::decode(format!(
+                "expected authentication challenge from gateway, received '{challenge_line}'"
+            )));
+        };
+        let auth = auth_response(challenge, key);
+        write_line(
+            &mut stream,
+            &format!(
+                "auth={auth}|dataset={dataset}|encoding=dbn|ts_out={}",
+                u8::from(ts_out)
+            ),
+        )?;
+        let response = read_line(&mut stream)?;
+        let fields = parse_fields(&response);
+        if field(&fields, "success") != Some("1") {
+            return Err(crate::Error::decode(format!(
+                "gateway rejected authentication: {}",
+                field(&fields, "error").unwrap_or(&response)
+            )));
+        }
+        Ok(Self {
+            session_id: field(&fields, "session_id").unwrap_or_default().to_owned(),
+            stream,
+        })
+    }
+
+    /// Returns the ID of the session assigned by the gateway.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Adds `subscription` to the session. Call before [`start()`](Self::start).
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to write to the stream.
+    pub fn subscribe(&mut self, subscription: &Subscription) -> crate::Result<()> {
+        let mut request = format!(
+            "schema={}|stype_in={}|symbols={}",
+            subscription.schema,
+            subscription.stype_in,
+            subscription.symbols.join(",")
+        );
+        if let Some(start) = subscription.start {
+            write!(request, "|start={start}").unwrap();
+        }
+        write_line(&mut self.stream, &request)
+    }
+
+    /// Starts the session and returns a [`Decoder`] of the DBN stream sent by the
+    /// gateway, beginning with its metadata.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to write to the stream or to decode
+    /// the metadata.
+    pub fn start(mut self) -> crate::Result<Decoder<io::BufReader<S>>> {
+        write_line(&mut self.stream, "start_session")?;
+        Decoder::new(self.stream)
+    }
+}
+
+/// Returns the response to the authentication `challenge` for `key`: the hex-encoded
+/// SHA-256 of the challenge and key followed by the bucket ID of the key.
+fn auth_response(challenge: &str, key: &str) -> String {
+    let digest = Sha256::digest(format!("{challenge}|{key}").as_bytes());
+    let mut res = String::with_capacity(2 * digest.len() + 1 + BUCKET_ID_LEN);
+    for byte in digest {
+        write!(res, "{byte:02x}").unwrap();
+    }
+    res.push('-');
+    res.push_str(&key[key.len() - BUCKET_ID_LEN..]);
+    res
+}
+
+/// Parses a gateway message of `|`-separated `key=value` fields.
+fn parse_fields(line: &str) -> Vec<(&str, &str)> {
+    line.split('|')
+        .filter_map(|field| field.split_once('='))
+        .collect()
+}
+
+fn field<'a>(fields: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    fields.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> crate::Result<String> {
+    let mut line = String::new();
+    let len = reader
+        .read_line(&mut line)
+        .map_err(|e| crate::Error::io(e, "reading from gateway"))?;
+    if len == 0 {
+        return Err(crate::Error::io(
+            io::Error::from(io::ErrorKind::UnexpectedEof),
+            "reading from gateway",
+        ));
+    }
+    Ok(line.trim_end().to_owned())
+}
+
+fn write_line<S: io::Write>(stream: &mut io::BufReader<S>, line: &str) -> crate::Result<()> {
+    let stream = stream.get_mut();
+    stream
+        .write_all(line.as_bytes())
+        .and_then(|_| stream.write_all(b"\n"))
+        .and_then(|_| stream.flush())
+        .map_err(|e| crate::Error::io(e, "writing to gateway"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread};
+
+    use super::*;
+    use crate::{
+        decode::{tests::TEST_DATA_PATH, DbnMetadata, DecodeRecordRef},
+        MboMsg,
+    };
+
+    const KEY: &str = "db-89s9oCYVn9GXwGFQ3MFwqxLB5fWCY";
+
+    #[test]
+    fn test_gateway_addr() {
+        assert_eq!(
+            gateway_addr("GLBX.MDP3"),
+            "glbx-mdp3.lsg.databento.com:13000"
+        );
+    }
+
+    #[test]
+    fn test_auth_response() {
+        assert_eq!(
+            auth_response("abcdef", KEY),
+            "8ff2b56cee30c4eb87d532f816550c2c9e7e3f93a6c61817e25e9a534f92d21a-5fWCY"
+        );
+    }
+
+    fn mock_gateway(success: bool) -> (TcpStream, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = io::BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            writer
+                .write_all(b"lsg_version=0.1.0\ncram=abcdef\n")
+                .unwrap();
+            let mut requests = Vec::new();
+            requests.push(read_line(&mut reader).unwrap());
+            if !success {
+                writer
+                    .write_all(b"success=0|error=Authentication failed.\n")
+                    .unwrap();
+                return requests;
+            }
+            writer.write_all(b"success=1|session_id=5|\n").unwrap();
+            loop {
+                let request = read_line(&mut reader).unwrap();
+                let is_start = request == "start_session";
+                requests.push(request);
+                if is_start {
+                    break;
+                }
+            }
+            writer
+                .write_all(&std::fs::read(format!("{TEST_DATA_PATH}/test_data.mbo.dbn")).unwrap())
+                .unwrap();
+            requests
+        });
+        (TcpStream::connect(addr).unwrap(), handle)
+    }
+
+    #[test]
+    fn test_session() {
+        let (stream, gateway) = mock_gateway(true);
+        let mut target = Client::authenticate(stream, KEY, "XNAS.ITCH", true).unwrap();
+        assert_eq!(target.session_id(), "5");
+        let mut subscription = Subscription::new(Schema::Mbo, SType::RawSymbol, ["AAPL", "TSLA"]);
+        subscription.start = Some(0);
+        target.subscribe(&subscription).unwrap();
+        let mut decoder = target.start().unwrap();
+        assert_eq!(decoder.metadata().schema, Some(Schema::Mbo));
+        let mut count = 0;
+        while let Some(rec) = decoder.decode_record_ref().unwrap() {
+            assert!(rec.get::<MboMsg>().is_some());
+            count += 1;
+        }
+        assert_eq!(count, 2);
+        assert_eq!(
+            gateway.join().unwrap(),
+            [
+                format!(
+                    "auth={}|dataset=XNAS.ITCH|encoding=dbn|ts_out=1",
+                    auth_response("abcdef", KEY)
+                ),
+                "schema=mbo|stype_in=raw_symbol|symbols=AAPL,TSLA|start=0".to_owned(),
+                "start_session".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejected_authentication() {
+        let (stream, gateway) = mock_gateway(false);
+        let err = Client::authenticate(stream, KEY, "XNAS.ITCH", false).unwrap_err();
+        assert!(
+            matches!(&err, crate::Error::Decode(msg) if msg.contains("Authentication failed.")),
+            "{err:?}"
+        );
+        gateway.join().unwrap();
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        assert!(matches!(
+            Client::authenticate(stream, "abc", "XNAS.ITCH", false),
+            Err(crate::Error::BadArgument { .. })
+        ));
+    }
+}
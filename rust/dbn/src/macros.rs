@@ -1,7 +1,10 @@
 //! Helper macros for working with multiple RTypes, Schemas, and types of records.
 
 // Re-export
-pub use dbn_macros::{dbn_record, CsvSerialize, DbnAttr, JsonSerialize, PyFieldDesc, RecordDebug};
+pub use dbn_macros::{
+    dbn_record, CsvSerialize, DbnAttr, DbnJsonSchema, DbnRecord, JsonSerialize, PyFieldDesc,
+    RecordBuilder, RecordDebug,
+};
 
 /// Base macro for type dispatch based on rtype.
 ///
@@ -26,6 +29,10 @@ macro_rules! rtype_dispatch_base {
                 | RType::Ohlcv1H
                 | RType::Ohlcv1D
                 | RType::OhlcvEod => $handler!(OhlcvMsg),
+                RType::Bbo1S | RType::Bbo1M => $handler!(BboMsg),
+                RType::Cbbo | RType::Cbbo1S | RType::Cbbo1M | RType::Tcbbo => {
+                    $handler!(CbboMsg)
+                }
                 RType::Imbalance => $handler!(ImbalanceMsg),
                 RType::Status => $handler!(StatusMsg),
                 RType::InstrumentDef => {
@@ -84,6 +91,10 @@ macro_rules! schema_dispatch_base {
             | Schema::OhlcvEod => {
                 $handler!(OhlcvMsg)
             }
+            Schema::Bbo1S | Schema::Bbo1M => $handler!(BboMsg),
+            Schema::Cbbo | Schema::Cbbo1S | Schema::Cbbo1M | Schema::Tcbbo => {
+                $handler!(CbboMsg)
+            }
             Schema::Definition => $handler!(InstrumentDefMsg),
             Schema::Statistics => $handler!(StatMsg),
             Schema::Status => $handler!(StatusMsg),
@@ -0,0 +1,230 @@
+//! K-way merging of multiple streams of records by timestamp, e.g. for combining
+//! files of different schemas or datasets into a single stream for a backtest.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    decode::{DbnMetadata, DecodeRecordRef},
+    Metadata, Record, RecordArena, RecordRef, SortKey,
+};
+
+/// Returns a decoder of the records of all of `decoders` merged in the order of their
+/// [`SortKey`]. See [`MergeRecordDecoder`].
+pub fn merge_streams<D>(decoders: impl IntoIterator<Item = D>) -> MergeRecordDecoder<D>
+where
+    D: DecodeRecordRef,
+{
+    MergeRecordDecoder::new(decoders)
+}
+
+/// Type for decoding the records of multiple decoders merged in the order of their
+/// [`SortKey`], i.e. by their [`key_ts()`](Record::key_ts) and then `ts_event`. The
+/// records of each decoder must already be in that order.
+///
+/// The merge is stable: records with equal sort keys are returned in the order of
+/// their decoders, and the records of a single decoder are returned in their original
+/// order.
+///
+/// The next record of each decoder is copied to be compared against the others, so
+/// merging `k` decoders takes O(log k) time per record.
+#[derive(Debug)]
+pub struct MergeRecordDecoder<D> {
+    decoders: Vec<D>,
+    /// The next record of each decoder.
+    next_records: Vec<RecordArena>,
+    /// The sort key and decoder index of each decoder with a next record.
+    heap: BinaryHeap<Reverse<(SortKey, usize)>>,
+    /// The index of the decoder whose record was returned last and needs to be
+    /// advanced.
+    last_idx: Option<usize>,
+    is_initialized: bool,
+}
+
+impl<D> MergeRecordDecoder<D>
+where
+    D: DecodeRecordRef,
+{
+    /// Creates a new [`MergeRecordDecoder`] merging the records of `decoders`.
+    pub fn new(decoders: impl IntoIterator<Item = D>) -> Self {
+        let decoders: Vec<_> = decoders.into_iter().collect();
+        Self {
+            next_records: vec![RecordArena::new(); decoders.len()],
+            heap: BinaryHeap::with_capacity(decoders.len()),
+            decoders,
+            last_idx: None,
+            is_initialized: false,
+        }
+    }
+
+    /// Consumes the decoder and returns the inner decoders.
+    pub fn into_inner(self) -> Vec<D> {
+        self.decoders
+    }
+
+    /// Decodes the next record of the decoder at `idx` and adds it to the heap.
+    fn advance(&mut self, idx: usize) -> crate::Result<()> {
+        let next_record = &mut self.next_records[idx];
+        next_record.clear();
+        if let Some(rec) = self.decoders[idx].decode_record_ref()? {
+            next_record.push(rec);
+            self.heap.push(Reverse((rec.sort_key(), idx)));
+        }
+        Ok(())
+    }
+}
+
+impl<D> DecodeRecordRef for MergeRecordDecoder<D>
+where
+    D: DecodeRecordRef,
+{
+    fn decode_record_ref(&mut self) -> crate::Result<Option<RecordRef>> {
+        if !self.is_initialized {
+            for idx in 0..self.decoders.len() {
+                self.advance(idx)?;
+            }
+            self.is_initialized = true;
+        } else if let Some(idx) = self.last_idx.take() {
+            self.advance(idx)?;
+        }
+        let Some(Reverse((_, idx))) = self.heap.pop() else {
+            return Ok(None);
+        };
+        self.last_idx = Some(idx);
+        Ok(self.next_records[idx].get(0))
+    }
+}
+
+/// Type for decoding the records of multiple DBN decoders merged in the order of their
+/// [`SortKey`] with their metadata merged with [`Metadata::merge()`]. See
+/// [`MergeRecordDecoder`] for the ordering.
+///
+/// Because the metadata can only be merged when it has the same dataset, use
+/// [`merge_streams()`] to merge records from different datasets.
+#[derive(Debug)]
+pub struct MergeDecoder<D> {
+    metadata: Metadata,
+    decoder: MergeRecordDecoder<D>,
+}
+
+impl<D> MergeDecoder<D>
+where
+    D: DecodeRecordRef + DbnMetadata,
+{
+    /// Creates a new [`MergeDecoder`] merging the metadata and records of `decoders`.
+    ///
+    /// # Errors
+    /// This function returns an error if `decoders` is empty or their metadata can't
+    /// be merged.
+    pub fn new(decoders: impl IntoIterator<Item = D>) -> crate::Result<Self> {
+        let decoder = MergeRecordDecoder::new(decoders);
+        let metadata = Metadata::merge(
+            &decoder
+                .decoders
+                .iter()
+                .map(|decoder| decoder.metadata().clone())
+                .collect::<Vec<_>>(),
+        )?;
+        Ok(Self { metadata, decoder })
+    }
+
+    /// Consumes the decoder and returns the inner decoders.
+    pub fn into_inner(self) -> Vec<D> {
+        self.decoder.into_inner()
+    }
+}
+
+impl<D> DbnMetadata for MergeDecoder<D> {
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+impl<D> DecodeRecordRef for MergeDecoder<D>
+where
+    D: DecodeRecordRef,
+{
+    fn decode_record_ref(&mut self) -> crate::Result<Option<RecordRef>> {
+        self.decoder.decode_record_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode::{
+            dbn::{Decoder, RecordDecoder},
+            tests::TEST_DATA_PATH,
+        },
+        encode::{DbnRecordEncoder, EncodeRecord},
+        rtype, MboMsg, RecordHeader, TradeMsg, UNDEF_TIMESTAMP,
+    };
+
+    fn encode(records: &[MboMsg]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        DbnRecordEncoder::new(&mut buffer)
+            .encode_records(records)
+            .unwrap();
+        buffer
+    }
+
+    fn mbo(ts_recv: u64, order_id: u64) -> MboMsg {
+        MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, ts_recv),
+            ts_recv,
+            order_id,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_streams() {
+        let first = encode(&[mbo(1, 0), mbo(3, 1), mbo(3, 2), mbo(UNDEF_TIMESTAMP, 3)]);
+        let second = encode(&[mbo(2, 4), mbo(3, 5), mbo(4, 6)]);
+        let third = encode(&[]);
+        let mut target = merge_streams([
+            RecordDecoder::new(first.as_slice()),
+            RecordDecoder::new(second.as_slice()),
+            RecordDecoder::new(third.as_slice()),
+        ]);
+        let mut order_ids = Vec::new();
+        while let Some(rec) = target.decode_record_ref().unwrap() {
+            order_ids.push(rec.get::<MboMsg>().unwrap().order_id);
+        }
+        // Ties are broken by the order of the decoders
+        assert_eq!(order_ids, [0, 4, 1, 2, 5, 6, 3]);
+        assert!(target.decode_record_ref().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge_decoder() {
+        let mut target = MergeDecoder::new([
+            Decoder::from_file(format!("{TEST_DATA_PATH}/test_data.mbo.dbn")).unwrap(),
+            Decoder::from_file(format!("{TEST_DATA_PATH}/test_data.trades.dbn")).unwrap(),
+        ])
+        .unwrap();
+        assert!(target.metadata().schema.is_none());
+        let mut last_key = None;
+        let mut count = 0;
+        while let Some(rec) = target.decode_record_ref().unwrap() {
+            let key = rec.sort_key();
+            assert!(last_key <= Some(key));
+            last_key = Some(key);
+            assert!(rec.get::<MboMsg>().is_some() || rec.get::<TradeMsg>().is_some());
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn test_merge_decoder_no_decoders() {
+        assert!(matches!(
+            MergeDecoder::<Decoder<&[u8]>>::new([]),
+            Err(crate::Error::BadArgument { .. })
+        ));
+    }
+}
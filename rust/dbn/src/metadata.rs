@@ -1,13 +1,17 @@
 //! Contains [`Metadata`] struct which comes at the beginning of any DBN file or
 //! stream and [`MetadataBuilder`] for creating a [`Metadata`] with defaults.
-use std::num::NonZeroU64;
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+    fmt,
+    num::NonZeroU64,
+};
 
 // Dummy derive macro to get around `cfg_attr` incompatibility of several
 // of pyo3's attribute macros. See https://github.com/PyO3/pyo3/issues/780
 #[cfg(not(feature = "python"))]
 pub use dbn_macros::MockPyo3;
 #[cfg(feature = "serde")]
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     compat::version_symbol_cstr_len, record::as_u8_slice, PitSymbolMap, SType, Schema, TsSymbolMap,
@@ -16,7 +20,12 @@ use crate::{
 
 /// Information about the data contained in a DBN file or stream. DBN requires the
 /// Metadata to be included at the start of the encoded data.
+///
+/// With the `serde` feature, `Metadata` implements `Serialize` and `Deserialize` and can
+/// be deserialized from the JSON output of the JSON encoder, with or without pretty
+/// timestamps. Human-readable formats like JSON use ISO 8601 timestamps and dates.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "python", pyo3::pyclass(module = "databento_dbn"))]
 #[cfg_attr(not(feature = "python"), derive(MockPyo3))] // bring `pyo3` attribute into scope
 pub struct Metadata {
@@ -30,21 +39,39 @@ pub struct Metadata {
     /// The data record schema. Specifies which record types are in the DBN stream.
     /// `None` indicates the DBN stream _may_ contain more than one record type.
     #[pyo3(get)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub schema: Option<Schema>,
     /// The UNIX nanosecond timestamp of the query start, or the first record if the
     /// file was split.
     #[pyo3(get)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "serialize_ts", deserialize_with = "deserialize_ts")
+    )]
     pub start: u64,
     /// The UNIX nanosecond timestamp of the query end, or the last record if the file
     /// was split.
     #[pyo3(get)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            serialize_with = "serialize_opt_ts",
+            deserialize_with = "deserialize_opt_ts"
+        )
+    )]
     pub end: Option<NonZeroU64>,
     /// The optional maximum number of records for the query.
     #[pyo3(get)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "deserialize_limit")
+    )]
     pub limit: Option<NonZeroU64>,
     /// The input symbology type to map from. `None` indicates a mix, such as in the
     /// case of live data.
     #[pyo3(get)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub stype_in: Option<SType>,
     /// The output symbology type to map to.
     #[pyo3(get)]
@@ -58,16 +85,29 @@ pub struct Metadata {
     #[pyo3(get)]
     pub symbol_cstr_len: usize,
     /// The original query input symbols from the request.
-    #[pyo3(get)]
+    #[pyo3(get, set)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub symbols: Vec<String>,
     /// Symbols that did not resolve for _at least one day_ in the query time range.
     #[pyo3(get)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub partial: Vec<String>,
     /// Symbols that did not resolve for _any_ day in the query time range.
     #[pyo3(get)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub not_found: Vec<String>,
     /// Symbol mappings containing a raw symbol and its mapping intervals.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub mappings: Vec<SymbolMapping>,
+    /// User-defined key-value properties, e.g. to record the provenance of the data.
+    /// These are encoded after the symbol mappings in an extensible section that's
+    /// ignored by older decoders.
+    #[pyo3(get, set)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub custom: BTreeMap<String, String>,
 }
 
 impl Metadata {
@@ -118,12 +158,412 @@ impl Metadata {
         TsSymbolMap::from_metadata(self)
     }
 
+    /// Serializes the metadata to a JSON string in the same format as the
+    /// [JSON encoder](crate::encode::json::Encoder::encode_metadata). With
+    /// `should_pretty_print`, the JSON is indented and timestamps and dates are
+    /// formatted as ISO 8601.
+    pub fn to_json(&self, should_pretty_print: bool) -> String {
+        let mut json = crate::encode::json::serialize::to_json_string(
+            self,
+            should_pretty_print,
+            false,
+            should_pretty_print,
+        );
+        // Remove trailing newline
+        json.pop();
+        json
+    }
+
+    /// Deserializes metadata from a JSON string, such as one returned from
+    /// [`Self::to_json()`] or the output of the JSON encoder. Requires the `serde`
+    /// feature.
+    ///
+    /// # Errors
+    /// This function returns an error if `json` isn't valid JSON or doesn't contain
+    /// valid metadata.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| crate::Error::decode(format!("invalid JSON metadata: {e}")))
+    }
+
+    /// Adds the symbology resolution from `json` to `mappings`, `partial`, and
+    /// `not_found`. `json` should be in the format returned by the symbology
+    /// resolution endpoint of the Databento HTTP API or of the `symbology.json` file
+    /// included in batch downloads. Requires the `serde` feature.
+    ///
+    /// # Errors
+    /// This function returns an error if `json` isn't valid symbology JSON or if any
+    /// of its intervals are invalid or overlap with existing intervals.
+    #[cfg(feature = "serde")]
+    pub fn add_symbology(&mut self, json: &str) -> crate::Result<()> {
+        let symbology: Symbology = serde_json::from_str(json)
+            .map_err(|e| crate::Error::decode(format!("invalid symbology JSON: {e}")))?;
+        for (raw_symbol, intervals) in symbology.result {
+            for interval in intervals {
+                self.add_mapping_interval(&raw_symbol, interval)?;
+            }
+        }
+        for (symbols, new_symbols) in [
+            (&mut self.partial, symbology.partial),
+            (&mut self.not_found, symbology.not_found),
+        ] {
+            for symbol in new_symbols {
+                if !symbols.contains(&symbol) {
+                    symbols.push(symbol);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Merges the metadata of multiple DBN files or streams, e.g. when concatenating
+    /// them. The symbols, partial, not found, and mappings are combined, the query
+    /// range is widened to cover all the inputs, and the limits are summed. The schema
+    /// is `None` if the inputs have different schemas. For custom properties present in
+    /// multiple inputs, the value from the first input is kept.
+    ///
+    /// # Errors
+    /// This function returns an error if `metadata` is empty or the inputs have
+    /// different versions, datasets, input or output symbology types, or `ts_out`.
+    pub fn merge(metadata: &[Metadata]) -> crate::Result<Metadata> {
+        let Some((first, rest)) = metadata.split_first() else {
+            return Err(crate::Error::BadArgument {
+                param_name: "metadata".to_owned(),
+                desc: "must contain at least one Metadata".to_owned(),
+            });
+        };
+        let mut res = first.clone();
+        res.symbols.clear();
+        res.partial.clear();
+        res.not_found.clear();
+        res.mappings.clear();
+        for other in rest {
+            let mismatch = if other.version != res.version {
+                Some("version")
+            } else if other.dataset != res.dataset {
+                Some("dataset")
+            } else if other.stype_in != res.stype_in {
+                Some("stype_in")
+            } else if other.stype_out != res.stype_out {
+                Some("stype_out")
+            } else if other.ts_out != res.ts_out {
+                Some("ts_out")
+            } else {
+                None
+            };
+            if let Some(field) = mismatch {
+                return Err(crate::Error::BadArgument {
+                    param_name: "metadata".to_owned(),
+                    desc: format!("can't merge Metadata with different {field}s"),
+                });
+            }
+            if other.schema != res.schema {
+                res.schema = None;
+            }
+            res.start = res.start.min(other.start);
+            res.end = res.end.zip(other.end).map(|(end, other)| end.max(other));
+            res.limit = res
+                .limit
+                .zip(other.limit)
+                .and_then(|(limit, other)| limit.checked_add(other.get()));
+            for (key, value) in other.custom.iter() {
+                res.custom
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+        let mut seen_symbols = HashSet::new();
+        let mut seen_partial = HashSet::new();
+        let mut seen_not_found = HashSet::new();
+        // Index of each raw symbol in `mappings`
+        let mut mapping_indices = HashMap::new();
+        for other in metadata {
+            for (merged, seen, symbols) in [
+                (&mut res.symbols, &mut seen_symbols, &other.symbols),
+                (&mut res.partial, &mut seen_partial, &other.partial),
+                (&mut res.not_found, &mut seen_not_found, &other.not_found),
+            ] {
+                for symbol in symbols {
+                    if seen.insert(symbol.as_str()) {
+                        merged.push(symbol.clone());
+                    }
+                }
+            }
+            for mapping in other.mappings.iter() {
+                match mapping_indices.entry(mapping.raw_symbol.as_str()) {
+                    Entry::Occupied(entry) => {
+                        let merged = &mut res.mappings[*entry.get()];
+                        for interval in mapping.intervals.iter() {
+                            if !merged.intervals.contains(interval) {
+                                merged.intervals.push(interval.clone());
+                            }
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(res.mappings.len());
+                        res.mappings.push(mapping.clone());
+                    }
+                }
+            }
+        }
+        for mapping in res.mappings.iter_mut() {
+            mapping
+                .intervals
+                .sort_by_key(|interval| interval.start_date);
+        }
+        Ok(res)
+    }
+
     /// Upgrades the metadata according to `upgrade_policy` if necessary.
     pub fn upgrade(&mut self, upgrade_policy: VersionUpgradePolicy) {
         if self.version < crate::DBN_VERSION && upgrade_policy == VersionUpgradePolicy::Upgrade {
-            self.version = crate::DBN_VERSION;
-            self.symbol_cstr_len = crate::SYMBOL_CSTR_LEN;
+            self.apply_version(crate::DBN_VERSION);
+        }
+    }
+
+    /// Converts the metadata to DBN version `version`, upgrading or downgrading it and
+    /// updating the fields that differ between versions, such as `symbol_cstr_len`.
+    ///
+    /// # Errors
+    /// This function returns an error if `version` isn't a supported DBN version or if
+    /// any of the symbols are too long for the symbol length of `version`.
+    pub fn set_version(&mut self, version: u8) -> crate::Result<()> {
+        if !(1..=crate::DBN_VERSION).contains(&version) {
+            return Err(crate::Error::BadArgument {
+                param_name: "version".to_owned(),
+                desc: format!("must be between 1 and {}", crate::DBN_VERSION),
+            });
+        }
+        let symbol_cstr_len = version_symbol_cstr_len(version);
+        let too_long = self
+            .symbols
+            .iter()
+            .chain(self.partial.iter())
+            .chain(self.not_found.iter())
+            .chain(self.mappings.iter().flat_map(|mapping| {
+                std::iter::once(&mapping.raw_symbol)
+                    .chain(mapping.intervals.iter().map(|interval| &interval.symbol))
+            }))
+            .find(|symbol| symbol.len() > symbol_cstr_len);
+        if let Some(symbol) = too_long {
+            return Err(crate::Error::BadArgument {
+                param_name: "version".to_owned(),
+                desc: format!(
+                    "'{symbol}' is too long for DBN version {version}; it cannot be longer than {symbol_cstr_len} characters"
+                ),
+            });
+        }
+        self.apply_version(version);
+        Ok(())
+    }
+
+    /// Compares the metadata with `other`, e.g. to verify that a re-request matches
+    /// an original download. Symbols and mappings are compared without regard to
+    /// order.
+    pub fn diff(&self, other: &Metadata) -> MetadataDiff {
+        let mut fields = Vec::new();
+        for (field, differs) in [
+            ("version", self.version != other.version),
+            ("dataset", self.dataset != other.dataset),
+            ("schema", self.schema != other.schema),
+            ("start", self.start != other.start),
+            ("end", self.end != other.end),
+            ("limit", self.limit != other.limit),
+            ("stype_in", self.stype_in != other.stype_in),
+            ("stype_out", self.stype_out != other.stype_out),
+            ("ts_out", self.ts_out != other.ts_out),
+            (
+                "symbol_cstr_len",
+                self.symbol_cstr_len != other.symbol_cstr_len,
+            ),
+            ("custom", self.custom != other.custom),
+        ] {
+            if differs {
+                fields.push(field);
+            }
+        }
+        let mut changed_mappings = Vec::new();
+        for mapping in self.mappings.iter() {
+            let other_mapping = other
+                .mappings
+                .iter()
+                .find(|other_mapping| other_mapping.raw_symbol == mapping.raw_symbol);
+            if other_mapping.map_or(true, |other_mapping| {
+                !same_intervals(&mapping.intervals, &other_mapping.intervals)
+            }) {
+                changed_mappings.push(mapping.raw_symbol.clone());
+            }
+        }
+        for other_mapping in other.mappings.iter() {
+            if !self
+                .mappings
+                .iter()
+                .any(|mapping| mapping.raw_symbol == other_mapping.raw_symbol)
+            {
+                changed_mappings.push(other_mapping.raw_symbol.clone());
+            }
+        }
+        MetadataDiff {
+            fields,
+            symbols: SymbolsDiff::new(&self.symbols, &other.symbols),
+            partial: SymbolsDiff::new(&self.partial, &other.partial),
+            not_found: SymbolsDiff::new(&self.not_found, &other.not_found),
+            changed_mappings,
+        }
+    }
+
+    /// Adds `interval` to the mappings of `raw_symbol`, keeping the intervals sorted by
+    /// start date.
+    ///
+    /// # Errors
+    /// This function returns an error if `interval` is empty or overlaps with an
+    /// existing interval of `raw_symbol`.
+    pub fn add_mapping_interval(
+        &mut self,
+        raw_symbol: &str,
+        interval: MappingInterval,
+    ) -> crate::Result<()> {
+        if interval.start_date >= interval.end_date {
+            return Err(crate::Error::BadArgument {
+                param_name: "interval".to_owned(),
+                desc: format!(
+                    "start_date {} must be before end_date {}",
+                    interval.start_date, interval.end_date
+                ),
+            });
+        }
+        let Some(mapping) = self
+            .mappings
+            .iter_mut()
+            .find(|mapping| mapping.raw_symbol == raw_symbol)
+        else {
+            self.mappings.push(SymbolMapping {
+                raw_symbol: raw_symbol.to_owned(),
+                intervals: vec![interval],
+            });
+            return Ok(());
+        };
+        if let Some(existing) = mapping.intervals.iter().find(|existing| {
+            existing.start_date < interval.end_date && interval.start_date < existing.end_date
+        }) {
+            return Err(crate::Error::BadArgument {
+                param_name: "interval".to_owned(),
+                desc: format!(
+                    "overlaps with existing interval from {} to {} for '{raw_symbol}'",
+                    existing.start_date, existing.end_date
+                ),
+            });
+        }
+        let idx = mapping
+            .intervals
+            .partition_point(|existing| existing.start_date < interval.start_date);
+        mapping.intervals.insert(idx, interval);
+        Ok(())
+    }
+
+    /// Trims the mapping intervals to the date range from `start_date` (inclusive) to
+    /// `end_date` (exclusive), e.g. after slicing a file by time. Intervals outside the
+    /// range and mappings left without intervals are removed.
+    pub fn trim_mappings(&mut self, start_date: time::Date, end_date: time::Date) {
+        for mapping in self.mappings.iter_mut() {
+            for interval in mapping.intervals.iter_mut() {
+                interval.start_date = interval.start_date.max(start_date);
+                interval.end_date = interval.end_date.min(end_date);
+            }
+            mapping
+                .intervals
+                .retain(|interval| interval.start_date < interval.end_date);
+        }
+        self.mappings
+            .retain(|mapping| !mapping.intervals.is_empty());
+    }
+
+    /// Splits every mapping interval that spans `date` into two intervals, one ending
+    /// and the other starting on `date`.
+    pub fn split_mappings(&mut self, date: time::Date) {
+        for mapping in self.mappings.iter_mut() {
+            let mut i = 0;
+            while i < mapping.intervals.len() {
+                let interval = &mut mapping.intervals[i];
+                if interval.start_date < date && date < interval.end_date {
+                    let second = MappingInterval {
+                        start_date: date,
+                        end_date: interval.end_date,
+                        symbol: interval.symbol.clone(),
+                    };
+                    interval.end_date = date;
+                    mapping.intervals.insert(i + 1, second);
+                    i += 1;
+                }
+                i += 1;
+            }
+        }
+    }
+
+    /// Applies a limit of `limit` records, e.g. after truncating a file. The
+    /// resulting `limit` is the smaller of the existing limit, if any, and `limit`.
+    pub fn apply_limit(&mut self, limit: NonZeroU64) {
+        self.limit = Some(self.limit.map_or(limit, |existing| existing.min(limit)));
+    }
+
+    /// Narrows the query range to the UNIX nanosecond timestamps `start` and `end`,
+    /// e.g. after slicing a file by time. The mappings are trimmed to the dates within
+    /// the new range.
+    ///
+    /// # Errors
+    /// This function returns an error if `start` is after `end` or the range doesn't
+    /// overlap with the existing query range.
+    pub fn restrict(&mut self, start: u64, end: NonZeroU64) -> crate::Result<()> {
+        let new_start = self.start.max(start);
+        let new_end = self.end.map_or(end, |existing| existing.min(end));
+        if new_start > new_end.get() {
+            return Err(crate::Error::BadArgument {
+                param_name: "start".to_owned(),
+                desc: format!("range from {start} to {end} doesn't overlap with the query range"),
+            });
         }
+        self.start = new_start;
+        self.end = Some(new_end);
+        self.trim_mappings(self.start().date(), query_end_date(new_end));
+        Ok(())
+    }
+
+    /// Checks the symbol mappings for periods where a raw symbol isn't mapped and
+    /// returns a warning for each: gaps between consecutive mapping intervals and
+    /// mappings whose last interval ends before [`end`](Self::end). Records from these
+    /// periods can't be resolved to a symbol.
+    pub fn validate_mappings(&self) -> Vec<MappingWarning> {
+        let end_date = self.end.map(query_end_date);
+        let mut warnings = Vec::new();
+        for mapping in self.mappings.iter() {
+            let mut intervals: Vec<_> = mapping.intervals.iter().collect();
+            intervals.sort_by_key(|interval| interval.start_date);
+            for pair in intervals.windows(2) {
+                if pair[0].end_date < pair[1].start_date {
+                    warnings.push(MappingWarning::Gap {
+                        raw_symbol: mapping.raw_symbol.clone(),
+                        start_date: pair[0].end_date,
+                        end_date: pair[1].start_date,
+                    });
+                }
+            }
+            let mapped_until = intervals.iter().map(|interval| interval.end_date).max();
+            if let Some((mapped_until, end_date)) = mapped_until.zip(end_date) {
+                if mapped_until < end_date {
+                    warnings.push(MappingWarning::EndsEarly {
+                        raw_symbol: mapping.raw_symbol.clone(),
+                        end_date: mapped_until,
+                    });
+                }
+            }
+        }
+        warnings
+    }
+
+    fn apply_version(&mut self, version: u8) {
+        self.version = version;
+        self.symbol_cstr_len = version_symbol_cstr_len(version);
     }
 }
 
@@ -153,6 +593,7 @@ pub struct MetadataBuilder<D, Sch, Start, StIn, StOut> {
     partial: Vec<String>,
     not_found: Vec<String>,
     mappings: Vec<SymbolMapping>,
+    custom: BTreeMap<String, String>,
 }
 
 /// Sentinel type for a required field that has not yet been set.
@@ -194,6 +635,7 @@ impl<D, Sch, Start, StIn, StOut> MetadataBuilder<D, Sch, Start, StIn, StOut> {
             partial: self.partial,
             not_found: self.not_found,
             mappings: self.mappings,
+            custom: self.custom,
         }
     }
 
@@ -216,6 +658,7 @@ impl<D, Sch, Start, StIn, StOut> MetadataBuilder<D, Sch, Start, StIn, StOut> {
             partial: self.partial,
             not_found: self.not_found,
             mappings: self.mappings,
+            custom: self.custom,
         }
     }
 
@@ -235,6 +678,7 @@ impl<D, Sch, Start, StIn, StOut> MetadataBuilder<D, Sch, Start, StIn, StOut> {
             partial: self.partial,
             not_found: self.not_found,
             mappings: self.mappings,
+            custom: self.custom,
         }
     }
 
@@ -269,6 +713,7 @@ impl<D, Sch, Start, StIn, StOut> MetadataBuilder<D, Sch, Start, StIn, StOut> {
             partial: self.partial,
             not_found: self.not_found,
             mappings: self.mappings,
+            custom: self.custom,
         }
     }
 
@@ -288,6 +733,7 @@ impl<D, Sch, Start, StIn, StOut> MetadataBuilder<D, Sch, Start, StIn, StOut> {
             partial: self.partial,
             not_found: self.not_found,
             mappings: self.mappings,
+            custom: self.custom,
         }
     }
 
@@ -320,6 +766,12 @@ impl<D, Sch, Start, StIn, StOut> MetadataBuilder<D, Sch, Start, StIn, StOut> {
         self.mappings = mappings;
         self
     }
+
+    /// Sets [`custom`](Metadata::custom) and returns the builder.
+    pub fn custom(mut self, custom: BTreeMap<String, String>) -> Self {
+        self.custom = custom;
+        self
+    }
 }
 
 impl MetadataBuilder<String, Option<Schema>, u64, Option<SType>, SType> {
@@ -340,6 +792,7 @@ impl MetadataBuilder<String, Option<Schema>, u64, Option<SType>, SType> {
             partial: self.partial,
             not_found: self.not_found,
             mappings: self.mappings,
+            custom: self.custom,
             symbol_cstr_len: version_symbol_cstr_len(self.version),
         }
     }
@@ -361,13 +814,14 @@ impl Default for MetadataBuilder<Unset, Unset, Unset, Unset, Unset> {
             partial: vec![],
             not_found: vec![],
             mappings: vec![],
+            custom: BTreeMap::new(),
         }
     }
 }
 
 /// A raw symbol and its symbol mappings for different time ranges within the query range.
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "python", derive(pyo3::FromPyObject))]
 pub struct SymbolMapping {
     /// The symbol assigned by publisher.
@@ -378,33 +832,801 @@ pub struct SymbolMapping {
 
 /// The resolved symbol for a date range.
 #[derive(Debug, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MappingInterval {
     /// The UTC start date of interval (inclusive).
     #[cfg_attr(
         feature = "serde",
-        serde(rename = "d0", deserialize_with = "deserialize_date")
+        serde(
+            rename(serialize = "start_date", deserialize = "d0"),
+            alias = "start_date",
+            serialize_with = "serialize_date",
+            deserialize_with = "deserialize_date"
+        )
     )]
     pub start_date: time::Date,
     /// The UTC end date of interval (exclusive).
     #[cfg_attr(
         feature = "serde",
-        serde(rename = "d1", deserialize_with = "deserialize_date")
+        serde(
+            rename(serialize = "end_date", deserialize = "d1"),
+            alias = "end_date",
+            serialize_with = "serialize_date",
+            deserialize_with = "deserialize_date"
+        )
     )]
     pub end_date: time::Date,
     /// The resolved symbol for this interval.
-    #[cfg_attr(feature = "serde", serde(rename = "s"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename(serialize = "symbol", deserialize = "s"), alias = "symbol")
+    )]
     pub symbol: String,
 }
 
+/// The differences between two [`Metadata`], returned by [`Metadata::diff()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataDiff {
+    /// The names of the non-symbol fields that differ, e.g. `"start"` or `"limit"`.
+    pub fields: Vec<&'static str>,
+    /// The differences in the query input symbols.
+    pub symbols: SymbolsDiff,
+    /// The differences in the symbols that partially resolved.
+    pub partial: SymbolsDiff,
+    /// The differences in the symbols that didn't resolve.
+    pub not_found: SymbolsDiff,
+    /// The raw symbols whose mapping intervals differ or that only have mappings in
+    /// one of the metadata.
+    pub changed_mappings: Vec<String>,
+}
+
+impl MetadataDiff {
+    /// Returns `true` if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.symbols.is_empty()
+            && self.partial.is_empty()
+            && self.not_found.is_empty()
+            && self.changed_mappings.is_empty()
+    }
+}
+
+/// The differences between two lists of symbols.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolsDiff {
+    /// Symbols only present in the other metadata.
+    pub added: Vec<String>,
+    /// Symbols only present in the original metadata.
+    pub removed: Vec<String>,
+}
+
+impl SymbolsDiff {
+    fn new(original: &[String], other: &[String]) -> Self {
+        Self {
+            added: other
+                .iter()
+                .filter(|symbol| !original.contains(symbol))
+                .cloned()
+                .collect(),
+            removed: original
+                .iter()
+                .filter(|symbol| !other.contains(symbol))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if no symbols were added or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A period where a raw symbol isn't mapped, returned by
+/// [`Metadata::validate_mappings()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MappingWarning {
+    /// There's no mapping interval between the end of one interval and the start of
+    /// the next.
+    Gap {
+        /// The raw symbol.
+        raw_symbol: String,
+        /// The UTC start date of the gap (inclusive).
+        start_date: time::Date,
+        /// The UTC end date of the gap (exclusive).
+        end_date: time::Date,
+    },
+    /// The last mapping interval ends before the end of the query.
+    EndsEarly {
+        /// The raw symbol.
+        raw_symbol: String,
+        /// The UTC end date of the last interval (exclusive).
+        end_date: time::Date,
+    },
+}
+
+impl fmt::Display for MappingWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingWarning::Gap {
+                raw_symbol,
+                start_date,
+                end_date,
+            } => write!(
+                f,
+                "'{raw_symbol}' isn't mapped from {start_date} until {end_date}"
+            ),
+            MappingWarning::EndsEarly {
+                raw_symbol,
+                end_date,
+            } => write!(
+                f,
+                "'{raw_symbol}' isn't mapped from {end_date} until the end of the query"
+            ),
+        }
+    }
+}
+
+/// Returns the exclusive end date of a query ending at `end`.
+fn query_end_date(end: NonZeroU64) -> time::Date {
+    // `end` is exclusive
+    time::OffsetDateTime::from_unix_timestamp_nanos(end.get() as i128 - 1)
+        .unwrap()
+        .date()
+        .next_day()
+        .unwrap_or(time::Date::MAX)
+}
+
+fn same_intervals(intervals: &[MappingInterval], other: &[MappingInterval]) -> bool {
+    intervals.len() == other.len() && intervals.iter().all(|interval| other.contains(interval))
+}
+
+/// The response of symbology resolution from the Databento HTTP API.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct Symbology {
+    result: BTreeMap<String, Vec<MappingInterval>>,
+    #[serde(default)]
+    partial: Vec<String>,
+    #[serde(default)]
+    not_found: Vec<String>,
+}
+
 /// The date format used for date strings when serializing [`Metadata`].
 pub const DATE_FORMAT: &[time::format_description::FormatItem<'static>] =
     time::macros::format_description!("[year]-[month]-[day]");
 
+/// A date or timestamp that was serialized either as a number or as a string.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IntOrStr {
+    Int(u64),
+    Str(String),
+}
+
+/// Deserializes a date from either a `YYYY-MM-DD` string or a `YYYYMMDD` integer, the
+/// format used by the JSON encoder when `pretty_ts` is `false`.
 #[cfg(feature = "serde")]
 fn deserialize_date<'de, D: serde::Deserializer<'de>>(
     deserializer: D,
 ) -> Result<time::Date, D::Error> {
-    let date_str = String::deserialize(deserializer)?;
-    time::Date::parse(&date_str, DATE_FORMAT).map_err(serde::de::Error::custom)
+    match IntOrStr::deserialize(deserializer)? {
+        IntOrStr::Str(date_str) => {
+            time::Date::parse(&date_str, DATE_FORMAT).map_err(serde::de::Error::custom)
+        }
+        IntOrStr::Int(date_int) => {
+            let month = u8::try_from(date_int / 100 % 100)
+                .ok()
+                .and_then(|m| time::Month::try_from(m).ok())
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid date {date_int}")))?;
+            time::Date::from_calendar_date(
+                (date_int / 10_000) as i32,
+                month,
+                (date_int % 100) as u8,
+            )
+            .map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+/// Deserializes a UNIX nanosecond timestamp from an integer, a string of an integer,
+/// or an RFC 3339 datetime string.
+#[cfg(feature = "serde")]
+fn deserialize_ts<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match IntOrStr::deserialize(deserializer)? {
+        IntOrStr::Int(ts) => Ok(ts),
+        IntOrStr::Str(ts_str) => parse_ts(&ts_str).map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_opt_ts<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NonZeroU64>, D::Error> {
+    let ts = match Option::<IntOrStr>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(IntOrStr::Int(ts)) => ts,
+        Some(IntOrStr::Str(ts_str)) => parse_ts(&ts_str).map_err(serde::de::Error::custom)?,
+    };
+    Ok(NonZeroU64::new(ts))
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_limit<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NonZeroU64>, D::Error> {
+    let limit = match Option::<IntOrStr>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(IntOrStr::Int(limit)) => limit,
+        Some(IntOrStr::Str(limit_str)) => limit_str.parse().map_err(serde::de::Error::custom)?,
+    };
+    Ok(NonZeroU64::new(limit))
+}
+
+/// Serializes a UNIX nanosecond timestamp as an RFC 3339 datetime string in
+/// human-readable formats and as an integer otherwise.
+#[cfg(feature = "serde")]
+fn serialize_ts<S: serde::Serializer>(ts: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format_ts(*ts).map_err(serde::ser::Error::custom)?)
+    } else {
+        serializer.serialize_u64(*ts)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_opt_ts<S: serde::Serializer>(
+    ts: &Option<NonZeroU64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match ts {
+        None => serializer.serialize_none(),
+        Some(ts) if serializer.is_human_readable() => {
+            serializer.serialize_some(&format_ts(ts.get()).map_err(serde::ser::Error::custom)?)
+        }
+        Some(ts) => serializer.serialize_some(&ts.get()),
+    }
+}
+
+/// Serializes a date as a `YYYY-MM-DD` string in human-readable formats and as a
+/// `YYYYMMDD` integer otherwise.
+#[cfg(feature = "serde")]
+fn serialize_date<S: serde::Serializer>(
+    date: &time::Date,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(
+            &date
+                .format(DATE_FORMAT)
+                .map_err(serde::ser::Error::custom)?,
+        )
+    } else {
+        serializer.serialize_u32(
+            date.year() as u32 * 10_000 + u8::from(date.month()) as u32 * 100 + date.day() as u32,
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+fn format_ts(ts: u64) -> Result<String, time::error::Format> {
+    // `u64::MAX` is within the allowable range for `OffsetDateTime`s
+    time::OffsetDateTime::from_unix_timestamp_nanos(ts as i128)
+        .unwrap()
+        .format(&time::format_description::well_known::Rfc3339)
+}
+
+#[cfg(feature = "serde")]
+fn parse_ts(ts_str: &str) -> Result<u64, String> {
+    if let Ok(ts) = ts_str.parse::<u64>() {
+        return Ok(ts);
+    }
+    let dt = time::OffsetDateTime::parse(ts_str, &time::format_description::well_known::Rfc3339)
+        .map_err(|e| format!("invalid timestamp `{ts_str}`: {e}"))?;
+    u64::try_from(dt.unix_timestamp_nanos())
+        .map_err(|_| format!("timestamp `{ts_str}` is before the UNIX epoch"))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+    use crate::Dataset;
+
+    fn metadata(start: u64, end: u64) -> Metadata {
+        Metadata::builder()
+            .dataset(Dataset::XnasItch.as_str().to_owned())
+            .schema(Some(Schema::Trades))
+            .start(start)
+            .end(NonZeroU64::new(end))
+            .limit(NonZeroU64::new(10))
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .build()
+    }
+
+    fn mapping(raw_symbol: &str, intervals: &[(time::Date, time::Date, &str)]) -> SymbolMapping {
+        SymbolMapping {
+            raw_symbol: raw_symbol.to_owned(),
+            intervals: intervals
+                .iter()
+                .map(|(start_date, end_date, symbol)| MappingInterval {
+                    start_date: *start_date,
+                    end_date: *end_date,
+                    symbol: (*symbol).to_owned(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_start_end() {
+        let mut target = metadata(1_688_169_600_000_000_000, 1_688_256_000_000_000_000);
+        assert_eq!(target.start(), datetime!(2023-07-01 00:00 UTC));
+        assert_eq!(target.end(), Some(datetime!(2023-07-02 00:00 UTC)));
+        target.end = None;
+        assert!(target.end().is_none());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut first = metadata(100, 200);
+        first.symbols = vec!["AAPL".to_owned(), "MSFT".to_owned()];
+        first.not_found = vec!["ZZZZ".to_owned()];
+        first.mappings = vec![mapping(
+            "AAPL",
+            &[(date!(2023 - 07 - 02), date!(2023 - 07 - 03), "32")],
+        )];
+        let mut second = metadata(50, 150);
+        second.schema = Some(Schema::Mbo);
+        second.symbols = vec!["MSFT".to_owned(), "TSLA".to_owned()];
+        second.mappings = vec![
+            mapping(
+                "AAPL",
+                &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32")],
+            ),
+            mapping(
+                "TSLA",
+                &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "10221")],
+            ),
+        ];
+        let merged = Metadata::merge(&[first, second]).unwrap();
+        assert_eq!(merged.schema, None);
+        assert_eq!(merged.start, 50);
+        assert_eq!(merged.end, NonZeroU64::new(200));
+        assert_eq!(merged.limit, NonZeroU64::new(20));
+        assert_eq!(merged.symbols, ["AAPL", "MSFT", "TSLA"]);
+        assert_eq!(merged.not_found, ["ZZZZ"]);
+        assert_eq!(
+            merged.mappings,
+            [
+                mapping(
+                    "AAPL",
+                    &[
+                        (date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32"),
+                        (date!(2023 - 07 - 02), date!(2023 - 07 - 03), "32")
+                    ],
+                ),
+                mapping(
+                    "TSLA",
+                    &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "10221")],
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_version() {
+        let mut target = metadata(100, 200);
+        target.symbols = vec!["ESZ3 C4500".to_owned()];
+        target.set_version(1).unwrap();
+        assert_eq!(target.version, 1);
+        assert_eq!(target.symbol_cstr_len, crate::compat::SYMBOL_CSTR_LEN_V1);
+        target.set_version(crate::DBN_VERSION).unwrap();
+        assert_eq!(target.version, crate::DBN_VERSION);
+        assert_eq!(target.symbol_cstr_len, crate::SYMBOL_CSTR_LEN);
+        assert!(target.set_version(0).is_err());
+        assert!(target.set_version(crate::DBN_VERSION + 1).is_err());
+    }
+
+    #[test]
+    fn test_set_version_symbol_too_long() {
+        let mut target = metadata(100, 200);
+        target.mappings = vec![mapping(
+            "A_VERY_LONG_RAW_SYMBOL_NAME",
+            &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32")],
+        )];
+        let res = target.set_version(1);
+        assert!(
+            matches!(res, Err(crate::Error::BadArgument { desc, .. }) if desc.contains("A_VERY_LONG_RAW_SYMBOL_NAME"))
+        );
+        assert_eq!(target.version, crate::DBN_VERSION);
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest::rstest]
+    fn test_json_round_trip(#[values(false, true)] should_pretty_print: bool) {
+        let mut target = metadata(1_688_169_600_000_000_000, 1_688_256_000_000_000_000);
+        target.symbols = vec!["AAPL".to_owned()];
+        target.mappings = vec![mapping(
+            "AAPL",
+            &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32")],
+        )];
+        let json = target.to_json(should_pretty_print);
+        assert_eq!(Metadata::from_json(&json).unwrap(), target);
+        let serialized = serde_json::to_string(&target).unwrap();
+        assert!(serialized.contains(r#""start":"2023-07-01T00:00:00Z""#));
+        assert!(serialized.contains(r#""start_date":"2023-07-01""#));
+        assert!(serialized.contains(r#""stype_out":"instrument_id""#));
+        assert_eq!(Metadata::from_json(&serialized).unwrap(), target);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_round_trip() {
+        let mut target = metadata(1_688_169_600_000_000_000, 1_688_256_000_000_000_000);
+        target.symbols = vec!["AAPL".to_owned(), "TSLA".to_owned(), "QQQ".to_owned()];
+        target.partial = vec!["TSLA".to_owned()];
+        target.not_found = vec!["QQQ".to_owned()];
+        target.mappings = vec![
+            mapping(
+                "AAPL",
+                &[
+                    (date!(2023 - 06 - 30), date!(2023 - 07 - 01), "31"),
+                    (date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32"),
+                ],
+            ),
+            mapping(
+                "TSLA",
+                &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "10451")],
+            ),
+        ];
+        target.custom = BTreeMap::from([("source".to_owned(), "test".to_owned())]);
+        let value = serde_json::to_value(&target).unwrap();
+        assert_eq!(
+            value["mappings"][0]["intervals"][1]["start_date"],
+            "2023-07-01"
+        );
+        assert_eq!(serde_json::from_value::<Metadata>(value).unwrap(), target);
+        // Optional fields can be omitted
+        target.schema = None;
+        target.end = None;
+        target.limit = None;
+        target.stype_in = None;
+        target.custom.clear();
+        let serialized = serde_json::to_string(&target).unwrap();
+        assert_eq!(Metadata::from_json(&serialized).unwrap(), target);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_int_dates_and_timestamps() {
+        const JSON: &str = r#"{
+            "version": 2,
+            "dataset": "XNAS.ITCH",
+            "schema": "trades",
+            "start": 1688169600000000000,
+            "end": "1688256000000000000",
+            "stype_in": "raw_symbol",
+            "stype_out": "instrument_id",
+            "ts_out": false,
+            "symbol_cstr_len": 71,
+            "mappings": [{
+                "raw_symbol": "AAPL",
+                "intervals": [{"start_date": 20230701, "end_date": 20230702, "symbol": "32"}]
+            }]
+        }"#;
+        let mut expected = metadata(1_688_169_600_000_000_000, 1_688_256_000_000_000_000);
+        expected.limit = None;
+        expected.mappings = vec![mapping(
+            "AAPL",
+            &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32")],
+        )];
+        assert_eq!(Metadata::from_json(JSON).unwrap(), expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_add_symbology() {
+        const JSON: &str = r#"{
+            "result": {
+                "AAPL": [{"d0": "2023-07-01", "d1": "2023-07-03", "s": "32"}],
+                "MSFT": [
+                    {"d0": "2023-07-01", "d1": "2023-07-02", "s": "7152"},
+                    {"d0": "2023-07-02", "d1": "2023-07-03", "s": "7153"}
+                ]
+            },
+            "symbols": ["AAPL", "MSFT", "ZZZZ"],
+            "stype_in": "raw_symbol",
+            "stype_out": "instrument_id",
+            "start_date": "2023-07-01",
+            "end_date": "2023-07-03",
+            "partial": [],
+            "not_found": ["ZZZZ"],
+            "message": "OK",
+            "status": 0
+        }"#;
+        let mut target = metadata(100, 200);
+        target.add_symbology(JSON).unwrap();
+        assert_eq!(
+            target.mappings,
+            vec![
+                mapping(
+                    "AAPL",
+                    &[(date!(2023 - 07 - 01), date!(2023 - 07 - 03), "32")]
+                ),
+                mapping(
+                    "MSFT",
+                    &[
+                        (date!(2023 - 07 - 01), date!(2023 - 07 - 02), "7152"),
+                        (date!(2023 - 07 - 02), date!(2023 - 07 - 03), "7153"),
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(target.not_found, vec!["ZZZZ".to_owned()]);
+        // Overlaps with the existing mappings
+        assert!(target.add_symbology(JSON).is_err());
+        assert!(target.add_symbology("{}").is_err());
+    }
+
+    #[test]
+    fn test_merge_custom() {
+        let mut first = metadata(100, 200);
+        first.custom = BTreeMap::from([
+            ("generator".to_owned(), "first".to_owned()),
+            ("request_id".to_owned(), "1".to_owned()),
+        ]);
+        let mut second = metadata(100, 200);
+        second.custom = BTreeMap::from([
+            ("generator".to_owned(), "second".to_owned()),
+            ("source".to_owned(), "archive".to_owned()),
+        ]);
+        let merged = Metadata::merge(&[first, second]).unwrap();
+        assert_eq!(
+            merged.custom,
+            BTreeMap::from([
+                ("generator".to_owned(), "first".to_owned()),
+                ("request_id".to_owned(), "1".to_owned()),
+                ("source".to_owned(), "archive".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_merge_single() {
+        let target = metadata(100, 200);
+        assert_eq!(Metadata::merge(&[target.clone()]).unwrap(), target);
+    }
+
+    #[test]
+    fn test_merge_incompatible() {
+        assert!(Metadata::merge(&[]).is_err());
+        let mut other = metadata(100, 200);
+        other.stype_in = Some(SType::Parent);
+        let res = Metadata::merge(&[metadata(100, 200), other]);
+        assert!(
+            matches!(res, Err(crate::Error::BadArgument { desc, .. }) if desc.contains("stype_in"))
+        );
+    }
+
+    #[test]
+    fn test_add_mapping_interval() {
+        let mut target = metadata(100, 200);
+        let interval = |start_date, end_date| MappingInterval {
+            start_date,
+            end_date,
+            symbol: "32".to_owned(),
+        };
+        target
+            .add_mapping_interval(
+                "AAPL",
+                interval(date!(2023 - 07 - 03), date!(2023 - 07 - 04)),
+            )
+            .unwrap();
+        target
+            .add_mapping_interval(
+                "AAPL",
+                interval(date!(2023 - 07 - 01), date!(2023 - 07 - 03)),
+            )
+            .unwrap();
+        assert_eq!(
+            target.mappings,
+            vec![mapping(
+                "AAPL",
+                &[
+                    (date!(2023 - 07 - 01), date!(2023 - 07 - 03), "32"),
+                    (date!(2023 - 07 - 03), date!(2023 - 07 - 04), "32"),
+                ]
+            )]
+        );
+        assert!(target
+            .add_mapping_interval(
+                "AAPL",
+                interval(date!(2023 - 07 - 02), date!(2023 - 07 - 05))
+            )
+            .is_err());
+        assert!(target
+            .add_mapping_interval(
+                "MSFT",
+                interval(date!(2023 - 07 - 02), date!(2023 - 07 - 02))
+            )
+            .is_err());
+        assert_eq!(target.mappings.len(), 1);
+    }
+
+    #[test]
+    fn test_trim_and_split_mappings() {
+        let mut target = metadata(100, 200);
+        target.mappings = vec![
+            mapping(
+                "AAPL",
+                &[
+                    (date!(2023 - 07 - 01), date!(2023 - 07 - 03), "32"),
+                    (date!(2023 - 07 - 03), date!(2023 - 07 - 06), "33"),
+                ],
+            ),
+            mapping(
+                "MSFT",
+                &[(date!(2023 - 07 - 05), date!(2023 - 07 - 06), "7152")],
+            ),
+        ];
+        target.trim_mappings(date!(2023 - 07 - 02), date!(2023 - 07 - 05));
+        assert_eq!(
+            target.mappings,
+            vec![mapping(
+                "AAPL",
+                &[
+                    (date!(2023 - 07 - 02), date!(2023 - 07 - 03), "32"),
+                    (date!(2023 - 07 - 03), date!(2023 - 07 - 05), "33"),
+                ]
+            )]
+        );
+        target.split_mappings(date!(2023 - 07 - 04));
+        assert_eq!(
+            target.mappings,
+            vec![mapping(
+                "AAPL",
+                &[
+                    (date!(2023 - 07 - 02), date!(2023 - 07 - 03), "32"),
+                    (date!(2023 - 07 - 03), date!(2023 - 07 - 04), "33"),
+                    (date!(2023 - 07 - 04), date!(2023 - 07 - 05), "33"),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_apply_limit() {
+        let mut target = metadata(100, 200);
+        target.apply_limit(NonZeroU64::new(20).unwrap());
+        assert_eq!(target.limit, NonZeroU64::new(10));
+        target.apply_limit(NonZeroU64::new(5).unwrap());
+        assert_eq!(target.limit, NonZeroU64::new(5));
+        target.limit = None;
+        target.apply_limit(NonZeroU64::new(20).unwrap());
+        assert_eq!(target.limit, NonZeroU64::new(20));
+    }
+
+    #[test]
+    fn test_restrict() {
+        // 2023-07-01T00:00:00Z to 2023-07-04T00:00:00Z
+        let mut target = metadata(1_688_169_600_000_000_000, 1_688_428_800_000_000_000);
+        target.mappings = vec![mapping(
+            "AAPL",
+            &[
+                (date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32"),
+                (date!(2023 - 07 - 02), date!(2023 - 07 - 04), "33"),
+            ],
+        )];
+        // 2023-07-02T12:00:00Z to 2023-07-03T00:00:00Z
+        target
+            .restrict(
+                1_688_299_200_000_000_000,
+                NonZeroU64::new(1_688_342_400_000_000_000).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(target.start, 1_688_299_200_000_000_000);
+        assert_eq!(target.end, NonZeroU64::new(1_688_342_400_000_000_000));
+        assert_eq!(
+            target.mappings,
+            vec![mapping(
+                "AAPL",
+                &[(date!(2023 - 07 - 02), date!(2023 - 07 - 03), "33")]
+            )]
+        );
+        assert!(target.restrict(0, NonZeroU64::new(100).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_validate_mappings() {
+        // 2023-07-01T00:00:00Z to 2023-07-05T12:00:00Z
+        let mut target = metadata(1_688_169_600_000_000_000, 1_688_558_400_000_000_000);
+        target.mappings = vec![
+            mapping(
+                "AAPL",
+                &[
+                    (date!(2023 - 07 - 01), date!(2023 - 07 - 03), "32"),
+                    (date!(2023 - 07 - 03), date!(2023 - 07 - 06), "33"),
+                ],
+            ),
+            mapping(
+                "MSFT",
+                &[
+                    (date!(2023 - 07 - 04), date!(2023 - 07 - 05), "7153"),
+                    (date!(2023 - 07 - 01), date!(2023 - 07 - 02), "7152"),
+                ],
+            ),
+        ];
+        let warnings = target.validate_mappings();
+        assert_eq!(
+            warnings,
+            vec![
+                MappingWarning::Gap {
+                    raw_symbol: "MSFT".to_owned(),
+                    start_date: date!(2023 - 07 - 02),
+                    end_date: date!(2023 - 07 - 04),
+                },
+                MappingWarning::EndsEarly {
+                    raw_symbol: "MSFT".to_owned(),
+                    end_date: date!(2023 - 07 - 05),
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "'MSFT' isn't mapped from 2023-07-02 until 2023-07-04"
+        );
+        // No end to check against
+        target.end = None;
+        assert_eq!(target.validate_mappings(), warnings[..1]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut original = metadata(100, 200);
+        original.symbols = vec!["AAPL".to_owned(), "MSFT".to_owned()];
+        original.mappings = vec![
+            mapping(
+                "AAPL",
+                &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "32")],
+            ),
+            mapping(
+                "MSFT",
+                &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "7152")],
+            ),
+        ];
+        assert!(original.diff(&original).is_empty());
+
+        let mut other = metadata(100, 300);
+        other.limit = None;
+        other.symbols = vec!["MSFT".to_owned(), "TSLA".to_owned()];
+        other.mappings = vec![
+            mapping(
+                "MSFT",
+                &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "7152")],
+            ),
+            mapping(
+                "TSLA",
+                &[(date!(2023 - 07 - 01), date!(2023 - 07 - 02), "10451")],
+            ),
+        ];
+        let diff = original.diff(&other);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff,
+            MetadataDiff {
+                fields: vec!["end", "limit"],
+                symbols: SymbolsDiff {
+                    added: vec!["TSLA".to_owned()],
+                    removed: vec!["AAPL".to_owned()],
+                },
+                partial: SymbolsDiff::default(),
+                not_found: SymbolsDiff::default(),
+                changed_mappings: vec!["AAPL".to_owned(), "TSLA".to_owned()],
+            }
+        );
+    }
 }
@@ -5,7 +5,11 @@ use std::fmt;
 
 use time::format_description::FormatItem;
 
-use crate::FIXED_PRICE_SCALE;
+use crate::{FIXED_PRICE_SCALE, UNDEF_PRICE};
+
+const TS_FORMAT: &[FormatItem<'static>] = time::macros::format_description!(
+    "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:9]Z"
+);
 
 /// A new type for formatting nanosecond UNIX timestamps.
 #[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
@@ -37,56 +41,218 @@ impl fmt::Debug for Ts {
 
 impl fmt::Debug for Px {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&fmt_px(self.0))
+        f.write_str(PxBuffer::new().format(self.0))
     }
 }
 
 impl fmt::Display for Ts {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&fmt_ts(self.0))
+        if self.0 == 0 {
+            Ok(())
+        } else {
+            f.write_str(TsBuffer::new().format(self.0))
+        }
     }
 }
 
 impl fmt::Display for Px {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&fmt_px(self.0))
+        f.write_str(PxBuffer::new().format(self.0))
     }
 }
 
 /// Converts a fixed-precision price to a decimal string.
 pub fn fmt_px(px: i64) -> String {
-    if px == crate::UNDEF_PRICE {
-        "UNDEF_PRICE".to_owned()
-    } else {
-        let (sign, px_abs) = if px < 0 { ("-", -px) } else { ("", px) };
-        let px_integer = px_abs / FIXED_PRICE_SCALE;
-        let px_fraction = px_abs % FIXED_PRICE_SCALE;
-        format!("{sign}{px_integer}.{px_fraction:09}")
-    }
+    let mut buffer = String::new();
+    fmt_px_into(&mut buffer, px);
+    buffer
+}
+
+/// Converts a fixed-precision price to a decimal string like [`fmt_px`], appending it
+/// to `buffer`. Reusing `buffer` avoids allocating for each price.
+pub fn fmt_px_into(buffer: &mut String, px: i64) {
+    buffer.push_str(PxBuffer::new().format(px));
 }
 
 /// Converts a nanosecond UNIX timestamp to a human-readable string in the format
 /// `[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:9]Z`.
 pub fn fmt_ts(ts: u64) -> String {
-    const TS_FORMAT: &[FormatItem<'static>] = time::macros::format_description!(
-        "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:9]Z"
-    );
-    if ts == 0 {
-        String::new()
+    let mut buffer = String::new();
+    fmt_ts_into(&mut buffer, ts);
+    buffer
+}
+
+/// Converts a nanosecond UNIX timestamp to a human-readable string like [`fmt_ts`],
+/// appending it to `buffer`. Reusing `buffer` avoids allocating for each timestamp.
+pub fn fmt_ts_into(buffer: &mut String, ts: u64) {
+    if ts != 0 {
+        buffer.push_str(TsBuffer::new().format(ts));
+    }
+}
+
+/// A buffer for formatting prices like [`fmt_px`] without allocating, similar to
+/// [`itoa::Buffer`].
+pub(crate) struct PxBuffer {
+    bytes: [u8; Self::MAX_LEN],
+}
+
+impl PxBuffer {
+    /// The length of `i64::MIN` formatted as a price.
+    const MAX_LEN: usize = 21;
+
+    pub fn new() -> Self {
+        Self {
+            bytes: [0; Self::MAX_LEN],
+        }
+    }
+
+    /// Formats `px` into the buffer and returns the formatted string.
+    pub fn format(&mut self, px: i64) -> &str {
+        if px == UNDEF_PRICE {
+            return "UNDEF_PRICE";
+        }
+        let px_abs = px.unsigned_abs();
+        let mut len = 0;
+        if px < 0 {
+            self.bytes[0] = b'-';
+            len += 1;
+        }
+        let mut itoa_buf = itoa::Buffer::new();
+        let px_integer = itoa_buf.format(px_abs / FIXED_PRICE_SCALE as u64);
+        self.bytes[len..len + px_integer.len()].copy_from_slice(px_integer.as_bytes());
+        len += px_integer.len();
+        self.bytes[len] = b'.';
+        len += 1;
+        let px_fraction = itoa_buf.format(px_abs % FIXED_PRICE_SCALE as u64);
+        let zeros = 9 - px_fraction.len();
+        self.bytes[len..len + zeros].fill(b'0');
+        len += zeros;
+        self.bytes[len..len + px_fraction.len()].copy_from_slice(px_fraction.as_bytes());
+        len += px_fraction.len();
+        // only ASCII is written
+        std::str::from_utf8(&self.bytes[..len]).unwrap()
+    }
+}
+
+/// A buffer for formatting non-zero timestamps like [`fmt_ts`] without allocating,
+/// similar to [`itoa::Buffer`].
+pub(crate) struct TsBuffer {
+    bytes: [u8; Self::MAX_LEN],
+}
+
+impl TsBuffer {
+    /// Large enough for both formatted timestamps and `u64::MAX`.
+    const MAX_LEN: usize = 32;
+
+    pub fn new() -> Self {
+        Self {
+            bytes: [0; Self::MAX_LEN],
+        }
+    }
+
+    /// Formats `ts` into the buffer and returns the formatted string. Timestamps that
+    /// can't be formatted as a date and time are formatted as an integer.
+    pub fn format(&mut self, ts: u64) -> &str {
+        let formatted = time::OffsetDateTime::from_unix_timestamp_nanos(ts as i128)
+            .ok()
+            .and_then(|dt| {
+                dt.format_into(&mut self.bytes.as_mut_slice(), TS_FORMAT)
+                    .ok()
+            });
+        let len = match formatted {
+            Some(len) => len,
+            None => {
+                let mut itoa_buf = itoa::Buffer::new();
+                let ts = itoa_buf.format(ts);
+                self.bytes[..ts.len()].copy_from_slice(ts.as_bytes());
+                ts.len()
+            }
+        };
+        // only ASCII is written
+        std::str::from_utf8(&self.bytes[..len]).unwrap()
+    }
+}
+
+/// Parses a decimal price string, such as one returned by [`fmt_px`], into a
+/// fixed-precision price. `"UNDEF_PRICE"` is parsed as [`UNDEF_PRICE`].
+///
+/// # Errors
+/// This function returns an error if `s` isn't a decimal number with at most 9
+/// fractional digits or is out of range.
+pub fn parse_px(s: &str) -> crate::Result<i64> {
+    if s == "UNDEF_PRICE" {
+        return Ok(UNDEF_PRICE);
+    }
+    let err = || crate::Error::conversion::<Px>(s);
+    let (is_negative, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, s),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if (integer.is_empty() && fraction.is_empty())
+        || fraction.len() > 9
+        || !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(err());
+    }
+    let integer = if integer.is_empty() {
+        0
     } else {
-        time::OffsetDateTime::from_unix_timestamp_nanos(ts as i128)
-            .map_err(|_| ())
-            .and_then(|dt| dt.format(TS_FORMAT).map_err(|_| ()))
-            .unwrap_or_else(|_| ts.to_string())
+        integer.parse::<i64>().map_err(|_| err())?
+    };
+    let fraction = format!("{fraction:0<9}")
+        .parse::<i64>()
+        .map_err(|_| err())?;
+    let integer_px = integer.checked_mul(FIXED_PRICE_SCALE).ok_or_else(err)?;
+    // build negative prices negatively because `i64::MIN` has no positive counterpart
+    if is_negative {
+        (-integer_px).checked_sub(fraction)
+    } else {
+        integer_px.checked_add(fraction)
+    }
+    .ok_or_else(err)
+}
+
+/// Parses a timestamp string, such as one returned by [`fmt_ts`], into a nanosecond
+/// UNIX timestamp. An empty string is parsed as `0` and a string of digits is
+/// parsed as the timestamp itself.
+///
+/// # Errors
+/// This function returns an error if `s` is neither in the format of [`fmt_ts`] nor
+/// an integer, or the timestamp is before the UNIX epoch.
+pub fn parse_ts(s: &str) -> crate::Result<u64> {
+    if s.is_empty() {
+        return Ok(0);
+    }
+    if let Ok(ts) = s.parse::<u64>() {
+        return Ok(ts);
     }
+    let err = || crate::Error::conversion::<Ts>(s);
+    let ts = time::PrimitiveDateTime::parse(s, TS_FORMAT)
+        .map_err(|_| err())?
+        .assume_utc()
+        .unix_timestamp_nanos();
+    u64::try_from(ts).map_err(|_| err())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::UNDEF_PRICE;
-
     use super::*;
 
+    #[test]
+    fn test_fmt_into_appends() {
+        let mut buffer = String::new();
+        fmt_px_into(&mut buffer, 32_500_000_000);
+        buffer.push(',');
+        fmt_ts_into(&mut buffer, 0);
+        buffer.push(',');
+        fmt_ts_into(&mut buffer, 1_622_838_300_000_000_000);
+        assert_eq!(buffer, "32.500000000,,2021-06-04T20:25:00.000000000Z");
+    }
+
     #[test]
     fn test_fmt_px_negative() {
         assert_eq!(fmt_px(-100_000), "-0.000100000");
@@ -102,6 +268,11 @@ mod tests {
         assert_eq!(fmt_px(0), "0.000000000");
     }
 
+    #[test]
+    fn test_fmt_px_min() {
+        assert_eq!(fmt_px(i64::MIN), "-9223372036.854775808");
+    }
+
     #[test]
     fn test_fmt_px_undef() {
         assert_eq!(fmt_px(UNDEF_PRICE), "UNDEF_PRICE");
@@ -124,4 +295,41 @@ mod tests {
             "2021-06-04T20:25:00.000000000Z"
         );
     }
+
+    #[test]
+    fn test_parse_px() {
+        for px in [-100_000, 32_500_000_000, 0, UNDEF_PRICE, i64::MIN] {
+            assert_eq!(parse_px(&fmt_px(px)).unwrap(), px);
+        }
+        assert_eq!(parse_px("1.5").unwrap(), 1_500_000_000);
+        assert_eq!(parse_px("-.25").unwrap(), -250_000_000);
+        assert_eq!(parse_px("12").unwrap(), 12_000_000_000);
+        assert_eq!(fmt_px(i64::MIN), "-9223372036.854775808");
+        assert!(parse_px("-9223372036.854775809").is_err());
+    }
+
+    #[test]
+    fn test_parse_px_invalid() {
+        for s in [
+            "",
+            "-",
+            ".",
+            "1.0000000001",
+            "1e5",
+            "+1",
+            "1.2.3",
+            "9223372037",
+        ] {
+            assert!(parse_px(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_parse_ts() {
+        for ts in [0, 1, 1622838300000000000] {
+            assert_eq!(parse_ts(&fmt_ts(ts)).unwrap(), ts);
+        }
+        assert!(parse_ts("2021-06-04").is_err());
+        assert!(parse_ts("1969-12-31T23:59:59.000000000Z").is_err());
+    }
 }
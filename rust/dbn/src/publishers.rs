@@ -1027,3 +1027,33 @@ mod deserialize {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_publisher_round_trip() {
+        let publishers: Vec<_> = (0..=u16::MAX)
+            .filter_map(|id| Publisher::try_from(id).ok())
+            .collect();
+        assert_eq!(publishers[0], Publisher::GlbxMdp3Glbx);
+        for publisher in publishers {
+            assert_eq!(Publisher::from_str(publisher.as_str()).unwrap(), publisher);
+            assert_eq!(
+                Publisher::from_dataset_venue(publisher.dataset(), publisher.venue()).unwrap(),
+                publisher
+            );
+            let dataset = publisher.dataset();
+            assert_eq!(Dataset::from_str(&dataset.to_string()).unwrap(), dataset);
+            let venue = publisher.venue();
+            assert_eq!(Venue::from_str(&venue.to_string()).unwrap(), venue);
+            assert_eq!(
+                publisher.as_str(),
+                format!("{}.{}", dataset.as_str(), venue.as_str())
+            );
+        }
+    }
+}
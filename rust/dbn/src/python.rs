@@ -5,9 +5,10 @@
 use std::fmt;
 
 use pyo3::{
-    exceptions::PyValueError,
+    exceptions::{PyTypeError, PyValueError},
+    intern,
     prelude::*,
-    types::{PyDate, PyDateAccess},
+    types::{timezone_utc, PyDate, PyDateAccess, PyDateTime, PyTimeAccess},
 };
 use strum::IntoEnumIterator;
 
@@ -15,12 +16,27 @@ mod enums;
 mod metadata;
 mod record;
 
+pub use record::from_bytes;
+
+pyo3::create_exception!(
+    databento_dbn,
+    DBNError,
+    PyValueError,
+    "An error from decoding malformed DBN data."
+);
+
 /// A helper function for converting any type that implements `Debug` to a Python
 /// `ValueError`.
 pub fn to_val_err(e: impl fmt::Debug) -> PyErr {
     PyValueError::new_err(format!("{e:?}"))
 }
 
+/// A helper function for converting any type that implements `Display` to a Python
+/// `DBNError`.
+pub fn to_dbn_err(e: impl fmt::Display) -> PyErr {
+    DBNError::new_err(e.to_string())
+}
+
 /// Python iterator over the variants of an enum.
 #[pyclass(module = "databento_dbn")]
 pub struct EnumIterator {
@@ -68,6 +84,51 @@ pub fn py_to_time_date(py_date: &PyDate) -> PyResult<time::Date> {
         .map_err(|e| to_val_err(e.to_string()))
 }
 
+/// Tries to convert `obj`, either an `int` or a `datetime`, to a UNIX nanosecond
+/// timestamp. Naive datetimes are assumed to be in UTC. The `nanosecond` attribute of
+/// `pandas.Timestamp` is respected.
+///
+/// # Errors
+/// This function returns an error if `obj` is of another type or is before the UNIX
+/// epoch.
+pub fn py_to_unix_nanos(obj: &PyAny) -> PyResult<u64> {
+    let py = obj.py();
+    if let Ok(ts) = obj.extract::<u64>() {
+        return Ok(ts);
+    }
+    let Ok(datetime) = obj.downcast::<PyDateTime>() else {
+        return Err(PyTypeError::new_err(format!(
+            "expected an int or datetime, got {}",
+            obj.get_type().name()?
+        )));
+    };
+    let datetime: &PyDateTime = if datetime.getattr(intern!(py, "tzinfo"))?.is_none() {
+        datetime
+    } else {
+        datetime
+            .call_method1(intern!(py, "astimezone"), (timezone_utc(py),))?
+            .downcast()?
+    };
+    let date = py_to_time_date(datetime.downcast()?)?;
+    let time = time::Time::from_hms_micro(
+        datetime.get_hour(),
+        datetime.get_minute(),
+        datetime.get_second(),
+        datetime.get_microsecond(),
+    )
+    .map_err(|e| to_val_err(e.to_string()))?;
+    let nanosecond = if datetime.hasattr(intern!(py, "nanosecond"))? {
+        datetime
+            .getattr(intern!(py, "nanosecond"))?
+            .extract::<u16>()?
+    } else {
+        0
+    };
+    let ts = date.with_time(time).assume_utc().unix_timestamp_nanos() + nanosecond as i128;
+    u64::try_from(ts)
+        .map_err(|_| PyValueError::new_err(format!("{datetime} is before the UNIX epoch")))
+}
+
 /// A trait for records that provide descriptions of their fields.
 pub(crate) trait PyFieldDesc {
     /// Returns a list of all fields and their numpy dtypes.
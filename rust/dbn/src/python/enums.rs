@@ -1,9 +1,13 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use pyo3::{prelude::*, pyclass::CompareOp, type_object::PyTypeInfo, types::PyType};
+use strum::IntoEnumIterator;
 
 use crate::{
-    enums::{Compression, Encoding, SType, Schema, SecurityUpdateAction, UserDefinedInstrument},
+    enums::{
+        Action, Compression, Encoding, SType, Schema, SecurityUpdateAction, Side,
+        UserDefinedInstrument,
+    },
     RType,
 };
 
@@ -329,6 +333,137 @@ impl RType {
     }
 }
 
+#[pymethods]
+impl Side {
+    #[new]
+    fn py_new(py: Python<'_>, value: &PyAny) -> PyResult<Self> {
+        let t = Self::type_object(py);
+        Self::py_from_str(t, value)
+    }
+
+    fn __hash__(&self) -> isize {
+        *self as isize
+    }
+
+    fn __str__(&self) -> String {
+        self.value().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Side.{}: '{}'>", self.name(), self.value(),)
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
+        let Ok(other_enum) = Self::py_from_str(Self::type_object(py), other) else {
+            return py.NotImplemented();
+        };
+        match op {
+            CompareOp::Eq => self.eq(&other_enum).into_py(py),
+            CompareOp::Ne => self.ne(&other_enum).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        format!("{self:?}").to_uppercase()
+    }
+
+    #[getter]
+    fn value(&self) -> char {
+        char::from(*self)
+    }
+
+    #[classmethod]
+    fn variants(_: &PyType, py: Python<'_>) -> EnumIterator {
+        EnumIterator::new::<Self>(py)
+    }
+
+    /// Parses either the single-character value, e.g. 'B', or the variant name,
+    /// e.g. "BID".
+    #[classmethod]
+    #[pyo3(name = "from_str")]
+    fn py_from_str(_: &PyType, value: &PyAny) -> PyResult<Self> {
+        let value_str: &str = value.str().and_then(|s| s.extract())?;
+        char_enum_from_str(value_str)
+    }
+}
+
+#[pymethods]
+impl Action {
+    #[new]
+    fn py_new(py: Python<'_>, value: &PyAny) -> PyResult<Self> {
+        let t = Self::type_object(py);
+        Self::py_from_str(t, value)
+    }
+
+    fn __hash__(&self) -> isize {
+        *self as isize
+    }
+
+    fn __str__(&self) -> String {
+        self.value().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Action.{}: '{}'>", self.name(), self.value(),)
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
+        let Ok(other_enum) = Self::py_from_str(Self::type_object(py), other) else {
+            return py.NotImplemented();
+        };
+        match op {
+            CompareOp::Eq => self.eq(&other_enum).into_py(py),
+            CompareOp::Ne => self.ne(&other_enum).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        format!("{self:?}").to_uppercase()
+    }
+
+    #[getter]
+    fn value(&self) -> char {
+        char::from(*self)
+    }
+
+    #[classmethod]
+    fn variants(_: &PyType, py: Python<'_>) -> EnumIterator {
+        EnumIterator::new::<Self>(py)
+    }
+
+    /// Parses either the single-character value, e.g. 'A', or the variant name,
+    /// e.g. "ADD".
+    #[classmethod]
+    #[pyo3(name = "from_str")]
+    fn py_from_str(_: &PyType, value: &PyAny) -> PyResult<Self> {
+        let value_str: &str = value.str().and_then(|s| s.extract())?;
+        char_enum_from_str(value_str)
+    }
+}
+
+/// Matches `value` against the character values and variant names of a
+/// character-backed enum.
+fn char_enum_from_str<E>(value: &str) -> PyResult<E>
+where
+    E: IntoEnumIterator + fmt::Debug + Copy + Into<char>,
+{
+    let mut chars = value.chars();
+    let single_char = match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    };
+    E::iter()
+        .find(|variant| {
+            let name = format!("{variant:?}");
+            single_char == Some((*variant).into()) || name.eq_ignore_ascii_case(value)
+        })
+        .ok_or_else(|| to_val_err(crate::Error::conversion::<E>(value)))
+}
+
 impl PyFieldDesc for SecurityUpdateAction {
     fn field_dtypes(field_name: &str) -> Vec<(String, String)> {
         vec![(field_name.to_owned(), "S1".to_owned())]
@@ -1,6 +1,12 @@
-use std::{collections::HashMap, io, num::NonZeroU64};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+    num::NonZeroU64,
+    str::FromStr,
+};
 
 use pyo3::{
+    exceptions::PyValueError,
     intern,
     prelude::*,
     pyclass::CompareOp,
@@ -14,41 +20,70 @@ use crate::{
     MappingInterval, Metadata, SymbolMapping, VersionUpgradePolicy,
 };
 
-use super::{py_to_time_date, to_val_err};
+use super::{py_to_time_date, py_to_unix_nanos, to_dbn_err, to_val_err};
 
 #[pymethods]
 impl Metadata {
     #[new]
+    #[pyo3(signature = (
+        dataset,
+        start,
+        stype_out,
+        symbols = Vec::new(),
+        partial = Vec::new(),
+        not_found = Vec::new(),
+        mappings = Vec::new(),
+        schema = None,
+        stype_in = None,
+        end = None,
+        limit = None,
+        ts_out = None,
+        version = None,
+        custom = BTreeMap::new(),
+    ))]
     fn py_new(
         dataset: String,
-        start: u64,
-        stype_out: SType,
+        start: &PyAny,
+        stype_out: &PyAny,
         symbols: Vec<String>,
         partial: Vec<String>,
         not_found: Vec<String>,
         mappings: Vec<SymbolMapping>,
-        schema: Option<Schema>,
-        stype_in: Option<SType>,
-        end: Option<u64>,
+        schema: Option<&PyAny>,
+        stype_in: Option<&PyAny>,
+        end: Option<&PyAny>,
         limit: Option<u64>,
         ts_out: Option<bool>,
         version: Option<u8>,
-    ) -> Metadata {
-        Metadata::builder()
+        custom: BTreeMap<String, String>,
+    ) -> PyResult<Metadata> {
+        Ok(Metadata::builder()
             .dataset(dataset)
-            .start(start)
-            .stype_out(stype_out)
+            .start(extract_param("start", start, py_to_unix_nanos)?)
+            .stype_out(extract_param("stype_out", stype_out, py_to_enum)?)
             .symbols(symbols)
             .partial(partial)
             .not_found(not_found)
             .mappings(mappings)
-            .schema(schema)
-            .stype_in(stype_in)
-            .end(NonZeroU64::new(end.unwrap_or_default()))
+            .schema(
+                schema
+                    .map(|schema| extract_param("schema", schema, py_to_enum))
+                    .transpose()?,
+            )
+            .stype_in(
+                stype_in
+                    .map(|stype_in| extract_param("stype_in", stype_in, py_to_enum))
+                    .transpose()?,
+            )
+            .end(match end {
+                Some(end) => NonZeroU64::new(extract_param("end", end, py_to_unix_nanos)?),
+                None => None,
+            })
             .limit(NonZeroU64::new(limit.unwrap_or_default()))
             .ts_out(ts_out.unwrap_or_default())
             .version(version.unwrap_or(crate::DBN_VERSION))
-            .build()
+            .custom(custom)
+            .build())
     }
 
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
@@ -68,6 +103,12 @@ impl Metadata {
         self.py_encode(py)
     }
 
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        let py = slf.py();
+        let decode = slf.get_type().getattr(intern!(py, "decode"))?;
+        Ok((decode, (slf.try_borrow()?.py_encode(py)?,)).into_py(py))
+    }
+
     #[getter]
     fn get_mappings(&self) -> HashMap<String, Vec<MappingInterval>> {
         let mut res = HashMap::new();
@@ -77,6 +118,26 @@ impl Metadata {
         res
     }
 
+    #[setter]
+    fn set_start(&mut self, start: &PyAny) -> PyResult<()> {
+        self.start = extract_param("start", start, py_to_unix_nanos)?;
+        Ok(())
+    }
+
+    #[setter]
+    fn set_end(&mut self, end: Option<&PyAny>) -> PyResult<()> {
+        self.end = match end {
+            Some(end) => NonZeroU64::new(extract_param("end", end, py_to_unix_nanos)?),
+            None => None,
+        };
+        Ok(())
+    }
+
+    #[setter]
+    fn set_limit(&mut self, limit: Option<u64>) {
+        self.limit = NonZeroU64::new(limit.unwrap_or_default());
+    }
+
     #[pyo3(name = "decode")]
     #[classmethod]
     fn py_decode(
@@ -87,7 +148,7 @@ impl Metadata {
         let upgrade_policy = upgrade_policy.unwrap_or_default();
         let reader = io::BufReader::new(data.as_bytes());
         let mut metadata = DynDecoder::inferred_with_buffer(reader, upgrade_policy)
-            .map_err(to_val_err)?
+            .map_err(to_dbn_err)?
             .metadata()
             .clone();
         metadata.upgrade(upgrade_policy);
@@ -103,6 +164,28 @@ impl Metadata {
     }
 }
 
+/// Converts `value` with `extract`, adding `param_name` to the message of any error.
+fn extract_param<'py, T>(
+    param_name: &str,
+    value: &'py PyAny,
+    extract: impl FnOnce(&'py PyAny) -> PyResult<T>,
+) -> PyResult<T> {
+    extract(value).map_err(|e| {
+        let py = value.py();
+        PyErr::from_type(
+            e.get_type(py),
+            format!("invalid `{param_name}`: {}", e.value(py)),
+        )
+    })
+}
+
+/// Converts `value`, either an enum variant or its string value, to an enum such as
+/// [`Schema`] or [`SType`].
+fn py_to_enum<E: FromStr<Err = crate::Error>>(value: &PyAny) -> PyResult<E> {
+    let value_str: &str = value.str().and_then(|s| s.extract())?;
+    E::from_str(&value_str.to_lowercase()).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
 impl IntoPy<PyObject> for SymbolMapping {
     fn into_py(self, py: Python<'_>) -> PyObject {
         self.to_object(py)
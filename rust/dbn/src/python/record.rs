@@ -1,24 +1,46 @@
-use std::{ffi::c_char, mem};
+use std::{
+    collections::hash_map::DefaultHasher,
+    ffi::c_char,
+    hash::{Hash, Hasher},
+    mem,
+};
 
 use pyo3::{
     intern,
     prelude::*,
     pyclass::CompareOp,
-    types::{timezone_utc, PyDateTime, PyDict},
+    types::{timezone_utc, PyBytes, PyDateTime, PyDict, PyIterator, PyType},
+    PyClass,
 };
 
 use crate::{
     compat::{ErrorMsgV1, InstrumentDefMsgV1, SymbolMappingMsgV1, SystemMsgV1},
-    record::str_to_c_chars,
-    rtype, BidAskPair, ErrorMsg, HasRType, ImbalanceMsg, InstrumentDefMsg, MboMsg, Mbp10Msg,
-    Mbp1Msg, OhlcvMsg, Record, RecordHeader, SType, SecurityUpdateAction, StatMsg,
-    StatUpdateAction, StatusAction, StatusMsg, StatusReason, SymbolMappingMsg, SystemMsg, TradeMsg,
-    TradingEvent, TriState, UserDefinedInstrument, WithTsOut, FIXED_PRICE_SCALE, UNDEF_ORDER_SIZE,
-    UNDEF_PRICE, UNDEF_TIMESTAMP,
+    record::{px_to_f64, str_to_c_chars},
+    rtype, Action, BboMsg, BidAskPair, CbboMsg, ConsolidatedBidAskPair, ErrorMsg, HasRType,
+    ImbalanceMsg, InstrumentDefMsg, MatchAlgorithm, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, RType,
+    Record, RecordHeader, SType, SecurityUpdateAction, Side, StatMsg, StatUpdateAction,
+    StatusAction, StatusMsg, StatusReason, SymbolMappingMsg, SystemMsg, TradeMsg, TradingEvent,
+    TriState, UserDefinedInstrument, WithTsOut, UNDEF_ORDER_SIZE, UNDEF_PRICE, UNDEF_STAT_QUANTITY,
+    UNDEF_TIMESTAMP,
 };
 
 use super::{to_val_err, PyFieldDesc};
 
+#[pymethods]
+impl RecordHeader {
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    /// The name of the record type, e.g. `"mbo"`.
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        RType::try_from(self.rtype)
+            .map(|rtype| rtype.as_str())
+            .map_err(to_val_err)
+    }
+}
+
 #[pymethods]
 impl MboMsg {
     #[new]
@@ -26,29 +48,29 @@ impl MboMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        order_id: u64,
-        price: i64,
-        size: u32,
-        channel_id: u8,
-        action: c_char,
-        side: c_char,
-        ts_recv: u64,
-        ts_in_delta: i32,
-        sequence: u32,
+        order_id: Option<u64>,
+        price: Option<i64>,
+        size: Option<u32>,
+        channel_id: Option<u8>,
+        action: Option<c_char>,
+        side: Option<c_char>,
+        ts_recv: Option<u64>,
+        ts_in_delta: Option<i32>,
+        sequence: Option<u32>,
         flags: Option<u8>,
     ) -> Self {
         Self {
             hd: RecordHeader::new::<Self>(rtype::MBO, publisher_id, instrument_id, ts_event),
-            order_id,
-            price,
-            size,
+            order_id: order_id.unwrap_or_default(),
+            price: price.unwrap_or(UNDEF_PRICE),
+            size: size.unwrap_or(UNDEF_ORDER_SIZE),
             flags: flags.unwrap_or_default(),
-            channel_id,
-            action,
-            side,
-            ts_recv,
-            ts_in_delta,
-            sequence,
+            channel_id: channel_id.unwrap_or_default(),
+            action: action.unwrap_or_default(),
+            side: side.unwrap_or_default(),
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            ts_in_delta: ts_in_delta.unwrap_or_default(),
+            sequence: sequence.unwrap_or_default(),
         }
     }
 
@@ -56,6 +78,10 @@ impl MboMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -64,15 +90,50 @@ impl MboMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -84,20 +145,21 @@ impl MboMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_price")]
     fn py_pretty_price(&self) -> f64 {
-        self.price as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -118,14 +180,14 @@ impl MboMsg {
 
     #[getter]
     #[pyo3(name = "action")]
-    fn py_action(&self) -> char {
-        self.action as u8 as char
+    fn py_action(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Action>(py, self.action)
     }
 
     #[getter]
     #[pyo3(name = "side")]
-    fn py_side(&self) -> char {
-        self.side as u8 as char
+    fn py_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.side)
     }
 
     #[classattr]
@@ -180,22 +242,27 @@ impl BidAskPair {
         }
     }
 
+    fn __getnewargs__(&self) -> (i64, i64, u32, u32, u32, u32) {
+        (
+            self.bid_px,
+            self.ask_px,
+            self.bid_sz,
+            self.ask_sz,
+            self.bid_ct,
+            self.ask_ct,
+        )
+    }
+
     #[getter]
     #[pyo3(name = "pretty_ask_px")]
     fn py_pretty_ask_px(&self) -> f64 {
-        match self.ask_px {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.ask_px as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.ask_px)
     }
 
     #[getter]
     #[pyo3(name = "pretty_bid_px")]
     fn py_pretty_bid_px(&self) -> f64 {
-        match self.bid_px {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.bid_px as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.bid_px)
     }
 
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
@@ -206,6 +273,10 @@ impl BidAskPair {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
@@ -218,27 +289,27 @@ impl TradeMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        price: i64,
-        size: u32,
-        action: c_char,
-        side: c_char,
-        depth: u8,
-        ts_recv: u64,
-        ts_in_delta: i32,
-        sequence: u32,
+        price: Option<i64>,
+        size: Option<u32>,
+        action: Option<c_char>,
+        side: Option<c_char>,
+        depth: Option<u8>,
+        ts_recv: Option<u64>,
+        ts_in_delta: Option<i32>,
+        sequence: Option<u32>,
         flags: Option<u8>,
     ) -> Self {
         Self {
             hd: RecordHeader::new::<Self>(rtype::MBP_0, publisher_id, instrument_id, ts_event),
-            price,
-            size,
-            action,
-            side,
+            price: price.unwrap_or(UNDEF_PRICE),
+            size: size.unwrap_or(UNDEF_ORDER_SIZE),
+            action: action.unwrap_or_default(),
+            side: side.unwrap_or_default(),
             flags: flags.unwrap_or_default(),
-            depth,
-            ts_recv,
-            ts_in_delta,
-            sequence,
+            depth: depth.unwrap_or_default(),
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            ts_in_delta: ts_in_delta.unwrap_or_default(),
+            sequence: sequence.unwrap_or_default(),
         }
     }
 
@@ -246,6 +317,10 @@ impl TradeMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -254,15 +329,50 @@ impl TradeMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -274,20 +384,21 @@ impl TradeMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_price")]
     fn py_pretty_price(&self) -> f64 {
-        self.price as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -308,14 +419,14 @@ impl TradeMsg {
 
     #[getter]
     #[pyo3(name = "action")]
-    fn py_action(&self) -> char {
-        self.action as u8 as char
+    fn py_action(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Action>(py, self.action)
     }
 
     #[getter]
     #[pyo3(name = "side")]
-    fn py_side(&self) -> char {
-        self.side as u8 as char
+    fn py_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.side)
     }
 
     #[classattr]
@@ -356,28 +467,28 @@ impl Mbp1Msg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        price: i64,
-        size: u32,
-        action: c_char,
-        side: c_char,
-        flags: u8,
-        depth: u8,
-        ts_recv: u64,
-        ts_in_delta: i32,
-        sequence: u32,
+        price: Option<i64>,
+        size: Option<u32>,
+        action: Option<c_char>,
+        side: Option<c_char>,
+        flags: Option<u8>,
+        depth: Option<u8>,
+        ts_recv: Option<u64>,
+        ts_in_delta: Option<i32>,
+        sequence: Option<u32>,
         levels: Option<BidAskPair>,
     ) -> Self {
         Self {
             hd: RecordHeader::new::<Self>(rtype::MBP_1, publisher_id, instrument_id, ts_event),
-            price,
-            size,
-            action,
-            side,
-            flags,
-            depth,
-            ts_recv,
-            ts_in_delta,
-            sequence,
+            price: price.unwrap_or(UNDEF_PRICE),
+            size: size.unwrap_or(UNDEF_ORDER_SIZE),
+            action: action.unwrap_or_default(),
+            side: side.unwrap_or_default(),
+            flags: flags.unwrap_or_default(),
+            depth: depth.unwrap_or_default(),
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            ts_in_delta: ts_in_delta.unwrap_or_default(),
+            sequence: sequence.unwrap_or_default(),
             levels: [levels.unwrap_or_default()],
         }
     }
@@ -386,6 +497,10 @@ impl Mbp1Msg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -394,15 +509,50 @@ impl Mbp1Msg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -414,20 +564,21 @@ impl Mbp1Msg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_price")]
     fn py_pretty_price(&self) -> f64 {
-        self.price as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -448,14 +599,421 @@ impl Mbp1Msg {
 
     #[getter]
     #[pyo3(name = "action")]
-    fn py_action(&self) -> char {
-        self.action as u8 as char
+    fn py_action(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Action>(py, self.action)
+    }
+
+    #[getter]
+    #[pyo3(name = "side")]
+    fn py_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.side)
+    }
+
+    #[classattr]
+    #[pyo3(name = "_dtypes")]
+    fn py_dtypes() -> Vec<(String, String)> {
+        Self::field_dtypes("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_price_fields")]
+    fn py_price_fields() -> Vec<String> {
+        Self::price_fields("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_timestamp_fields")]
+    fn py_timestamp_fields() -> Vec<String> {
+        Self::timestamp_fields("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_hidden_fields")]
+    fn py_hidden_fields() -> Vec<String> {
+        Self::hidden_fields("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_ordered_fields")]
+    fn py_ordered_fields() -> Vec<String> {
+        Self::ordered_fields("")
+    }
+}
+
+#[pymethods]
+impl BboMsg {
+    #[new]
+    fn py_new(
+        rtype: u8,
+        publisher_id: u16,
+        instrument_id: u32,
+        ts_event: u64,
+        price: Option<i64>,
+        size: Option<u32>,
+        side: Option<c_char>,
+        flags: Option<u8>,
+        ts_recv: Option<u64>,
+        sequence: Option<u32>,
+        levels: Option<BidAskPair>,
+    ) -> Self {
+        Self {
+            hd: RecordHeader::new::<Self>(rtype, publisher_id, instrument_id, ts_event),
+            price: price.unwrap_or(UNDEF_PRICE),
+            size: size.unwrap_or(UNDEF_ORDER_SIZE),
+            _reserved1: Default::default(),
+            side: side.unwrap_or(Side::None as c_char),
+            flags: flags.unwrap_or_default(),
+            _reserved2: Default::default(),
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            _reserved3: Default::default(),
+            sequence: sequence.unwrap_or_default(),
+            levels: [levels.unwrap_or_default()],
+        }
+    }
+
+    fn __bytes__(&self) -> &[u8] {
+        self.as_ref()
+    }
+
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
+        match op {
+            CompareOp::Eq => self.eq(other).into_py(py),
+            CompareOp::Ne => self.ne(other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
+    #[getter]
+    fn rtype(&self) -> u8 {
+        self.hd.rtype
+    }
+
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
+    #[getter]
+    fn publisher_id(&self) -> u16 {
+        self.hd.publisher_id
+    }
+
+    #[getter]
+    fn instrument_id(&self) -> u32 {
+        self.hd.instrument_id
+    }
+
+    #[getter]
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
+        self.hd.ts_event
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_price")]
+    fn py_pretty_price(&self) -> f64 {
+        px_to_f64(self.price)
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_ts_event")]
+    fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_ts_recv")]
+    fn py_pretty_ts_recv(&self, py: Python<'_>) -> PyResult<PyObject> {
+        get_utc_nanosecond_timestamp(py, self.ts_recv)
+    }
+
+    #[pyo3(name = "record_size")]
+    fn py_record_size(&self) -> usize {
+        self.record_size()
+    }
+
+    #[classattr]
+    fn size_hint() -> PyResult<usize> {
+        Ok(mem::size_of::<BboMsg>())
     }
 
     #[getter]
     #[pyo3(name = "side")]
-    fn py_side(&self) -> char {
-        self.side as u8 as char
+    fn py_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.side)
+    }
+
+    #[classattr]
+    #[pyo3(name = "_dtypes")]
+    fn py_dtypes() -> Vec<(String, String)> {
+        Self::field_dtypes("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_price_fields")]
+    fn py_price_fields() -> Vec<String> {
+        Self::price_fields("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_timestamp_fields")]
+    fn py_timestamp_fields() -> Vec<String> {
+        Self::timestamp_fields("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_hidden_fields")]
+    fn py_hidden_fields() -> Vec<String> {
+        Self::hidden_fields("")
+    }
+
+    #[classattr]
+    #[pyo3(name = "_ordered_fields")]
+    fn py_ordered_fields() -> Vec<String> {
+        Self::ordered_fields("")
+    }
+}
+
+#[pymethods]
+impl ConsolidatedBidAskPair {
+    #[new]
+    fn py_new(
+        bid_px: Option<i64>,
+        ask_px: Option<i64>,
+        bid_sz: Option<u32>,
+        ask_sz: Option<u32>,
+        bid_pb: Option<u16>,
+        ask_pb: Option<u16>,
+    ) -> Self {
+        Self {
+            bid_px: bid_px.unwrap_or(UNDEF_PRICE),
+            ask_px: ask_px.unwrap_or(UNDEF_PRICE),
+            bid_sz: bid_sz.unwrap_or_default(),
+            ask_sz: ask_sz.unwrap_or_default(),
+            bid_pb: bid_pb.unwrap_or_default(),
+            _reserved1: Default::default(),
+            ask_pb: ask_pb.unwrap_or_default(),
+            _reserved2: Default::default(),
+        }
+    }
+
+    fn __getnewargs__(&self) -> (i64, i64, u32, u32, u16, u16) {
+        (
+            self.bid_px,
+            self.ask_px,
+            self.bid_sz,
+            self.ask_sz,
+            self.bid_pb,
+            self.ask_pb,
+        )
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_ask_px")]
+    fn py_pretty_ask_px(&self) -> f64 {
+        px_to_f64(self.ask_px)
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_bid_px")]
+    fn py_pretty_bid_px(&self) -> f64 {
+        px_to_f64(self.bid_px)
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
+        match op {
+            CompareOp::Eq => self.eq(other).into_py(py),
+            CompareOp::Ne => self.ne(other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+#[pymethods]
+impl CbboMsg {
+    #[new]
+    fn py_new(
+        rtype: u8,
+        publisher_id: u16,
+        instrument_id: u32,
+        ts_event: u64,
+        price: Option<i64>,
+        size: Option<u32>,
+        side: Option<c_char>,
+        flags: Option<u8>,
+        ts_recv: Option<u64>,
+        sequence: Option<u32>,
+        levels: Option<ConsolidatedBidAskPair>,
+    ) -> Self {
+        Self {
+            hd: RecordHeader::new::<Self>(rtype, publisher_id, instrument_id, ts_event),
+            price: price.unwrap_or(UNDEF_PRICE),
+            size: size.unwrap_or(UNDEF_ORDER_SIZE),
+            _reserved1: Default::default(),
+            side: side.unwrap_or(Side::None as c_char),
+            flags: flags.unwrap_or_default(),
+            _reserved2: Default::default(),
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            _reserved3: Default::default(),
+            sequence: sequence.unwrap_or_default(),
+            levels: [levels.unwrap_or_default()],
+        }
+    }
+
+    fn __bytes__(&self) -> &[u8] {
+        self.as_ref()
+    }
+
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
+        match op {
+            CompareOp::Eq => self.eq(other).into_py(py),
+            CompareOp::Ne => self.ne(other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{self:?}")
+    }
+
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
+    #[getter]
+    fn rtype(&self) -> u8 {
+        self.hd.rtype
+    }
+
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
+    #[getter]
+    fn publisher_id(&self) -> u16 {
+        self.hd.publisher_id
+    }
+
+    #[getter]
+    fn instrument_id(&self) -> u32 {
+        self.hd.instrument_id
+    }
+
+    #[getter]
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
+        self.hd.ts_event
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_price")]
+    fn py_pretty_price(&self) -> f64 {
+        px_to_f64(self.price)
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_ts_event")]
+    fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_ts_recv")]
+    fn py_pretty_ts_recv(&self, py: Python<'_>) -> PyResult<PyObject> {
+        get_utc_nanosecond_timestamp(py, self.ts_recv)
+    }
+
+    #[pyo3(name = "record_size")]
+    fn py_record_size(&self) -> usize {
+        self.record_size()
+    }
+
+    #[classattr]
+    fn size_hint() -> PyResult<usize> {
+        Ok(mem::size_of::<CbboMsg>())
+    }
+
+    #[getter]
+    #[pyo3(name = "side")]
+    fn py_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.side)
     }
 
     #[classattr]
@@ -496,15 +1054,15 @@ impl Mbp10Msg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        price: i64,
-        size: u32,
-        action: c_char,
-        side: c_char,
-        flags: u8,
-        depth: u8,
-        ts_recv: u64,
-        ts_in_delta: i32,
-        sequence: u32,
+        price: Option<i64>,
+        size: Option<u32>,
+        action: Option<c_char>,
+        side: Option<c_char>,
+        flags: Option<u8>,
+        depth: Option<u8>,
+        ts_recv: Option<u64>,
+        ts_in_delta: Option<i32>,
+        sequence: Option<u32>,
         levels: Option<Vec<BidAskPair>>,
     ) -> PyResult<Self> {
         let levels = if let Some(level) = levels {
@@ -521,15 +1079,15 @@ impl Mbp10Msg {
         };
         Ok(Self {
             hd: RecordHeader::new::<Self>(rtype::MBP_10, publisher_id, instrument_id, ts_event),
-            price,
-            size,
-            action,
-            side,
-            flags,
-            depth,
-            ts_recv,
-            ts_in_delta,
-            sequence,
+            price: price.unwrap_or(UNDEF_PRICE),
+            size: size.unwrap_or(UNDEF_ORDER_SIZE),
+            action: action.unwrap_or_default(),
+            side: side.unwrap_or_default(),
+            flags: flags.unwrap_or_default(),
+            depth: depth.unwrap_or_default(),
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            ts_in_delta: ts_in_delta.unwrap_or_default(),
+            sequence: sequence.unwrap_or_default(),
             levels,
         })
     }
@@ -538,6 +1096,10 @@ impl Mbp10Msg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -546,15 +1108,50 @@ impl Mbp10Msg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -566,20 +1163,21 @@ impl Mbp10Msg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_price")]
     fn py_pretty_price(&self) -> f64 {
-        self.price as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -600,14 +1198,14 @@ impl Mbp10Msg {
 
     #[getter]
     #[pyo3(name = "action")]
-    fn py_action(&self) -> char {
-        self.action as u8 as char
+    fn py_action(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Action>(py, self.action)
     }
 
     #[getter]
     #[pyo3(name = "side")]
-    fn py_side(&self) -> char {
-        self.side as u8 as char
+    fn py_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.side)
     }
 
     #[classattr]
@@ -649,19 +1247,19 @@ impl OhlcvMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        open: i64,
-        high: i64,
-        low: i64,
-        close: i64,
-        volume: u64,
+        open: Option<i64>,
+        high: Option<i64>,
+        low: Option<i64>,
+        close: Option<i64>,
+        volume: Option<u64>,
     ) -> Self {
         Self {
             hd: RecordHeader::new::<Self>(rtype, publisher_id, instrument_id, ts_event),
-            open,
-            high,
-            low,
-            close,
-            volume,
+            open: open.unwrap_or(UNDEF_PRICE),
+            high: high.unwrap_or(UNDEF_PRICE),
+            low: low.unwrap_or(UNDEF_PRICE),
+            close: close.unwrap_or(UNDEF_PRICE),
+            volume: volume.unwrap_or_default(),
         }
     }
 
@@ -669,6 +1267,10 @@ impl OhlcvMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -677,15 +1279,50 @@ impl OhlcvMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -697,38 +1334,39 @@ impl OhlcvMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_open")]
     fn py_pretty_open(&self) -> f64 {
-        self.open as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.open)
     }
 
     #[getter]
     #[pyo3(name = "pretty_high")]
     fn py_pretty_high(&self) -> f64 {
-        self.high as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.high)
     }
 
     #[getter]
     #[pyo3(name = "pretty_low")]
     fn py_pretty_low(&self) -> f64 {
-        self.low as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.low)
     }
 
     #[getter]
     #[pyo3(name = "pretty_close")]
     fn py_pretty_close(&self) -> f64 {
-        self.close as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.close)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[pyo3(name = "record_size")]
@@ -779,7 +1417,7 @@ impl StatusMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        ts_recv: u64,
+        ts_recv: Option<u64>,
         action: Option<u16>,
         reason: Option<u16>,
         trading_event: Option<u16>,
@@ -789,7 +1427,7 @@ impl StatusMsg {
     ) -> PyResult<Self> {
         Ok(Self {
             hd: RecordHeader::new::<Self>(rtype::STATUS, publisher_id, instrument_id, ts_event),
-            ts_recv,
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
             action: action.unwrap_or_else(|| StatusAction::default() as u16),
             reason: reason.unwrap_or_else(|| StatusReason::default() as u16),
             trading_event: trading_event.unwrap_or_else(|| TradingEvent::default() as u16),
@@ -804,6 +1442,10 @@ impl StatusMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -812,15 +1454,50 @@ impl StatusMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -832,14 +1509,15 @@ impl StatusMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -896,17 +1574,17 @@ impl InstrumentDefMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        ts_recv: u64,
-        min_price_increment: i64,
-        display_factor: i64,
-        min_lot_size_round_lot: i32,
-        raw_symbol: &str,
-        group: &str,
-        exchange: &str,
-        instrument_class: c_char,
-        match_algorithm: c_char,
-        md_security_trading_status: u8,
-        security_update_action: SecurityUpdateAction,
+        ts_recv: Option<u64>,
+        min_price_increment: Option<i64>,
+        display_factor: Option<i64>,
+        min_lot_size_round_lot: Option<i32>,
+        raw_symbol: Option<&str>,
+        group: Option<&str>,
+        exchange: Option<&str>,
+        instrument_class: Option<c_char>,
+        match_algorithm: Option<c_char>,
+        md_security_trading_status: Option<u8>,
+        security_update_action: Option<SecurityUpdateAction>,
         expiration: Option<u64>,
         activation: Option<u64>,
         high_limit_price: Option<i64>,
@@ -964,9 +1642,9 @@ impl InstrumentDefMsg {
                 instrument_id,
                 ts_event,
             ),
-            ts_recv,
-            min_price_increment,
-            display_factor,
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            min_price_increment: min_price_increment.unwrap_or(UNDEF_PRICE),
+            display_factor: display_factor.unwrap_or(UNDEF_PRICE),
             expiration: expiration.unwrap_or(UNDEF_TIMESTAMP),
             activation: activation.unwrap_or(UNDEF_TIMESTAMP),
             high_limit_price: high_limit_price.unwrap_or(UNDEF_PRICE),
@@ -985,7 +1663,7 @@ impl InstrumentDefMsg {
             max_trade_vol: max_trade_vol.unwrap_or(u32::MAX),
             min_lot_size: min_lot_size.unwrap_or(i32::MAX),
             min_lot_size_block: min_lot_size_block.unwrap_or(i32::MAX),
-            min_lot_size_round_lot,
+            min_lot_size_round_lot: min_lot_size_round_lot.unwrap_or(i32::MAX),
             min_trade_vol: min_trade_vol.unwrap_or(u32::MAX),
             contract_multiplier: contract_multiplier.unwrap_or(i32::MAX),
             decay_quantity: decay_quantity.unwrap_or(i32::MAX),
@@ -999,9 +1677,9 @@ impl InstrumentDefMsg {
             settl_currency: str_to_c_chars(settl_currency.unwrap_or_default())
                 .map_err(to_val_err)?,
             secsubtype: str_to_c_chars(secsubtype.unwrap_or_default()).map_err(to_val_err)?,
-            raw_symbol: str_to_c_chars(raw_symbol).map_err(to_val_err)?,
-            group: str_to_c_chars(group).map_err(to_val_err)?,
-            exchange: str_to_c_chars(exchange).map_err(to_val_err)?,
+            raw_symbol: str_to_c_chars(raw_symbol.unwrap_or_default()).map_err(to_val_err)?,
+            group: str_to_c_chars(group.unwrap_or_default()).map_err(to_val_err)?,
+            exchange: str_to_c_chars(exchange.unwrap_or_default()).map_err(to_val_err)?,
             asset: str_to_c_chars(asset.unwrap_or_default()).map_err(to_val_err)?,
             cfi: str_to_c_chars(cfi.unwrap_or_default()).map_err(to_val_err)?,
             security_type: str_to_c_chars(security_type.unwrap_or_default()).map_err(to_val_err)?,
@@ -1010,16 +1688,17 @@ impl InstrumentDefMsg {
             underlying: str_to_c_chars(underlying.unwrap_or_default()).map_err(to_val_err)?,
             strike_price_currency: str_to_c_chars(strike_price_currency.unwrap_or_default())
                 .map_err(to_val_err)?,
-            instrument_class,
+            instrument_class: instrument_class.unwrap_or_default(),
             strike_price: strike_price.unwrap_or(UNDEF_PRICE),
-            match_algorithm,
-            md_security_trading_status,
+            match_algorithm: match_algorithm.unwrap_or(MatchAlgorithm::Fifo as c_char),
+            md_security_trading_status: md_security_trading_status.unwrap_or(u8::MAX),
             main_fraction: main_fraction.unwrap_or(u8::MAX),
             price_display_format: price_display_format.unwrap_or(u8::MAX),
             settl_price_type: settl_price_type.unwrap_or(u8::MAX),
             sub_fraction: sub_fraction.unwrap_or(u8::MAX),
             underlying_product: underlying_product.unwrap_or(u8::MAX),
-            security_update_action: security_update_action as c_char,
+            security_update_action: security_update_action.unwrap_or(SecurityUpdateAction::Add)
+                as c_char,
             maturity_month: maturity_month.unwrap_or(u8::MAX),
             maturity_day: maturity_day.unwrap_or(u8::MAX),
             maturity_week: maturity_week.unwrap_or(u8::MAX),
@@ -1035,6 +1714,10 @@ impl InstrumentDefMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -1043,15 +1726,50 @@ impl InstrumentDefMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -1063,83 +1781,69 @@ impl InstrumentDefMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_min_price_increment")]
     fn py_pretty_min_price_increment(&self) -> f64 {
-        self.min_price_increment as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.min_price_increment)
     }
 
     #[getter]
     #[pyo3(name = "pretty_high_limit_price")]
     fn py_pretty_high_limit_price(&self) -> f64 {
-        match self.high_limit_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.high_limit_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.high_limit_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_low_limit_price")]
     fn py_pretty_low_limit_price(&self) -> f64 {
-        match self.low_limit_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.low_limit_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.low_limit_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_max_price_variation")]
     fn py_pretty_max_price_variation(&self) -> f64 {
-        match self.max_price_variation {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.max_price_variation as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.max_price_variation)
     }
 
     #[getter]
     #[pyo3(name = "pretty_trading_reference_price")]
     fn py_pretty_trading_reference_price(&self) -> f64 {
-        match self.trading_reference_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.trading_reference_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.trading_reference_price)
+    }
+
+    #[getter]
+    #[pyo3(name = "pretty_unit_of_measure_qty")]
+    fn py_pretty_unit_of_measure_qty(&self) -> f64 {
+        px_to_f64(self.unit_of_measure_qty)
     }
 
     #[getter]
     #[pyo3(name = "pretty_min_price_increment_amount")]
     fn py_pretty_min_price_increment_amount(&self) -> f64 {
-        match self.min_price_increment_amount {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.min_price_increment_amount as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.min_price_increment_amount)
     }
 
     #[getter]
     #[pyo3(name = "pretty_price_ratio")]
     fn py_pretty_price_ratio(&self) -> f64 {
-        match self.price_ratio {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.price_ratio as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.price_ratio)
     }
 
     #[getter]
     #[pyo3(name = "pretty_strike_price")]
     fn py_pretty_strike_price(&self) -> f64 {
-        match self.strike_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.strike_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.strike_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -1304,17 +2008,17 @@ impl InstrumentDefMsgV1 {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        ts_recv: u64,
-        min_price_increment: i64,
-        display_factor: i64,
+        ts_recv: Option<u64>,
+        min_price_increment: Option<i64>,
+        display_factor: Option<i64>,
         min_lot_size_round_lot: i32,
-        raw_symbol: &str,
-        group: &str,
-        exchange: &str,
-        instrument_class: c_char,
-        match_algorithm: c_char,
+        raw_symbol: Option<&str>,
+        group: Option<&str>,
+        exchange: Option<&str>,
+        instrument_class: Option<c_char>,
+        match_algorithm: Option<c_char>,
         md_security_trading_status: u8,
-        security_update_action: SecurityUpdateAction,
+        security_update_action: Option<SecurityUpdateAction>,
         expiration: Option<u64>,
         activation: Option<u64>,
         high_limit_price: Option<i64>,
@@ -1372,9 +2076,9 @@ impl InstrumentDefMsgV1 {
                 instrument_id,
                 ts_event,
             ),
-            ts_recv,
-            min_price_increment,
-            display_factor,
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            min_price_increment: min_price_increment.unwrap_or(UNDEF_PRICE),
+            display_factor: display_factor.unwrap_or(UNDEF_PRICE),
             expiration: expiration.unwrap_or(UNDEF_TIMESTAMP),
             activation: activation.unwrap_or(UNDEF_TIMESTAMP),
             high_limit_price: high_limit_price.unwrap_or(UNDEF_PRICE),
@@ -1407,9 +2111,9 @@ impl InstrumentDefMsgV1 {
             settl_currency: str_to_c_chars(settl_currency.unwrap_or_default())
                 .map_err(to_val_err)?,
             secsubtype: str_to_c_chars(secsubtype.unwrap_or_default()).map_err(to_val_err)?,
-            raw_symbol: str_to_c_chars(raw_symbol).map_err(to_val_err)?,
-            group: str_to_c_chars(group).map_err(to_val_err)?,
-            exchange: str_to_c_chars(exchange).map_err(to_val_err)?,
+            raw_symbol: str_to_c_chars(raw_symbol.unwrap_or_default()).map_err(to_val_err)?,
+            group: str_to_c_chars(group.unwrap_or_default()).map_err(to_val_err)?,
+            exchange: str_to_c_chars(exchange.unwrap_or_default()).map_err(to_val_err)?,
             asset: str_to_c_chars(asset.unwrap_or_default()).map_err(to_val_err)?,
             cfi: str_to_c_chars(cfi.unwrap_or_default()).map_err(to_val_err)?,
             security_type: str_to_c_chars(security_type.unwrap_or_default()).map_err(to_val_err)?,
@@ -1418,16 +2122,16 @@ impl InstrumentDefMsgV1 {
             underlying: str_to_c_chars(underlying.unwrap_or_default()).map_err(to_val_err)?,
             strike_price_currency: str_to_c_chars(strike_price_currency.unwrap_or_default())
                 .map_err(to_val_err)?,
-            instrument_class,
+            instrument_class: instrument_class.unwrap_or_default(),
             strike_price: strike_price.unwrap_or(UNDEF_PRICE),
-            match_algorithm,
+            match_algorithm: match_algorithm.unwrap_or(MatchAlgorithm::Fifo as c_char),
             md_security_trading_status,
             main_fraction: main_fraction.unwrap_or(u8::MAX),
             price_display_format: price_display_format.unwrap_or(u8::MAX),
             settl_price_type: settl_price_type.unwrap_or(u8::MAX),
             sub_fraction: sub_fraction.unwrap_or(u8::MAX),
             underlying_product: underlying_product.unwrap_or(u8::MAX),
-            security_update_action,
+            security_update_action: security_update_action.unwrap_or(SecurityUpdateAction::Add),
             maturity_month: maturity_month.unwrap_or(u8::MAX),
             maturity_day: maturity_day.unwrap_or(u8::MAX),
             maturity_week: maturity_week.unwrap_or(u8::MAX),
@@ -1447,6 +2151,10 @@ impl InstrumentDefMsgV1 {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -1455,15 +2163,50 @@ impl InstrumentDefMsgV1 {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -1475,83 +2218,63 @@ impl InstrumentDefMsgV1 {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_min_price_increment")]
     fn py_pretty_min_price_increment(&self) -> f64 {
-        self.min_price_increment as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.min_price_increment)
     }
 
     #[getter]
     #[pyo3(name = "pretty_high_limit_price")]
     fn py_pretty_high_limit_price(&self) -> f64 {
-        match self.high_limit_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.high_limit_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.high_limit_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_low_limit_price")]
     fn py_pretty_low_limit_price(&self) -> f64 {
-        match self.low_limit_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.low_limit_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.low_limit_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_max_price_variation")]
     fn py_pretty_max_price_variation(&self) -> f64 {
-        match self.max_price_variation {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.max_price_variation as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.max_price_variation)
     }
 
     #[getter]
     #[pyo3(name = "pretty_trading_reference_price")]
     fn py_pretty_trading_reference_price(&self) -> f64 {
-        match self.trading_reference_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.trading_reference_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.trading_reference_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_min_price_increment_amount")]
     fn py_pretty_min_price_increment_amount(&self) -> f64 {
-        match self.min_price_increment_amount {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.min_price_increment_amount as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.min_price_increment_amount)
     }
 
     #[getter]
     #[pyo3(name = "pretty_price_ratio")]
     fn py_pretty_price_ratio(&self) -> f64 {
-        match self.price_ratio {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.price_ratio as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.price_ratio)
     }
 
     #[getter]
     #[pyo3(name = "pretty_strike_price")]
     fn py_pretty_strike_price(&self) -> f64 {
-        match self.strike_price {
-            UNDEF_PRICE => f64::NAN,
-            _ => self.strike_price as f64 / FIXED_PRICE_SCALE as f64,
-        }
+        px_to_f64(self.strike_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -1716,38 +2439,49 @@ impl ImbalanceMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        ts_recv: u64,
-        ref_price: i64,
-        cont_book_clr_price: i64,
-        auct_interest_clr_price: i64,
-        paired_qty: u32,
-        total_imbalance_qty: u32,
-        auction_type: c_char,
-        side: c_char,
-        significant_imbalance: c_char,
+        ts_recv: Option<u64>,
+        ref_price: Option<i64>,
+        cont_book_clr_price: Option<i64>,
+        auct_interest_clr_price: Option<i64>,
+        paired_qty: Option<u32>,
+        total_imbalance_qty: Option<u32>,
+        auction_type: Option<c_char>,
+        side: Option<c_char>,
+        significant_imbalance: Option<c_char>,
+        auction_time: Option<u64>,
+        ssr_filling_price: Option<i64>,
+        ind_match_price: Option<i64>,
+        upper_collar: Option<i64>,
+        lower_collar: Option<i64>,
+        market_imbalance_qty: Option<u32>,
+        unpaired_qty: Option<u32>,
+        auction_status: Option<u8>,
+        freeze_status: Option<u8>,
+        num_extensions: Option<u8>,
+        unpaired_side: Option<c_char>,
     ) -> Self {
         Self {
             hd: RecordHeader::new::<Self>(rtype::IMBALANCE, publisher_id, instrument_id, ts_event),
-            ts_recv,
-            ref_price,
-            auction_time: 0,
-            cont_book_clr_price,
-            auct_interest_clr_price,
-            ssr_filling_price: UNDEF_PRICE,
-            ind_match_price: UNDEF_PRICE,
-            upper_collar: UNDEF_PRICE,
-            lower_collar: UNDEF_PRICE,
-            paired_qty,
-            total_imbalance_qty,
-            market_imbalance_qty: UNDEF_ORDER_SIZE,
-            unpaired_qty: UNDEF_ORDER_SIZE,
-            auction_type,
-            side,
-            auction_status: 0,
-            freeze_status: 0,
-            num_extensions: 0,
-            unpaired_side: 0,
-            significant_imbalance,
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            ref_price: ref_price.unwrap_or(UNDEF_PRICE),
+            auction_time: auction_time.unwrap_or_default(),
+            cont_book_clr_price: cont_book_clr_price.unwrap_or(UNDEF_PRICE),
+            auct_interest_clr_price: auct_interest_clr_price.unwrap_or(UNDEF_PRICE),
+            ssr_filling_price: ssr_filling_price.unwrap_or(UNDEF_PRICE),
+            ind_match_price: ind_match_price.unwrap_or(UNDEF_PRICE),
+            upper_collar: upper_collar.unwrap_or(UNDEF_PRICE),
+            lower_collar: lower_collar.unwrap_or(UNDEF_PRICE),
+            paired_qty: paired_qty.unwrap_or(UNDEF_ORDER_SIZE),
+            total_imbalance_qty: total_imbalance_qty.unwrap_or(UNDEF_ORDER_SIZE),
+            market_imbalance_qty: market_imbalance_qty.unwrap_or(UNDEF_ORDER_SIZE),
+            unpaired_qty: unpaired_qty.unwrap_or(UNDEF_ORDER_SIZE),
+            auction_type: auction_type.unwrap_or(b'~' as c_char),
+            side: side.unwrap_or(Side::None as c_char),
+            auction_status: auction_status.unwrap_or_default(),
+            freeze_status: freeze_status.unwrap_or_default(),
+            num_extensions: num_extensions.unwrap_or_default(),
+            unpaired_side: unpaired_side.unwrap_or_default(),
+            significant_imbalance: significant_imbalance.unwrap_or(b'~' as c_char),
             _reserved: [0],
         }
     }
@@ -1756,6 +2490,10 @@ impl ImbalanceMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -1764,15 +2502,50 @@ impl ImbalanceMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -1784,26 +2557,27 @@ impl ImbalanceMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_auct_interest_clr_price")]
     fn py_pretty_auct_interest_clr_price(&self) -> f64 {
-        self.auct_interest_clr_price as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.auct_interest_clr_price)
     }
 
     #[getter]
-    #[pyo3(name = "pretty_cont_book_clear_price")]
-    fn py_pretty_cont_book_clear_price(&self) -> f64 {
-        self.cont_book_clr_price as f64 / FIXED_PRICE_SCALE as f64
+    #[pyo3(name = "pretty_cont_book_clr_price")]
+    fn py_pretty_cont_book_clr_price(&self) -> f64 {
+        px_to_f64(self.cont_book_clr_price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ref_price")]
     fn py_pretty_ref_price(&self) -> f64 {
-        self.ref_price as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.ref_price)
     }
 
     #[pyo3(name = "record_size")]
@@ -1814,7 +2588,7 @@ impl ImbalanceMsg {
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -1836,14 +2610,14 @@ impl ImbalanceMsg {
 
     #[getter]
     #[pyo3(name = "side")]
-    fn py_side(&self) -> char {
-        self.side as u8 as char
+    fn py_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.side)
     }
 
     #[getter]
     #[pyo3(name = "unpaired_side")]
-    fn py_unpaired_side(&self) -> char {
-        self.unpaired_side as u8 as char
+    fn py_unpaired_side(&self, py: Python<'_>) -> PyObject {
+        char_enum_to_py::<Side>(py, self.unpaired_side)
     }
 
     #[getter]
@@ -1890,27 +2664,27 @@ impl StatMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        ts_recv: u64,
-        ts_ref: u64,
-        price: i64,
-        quantity: i32,
-        sequence: u32,
-        ts_in_delta: i32,
-        stat_type: u16,
-        channel_id: u16,
+        ts_recv: Option<u64>,
+        ts_ref: Option<u64>,
+        price: Option<i64>,
+        quantity: Option<i32>,
+        sequence: Option<u32>,
+        ts_in_delta: Option<i32>,
+        stat_type: Option<u16>,
+        channel_id: Option<u16>,
         update_action: Option<u8>,
         stat_flags: Option<u8>,
     ) -> Self {
         Self {
             hd: RecordHeader::new::<Self>(rtype::STATISTICS, publisher_id, instrument_id, ts_event),
-            ts_recv,
-            ts_ref,
-            price,
-            quantity,
-            sequence,
-            ts_in_delta,
-            stat_type,
-            channel_id,
+            ts_recv: ts_recv.unwrap_or(UNDEF_TIMESTAMP),
+            ts_ref: ts_ref.unwrap_or(UNDEF_TIMESTAMP),
+            price: price.unwrap_or(UNDEF_PRICE),
+            quantity: quantity.unwrap_or(UNDEF_STAT_QUANTITY),
+            sequence: sequence.unwrap_or_default(),
+            ts_in_delta: ts_in_delta.unwrap_or_default(),
+            stat_type: stat_type.unwrap_or_default(),
+            channel_id: channel_id.unwrap_or_default(),
             update_action: update_action.unwrap_or(StatUpdateAction::New as u8),
             stat_flags: stat_flags.unwrap_or_default(),
             _reserved: Default::default(),
@@ -1921,6 +2695,10 @@ impl StatMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -1929,14 +2707,49 @@ impl StatMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
+
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -1948,20 +2761,21 @@ impl StatMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_price")]
     fn py_pretty_price(&self) -> f64 {
-        self.price as f64 / FIXED_PRICE_SCALE as f64
+        px_to_f64(self.price)
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -2028,6 +2842,10 @@ impl ErrorMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -2036,15 +2854,50 @@ impl ErrorMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -2056,14 +2909,15 @@ impl ErrorMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[pyo3(name = "record_size")]
@@ -2124,6 +2978,10 @@ impl ErrorMsgV1 {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -2132,15 +2990,50 @@ impl ErrorMsgV1 {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -2152,14 +3045,15 @@ impl ErrorMsgV1 {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[pyo3(name = "record_size")]
@@ -2216,12 +3110,12 @@ impl SymbolMappingMsg {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        stype_in: SType,
-        stype_in_symbol: &str,
-        stype_out: SType,
-        stype_out_symbol: &str,
-        start_ts: u64,
-        end_ts: u64,
+        stype_in: Option<SType>,
+        stype_in_symbol: Option<&str>,
+        stype_out: Option<SType>,
+        stype_out_symbol: Option<&str>,
+        start_ts: Option<u64>,
+        end_ts: Option<u64>,
     ) -> PyResult<Self> {
         Ok(Self {
             hd: RecordHeader::new::<Self>(
@@ -2230,12 +3124,14 @@ impl SymbolMappingMsg {
                 instrument_id,
                 ts_event,
             ),
-            stype_in: stype_in as u8,
-            stype_in_symbol: str_to_c_chars(stype_in_symbol).map_err(to_val_err)?,
-            stype_out: stype_out as u8,
-            stype_out_symbol: str_to_c_chars(stype_out_symbol).map_err(to_val_err)?,
-            start_ts,
-            end_ts,
+            stype_in: stype_in.unwrap_or(SType::RawSymbol) as u8,
+            stype_in_symbol: str_to_c_chars(stype_in_symbol.unwrap_or_default())
+                .map_err(to_val_err)?,
+            stype_out: stype_out.unwrap_or(SType::InstrumentId) as u8,
+            stype_out_symbol: str_to_c_chars(stype_out_symbol.unwrap_or_default())
+                .map_err(to_val_err)?,
+            start_ts: start_ts.unwrap_or(UNDEF_TIMESTAMP),
+            end_ts: end_ts.unwrap_or(UNDEF_TIMESTAMP),
         })
     }
 
@@ -2243,6 +3139,10 @@ impl SymbolMappingMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -2251,15 +3151,50 @@ impl SymbolMappingMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -2271,14 +3206,15 @@ impl SymbolMappingMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -2365,10 +3301,10 @@ impl SymbolMappingMsgV1 {
         publisher_id: u16,
         instrument_id: u32,
         ts_event: u64,
-        stype_in_symbol: &str,
-        stype_out_symbol: &str,
-        start_ts: u64,
-        end_ts: u64,
+        stype_in_symbol: Option<&str>,
+        stype_out_symbol: Option<&str>,
+        start_ts: Option<u64>,
+        end_ts: Option<u64>,
     ) -> PyResult<Self> {
         Ok(Self {
             hd: RecordHeader::new::<Self>(
@@ -2377,10 +3313,12 @@ impl SymbolMappingMsgV1 {
                 instrument_id,
                 ts_event,
             ),
-            stype_in_symbol: str_to_c_chars(stype_in_symbol).map_err(to_val_err)?,
-            stype_out_symbol: str_to_c_chars(stype_out_symbol).map_err(to_val_err)?,
-            start_ts,
-            end_ts,
+            stype_in_symbol: str_to_c_chars(stype_in_symbol.unwrap_or_default())
+                .map_err(to_val_err)?,
+            stype_out_symbol: str_to_c_chars(stype_out_symbol.unwrap_or_default())
+                .map_err(to_val_err)?,
+            start_ts: start_ts.unwrap_or(UNDEF_TIMESTAMP),
+            end_ts: end_ts.unwrap_or(UNDEF_TIMESTAMP),
             _dummy: Default::default(),
         })
     }
@@ -2389,6 +3327,10 @@ impl SymbolMappingMsgV1 {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -2397,15 +3339,50 @@ impl SymbolMappingMsgV1 {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -2417,14 +3394,15 @@ impl SymbolMappingMsgV1 {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[getter]
@@ -2503,6 +3481,10 @@ impl SystemMsg {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -2511,15 +3493,50 @@ impl SystemMsg {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -2531,14 +3548,15 @@ impl SystemMsg {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[pyo3(name = "record_size")]
@@ -2604,6 +3622,10 @@ impl SystemMsgV1 {
         self.as_ref()
     }
 
+    fn to_bytes(slf: &PyCell<Self>) -> PyResult<&PyBytes> {
+        record_to_bytes(slf)
+    }
+
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> Py<PyAny> {
         match op {
             CompareOp::Eq => self.eq(other).into_py(py),
@@ -2612,15 +3634,50 @@ impl SystemMsgV1 {
         }
     }
 
+    fn __hash__(&self) -> isize {
+        hash_record(self)
+    }
+
     fn __repr__(&self) -> String {
         format!("{self:?}")
     }
 
+    #[pyo3(name = "asdict")]
+    fn py_asdict<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyDict> {
+        fields_to_dict::<Self>(slf)
+    }
+
+    fn __iter__<'py>(slf: &'py PyCell<Self>) -> PyResult<&'py PyIterator> {
+        fields_to_dict::<Self>(slf)?
+            .call_method0(intern!(slf.py(), "items"))?
+            .iter()
+    }
+
+    #[classmethod]
+    #[pyo3(name = "from_bytes")]
+    fn py_from_bytes(_cls: &PyType, data: &[u8]) -> PyResult<Self> {
+        record_from_bytes(data)
+    }
+
+    fn __reduce__(slf: &PyCell<Self>) -> PyResult<PyObject> {
+        reduce_record(slf)
+    }
+
     #[getter]
     fn rtype(&self) -> u8 {
         self.hd.rtype
     }
 
+    #[getter]
+    fn header(&self) -> RecordHeader {
+        self.hd.clone()
+    }
+
+    #[getter]
+    fn rtype_name(&self) -> PyResult<&'static str> {
+        self.hd.rtype_name()
+    }
+
     #[getter]
     fn publisher_id(&self) -> u16 {
         self.hd.publisher_id
@@ -2632,14 +3689,15 @@ impl SystemMsgV1 {
     }
 
     #[getter]
-    fn ts_event(&self) -> u64 {
+    #[pyo3(name = "ts_event")]
+    fn py_ts_event(&self) -> u64 {
         self.hd.ts_event
     }
 
     #[getter]
     #[pyo3(name = "pretty_ts_event")]
     fn py_pretty_ts_event(&self, py: Python<'_>) -> PyResult<PyObject> {
-        get_utc_nanosecond_timestamp(py, self.ts_event())
+        get_utc_nanosecond_timestamp(py, self.hd.ts_event)
     }
 
     #[pyo3(name = "record_size")]
@@ -2735,7 +3793,61 @@ impl<const N: usize> PyFieldDesc for [BidAskPair; N] {
     }
 }
 
-// `WithTsOut` is converted to a 2-tuple in Python
+impl<const N: usize> PyFieldDesc for [ConsolidatedBidAskPair; N] {
+    fn field_dtypes(_field_name: &str) -> Vec<(String, String)> {
+        let mut res = Vec::new();
+        let field_dtypes = ConsolidatedBidAskPair::field_dtypes("");
+        for level in 0..N {
+            let mut dtypes = field_dtypes.clone();
+            for dtype in dtypes.iter_mut() {
+                dtype.0.push_str(&format!("_{level:02}"));
+            }
+            res.extend(dtypes);
+        }
+        res
+    }
+
+    fn price_fields(_field_name: &str) -> Vec<String> {
+        let mut res = Vec::new();
+        let price_fields = ConsolidatedBidAskPair::price_fields("");
+        for level in 0..N {
+            let mut fields = price_fields.clone();
+            for field in fields.iter_mut() {
+                field.push_str(&format!("_{level:02}"));
+            }
+            res.extend(fields);
+        }
+        res
+    }
+
+    fn hidden_fields(_field_name: &str) -> Vec<String> {
+        let mut res = Vec::new();
+        let hidden_fields = ConsolidatedBidAskPair::hidden_fields("");
+        for level in 0..N {
+            let mut fields = hidden_fields.clone();
+            for field in fields.iter_mut() {
+                field.push_str(&format!("_{level:02}"));
+            }
+            res.extend(fields);
+        }
+        res
+    }
+
+    fn ordered_fields(_field_name: &str) -> Vec<String> {
+        let mut res = Vec::new();
+        let ordered_fields = ConsolidatedBidAskPair::ordered_fields("");
+        for level in 0..N {
+            let mut fields = ordered_fields.clone();
+            for field in fields.iter_mut() {
+                field.push_str(&format!("_{level:02}"));
+            }
+            res.extend(fields);
+        }
+        res
+    }
+}
+
+// `WithTsOut` is converted to the inner record with an added `ts_out` attribute in Python
 impl<R: HasRType + IntoPy<Py<PyAny>>> IntoPy<PyObject> for WithTsOut<R> {
     fn into_py(self, py: Python<'_>) -> PyObject {
         let obj = self.rec.into_py(py);
@@ -2744,7 +3856,169 @@ impl<R: HasRType + IntoPy<Py<PyAny>>> IntoPy<PyObject> for WithTsOut<R> {
     }
 }
 
+fn hash_record<R: Hash>(rec: &R) -> isize {
+    let mut hasher = DefaultHasher::new();
+    rec.hash(&mut hasher);
+    hasher.finish() as isize
+}
+
+/// Returns a `dict` of the fields of the record `obj` in their ordered position. The
+/// fields of `BidAskPair` levels are flattened with a level suffix, e.g. `bid_px_00`.
+fn fields_to_dict<R: PyFieldDesc>(obj: &PyAny) -> PyResult<&PyDict> {
+    let py = obj.py();
+    let dict = PyDict::new(py);
+    for field in R::ordered_fields("") {
+        let value = match obj.getattr(field.as_str()) {
+            Ok(value) => value,
+            Err(err) => {
+                let Some((name, level)) = field
+                    .rsplit_once('_')
+                    .and_then(|(name, level)| Some((name, level.parse::<usize>().ok()?)))
+                else {
+                    return Err(err);
+                };
+                obj.getattr(intern!(py, "levels"))?
+                    .get_item(level)?
+                    .getattr(name)?
+            }
+        };
+        dict.set_item(field, value)?;
+    }
+    if let Ok(ts_out) = obj.getattr(intern!(py, "ts_out")) {
+        dict.set_item(intern!(py, "ts_out"), ts_out)?;
+    }
+    Ok(dict)
+}
+
+/// Decodes a single record of any type from its DBN bytes, such as those returned by
+/// a record's `to_bytes()`. A trailing `ts_out` included in the record's length is
+/// set as its `ts_out` attribute.
+#[pyfunction]
+pub fn from_bytes(py: Python<'_>, data: &[u8]) -> PyResult<PyObject> {
+    // compat records share an rtype with their current version but are smaller
+    fn is_v1<V1: HasRType>(record_size: usize) -> bool {
+        record_size == mem::size_of::<V1>() || record_size == mem::size_of::<WithTsOut<V1>>()
+    }
+
+    if data.len() < mem::size_of::<RecordHeader>() {
+        return Err(to_val_err(format!(
+            "Expected at least {} bytes for a record header, found {}",
+            mem::size_of::<RecordHeader>(),
+            data.len()
+        )));
+    }
+    // `length` is the first byte of the header and `rtype` the second
+    let record_size = data[0] as usize * RecordHeader::LENGTH_MULTIPLIER;
+    let rtype = RType::try_from(data[1])
+        .map_err(|_| to_val_err(format!("Invalid rtype {:#04X}", data[1])))?;
+    #[allow(deprecated)]
+    match rtype {
+        RType::Mbp0 => record_to_py::<TradeMsg>(py, data),
+        RType::Mbp1 => record_to_py::<Mbp1Msg>(py, data),
+        RType::Mbp10 => record_to_py::<Mbp10Msg>(py, data),
+        RType::OhlcvDeprecated
+        | RType::Ohlcv1S
+        | RType::Ohlcv1M
+        | RType::Ohlcv1H
+        | RType::Ohlcv1D
+        | RType::OhlcvEod => record_to_py::<OhlcvMsg>(py, data),
+        RType::Bbo1S | RType::Bbo1M => record_to_py::<BboMsg>(py, data),
+        RType::Cbbo | RType::Cbbo1S | RType::Cbbo1M | RType::Tcbbo => {
+            record_to_py::<CbboMsg>(py, data)
+        }
+        RType::Status => record_to_py::<StatusMsg>(py, data),
+        RType::InstrumentDef if is_v1::<InstrumentDefMsgV1>(record_size) => {
+            record_to_py::<InstrumentDefMsgV1>(py, data)
+        }
+        RType::InstrumentDef => record_to_py::<InstrumentDefMsg>(py, data),
+        RType::Imbalance => record_to_py::<ImbalanceMsg>(py, data),
+        RType::Error if is_v1::<ErrorMsgV1>(record_size) => record_to_py::<ErrorMsgV1>(py, data),
+        RType::Error => record_to_py::<ErrorMsg>(py, data),
+        RType::SymbolMapping if is_v1::<SymbolMappingMsgV1>(record_size) => {
+            record_to_py::<SymbolMappingMsgV1>(py, data)
+        }
+        RType::SymbolMapping => record_to_py::<SymbolMappingMsg>(py, data),
+        RType::System if is_v1::<SystemMsgV1>(record_size) => record_to_py::<SystemMsgV1>(py, data),
+        RType::System => record_to_py::<SystemMsg>(py, data),
+        RType::Statistics => record_to_py::<StatMsg>(py, data),
+        RType::Mbo => record_to_py::<MboMsg>(py, data),
+    }
+}
+
+fn record_to_py<R: HasRType + IntoPy<PyObject>>(py: Python<'_>, data: &[u8]) -> PyResult<PyObject> {
+    let rec = record_from_bytes::<R>(data)?;
+    let size = mem::size_of::<R>();
+    let record_size = rec.record_size();
+    if record_size == mem::size_of::<WithTsOut<R>>() && data.len() >= record_size {
+        let ts_out = u64::from_le_bytes(data[size..record_size].try_into().unwrap());
+        Ok(WithTsOut { rec, ts_out }.into_py(py))
+    } else {
+        Ok(rec.into_py(py))
+    }
+}
+
+/// Returns the DBN bytes of a record, including its `ts_out` attribute if set.
+fn record_to_bytes<R: PyClass + AsRef<[u8]>>(slf: &PyCell<R>) -> PyResult<&PyBytes> {
+    let py = slf.py();
+    let mut data = slf.try_borrow()?.as_ref().to_vec();
+    let state: &PyDict = slf.getattr(intern!(py, "__dict__"))?.downcast()?;
+    if let Some(ts_out) = state.get_item(intern!(py, "ts_out"))? {
+        data.extend_from_slice(&ts_out.extract::<u64>()?.to_le_bytes());
+        // `length` is the first byte of the header
+        data[0] = (data.len() / RecordHeader::LENGTH_MULTIPLIER) as u8;
+    }
+    Ok(PyBytes::new(py, &data))
+}
+
+/// Copies a record of type `R` out of `data`, which doesn't need to be aligned.
+fn record_from_bytes<R: HasRType>(data: &[u8]) -> PyResult<R> {
+    if data.len() < mem::size_of::<R>() {
+        return Err(to_val_err(format!(
+            "Expected at least {} bytes for {}, found {}",
+            mem::size_of::<R>(),
+            std::any::type_name::<R>(),
+            data.len()
+        )));
+    }
+    // `rtype` is the second byte of the header
+    if !R::has_rtype(data[1]) {
+        return Err(to_val_err(format!(
+            "Invalid rtype {:#04X} for {}",
+            data[1],
+            std::any::type_name::<R>()
+        )));
+    }
+    // Safety: checked length and rtype. All record fields are plain integers or
+    // arrays of integers so any bit pattern is valid.
+    Ok(unsafe { std::ptr::read_unaligned(data.as_ptr().cast::<R>()) })
+}
+
+/// Pickles a record as its DBN bytes along with its `__dict__`, which contains
+/// `ts_out` if present.
+fn reduce_record<R: PyClass + AsRef<[u8]>>(slf: &PyCell<R>) -> PyResult<PyObject> {
+    let py = slf.py();
+    let from_bytes = slf.get_type().getattr(intern!(py, "from_bytes"))?;
+    let data = PyBytes::new(py, slf.try_borrow()?.as_ref());
+    let state = slf.getattr(intern!(py, "__dict__"))?;
+    Ok((from_bytes, (data,), state).into_py(py))
+}
+
+/// Converts a raw character field to its enum, falling back to the raw character
+/// for values without a corresponding variant.
+fn char_enum_to_py<E>(py: Python<'_>, c: c_char) -> PyObject
+where
+    E: TryFrom<u8> + IntoPy<PyObject>,
+{
+    match E::try_from(c as u8) {
+        Ok(variant) => variant.into_py(py),
+        Err(_) => (c as u8 as char).into_py(py),
+    }
+}
+
 fn get_utc_nanosecond_timestamp(py: Python<'_>, timestamp: u64) -> PyResult<PyObject> {
+    if timestamp == UNDEF_TIMESTAMP {
+        return Ok(py.None());
+    }
     if let Ok(pandas) = PyModule::import(py, intern!(py, "pandas")) {
         let kwargs = PyDict::new(py);
         if kwargs.set_item(intern!(py, "utc"), true).is_ok()
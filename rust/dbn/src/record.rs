@@ -2,6 +2,7 @@
 //! and conversion functions.
 
 pub(crate) mod conv;
+mod custom;
 mod impl_default;
 mod methods;
 
@@ -18,7 +19,7 @@ use crate::{
         Action, InstrumentClass, MatchAlgorithm, SecurityUpdateAction, Side, StatType,
         StatUpdateAction, UserDefinedInstrument,
     },
-    macros::{dbn_record, CsvSerialize, JsonSerialize, RecordDebug},
+    macros::{dbn_record, CsvSerialize, JsonSerialize, RecordBuilder, RecordDebug},
     publishers::Publisher,
     Error, Result, SYMBOL_CSTR_LEN,
 };
@@ -26,9 +27,10 @@ pub(crate) use conv::as_u8_slice;
 #[cfg(feature = "serde")]
 pub(crate) use conv::cstr_serde;
 pub use conv::{
-    c_chars_to_str, str_to_c_chars, transmute_header_bytes, transmute_record,
+    c_chars_to_str, f64_to_px, px_to_f64, str_to_c_chars, transmute_header_bytes, transmute_record,
     transmute_record_bytes, transmute_record_mut, ts_to_dt,
 };
+pub use custom::CustomRecord;
 
 /// Common data for all Databento records. Always found at the beginning of a record
 /// struct.
@@ -36,6 +38,7 @@ pub use conv::{
 #[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(get_all, set_all, dict, module = "databento_dbn"),
@@ -64,9 +67,10 @@ pub struct RecordHeader {
 /// A market-by-order (MBO) tick message. The record of the
 /// [`Mbo`](crate::enums::Schema::Mbo) schema.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(set_all, dict, module = "databento_dbn", name = "MBOMsg"),
@@ -127,6 +131,7 @@ pub struct MboMsg {
 #[derive(Clone, JsonSerialize, RecordDebug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(get_all, set_all, dict, module = "databento_dbn"),
@@ -153,9 +158,10 @@ pub struct BidAskPair {
 /// Market by price implementation with a book depth of 0. Equivalent to
 /// MBP-0. The record of the [`Trades`](crate::enums::Schema::Trades) schema.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(set_all, dict, module = "databento_dbn"),
@@ -209,9 +215,10 @@ pub struct TradeMsg {
 /// Market by price implementation with a known book depth of 1. The record of the
 /// [`Mbp1`](crate::enums::Schema::Mbp1) schema.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(set_all, dict, module = "databento_dbn", name = "MBP1Msg"),
@@ -269,9 +276,10 @@ pub struct Mbp1Msg {
 /// Market by price implementation with a known book depth of 10. The record of the
 /// [`Mbp10`](crate::enums::Schema::Mbp10) schema.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(set_all, dict, module = "databento_dbn", name = "MBP10Msg"),
@@ -329,6 +337,186 @@ pub struct Mbp10Msg {
 /// The record of the [`Tbbo`](crate::enums::Schema::Tbbo) schema.
 pub type TbboMsg = Mbp1Msg;
 
+/// A consolidated level with the publisher of the best bid and offer.
+#[repr(C)]
+#[derive(Clone, JsonSerialize, RecordDebug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trivial_copy", derive(Copy))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(get_all, set_all, dict, module = "databento_dbn"),
+    derive(crate::macros::PyFieldDesc)
+)]
+#[cfg_attr(test, derive(type_layout::TypeLayout))]
+pub struct ConsolidatedBidAskPair {
+    /// The bid price.
+    #[dbn(fixed_price)]
+    pub bid_px: i64,
+    /// The ask price.
+    #[dbn(fixed_price)]
+    pub ask_px: i64,
+    /// The bid size.
+    pub bid_sz: u32,
+    /// The ask size.
+    pub ask_sz: u32,
+    /// The publisher ID of the bid.
+    pub bid_pb: u16,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved1: [u8; 2],
+    /// The publisher ID of the ask.
+    pub ask_pb: u16,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved2: [u8; 2],
+}
+
+/// Subsampled market by price with a known book depth of 1. The record of the
+/// following schemas:
+/// - [`Bbo1S`](crate::enums::Schema::Bbo1S)
+/// - [`Bbo1M`](crate::enums::Schema::Bbo1M)
+#[repr(C)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trivial_copy", derive(Copy))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(set_all, dict, module = "databento_dbn", name = "BBOMsg"),
+    derive(crate::macros::PyFieldDesc)
+)]
+#[cfg_attr(not(feature = "python"), derive(MockPyo3))] // bring `pyo3` attribute into scope
+#[cfg_attr(test, derive(type_layout::TypeLayout))]
+#[dbn_record(rtype::BBO_1S, rtype::BBO_1M)]
+pub struct BboMsg {
+    /// The common header.
+    #[pyo3(get)]
+    pub hd: RecordHeader,
+    /// The price of the last trade expressed as a signed integer where every 1 unit
+    /// corresponds to 1e-9, i.e. 1/1,000,000,000 or 0.000000001.
+    #[dbn(fixed_price)]
+    #[pyo3(get)]
+    pub price: i64,
+    /// The quantity of the last trade.
+    #[pyo3(get)]
+    pub size: u32,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved1: u8,
+    /// The side that initiated the last trade. Can be **A**sk for a sell order (or sell
+    /// aggressor in a trade), **B**id for a buy order (or buy aggressor in a trade), or
+    /// **N**one where no side is specified by the original source.
+    #[dbn(c_char, encode_order(2))]
+    pub side: c_char,
+    /// A combination of packet end with matching engine status. See
+    /// [`enums::flags`](crate::enums::flags) for possible values.
+    #[dbn(fmt_binary)]
+    #[pyo3(get)]
+    pub flags: u8,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved2: u8,
+    /// The end timestamp of the interval, clamped to the second/minute boundary,
+    /// expressed as the number of nanoseconds since the UNIX epoch.
+    #[dbn(encode_order(0), index_ts, unix_nanos)]
+    #[pyo3(get)]
+    pub ts_recv: u64,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved3: [u8; 4],
+    /// The message sequence number assigned at the venue of the last update.
+    #[pyo3(get)]
+    pub sequence: u32,
+    /// The top of the order book.
+    #[pyo3(get)]
+    pub levels: [BidAskPair; 1],
+}
+
+/// The record of the [`Bbo1S`](crate::enums::Schema::Bbo1S) schema.
+pub type Bbo1SMsg = BboMsg;
+/// The record of the [`Bbo1M`](crate::enums::Schema::Bbo1M) schema.
+pub type Bbo1MMsg = BboMsg;
+
+/// Consolidated market by price with a known book depth of 1. The record of the
+/// following schemas:
+/// - [`Cbbo`](crate::enums::Schema::Cbbo)
+/// - [`Cbbo1S`](crate::enums::Schema::Cbbo1S)
+/// - [`Cbbo1M`](crate::enums::Schema::Cbbo1M)
+/// - [`Tcbbo`](crate::enums::Schema::Tcbbo)
+#[repr(C)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trivial_copy", derive(Copy))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(set_all, dict, module = "databento_dbn", name = "CBBOMsg"),
+    derive(crate::macros::PyFieldDesc)
+)]
+#[cfg_attr(not(feature = "python"), derive(MockPyo3))] // bring `pyo3` attribute into scope
+#[cfg_attr(test, derive(type_layout::TypeLayout))]
+#[dbn_record(rtype::CBBO, rtype::CBBO_1S, rtype::CBBO_1M, rtype::TCBBO)]
+pub struct CbboMsg {
+    /// The common header.
+    #[pyo3(get)]
+    pub hd: RecordHeader,
+    /// The price of the last trade expressed as a signed integer where every 1 unit
+    /// corresponds to 1e-9, i.e. 1/1,000,000,000 or 0.000000001.
+    #[dbn(fixed_price)]
+    #[pyo3(get)]
+    pub price: i64,
+    /// The quantity of the last trade.
+    #[pyo3(get)]
+    pub size: u32,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved1: u8,
+    /// The side that initiated the last trade. Can be **A**sk for a sell order (or sell
+    /// aggressor in a trade), **B**id for a buy order (or buy aggressor in a trade), or
+    /// **N**one where no side is specified by the original source.
+    #[dbn(c_char, encode_order(2))]
+    pub side: c_char,
+    /// A combination of packet end with matching engine status. See
+    /// [`enums::flags`](crate::enums::flags) for possible values.
+    #[dbn(fmt_binary)]
+    #[pyo3(get)]
+    pub flags: u8,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved2: u8,
+    /// The capture-server-received timestamp expressed as the number of nanoseconds
+    /// since the UNIX epoch. For the subsampled schemas, the end timestamp of the
+    /// interval, clamped to the second/minute boundary.
+    #[dbn(encode_order(0), index_ts, unix_nanos)]
+    #[pyo3(get)]
+    pub ts_recv: u64,
+    // Reserved for later usage.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _reserved3: [u8; 4],
+    /// The message sequence number assigned at the venue of the last update.
+    #[pyo3(get)]
+    pub sequence: u32,
+    /// The top of the consolidated order book.
+    #[pyo3(get)]
+    pub levels: [ConsolidatedBidAskPair; 1],
+}
+
+/// The record of the [`Cbbo1S`](crate::enums::Schema::Cbbo1S) schema.
+pub type Cbbo1SMsg = CbboMsg;
+/// The record of the [`Cbbo1M`](crate::enums::Schema::Cbbo1M) schema.
+pub type Cbbo1MMsg = CbboMsg;
+/// The record of the [`Tcbbo`](crate::enums::Schema::Tcbbo) schema.
+pub type TcbboMsg = CbboMsg;
+
 /// Open, high, low, close, and volume. The record of the following schemas:
 /// - [`Ohlcv1S`](crate::enums::Schema::Ohlcv1S)
 /// - [`Ohlcv1M`](crate::enums::Schema::Ohlcv1M)
@@ -336,9 +524,10 @@ pub type TbboMsg = Mbp1Msg;
 /// - [`Ohlcv1D`](crate::enums::Schema::Ohlcv1D)
 /// - [`OhlcvEod`](crate::enums::Schema::OhlcvEod)
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(get_all, set_all, dict, module = "databento_dbn", name = "OHLCVMsg"),
@@ -375,9 +564,10 @@ pub struct OhlcvMsg {
 /// A trrading status update message. The record of the
 /// [`Status`](crate::enums::Schema::Status) schema.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -428,9 +618,10 @@ pub struct StatusMsg {
 /// Definition of an instrument. The record of the
 /// [`Definition`](crate::enums::Schema::Definition) schema.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -672,9 +863,10 @@ pub struct InstrumentDefMsg {
 
 /// An auction imbalance message.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(set_all, dict, module = "databento_dbn"),
@@ -766,9 +958,10 @@ pub struct ImbalanceMsg {
 /// A statistics message. A catchall for various data disseminated by publishers.
 /// The [`stat_type`](Self::stat_type) indicates the statistic contained in the message.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(get_all, set_all, dict, module = "databento_dbn"),
@@ -822,9 +1015,10 @@ pub struct StatMsg {
 
 /// An error message from the Databento Live Subscription Gateway (LSG).
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -853,9 +1047,10 @@ pub struct ErrorMsg {
 /// A symbol mapping message which maps a symbol of one [`SType`](crate::enums::SType)
 /// to another.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -900,9 +1095,10 @@ pub struct SymbolMappingMsg {
 /// A non-error message from the Databento Live Subscription Gateway (LSG). Also used
 /// for heartbeating.
 #[repr(C)]
-#[derive(Clone, CsvSerialize, JsonSerialize, PartialEq, Eq, Hash)]
+#[derive(Clone, CsvSerialize, JsonSerialize, RecordBuilder, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "trivial_copy", derive(Copy))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(crate::macros::DbnJsonSchema))]
 #[cfg_attr(
     feature = "python",
     pyo3::pyclass(dict, module = "databento_dbn"),
@@ -956,6 +1152,23 @@ pub trait Record {
         self.header().publisher()
     }
 
+    /// Returns the raw publisher ID assigned by Databento, which denotes the dataset
+    /// and venue.
+    fn publisher_id(&self) -> u16 {
+        self.header().publisher_id
+    }
+
+    /// Returns the numeric ID assigned to the instrument.
+    fn instrument_id(&self) -> u32 {
+        self.header().instrument_id
+    }
+
+    /// Parses the raw matching-engine-received timestamp into a datetime. Returns
+    /// `None` if `ts_event` contains the sentinel for a null timestamp.
+    fn ts_event(&self) -> Option<time::OffsetDateTime> {
+        self.header().ts_event()
+    }
+
     /// Returns the raw primary timestamp for the record.
     ///
     /// This timestamp should be used for sorting records as well as indexing into any
@@ -979,6 +1192,39 @@ pub trait Record {
     fn index_date(&self) -> Option<time::Date> {
         self.index_ts().map(|dt| dt.date())
     }
+
+    /// Returns the raw timestamp for ordering records of any type: the primary
+    /// timestamp (`raw_index_ts()`), e.g. `ts_recv`, or `ts_event` if the primary
+    /// timestamp contains the sentinel value for a null timestamp.
+    fn key_ts(&self) -> u64 {
+        match self.raw_index_ts() {
+            crate::UNDEF_TIMESTAMP => self.header().ts_event,
+            index_ts => index_ts,
+        }
+    }
+
+    /// Returns a key for ordering records of any type consistently, e.g. when merging
+    /// multiple streams of records.
+    fn sort_key(&self) -> SortKey {
+        SortKey {
+            key_ts: self.key_ts(),
+            ts_event: self.header().ts_event,
+        }
+    }
+}
+
+/// A key for ordering records of different types. Records are ordered by their
+/// [`key_ts()`](Record::key_ts) with `ts_event` breaking ties. Because the sentinel
+/// for a null timestamp is `u64::MAX`, records where both timestamps are undefined
+/// sort last.
+///
+/// Unlike the `PartialOrd` implementations of the records, this is a total order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey {
+    /// The raw ordering timestamp of the record. See [`Record::key_ts()`].
+    pub key_ts: u64,
+    /// The raw matching-engine-received timestamp of the record.
+    pub ts_event: u64,
 }
 
 /// Used for polymorphism around mutable types beginning with a [`RecordHeader`].
@@ -1012,7 +1258,7 @@ mod tests {
     use rstest::rstest;
     use type_layout::{Field, TypeLayout};
 
-    use crate::UNDEF_TIMESTAMP;
+    use crate::{RecordEnum, RecordRef, RecordRefEnum, UNDEF_TIMESTAMP};
 
     use super::*;
 
@@ -1078,6 +1324,9 @@ mod tests {
     #[case::mbp1(Mbp1Msg::default(), mem::size_of::<TradeMsg>() + mem::size_of::<BidAskPair>())]
     #[case::mbp10(Mbp10Msg::default(), mem::size_of::<TradeMsg>() + mem::size_of::<BidAskPair>() * 10)]
     #[case::trade(TradeMsg::default(), 48)]
+    #[case::cba_pair(ConsolidatedBidAskPair::default(), 32)]
+    #[case::bbo(BboMsg::default_for_schema(crate::Schema::Bbo1S), 80)]
+    #[case::cbbo(CbboMsg::default_for_schema(crate::Schema::Cbbo), 80)]
     #[case::definition(InstrumentDefMsg::default(), 400)]
     #[case::status(StatusMsg::default(), 40)]
     #[case::imbalance(ImbalanceMsg::default(), 112)]
@@ -1098,6 +1347,9 @@ mod tests {
     #[case::mbp1(Mbp1Msg::default())]
     #[case::mbp10(Mbp10Msg::default())]
     #[case::trade(TradeMsg::default())]
+    #[case::cba_pair(ConsolidatedBidAskPair::default())]
+    #[case::bbo(BboMsg::default_for_schema(crate::Schema::Bbo1S))]
+    #[case::cbbo(CbboMsg::default_for_schema(crate::Schema::Cbbo))]
     #[case::definition(InstrumentDefMsg::default())]
     #[case::status(StatusMsg::default())]
     #[case::imbalance(ImbalanceMsg::default())]
@@ -1134,6 +1386,183 @@ mod tests {
         assert_eq!(rec.raw_index_ts(), 1);
     }
 
+    #[test]
+    fn test_mbo_builder() {
+        let rec = MboMsg::builder()
+            .publisher_id(1)
+            .instrument_id(5482)
+            .ts_event(1)
+            .price(372025000000000)
+            .size(10)
+            .action(Action::Add)
+            .side('B')
+            .ts_recv(2)
+            .build();
+        assert_eq!(rec.hd.rtype, rtype::MBO);
+        assert_eq!(rec.record_size(), mem::size_of::<MboMsg>());
+        assert_eq!(rec.hd.instrument_id, 5482);
+        assert_eq!(rec.price, 372025000000000);
+        assert_eq!(rec.action().unwrap(), Action::Add);
+        assert_eq!(rec.side().unwrap(), Side::Bid);
+        assert_eq!(rec.order_id, MboMsg::default().order_id);
+    }
+
+    #[test]
+    fn test_ohlcv_builder() {
+        let rec = OhlcvMsg::builder(crate::Schema::Ohlcv1D)
+            .open(1)
+            .close(2)
+            .build();
+        assert_eq!(rec.hd.rtype, rtype::OHLCV_1D);
+        assert_eq!(rec.open, 1);
+        assert_eq!(rec.close, 2);
+        assert_eq!(rec.high, crate::UNDEF_PRICE);
+    }
+
+    #[test]
+    fn test_builder_c_char_array() {
+        let rec = InstrumentDefMsg::builder()
+            .raw_symbol("ESZ4")
+            .unwrap()
+            .build();
+        assert_eq!(rec.raw_symbol().unwrap(), "ESZ4");
+        let res = SymbolMappingMsg::builder().stype_in_symbol(&"A".repeat(SYMBOL_CSTR_LEN));
+        assert!(matches!(res, Err(Error::Encode(_))));
+    }
+
+    #[rstest]
+    #[case::zero(0)]
+    #[case::integer(175 * crate::FIXED_PRICE_SCALE)]
+    #[case::fraction(4_500_500_000_000)]
+    #[case::negative(-1_250_000_001)]
+    #[case::smallest(1)]
+    fn test_px_f64_round_trip(#[case] px: i64) {
+        assert_eq!(f64_to_px(px_to_f64(px)), px);
+    }
+
+    #[test]
+    fn test_px_f64_undef() {
+        assert!(px_to_f64(crate::UNDEF_PRICE).is_nan());
+        assert_eq!(f64_to_px(f64::NAN), crate::UNDEF_PRICE);
+        let rec = Mbp1Msg {
+            price: 4_500_500_000_000,
+            ..Default::default()
+        };
+        assert_eq!(rec.price_f64(), 4500.5);
+        assert!(rec.levels[0].bid_px_f64().is_nan());
+    }
+
+    #[test]
+    fn test_sort_key() {
+        let mut recs = vec![
+            RecordEnum::from(MboMsg {
+                hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, 3),
+                ts_recv: 5,
+                ..Default::default()
+            }),
+            RecordEnum::from(InstrumentDefMsg::default()),
+            RecordEnum::from(TradeMsg {
+                hd: RecordHeader::new::<TradeMsg>(rtype::MBP_0, 1, 1, 2),
+                ts_recv: 5,
+                ..Default::default()
+            }),
+            RecordEnum::from(OHLCV_MSG),
+            // undefined `ts_recv` falls back to `ts_event`
+            RecordEnum::from(MboMsg {
+                hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 2, 4),
+                ts_recv: UNDEF_TIMESTAMP,
+                ..Default::default()
+            }),
+        ];
+        recs.sort_by_key(|rec| rec.sort_key());
+        let rtypes: Vec<_> = recs
+            .iter()
+            .map(|rec| (rec.header().rtype, rec.instrument_id()))
+            .collect();
+        assert_eq!(
+            rtypes,
+            [
+                (rtype::MBO, 2),
+                (rtype::MBP_0, 1),
+                (rtype::MBO, 1),
+                (rtype::OHLCV_1S, 5482),
+                (rtype::INSTRUMENT_DEF, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_ts() {
+        let mut rec = MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, 3),
+            ts_recv: 5,
+            ..Default::default()
+        };
+        assert_eq!(rec.key_ts(), 5);
+        assert_eq!(RecordRef::from(&rec).key_ts(), 5);
+        assert_eq!(RecordEnum::from(rec.clone()).key_ts(), 5);
+        rec.ts_recv = UNDEF_TIMESTAMP;
+        assert_eq!(rec.key_ts(), 3);
+        assert_eq!(RecordRef::from(&rec).key_ts(), 3);
+        assert_eq!(RecordEnum::from(rec.clone()).key_ts(), 3);
+        assert_eq!(RecordRefEnum::from(&rec).key_ts(), 3);
+    }
+
+    fn describe(rec: &impl Record) -> String {
+        format!(
+            "{:?} {} {} {}",
+            rec.rtype().unwrap(),
+            rec.publisher_id(),
+            rec.instrument_id(),
+            rec.raw_index_ts()
+        )
+    }
+
+    #[test]
+    fn test_record_generic_accessors() {
+        let exp = "Ohlcv1S 1 5482 1609160400000000000";
+        assert_eq!(describe(&OHLCV_MSG), exp);
+        assert_eq!(describe(&RecordRef::from(&OHLCV_MSG)), exp);
+        assert_eq!(describe(&RecordEnum::from(OHLCV_MSG)), exp);
+        assert_eq!(describe(&WithTsOut::new(OHLCV_MSG, 0)), exp);
+    }
+
+    #[test]
+    fn test_default_with() {
+        let rec = StatMsg::default_with(5482, 1);
+        assert_eq!(rec.hd.instrument_id, 5482);
+        assert_eq!(rec.hd.ts_event, 1);
+        assert_eq!(rec.hd.rtype, rtype::STATISTICS);
+        assert_eq!(rec.price, crate::UNDEF_PRICE);
+        assert_eq!(rec.ts_recv, UNDEF_TIMESTAMP);
+    }
+
+    #[rstest]
+    #[case::cbbo(crate::Schema::Cbbo)]
+    #[case::cbbo_1s(crate::Schema::Cbbo1S)]
+    #[case::cbbo_1m(crate::Schema::Cbbo1M)]
+    #[case::tcbbo(crate::Schema::Tcbbo)]
+    fn test_cbbo_record_ref(#[case] schema: crate::Schema) {
+        let rec = CbboMsg::default_for_schema(schema);
+        let rec_ref = RecordRef::from(&rec);
+        assert_eq!(rec_ref.rtype().unwrap(), RType::from(schema));
+        assert!(rec_ref.get::<CbboMsg>().is_some());
+        assert!(rec_ref.get::<BboMsg>().is_none());
+        assert!(matches!(rec_ref.to_owned().unwrap(), RecordEnum::Cbbo(_)));
+    }
+
+    #[rstest]
+    #[case::bbo_1s(crate::Schema::Bbo1S)]
+    #[case::bbo_1m(crate::Schema::Bbo1M)]
+    fn test_bbo_record_ref(#[case] schema: crate::Schema) {
+        let rec = BboMsg::default_for_schema(schema);
+        let rec_ref = RecordRef::from(&rec);
+        assert_eq!(rec_ref.rtype().unwrap(), RType::from(schema));
+        assert!(rec_ref.get::<BboMsg>().is_some());
+        assert!(rec_ref.get::<CbboMsg>().is_none());
+        assert!(matches!(rec_ref.to_owned().unwrap(), RecordEnum::Bbo(_)));
+    }
+
     #[test]
     fn test_db_ts_always_valid_time_offsetdatetime() {
         assert!(time::OffsetDateTime::from_unix_timestamp_nanos(0).is_ok());
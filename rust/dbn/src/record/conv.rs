@@ -99,7 +99,8 @@ pub unsafe fn transmute_record_mut<T: HasRType>(header: &mut RecordHeader) -> Op
 ///
 /// # Errors
 /// This function returns an error if `s` contains more than N - 1 characters. The last
-/// character is reserved for the null byte.
+/// character is reserved for the null byte. It also returns an error if `s` contains
+/// a null byte, as the string would be truncated when read back.
 pub fn str_to_c_chars<const N: usize>(s: &str) -> Result<[c_char; N]> {
     if s.len() > (N - 1) {
         return Err(Error::encode(format!(
@@ -108,6 +109,11 @@ pub fn str_to_c_chars<const N: usize>(s: &str) -> Result<[c_char; N]> {
             s.len(),
         )));
     }
+    if s.contains('\0') {
+        return Err(Error::encode(format!(
+            "string cannot contain a null byte; received {s:?}"
+        )));
+    }
     let mut res = [0; N];
     for (i, byte) in s.as_bytes().iter().enumerate() {
         res[i] = *byte as c_char;
@@ -142,6 +148,28 @@ pub fn ts_to_dt(ts: u64) -> Option<time::OffsetDateTime> {
     }
 }
 
+/// Converts a fixed-precision price to an `f64`. Returns NaN if `px` contains the
+/// sentinel for a null price.
+pub fn px_to_f64(px: i64) -> f64 {
+    if px == crate::UNDEF_PRICE {
+        f64::NAN
+    } else {
+        px as f64 / crate::FIXED_PRICE_SCALE as f64
+    }
+}
+
+/// Converts an `f64` price to fixed precision, rounding to the nearest nanounit so
+/// the output of [`px_to_f64`] converts back to the original price. NaN is
+/// converted to the sentinel for a null price.
+pub fn f64_to_px(px: f64) -> i64 {
+    if px.is_nan() {
+        crate::UNDEF_PRICE
+    } else {
+        // saturating cast
+        (px * crate::FIXED_PRICE_SCALE as f64).round() as i64
+    }
+}
+
 #[cfg(feature = "serde")]
 pub(crate) mod cstr_serde {
     use std::ffi::c_char;
@@ -0,0 +1,201 @@
+//! The [`CustomRecord`] type for user-defined records.
+
+use std::{fmt, mem, slice};
+
+use crate::{enums::rtype, Record, RecordHeader, RecordRef};
+
+/// An owned user-defined record with an rtype in the range reserved by
+/// [`rtype::USER_DEFINED_START`] and [`rtype::USER_DEFINED_END`] and an opaque
+/// payload. Allows embedding proprietary records in a DBN stream.
+///
+/// Encode it with [`EncodeRecordRef`](crate::encode::EncodeRecordRef) through
+/// [`as_record_ref()`](Self::as_record_ref). When decoding, check the rtype with
+/// [`rtype::is_user_defined()`] and convert the [`RecordRef`] with
+/// [`TryFrom`] or read its [`payload()`](RecordRef::payload) directly.
+///
+/// Because records are 8-byte aligned, the payload is padded with zeros to a multiple
+/// of 8 bytes.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CustomRecord {
+    /// `u64` for the alignment of the header.
+    buffer: Vec<u64>,
+}
+
+impl CustomRecord {
+    /// The maximum length of a payload in bytes.
+    pub const MAX_PAYLOAD_LEN: usize = (u8::MAX as usize * RecordHeader::LENGTH_MULTIPLIER)
+        / mem::size_of::<u64>()
+        * mem::size_of::<u64>()
+        - mem::size_of::<RecordHeader>();
+
+    /// Creates a new [`CustomRecord`] with the given header fields and `payload`.
+    ///
+    /// # Errors
+    /// This function returns an error if `rtype` isn't in the user-defined range or
+    /// `payload` is longer than [`MAX_PAYLOAD_LEN`](Self::MAX_PAYLOAD_LEN).
+    pub fn new(
+        rtype: u8,
+        publisher_id: u16,
+        instrument_id: u32,
+        ts_event: u64,
+        payload: &[u8],
+    ) -> crate::Result<Self> {
+        if !rtype::is_user_defined(rtype) {
+            return Err(crate::Error::BadArgument {
+                param_name: "rtype".to_owned(),
+                desc: format!(
+                    "{rtype:#04X} isn't in the user-defined range from {:#04X} to {:#04X}",
+                    rtype::USER_DEFINED_START,
+                    rtype::USER_DEFINED_END
+                ),
+            });
+        }
+        if payload.len() > Self::MAX_PAYLOAD_LEN {
+            return Err(crate::Error::BadArgument {
+                param_name: "payload".to_owned(),
+                desc: format!(
+                    "length of {} bytes exceeds the maximum of {} bytes",
+                    payload.len(),
+                    Self::MAX_PAYLOAD_LEN
+                ),
+            });
+        }
+        let header_len = mem::size_of::<RecordHeader>();
+        let size = (header_len + payload.len()).next_multiple_of(mem::size_of::<u64>());
+        let mut res = Self {
+            buffer: vec![0; size / mem::size_of::<u64>()],
+        };
+        let header = RecordHeader {
+            length: (size / RecordHeader::LENGTH_MULTIPLIER) as u8,
+            rtype,
+            publisher_id,
+            instrument_id,
+            ts_event,
+        };
+        // Safety: the buffer is aligned for and larger than a `RecordHeader`.
+        unsafe { res.buffer.as_mut_ptr().cast::<RecordHeader>().write(header) };
+        res.bytes_mut()[header_len..header_len + payload.len()].copy_from_slice(payload);
+        Ok(res)
+    }
+
+    /// Returns the payload following the header, including any padding.
+    pub fn payload(&self) -> &[u8] {
+        self.as_record_ref().payload()
+    }
+
+    /// Returns a reference to the record that can be encoded.
+    pub fn as_record_ref(&self) -> RecordRef<'_> {
+        // Safety: the buffer begins with a header whose length matches the buffer.
+        unsafe { RecordRef::new(self.as_ref()) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // Safety: `u8` has no alignment requirements and every byte is initialized.
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.buffer.as_mut_ptr().cast::<u8>(),
+                self.buffer.len() * mem::size_of::<u64>(),
+            )
+        }
+    }
+}
+
+impl TryFrom<RecordRef<'_>> for CustomRecord {
+    type Error = crate::Error;
+
+    /// Copies a user-defined record, e.g. from a decoder.
+    fn try_from(record: RecordRef) -> crate::Result<Self> {
+        let header = record.header();
+        Self::new(
+            header.rtype,
+            header.publisher_id,
+            header.instrument_id,
+            header.ts_event,
+            record.payload(),
+        )
+    }
+}
+
+impl Record for CustomRecord {
+    fn header(&self) -> &RecordHeader {
+        // Safety: the buffer always begins with a header.
+        unsafe { &*self.buffer.as_ptr().cast::<RecordHeader>() }
+    }
+}
+
+impl AsRef<[u8]> for CustomRecord {
+    fn as_ref(&self) -> &[u8] {
+        // Safety: `u8` has no alignment requirements and every byte is initialized.
+        unsafe {
+            slice::from_raw_parts(
+                self.buffer.as_ptr().cast::<u8>(),
+                self.buffer.len() * mem::size_of::<u64>(),
+            )
+        }
+    }
+}
+
+impl fmt::Debug for CustomRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomRecord")
+            .field("hd", self.header())
+            .field("payload", &self.payload())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode::{dbn::RecordDecoder, DecodeRecordRef},
+        encode::{dbn::RecordEncoder, EncodeRecordRef},
+    };
+
+    #[test]
+    fn test_round_trip() {
+        let record = CustomRecord::new(rtype::USER_DEFINED_START, 1, 2, 3, b"proprietary").unwrap();
+        assert_eq!(record.record_size(), 32);
+        assert_eq!(&record.payload()[..11], b"proprietary");
+        assert!(record.payload()[11..].iter().all(|b| *b == 0));
+        let mut buffer = Vec::new();
+        RecordEncoder::new(&mut buffer)
+            .encode_record_ref(record.as_record_ref())
+            .unwrap();
+        let mut decoder = RecordDecoder::new(buffer.as_slice());
+        let decoded = decoder.decode_record_ref().unwrap().unwrap();
+        assert!(rtype::is_user_defined(decoded.header().rtype));
+        assert_eq!(decoded.payload(), record.payload());
+        assert_eq!(CustomRecord::try_from(decoded).unwrap(), record);
+        assert!(decoder.decode_record_ref().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_max_payload() {
+        let record = CustomRecord::new(
+            rtype::USER_DEFINED_END,
+            1,
+            2,
+            3,
+            &[1; CustomRecord::MAX_PAYLOAD_LEN],
+        )
+        .unwrap();
+        assert_eq!(record.header().length, u8::MAX - 1);
+        assert!(CustomRecord::new(
+            rtype::USER_DEFINED_END,
+            1,
+            2,
+            3,
+            &[1; CustomRecord::MAX_PAYLOAD_LEN + 1]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_invalid_rtype() {
+        assert!(matches!(
+            CustomRecord::new(rtype::MBO, 1, 2, 3, &[]),
+            Err(crate::Error::BadArgument { .. })
+        ));
+    }
+}
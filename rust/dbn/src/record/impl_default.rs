@@ -114,6 +114,59 @@ impl OhlcvMsg {
     }
 }
 
+impl Default for ConsolidatedBidAskPair {
+    fn default() -> Self {
+        Self {
+            bid_px: UNDEF_PRICE,
+            ask_px: UNDEF_PRICE,
+            bid_sz: 0,
+            ask_sz: 0,
+            bid_pb: 0,
+            _reserved1: Default::default(),
+            ask_pb: 0,
+            _reserved2: Default::default(),
+        }
+    }
+}
+
+impl BboMsg {
+    /// Creates a new default BBO record for the given `schema`.
+    pub fn default_for_schema(schema: Schema) -> Self {
+        Self {
+            hd: RecordHeader::default::<Self>(RType::from(schema) as u8),
+            price: UNDEF_PRICE,
+            size: UNDEF_ORDER_SIZE,
+            _reserved1: 0,
+            side: Side::None as c_char,
+            flags: 0,
+            _reserved2: 0,
+            ts_recv: UNDEF_TIMESTAMP,
+            _reserved3: Default::default(),
+            sequence: 0,
+            levels: Default::default(),
+        }
+    }
+}
+
+impl CbboMsg {
+    /// Creates a new default consolidated BBO record for the given `schema`.
+    pub fn default_for_schema(schema: Schema) -> Self {
+        Self {
+            hd: RecordHeader::default::<Self>(RType::from(schema) as u8),
+            price: UNDEF_PRICE,
+            size: UNDEF_ORDER_SIZE,
+            _reserved1: 0,
+            side: Side::None as c_char,
+            flags: 0,
+            _reserved2: 0,
+            ts_recv: UNDEF_TIMESTAMP,
+            _reserved3: Default::default(),
+            sequence: 0,
+            levels: Default::default(),
+        }
+    }
+}
+
 impl Default for StatusMsg {
     fn default() -> Self {
         Self {
@@ -385,3 +438,69 @@ impl Default for SystemMsgV1 {
         }
     }
 }
+
+macro_rules! impl_builder {
+    ($($rec:ident => $builder:ident),+ $(,)?) => {
+        $(
+            impl $rec {
+                /// Creates a new builder starting from the default record.
+                pub fn builder() -> $builder {
+                    $builder::default()
+                }
+
+                /// Creates a new default record with `instrument_id` and `ts_event` set
+                /// in the header.
+                pub fn default_with(instrument_id: u32, ts_event: u64) -> Self {
+                    let mut res = Self::default();
+                    res.hd.instrument_id = instrument_id;
+                    res.hd.ts_event = ts_event;
+                    res
+                }
+            }
+
+            impl Default for $builder {
+                fn default() -> Self {
+                    Self::from($rec::default())
+                }
+            }
+        )+
+    };
+}
+
+impl_builder!(
+    MboMsg => MboMsgBuilder,
+    TradeMsg => TradeMsgBuilder,
+    Mbp1Msg => Mbp1MsgBuilder,
+    Mbp10Msg => Mbp10MsgBuilder,
+    StatusMsg => StatusMsgBuilder,
+    InstrumentDefMsg => InstrumentDefMsgBuilder,
+    ImbalanceMsg => ImbalanceMsgBuilder,
+    StatMsg => StatMsgBuilder,
+    ErrorMsg => ErrorMsgBuilder,
+    SymbolMappingMsg => SymbolMappingMsgBuilder,
+    SystemMsg => SystemMsgBuilder,
+);
+
+impl OhlcvMsg {
+    /// Creates a new builder starting from the default OHLCV bar for the given
+    /// `schema`.
+    pub fn builder(schema: Schema) -> OhlcvMsgBuilder {
+        OhlcvMsgBuilder::from(Self::default_for_schema(schema))
+    }
+}
+
+impl BboMsg {
+    /// Creates a new builder starting from the default BBO record for the given
+    /// `schema`.
+    pub fn builder(schema: Schema) -> BboMsgBuilder {
+        BboMsgBuilder::from(Self::default_for_schema(schema))
+    }
+}
+
+impl CbboMsg {
+    /// Creates a new builder starting from the default consolidated BBO record for the
+    /// given `schema`.
+    pub fn builder(schema: Schema) -> CbboMsgBuilder {
+        CbboMsgBuilder::from(Self::default_for_schema(schema))
+    }
+}
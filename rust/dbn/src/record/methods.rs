@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+};
 
 use num_enum::TryFromPrimitive;
 
@@ -119,6 +122,26 @@ impl MboMsg {
     pub fn ts_in_delta(&self) -> time::Duration {
         time::Duration::new(0, self.ts_in_delta)
     }
+
+    /// Returns `price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_f64(&self) -> f64 {
+        px_to_f64(self.price)
+    }
+}
+
+impl BidAskPair {
+    /// Returns `bid_px` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn bid_px_f64(&self) -> f64 {
+        px_to_f64(self.bid_px)
+    }
+
+    /// Returns `ask_px` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn ask_px_f64(&self) -> f64 {
+        px_to_f64(self.ask_px)
+    }
 }
 
 impl TradeMsg {
@@ -152,9 +175,75 @@ impl TradeMsg {
     pub fn ts_in_delta(&self) -> time::Duration {
         time::Duration::new(0, self.ts_in_delta)
     }
+
+    /// Returns `price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_f64(&self) -> f64 {
+        px_to_f64(self.price)
+    }
+}
+
+impl TryFrom<&MboMsg> for TradeMsg {
+    type Error = crate::Error;
+
+    /// Converts a trade event from the MBO schema to the record of the
+    /// [`Trades`](crate::enums::Schema::Trades) schema. The `depth` is always 0.
+    ///
+    /// # Errors
+    /// This function returns an error if the `action` of `mbo` isn't
+    /// [`Action::Trade`]. Fills aren't converted because they carry the side of the
+    /// resting order and repeat the trade of the preceding **T**rade event.
+    fn try_from(mbo: &MboMsg) -> crate::Result<Self> {
+        if mbo.action != Action::Trade as c_char {
+            return Err(Error::conversion::<Self>(format!(
+                "MboMsg with action {:#04X}",
+                mbo.action as u8
+            )));
+        }
+        Ok(Self {
+            hd: RecordHeader::new::<Self>(
+                rtype::MBP_0,
+                mbo.hd.publisher_id,
+                mbo.hd.instrument_id,
+                mbo.hd.ts_event,
+            ),
+            price: mbo.price,
+            size: mbo.size,
+            action: Action::Trade as c_char,
+            side: mbo.side,
+            flags: mbo.flags,
+            depth: 0,
+            ts_recv: mbo.ts_recv,
+            ts_in_delta: mbo.ts_in_delta,
+            sequence: mbo.sequence,
+        })
+    }
 }
 
 impl Mbp1Msg {
+    /// Creates a new `Mbp1Msg` from `trade` and the top of the book prior to the
+    /// trade, i.e. the record of the [`Tbbo`](crate::enums::Schema::Tbbo) schema.
+    pub fn from_trade_and_book(trade: &TradeMsg, book: BidAskPair) -> Self {
+        Self {
+            hd: RecordHeader::new::<Self>(
+                rtype::MBP_1,
+                trade.hd.publisher_id,
+                trade.hd.instrument_id,
+                trade.hd.ts_event,
+            ),
+            price: trade.price,
+            size: trade.size,
+            action: trade.action,
+            side: trade.side,
+            flags: trade.flags,
+            depth: trade.depth,
+            ts_recv: trade.ts_recv,
+            ts_in_delta: trade.ts_in_delta,
+            sequence: trade.sequence,
+            levels: [book],
+        }
+    }
+
     /// Tries to convert the raw `side` to an enum.
     ///
     /// # Errors
@@ -185,6 +274,12 @@ impl Mbp1Msg {
     pub fn ts_in_delta(&self) -> time::Duration {
         time::Duration::new(0, self.ts_in_delta)
     }
+
+    /// Returns `price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_f64(&self) -> f64 {
+        px_to_f64(self.price)
+    }
 }
 
 impl Mbp10Msg {
@@ -218,9 +313,137 @@ impl Mbp10Msg {
     pub fn ts_in_delta(&self) -> time::Duration {
         time::Duration::new(0, self.ts_in_delta)
     }
+
+    /// Returns `price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_f64(&self) -> f64 {
+        px_to_f64(self.price)
+    }
+}
+
+impl BboMsg {
+    /// Tries to convert the raw `side` to an enum.
+    ///
+    /// # Errors
+    /// This function returns an error if the `side` field does not
+    /// contain a valid [`Side`].
+    pub fn side(&self) -> Result<Side> {
+        Side::try_from(self.side as u8)
+            .map_err(|_| Error::conversion::<Side>(format!("{:#04X}", self.side as u8)))
+    }
+
+    /// Parses the raw interval end timestamp into a datetime. Returns `None`
+    /// if `ts_recv` contains the sentinel for a null timestamp.
+    pub fn ts_recv(&self) -> Option<time::OffsetDateTime> {
+        ts_to_dt(self.ts_recv)
+    }
+
+    /// Returns `price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_f64(&self) -> f64 {
+        px_to_f64(self.price)
+    }
+}
+
+impl ConsolidatedBidAskPair {
+    /// Returns `bid_px` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn bid_px_f64(&self) -> f64 {
+        px_to_f64(self.bid_px)
+    }
+
+    /// Returns `ask_px` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn ask_px_f64(&self) -> f64 {
+        px_to_f64(self.ask_px)
+    }
+}
+
+impl CbboMsg {
+    /// Tries to convert the raw `side` to an enum.
+    ///
+    /// # Errors
+    /// This function returns an error if the `side` field does not
+    /// contain a valid [`Side`].
+    pub fn side(&self) -> Result<Side> {
+        Side::try_from(self.side as u8)
+            .map_err(|_| Error::conversion::<Side>(format!("{:#04X}", self.side as u8)))
+    }
+
+    /// Parses the raw capture-server-received timestamp into a datetime. Returns `None`
+    /// if `ts_recv` contains the sentinel for a null timestamp.
+    pub fn ts_recv(&self) -> Option<time::OffsetDateTime> {
+        ts_to_dt(self.ts_recv)
+    }
+
+    /// Returns `price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_f64(&self) -> f64 {
+        px_to_f64(self.price)
+    }
+}
+
+impl OhlcvMsg {
+    /// Creates a new bar from the first `trade` in the aggregation period starting at
+    /// `ts_event`. `rtype` should be one of the OHLCV rtypes, e.g.
+    /// [`rtype::OHLCV_1M`].
+    pub fn from_trade(rtype: u8, ts_event: u64, trade: &TradeMsg) -> Self {
+        Self {
+            hd: RecordHeader::new::<Self>(
+                rtype,
+                trade.hd.publisher_id,
+                trade.hd.instrument_id,
+                ts_event,
+            ),
+            open: trade.price,
+            high: trade.price,
+            low: trade.price,
+            close: trade.price,
+            volume: u64::from(trade.size),
+        }
+    }
+
+    /// Updates the bar with a subsequent `trade` in the same aggregation period.
+    /// Trades should be added in the order they occurred.
+    pub fn add_trade(&mut self, trade: &TradeMsg) {
+        self.high = self.high.max(trade.price);
+        self.low = self.low.min(trade.price);
+        self.close = trade.price;
+        self.volume += u64::from(trade.size);
+    }
+
+    /// Returns `open` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn open_f64(&self) -> f64 {
+        px_to_f64(self.open)
+    }
+
+    /// Returns `high` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn high_f64(&self) -> f64 {
+        px_to_f64(self.high)
+    }
+
+    /// Returns `low` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn low_f64(&self) -> f64 {
+        px_to_f64(self.low)
+    }
+
+    /// Returns `close` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn close_f64(&self) -> f64 {
+        px_to_f64(self.close)
+    }
 }
 
 impl StatusMsg {
+    /// Parses the raw capture-server-received timestamp into a datetime. Returns `None`
+    /// if `ts_recv` contains the sentinel for a null timestamp.
+    pub fn ts_recv(&self) -> Option<time::OffsetDateTime> {
+        ts_to_dt(self.ts_recv)
+    }
+
     /// Tries to convert the raw status action to an enum.
     ///
     /// # Errors
@@ -303,6 +526,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.currency)
     }
 
+    /// Sets `currency` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `currency` is longer than 3 bytes or
+    /// contains a null byte.
+    pub fn set_currency(&mut self, currency: &str) -> Result<()> {
+        self.currency = str_to_c_chars(currency)?;
+        Ok(())
+    }
+
     /// Returns currency used for settlement as a `&str`.
     ///
     /// # Errors
@@ -311,6 +544,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.settl_currency)
     }
 
+    /// Sets `settl_currency` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `settl_currency` is longer than 3 bytes or
+    /// contains a null byte.
+    pub fn set_settl_currency(&mut self, settl_currency: &str) -> Result<()> {
+        self.settl_currency = str_to_c_chars(settl_currency)?;
+        Ok(())
+    }
+
     /// Returns the strategy type of the spread as a `&str`.
     ///
     /// # Errors
@@ -319,6 +562,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.secsubtype)
     }
 
+    /// Sets `secsubtype` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `secsubtype` is longer than 5 bytes or
+    /// contains a null byte.
+    pub fn set_secsubtype(&mut self, secsubtype: &str) -> Result<()> {
+        self.secsubtype = str_to_c_chars(secsubtype)?;
+        Ok(())
+    }
+
     /// Returns the instrument raw symbol assigned by the publisher as a `&str`.
     ///
     /// # Errors
@@ -327,6 +580,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.raw_symbol)
     }
 
+    /// Sets `raw_symbol` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `raw_symbol` is longer than `SYMBOL_CSTR_LEN - 1` bytes or
+    /// contains a null byte.
+    pub fn set_raw_symbol(&mut self, raw_symbol: &str) -> Result<()> {
+        self.raw_symbol = str_to_c_chars(raw_symbol)?;
+        Ok(())
+    }
+
     /// Returns exchange used to identify the instrument as a `&str`.
     ///
     /// # Errors
@@ -335,6 +598,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.exchange)
     }
 
+    /// Sets `exchange` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `exchange` is longer than 4 bytes or
+    /// contains a null byte.
+    pub fn set_exchange(&mut self, exchange: &str) -> Result<()> {
+        self.exchange = str_to_c_chars(exchange)?;
+        Ok(())
+    }
+
     /// Returns the underlying asset code (product code) of the instrument as a `&str`.
     ///
     /// # Errors
@@ -343,6 +616,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.asset)
     }
 
+    /// Sets `asset` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `asset` is longer than 6 bytes or
+    /// contains a null byte.
+    pub fn set_asset(&mut self, asset: &str) -> Result<()> {
+        self.asset = str_to_c_chars(asset)?;
+        Ok(())
+    }
+
     /// Returns the ISO standard instrument categorization code as a `&str`.
     ///
     /// # Errors
@@ -351,6 +634,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.cfi)
     }
 
+    /// Sets `cfi` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `cfi` is longer than 6 bytes or
+    /// contains a null byte.
+    pub fn set_cfi(&mut self, cfi: &str) -> Result<()> {
+        self.cfi = str_to_c_chars(cfi)?;
+        Ok(())
+    }
+
     /// Returns the type of the strument, e.g. FUT for future or future spread as
     /// a `&str`.
     ///
@@ -360,6 +653,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.security_type)
     }
 
+    /// Sets `security_type` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `security_type` is longer than 6 bytes or
+    /// contains a null byte.
+    pub fn set_security_type(&mut self, security_type: &str) -> Result<()> {
+        self.security_type = str_to_c_chars(security_type)?;
+        Ok(())
+    }
+
     /// Returns the unit of measure for the instrument's original contract size, e.g.
     /// USD or LBS, as a `&str`.
     ///
@@ -369,6 +672,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.unit_of_measure)
     }
 
+    /// Sets `unit_of_measure` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `unit_of_measure` is longer than 30 bytes or
+    /// contains a null byte.
+    pub fn set_unit_of_measure(&mut self, unit_of_measure: &str) -> Result<()> {
+        self.unit_of_measure = str_to_c_chars(unit_of_measure)?;
+        Ok(())
+    }
+
     /// Returns the symbol of the first underlying instrument as a `&str`.
     ///
     /// # Errors
@@ -377,6 +690,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.underlying)
     }
 
+    /// Sets `underlying` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `underlying` is longer than 20 bytes or
+    /// contains a null byte.
+    pub fn set_underlying(&mut self, underlying: &str) -> Result<()> {
+        self.underlying = str_to_c_chars(underlying)?;
+        Ok(())
+    }
+
     /// Returns the currency of [`strike_price`](Self::strike_price) as a `&str`.
     ///
     /// # Errors
@@ -385,6 +708,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.strike_price_currency)
     }
 
+    /// Sets `strike_price_currency` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `strike_price_currency` is longer than 3 bytes or
+    /// contains a null byte.
+    pub fn set_strike_price_currency(&mut self, strike_price_currency: &str) -> Result<()> {
+        self.strike_price_currency = str_to_c_chars(strike_price_currency)?;
+        Ok(())
+    }
+
     /// Returns the security group code of the instrumnet as a `&str`.
     ///
     /// # Errors
@@ -393,6 +726,16 @@ impl InstrumentDefMsg {
         c_chars_to_str(&self.group)
     }
 
+    /// Sets `group` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `group` is longer than 20 bytes or
+    /// contains a null byte.
+    pub fn set_group(&mut self, group: &str) -> Result<()> {
+        self.group = str_to_c_chars(group)?;
+        Ok(())
+    }
+
     /// Tries to convert the raw classification of the instrument to an enum.
     ///
     /// # Errors
@@ -429,6 +772,60 @@ impl InstrumentDefMsg {
             ))
         })
     }
+
+    /// Returns `min_price_increment` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn min_price_increment_f64(&self) -> f64 {
+        px_to_f64(self.min_price_increment)
+    }
+
+    /// Returns `high_limit_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn high_limit_price_f64(&self) -> f64 {
+        px_to_f64(self.high_limit_price)
+    }
+
+    /// Returns `low_limit_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn low_limit_price_f64(&self) -> f64 {
+        px_to_f64(self.low_limit_price)
+    }
+
+    /// Returns `max_price_variation` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn max_price_variation_f64(&self) -> f64 {
+        px_to_f64(self.max_price_variation)
+    }
+
+    /// Returns `trading_reference_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn trading_reference_price_f64(&self) -> f64 {
+        px_to_f64(self.trading_reference_price)
+    }
+
+    /// Returns `unit_of_measure_qty` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn unit_of_measure_qty_f64(&self) -> f64 {
+        px_to_f64(self.unit_of_measure_qty)
+    }
+
+    /// Returns `min_price_increment_amount` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn min_price_increment_amount_f64(&self) -> f64 {
+        px_to_f64(self.min_price_increment_amount)
+    }
+
+    /// Returns `price_ratio` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_ratio_f64(&self) -> f64 {
+        px_to_f64(self.price_ratio)
+    }
+
+    /// Returns `strike_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn strike_price_f64(&self) -> f64 {
+        px_to_f64(self.strike_price)
+    }
 }
 
 impl InstrumentDefMsgV1 {
@@ -572,11 +969,73 @@ impl InstrumentDefMsgV1 {
 }
 
 impl ImbalanceMsg {
+    /// Tries to convert the raw market side of the `total_imbalance_qty` to an enum.
+    ///
+    /// # Errors
+    /// This function returns an error if the `side` field does not
+    /// contain a valid [`Side`].
+    pub fn side(&self) -> Result<Side> {
+        Side::try_from(self.side as u8)
+            .map_err(|_| Error::conversion::<Side>(format!("{:#04X}", self.side as u8)))
+    }
+
+    /// Tries to convert the raw market side of the `unpaired_qty` to an enum.
+    ///
+    /// # Errors
+    /// This function returns an error if the `unpaired_side` field does not
+    /// contain a valid [`Side`].
+    pub fn unpaired_side(&self) -> Result<Side> {
+        Side::try_from(self.unpaired_side as u8)
+            .map_err(|_| Error::conversion::<Side>(format!("{:#04X}", self.unpaired_side as u8)))
+    }
+
     /// Parses the raw capture-server-received timestamp into a datetime. Returns `None`
     /// if `ts_recv` contains the sentinel for a null timestamp.
     pub fn ts_recv(&self) -> Option<time::OffsetDateTime> {
         ts_to_dt(self.ts_recv)
     }
+
+    /// Returns `ref_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn ref_price_f64(&self) -> f64 {
+        px_to_f64(self.ref_price)
+    }
+
+    /// Returns `cont_book_clr_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn cont_book_clr_price_f64(&self) -> f64 {
+        px_to_f64(self.cont_book_clr_price)
+    }
+
+    /// Returns `auct_interest_clr_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn auct_interest_clr_price_f64(&self) -> f64 {
+        px_to_f64(self.auct_interest_clr_price)
+    }
+
+    /// Returns `ssr_filling_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn ssr_filling_price_f64(&self) -> f64 {
+        px_to_f64(self.ssr_filling_price)
+    }
+
+    /// Returns `ind_match_price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn ind_match_price_f64(&self) -> f64 {
+        px_to_f64(self.ind_match_price)
+    }
+
+    /// Returns `upper_collar` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn upper_collar_f64(&self) -> f64 {
+        px_to_f64(self.upper_collar)
+    }
+
+    /// Returns `lower_collar` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn lower_collar_f64(&self) -> f64 {
+        px_to_f64(self.lower_collar)
+    }
 }
 
 impl StatMsg {
@@ -612,6 +1071,12 @@ impl StatMsg {
             Error::conversion::<StatUpdateAction>(format!("{:04X}", self.update_action))
         })
     }
+
+    /// Returns `price` as an `f64`, with [`UNDEF_PRICE`](crate::UNDEF_PRICE) mapped
+    /// to NaN.
+    pub fn price_f64(&self) -> f64 {
+        px_to_f64(self.price)
+    }
 }
 
 impl ErrorMsgV1 {
@@ -665,6 +1130,16 @@ impl ErrorMsg {
     pub fn err(&self) -> Result<&str> {
         c_chars_to_str(&self.err)
     }
+
+    /// Sets `err` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `err` is longer than 301 bytes or
+    /// contains a null byte.
+    pub fn set_err(&mut self, err: &str) -> Result<()> {
+        self.err = str_to_c_chars(err)?;
+        Ok(())
+    }
 }
 
 impl SymbolMappingMsg {
@@ -712,6 +1187,16 @@ impl SymbolMappingMsg {
         c_chars_to_str(&self.stype_in_symbol)
     }
 
+    /// Sets `stype_in_symbol` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `stype_in_symbol` is longer than `SYMBOL_CSTR_LEN - 1` bytes or
+    /// contains a null byte.
+    pub fn set_stype_in_symbol(&mut self, stype_in_symbol: &str) -> Result<()> {
+        self.stype_in_symbol = str_to_c_chars(stype_in_symbol)?;
+        Ok(())
+    }
+
     /// Returns the output symbology type.
     ///
     /// # Errors
@@ -728,6 +1213,16 @@ impl SymbolMappingMsg {
         c_chars_to_str(&self.stype_out_symbol)
     }
 
+    /// Sets `stype_out_symbol` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `stype_out_symbol` is longer than `SYMBOL_CSTR_LEN - 1` bytes or
+    /// contains a null byte.
+    pub fn set_stype_out_symbol(&mut self, stype_out_symbol: &str) -> Result<()> {
+        self.stype_out_symbol = str_to_c_chars(stype_out_symbol)?;
+        Ok(())
+    }
+
     /// Parses the raw start of the mapping interval into a datetime. Returns `None` if
     /// `start_ts` contains the sentinel for a null timestamp.
     pub fn start_ts(&self) -> Option<time::OffsetDateTime> {
@@ -834,6 +1329,16 @@ impl SystemMsg {
     pub fn msg(&self) -> Result<&str> {
         c_chars_to_str(&self.msg)
     }
+
+    /// Sets `msg` from a `&str`, adding a null terminator.
+    ///
+    /// # Errors
+    /// This function returns an error if `msg` is longer than 302 bytes or
+    /// contains a null byte.
+    pub fn set_msg(&mut self, msg: &str) -> Result<()> {
+        self.msg = str_to_c_chars(msg)?;
+        Ok(())
+    }
 }
 
 impl SystemMsgV1 {
@@ -904,6 +1409,20 @@ where
     }
 }
 
+impl<T: HasRType> Deref for WithTsOut<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rec
+    }
+}
+
+impl<T: HasRType> DerefMut for WithTsOut<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rec
+    }
+}
+
 impl<T: HasRType> WithTsOut<T> {
     /// Creates a new record with `ts_out`. Updates the `length` property in
     /// [`RecordHeader`] to ensure the additional field is accounted for.
@@ -922,6 +1441,8 @@ impl<T: HasRType> WithTsOut<T> {
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use crate::flags;
 
     use super::*;
@@ -1020,4 +1541,183 @@ mod tests {
             "SymbolMappingMsg { hd: RecordHeader { length: 44, rtype: SymbolMapping, publisher_id: 0, instrument_id: 5602, ts_event: 1704466940331347283 }, stype_in: RawSymbol, stype_in_symbol: \"ESM4\", stype_out: RawSymbol, stype_out_symbol: \"ESM4\", start_ts: 18446744073709551615, end_ts: 18446744073709551615 }"
         );
     }
+
+    #[test]
+    fn instrument_def_str_accessors() {
+        let rec = InstrumentDefMsg::builder()
+            .currency("USD")
+            .unwrap()
+            .raw_symbol("ESM4")
+            .unwrap()
+            .group("ES")
+            .unwrap()
+            .exchange("XCME")
+            .unwrap()
+            .asset("ES")
+            .unwrap()
+            .cfi("FFIXSX")
+            .unwrap()
+            .build();
+        assert_eq!(rec.currency().unwrap(), "USD");
+        assert_eq!(rec.raw_symbol().unwrap(), "ESM4");
+        assert_eq!(rec.group().unwrap(), "ES");
+        assert_eq!(rec.exchange().unwrap(), "XCME");
+        assert_eq!(rec.asset().unwrap(), "ES");
+        assert_eq!(rec.cfi().unwrap(), "FFIXSX");
+        assert_eq!(rec.underlying().unwrap(), "");
+        assert_eq!(rec.strike_price_currency().unwrap(), "");
+    }
+
+    #[test]
+    fn set_str_fields() {
+        let mut rec = InstrumentDefMsg::default();
+        rec.set_raw_symbol("ESM4").unwrap();
+        rec.set_exchange("XCME").unwrap();
+        assert_eq!(rec.raw_symbol().unwrap(), "ESM4");
+        assert_eq!(rec.exchange().unwrap(), "XCME");
+        // Too long
+        assert!(matches!(rec.set_exchange("XNYSE"), Err(Error::Encode(_))));
+        assert!(matches!(rec.set_currency("US\0D"), Err(Error::Encode(_))));
+        // Unchanged after an error
+        assert_eq!(rec.exchange().unwrap(), "XCME");
+        let mut rec = SymbolMappingMsg::default();
+        rec.set_stype_out_symbol("ESM4").unwrap();
+        assert!(rec
+            .set_stype_in_symbol(&"A".repeat(SYMBOL_CSTR_LEN))
+            .is_err());
+        assert_eq!(rec.stype_out_symbol().unwrap(), "ESM4");
+    }
+
+    #[test]
+    fn datetime_accessors() {
+        let rec = StatusMsg {
+            hd: RecordHeader::new::<StatusMsg>(rtype::STATUS, 0, 1, 1_704_468_548_242_628_731),
+            ..Default::default()
+        };
+        assert_eq!(
+            rec.ts_event().unwrap(),
+            time::macros::datetime!(2024-01-05 15:29:08.242628731 UTC)
+        );
+        assert!(rec.ts_recv().is_none());
+        let rec = InstrumentDefMsg::default();
+        assert!(rec.ts_event().is_none());
+        assert!(rec.expiration().is_none());
+    }
+
+    #[test]
+    fn imbalance_sides() {
+        let rec = ImbalanceMsg {
+            side: b'A' as c_char,
+            unpaired_side: b'X' as c_char,
+            ..Default::default()
+        };
+        assert_eq!(rec.side().unwrap(), Side::Ask);
+        assert!(matches!(rec.unpaired_side(), Err(Error::Conversion { .. })));
+    }
+
+    #[test]
+    fn with_ts_out_deref() {
+        let mut rec = WithTsOut::new(
+            MboMsg {
+                price: 4_500_500_000_000,
+                ..Default::default()
+            },
+            1,
+        );
+        assert_eq!(rec.price, 4_500_500_000_000);
+        assert_eq!(rec.price_f64(), 4500.5);
+        rec.size = 5;
+        assert_eq!(rec.rec.size, 5);
+        // Uses the length including `ts_out`
+        assert_eq!(rec.record_size(), mem::size_of::<WithTsOut<MboMsg>>());
+    }
+
+    #[test]
+    fn trade_from_mbo_trade() {
+        let mbo = MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 323, 1658441851000000000),
+            price: 4_500_500_000_000,
+            size: 3,
+            flags: flags::LAST,
+            action: b'T' as c_char,
+            side: b'A' as c_char,
+            sequence: 1067,
+            ..Default::default()
+        };
+        let trade = TradeMsg::try_from(&mbo).unwrap();
+        assert_eq!(trade.hd.rtype, rtype::MBP_0);
+        assert_eq!(trade.record_size(), mem::size_of::<TradeMsg>());
+        assert_eq!(trade.hd.instrument_id, 323);
+        assert_eq!(trade.action().unwrap(), Action::Trade);
+        assert_eq!(trade.side().unwrap(), Side::Ask);
+        assert_eq!(trade.price, mbo.price);
+        assert_eq!(trade.size, 3);
+        assert_eq!(trade.flags, flags::LAST);
+        assert_eq!(trade.sequence, 1067);
+    }
+
+    #[rstest]
+    #[case::fill(b'F')]
+    #[case::add(b'A')]
+    #[case::cancel(b'C')]
+    #[case::modify(b'M')]
+    fn trade_from_mbo_non_trade(#[case] action: u8) {
+        let mbo = MboMsg {
+            action: action as c_char,
+            side: b'B' as c_char,
+            ..Default::default()
+        };
+        assert!(matches!(
+            TradeMsg::try_from(&mbo),
+            Err(Error::Conversion { .. })
+        ));
+    }
+
+    #[test]
+    fn mbp1_from_trade_and_book() {
+        let trade = TradeMsg {
+            hd: RecordHeader::new::<TradeMsg>(rtype::MBP_0, 1, 323, 1658441851000000000),
+            price: 100,
+            size: 2,
+            action: b'T' as c_char,
+            side: b'B' as c_char,
+            ..Default::default()
+        };
+        let book = BidAskPair {
+            bid_px: 99,
+            ask_px: 100,
+            bid_sz: 5,
+            ask_sz: 2,
+            bid_ct: 1,
+            ask_ct: 1,
+        };
+        let tbbo = Mbp1Msg::from_trade_and_book(&trade, book.clone());
+        assert_eq!(tbbo.hd.rtype, rtype::MBP_1);
+        assert_eq!(tbbo.record_size(), mem::size_of::<Mbp1Msg>());
+        assert_eq!(tbbo.hd.ts_event, trade.hd.ts_event);
+        assert_eq!(tbbo.price, 100);
+        assert_eq!(tbbo.action, trade.action);
+        assert_eq!(tbbo.levels, [book]);
+    }
+
+    #[test]
+    fn ohlcv_from_trades() {
+        let trade = |price, size| TradeMsg {
+            hd: RecordHeader::new::<TradeMsg>(rtype::MBP_0, 1, 323, 0),
+            price,
+            size,
+            ..Default::default()
+        };
+        let mut bar = OhlcvMsg::from_trade(rtype::OHLCV_1M, 60_000_000_000, &trade(10, 1));
+        for (price, size) in [(12, 2), (8, 3), (11, 4)] {
+            bar.add_trade(&trade(price, size));
+        }
+        assert_eq!(bar.hd.rtype, rtype::OHLCV_1M);
+        assert_eq!(bar.hd.ts_event, 60_000_000_000);
+        assert_eq!(bar.record_size(), mem::size_of::<OhlcvMsg>());
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close, bar.volume),
+            (10, 12, 8, 11, 10)
+        );
+    }
 }
@@ -0,0 +1,179 @@
+//! The [`RecordArena`] struct for owning batches of DBN records without an allocation
+//! per record.
+
+use std::{mem, slice};
+
+use crate::{decode::DecodeRecordRef, RecordRef, RecordRefEnum};
+
+/// An owned batch of DBN records of mixed types stored contiguously in a single
+/// buffer. Each record is bump-allocated at the end of the buffer and all records are
+/// freed at once with [`clear()`](Self::clear), which retains the capacity for the
+/// next batch. Every record is 8-byte aligned, like the records of a decoder.
+///
+/// Unlike collecting [`RecordEnum`](crate::RecordEnum)s, each record only takes up
+/// its own size and references to the records aren't tied to the lifetime of the
+/// decoder.
+#[derive(Debug, Clone, Default)]
+pub struct RecordArena {
+    /// `u64` for the alignment of the record headers.
+    buffer: Vec<u64>,
+    /// The index in `buffer` of the start of each record.
+    offsets: Vec<usize>,
+}
+
+impl RecordArena {
+    /// Creates a new empty [`RecordArena`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new empty [`RecordArena`] with capacity for at least `capacity`
+    /// bytes of records.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: Vec::with_capacity(capacity.div_ceil(mem::size_of::<u64>())),
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Copies `record` to the end of the arena.
+    pub fn push(&mut self, record: RecordRef) {
+        let bytes = record.as_ref();
+        let start = self.buffer.len();
+        // Pad the record to a whole number of `u64`s so the next record is also aligned
+        self.buffer
+            .resize(start + bytes.len().div_ceil(mem::size_of::<u64>()), 0);
+        // Safety: `u8` has no alignment requirements and every element is initialized.
+        let buffer_bytes = unsafe {
+            slice::from_raw_parts_mut(
+                self.buffer.as_mut_ptr().cast::<u8>(),
+                self.buffer.len() * mem::size_of::<u64>(),
+            )
+        };
+        let byte_start = start * mem::size_of::<u64>();
+        buffer_bytes[byte_start..byte_start + bytes.len()].copy_from_slice(bytes);
+        self.offsets.push(start);
+    }
+
+    /// Clears the arena, then decodes up to `max_records` records from `decoder` into
+    /// it. Returns the number of records decoded, which is less than `max_records`
+    /// only once `decoder` is exhausted.
+    ///
+    /// # Errors
+    /// This function returns an error if `decoder` returns an error.
+    pub fn decode_batch<D: DecodeRecordRef>(
+        &mut self,
+        decoder: &mut D,
+        max_records: usize,
+    ) -> crate::Result<usize> {
+        self.clear();
+        while self.len() < max_records {
+            let Some(record) = decoder.decode_record_ref()? else {
+                break;
+            };
+            self.push(record);
+        }
+        Ok(self.len())
+    }
+
+    /// Removes all records from the arena, retaining its capacity.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.offsets.clear();
+    }
+
+    /// Returns the number of records in the arena.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if the arena contains no records.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns a reference to the record at `index` or `None` if `index` is out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<RecordRef<'_>> {
+        self.offsets
+            .get(index)
+            .map(|&offset| self.record_at(offset))
+    }
+
+    /// Returns an iterator over references to the records in the arena.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = RecordRef<'_>> + '_ {
+        self.offsets.iter().map(|&offset| self.record_at(offset))
+    }
+
+    /// Returns an iterator over the records in the arena as [`RecordRefEnum`]s.
+    ///
+    /// # Errors
+    /// An item is an error if the rtype of the record doesn't correspond with any
+    /// known DBN record type.
+    pub fn iter_enum(&self) -> impl ExactSizeIterator<Item = crate::Result<RecordRefEnum<'_>>> {
+        self.iter().map(RecordRefEnum::try_from)
+    }
+
+    fn record_at(&self, offset: usize) -> RecordRef<'_> {
+        // Safety: `offset` is the start of a complete record copied from a `RecordRef`
+        // and `u8` has no alignment requirements.
+        unsafe {
+            let remaining = &self.buffer[offset..];
+            RecordRef::new(slice::from_raw_parts(
+                remaining.as_ptr().cast::<u8>(),
+                remaining.len() * mem::size_of::<u64>(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode::{dbn::Decoder, tests::TEST_DATA_PATH},
+        rtype, MboMsg, RecordHeader, SystemMsg,
+    };
+
+    #[test]
+    fn test_push_and_iter() {
+        let mbo = MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 5482, 0),
+            order_id: 17,
+            ..Default::default()
+        };
+        let heartbeat = SystemMsg::heartbeat(1);
+        let mut target = RecordArena::new();
+        target.push(RecordRef::from(&mbo));
+        target.push(RecordRef::from(&heartbeat));
+        target.push(RecordRef::from(&mbo));
+        assert_eq!(target.len(), 3);
+        assert_eq!(target.get(0).unwrap().get::<MboMsg>(), Some(&mbo));
+        assert_eq!(target.get(1).unwrap().get::<SystemMsg>(), Some(&heartbeat));
+        assert!(target.get(3).is_none());
+        for rec in target.iter() {
+            let ptr = rec.header() as *const RecordHeader;
+            assert_eq!(ptr as usize % mem::align_of::<RecordHeader>(), 0);
+        }
+        assert!(matches!(
+            target.iter_enum().nth(2).unwrap().unwrap(),
+            RecordRefEnum::Mbo(rec) if rec == &mbo
+        ));
+        let capacity = target.buffer.capacity();
+        target.clear();
+        assert!(target.is_empty());
+        assert_eq!(target.buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_decode_batch() {
+        let mut decoder =
+            Decoder::from_zstd_file(format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst")).unwrap();
+        let mut target = RecordArena::new();
+        assert_eq!(target.decode_batch(&mut decoder, 1).unwrap(), 1);
+        let first = target.get(0).unwrap().get::<MboMsg>().unwrap().clone();
+        assert_eq!(target.decode_batch(&mut decoder, 10).unwrap(), 1);
+        assert_ne!(target.get(0).unwrap().get::<MboMsg>(), Some(&first));
+        assert_eq!(target.decode_batch(&mut decoder, 10).unwrap(), 0);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
-    Error, ErrorMsg, ImbalanceMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, RType,
-    Record, RecordMut, RecordRef, StatMsg, StatusMsg, SymbolMappingMsg, SystemMsg, TradeMsg,
+    BboMsg, CbboMsg, Error, ErrorMsg, ImbalanceMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg,
+    OhlcvMsg, RType, Record, RecordMut, RecordRef, StatMsg, StatusMsg, SymbolMappingMsg, SystemMsg,
+    TradeMsg,
 };
 
 /// An owned DBN record type of flexible type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RecordEnum {
     /// An market-by-order message.
     Mbo(MboMsg),
@@ -16,6 +17,10 @@ pub enum RecordEnum {
     Mbp10(Mbp10Msg),
     /// An open, high, low, close, and volume message.
     Ohlcv(OhlcvMsg),
+    /// A subsampled market-by-price message with a book depth of 1.
+    Bbo(BboMsg),
+    /// A consolidated market-by-price message with a book depth of 1.
+    Cbbo(CbboMsg),
     /// A trading status message.
     Status(StatusMsg),
     /// An instrument definition message.
@@ -34,7 +39,7 @@ pub enum RecordEnum {
 
 /// An immutable reference to a DBN record of flexible type. Unlike [`RecordRef`], this
 /// type allows `match`ing.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum RecordRefEnum<'a> {
     /// A reference to a market-by-order message.
     Mbo(&'a MboMsg),
@@ -46,6 +51,10 @@ pub enum RecordRefEnum<'a> {
     Mbp10(&'a Mbp10Msg),
     /// A reference to an open, high, low, close, and volume message.
     Ohlcv(&'a OhlcvMsg),
+    /// A reference to a subsampled market-by-price message with a book depth of 1.
+    Bbo(&'a BboMsg),
+    /// A reference to a consolidated market-by-price message with a book depth of 1.
+    Cbbo(&'a CbboMsg),
     /// A reference to a trading status message.
     Status(&'a StatusMsg),
     /// A reference to an instrument definition message.
@@ -72,6 +81,8 @@ impl<'a> From<&'a RecordEnum> for RecordRefEnum<'a> {
             RecordEnum::Mbp1(rec) => Self::Mbp1(rec),
             RecordEnum::Mbp10(rec) => Self::Mbp10(rec),
             RecordEnum::Ohlcv(rec) => Self::Ohlcv(rec),
+            RecordEnum::Bbo(rec) => Self::Bbo(rec),
+            RecordEnum::Cbbo(rec) => Self::Cbbo(rec),
             RecordEnum::Status(rec) => Self::Status(rec),
             RecordEnum::InstrumentDef(rec) => Self::InstrumentDef(rec),
             RecordEnum::Imbalance(rec) => Self::Imbalance(rec),
@@ -93,6 +104,8 @@ impl<'a> RecordRefEnum<'a> {
             Self::Mbp1(rec) => RecordEnum::from((*rec).clone()),
             Self::Mbp10(rec) => RecordEnum::from((*rec).clone()),
             Self::Ohlcv(rec) => RecordEnum::from((*rec).clone()),
+            Self::Bbo(rec) => RecordEnum::from((*rec).clone()),
+            Self::Cbbo(rec) => RecordEnum::from((*rec).clone()),
             Self::Status(rec) => RecordEnum::from((*rec).clone()),
             Self::InstrumentDef(rec) => RecordEnum::from((*rec).clone()),
             Self::Imbalance(rec) => RecordEnum::from((*rec).clone()),
@@ -121,6 +134,10 @@ impl<'a> TryFrom<RecordRef<'a>> for RecordRefEnum<'a> {
                 | RType::Ohlcv1H
                 | RType::Ohlcv1D
                 | RType::OhlcvEod => RecordRefEnum::Ohlcv(rec_ref.get_unchecked()),
+                RType::Bbo1S | RType::Bbo1M => RecordRefEnum::Bbo(rec_ref.get_unchecked()),
+                RType::Cbbo | RType::Cbbo1S | RType::Cbbo1M | RType::Tcbbo => {
+                    RecordRefEnum::Cbbo(rec_ref.get_unchecked())
+                }
                 RType::Status => RecordRefEnum::Status(rec_ref.get_unchecked()),
                 RType::InstrumentDef => {
                     // can't convert V1 structs here because an immutable reference
@@ -133,7 +150,15 @@ impl<'a> TryFrom<RecordRef<'a>> for RecordRefEnum<'a> {
                 }
                 RType::Imbalance => RecordRefEnum::Imbalance(rec_ref.get_unchecked()),
                 RType::Statistics => RecordRefEnum::Stat(rec_ref.get_unchecked()),
-                RType::Error => RecordRefEnum::Error(rec_ref.get_unchecked()),
+                RType::Error => {
+                    // can't convert V1 structs here because an immutable reference
+                    if rec_ref.record_size() < std::mem::size_of::<ErrorMsg>() {
+                        return Err(Error::conversion::<RecordRefEnum>(
+                            "ErrorMsgV1 (must be current version)",
+                        ));
+                    }
+                    RecordRefEnum::Error(rec_ref.get_unchecked())
+                }
                 RType::SymbolMapping => {
                     // can't convert V1 structs here because an immutable reference
                     if rec_ref.record_size() < std::mem::size_of::<SymbolMappingMsg>() {
@@ -143,7 +168,15 @@ impl<'a> TryFrom<RecordRef<'a>> for RecordRefEnum<'a> {
                     }
                     RecordRefEnum::SymbolMapping(rec_ref.get_unchecked())
                 }
-                RType::System => RecordRefEnum::System(rec_ref.get_unchecked()),
+                RType::System => {
+                    // can't convert V1 structs here because an immutable reference
+                    if rec_ref.record_size() < std::mem::size_of::<SystemMsg>() {
+                        return Err(Error::conversion::<RecordRefEnum>(
+                            "SystemMsgV1 (must be current version)",
+                        ));
+                    }
+                    RecordRefEnum::System(rec_ref.get_unchecked())
+                }
             }
         })
     }
@@ -199,6 +232,26 @@ impl<'a> From<&'a OhlcvMsg> for RecordRefEnum<'a> {
         Self::Ohlcv(rec)
     }
 }
+impl From<BboMsg> for RecordEnum {
+    fn from(rec: BboMsg) -> Self {
+        Self::Bbo(rec)
+    }
+}
+impl<'a> From<&'a BboMsg> for RecordRefEnum<'a> {
+    fn from(rec: &'a BboMsg) -> Self {
+        Self::Bbo(rec)
+    }
+}
+impl From<CbboMsg> for RecordEnum {
+    fn from(rec: CbboMsg) -> Self {
+        Self::Cbbo(rec)
+    }
+}
+impl<'a> From<&'a CbboMsg> for RecordRefEnum<'a> {
+    fn from(rec: &'a CbboMsg) -> Self {
+        Self::Cbbo(rec)
+    }
+}
 impl From<StatusMsg> for RecordEnum {
     fn from(rec: StatusMsg) -> Self {
         Self::Status(rec)
@@ -278,6 +331,8 @@ impl Record for RecordEnum {
             RecordEnum::Mbp1(rec) => rec.header(),
             RecordEnum::Mbp10(rec) => rec.header(),
             RecordEnum::Ohlcv(rec) => rec.header(),
+            RecordEnum::Bbo(rec) => rec.header(),
+            RecordEnum::Cbbo(rec) => rec.header(),
             RecordEnum::Status(rec) => rec.header(),
             RecordEnum::InstrumentDef(rec) => rec.header(),
             RecordEnum::Imbalance(rec) => rec.header(),
@@ -295,6 +350,8 @@ impl Record for RecordEnum {
             RecordEnum::Mbp1(rec) => rec.raw_index_ts(),
             RecordEnum::Mbp10(rec) => rec.raw_index_ts(),
             RecordEnum::Ohlcv(rec) => rec.raw_index_ts(),
+            RecordEnum::Bbo(rec) => rec.raw_index_ts(),
+            RecordEnum::Cbbo(rec) => rec.raw_index_ts(),
             RecordEnum::Status(rec) => rec.raw_index_ts(),
             RecordEnum::InstrumentDef(rec) => rec.raw_index_ts(),
             RecordEnum::Imbalance(rec) => rec.raw_index_ts(),
@@ -314,6 +371,8 @@ impl RecordMut for RecordEnum {
             RecordEnum::Mbp1(rec) => rec.header_mut(),
             RecordEnum::Mbp10(rec) => rec.header_mut(),
             RecordEnum::Ohlcv(rec) => rec.header_mut(),
+            RecordEnum::Bbo(rec) => rec.header_mut(),
+            RecordEnum::Cbbo(rec) => rec.header_mut(),
             RecordEnum::Status(rec) => rec.header_mut(),
             RecordEnum::InstrumentDef(rec) => rec.header_mut(),
             RecordEnum::Imbalance(rec) => rec.header_mut(),
@@ -333,6 +392,8 @@ impl<'a> Record for RecordRefEnum<'a> {
             RecordRefEnum::Mbp1(rec) => rec.header(),
             RecordRefEnum::Mbp10(rec) => rec.header(),
             RecordRefEnum::Ohlcv(rec) => rec.header(),
+            RecordRefEnum::Bbo(rec) => rec.header(),
+            RecordRefEnum::Cbbo(rec) => rec.header(),
             RecordRefEnum::Status(rec) => rec.header(),
             RecordRefEnum::InstrumentDef(rec) => rec.header(),
             RecordRefEnum::Imbalance(rec) => rec.header(),
@@ -350,6 +411,8 @@ impl<'a> Record for RecordRefEnum<'a> {
             RecordRefEnum::Mbp1(rec) => rec.raw_index_ts(),
             RecordRefEnum::Mbp10(rec) => rec.raw_index_ts(),
             RecordRefEnum::Ohlcv(rec) => rec.raw_index_ts(),
+            RecordRefEnum::Bbo(rec) => rec.raw_index_ts(),
+            RecordRefEnum::Cbbo(rec) => rec.raw_index_ts(),
             RecordRefEnum::Status(rec) => rec.raw_index_ts(),
             RecordRefEnum::InstrumentDef(rec) => rec.raw_index_ts(),
             RecordRefEnum::Imbalance(rec) => rec.raw_index_ts(),
@@ -1,10 +1,22 @@
-//! The [`RecordRef`] struct for non-owning references to DBN records.
+//! The [`RecordRef`] and [`RecordRefMut`] structs for non-owning references to DBN
+//! records.
 
-use std::{fmt::Debug, marker::PhantomData, mem, ptr::NonNull};
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem,
+    ptr::NonNull,
+};
 
 use crate::{
-    record::{HasRType, Record, RecordHeader},
-    rtype_dispatch, RecordEnum, RecordRefEnum,
+    compat::{ErrorMsgV1, InstrumentDefMsgV1, SymbolMappingMsgV1, SystemMsgV1},
+    enums::{rtype, RType},
+    record::{
+        BboMsg, CbboMsg, HasRType, ImbalanceMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, Record,
+        RecordHeader, RecordMut, StatMsg, StatusMsg, TradeMsg,
+    },
+    rtype_dispatch, Error, RecordEnum, RecordRefEnum,
 };
 
 /// A wrapper around a non-owning immutable reference to a DBN record. This wrapper
@@ -47,6 +59,20 @@ impl<'a> RecordRef<'a> {
         }
     }
 
+    /// Constructs a new reference to the DBN record at the start of `buffer` after
+    /// validating it. `buffer` may contain additional data after the record.
+    ///
+    /// # Errors
+    /// This function returns an error if `buffer` isn't aligned to a [`RecordHeader`],
+    /// is shorter than the record length encoded in the header, if the header
+    /// doesn't contain a known or user-defined rtype, or if the encoded length is
+    /// shorter than the record type of a known rtype.
+    pub fn try_new(buffer: &'a [u8]) -> crate::Result<Self> {
+        validate_record_buffer(buffer)?;
+        // Safety: validated length, alignment, and rtype above
+        Ok(unsafe { Self::new(buffer) })
+    }
+
     /// Constructs a new reference to the DBN record.
     ///
     /// # Safety
@@ -101,6 +127,16 @@ impl<'a> RecordRef<'a> {
         RecordRefEnum::try_from(*self)
     }
 
+    /// Copies the referenced record into an owned [`RecordEnum`], which isn't tied to
+    /// the lifetime of the underlying buffer.
+    ///
+    /// # Errors
+    /// This function returns a conversion error if the rtype does not correspond with
+    /// any known DBN record type.
+    pub fn to_owned(&self) -> crate::Result<RecordEnum> {
+        self.as_enum().map(|rec_enum| rec_enum.to_owned())
+    }
+
     /// Returns a reference to the underlying record of type `T` without checking if
     /// this object references a record of type `T`.
     ///
@@ -113,6 +149,13 @@ impl<'a> RecordRef<'a> {
         debug_assert!(self.record_size() >= mem::size_of::<T>());
         self.ptr.cast::<T>().as_ref()
     }
+
+    /// Returns the raw bytes of the record following the [`RecordHeader`]. Useful for
+    /// user-defined records whose layout isn't known to this crate. See
+    /// [`CustomRecord`](crate::CustomRecord).
+    pub fn payload(&self) -> &'a [u8] {
+        &self.as_ref()[mem::size_of::<RecordHeader>().min(self.record_size())..]
+    }
 }
 
 impl<'a, R> From<&'a R> for RecordRef<'a>
@@ -159,6 +202,8 @@ impl<'a> From<&'a RecordEnum> for RecordRef<'a> {
             RecordEnum::Mbp1(rec) => Self::from(rec),
             RecordEnum::Mbp10(rec) => Self::from(rec),
             RecordEnum::Ohlcv(rec) => Self::from(rec),
+            RecordEnum::Bbo(rec) => Self::from(rec),
+            RecordEnum::Cbbo(rec) => Self::from(rec),
             RecordEnum::Status(rec) => Self::from(rec),
             RecordEnum::InstrumentDef(rec) => Self::from(rec),
             RecordEnum::Imbalance(rec) => Self::from(rec),
@@ -178,6 +223,8 @@ impl<'a> From<RecordRefEnum<'a>> for RecordRef<'a> {
             RecordRefEnum::Mbp1(rec) => Self::from(rec),
             RecordRefEnum::Mbp10(rec) => Self::from(rec),
             RecordRefEnum::Ohlcv(rec) => Self::from(rec),
+            RecordRefEnum::Bbo(rec) => Self::from(rec),
+            RecordRefEnum::Cbbo(rec) => Self::from(rec),
             RecordRefEnum::Status(rec) => Self::from(rec),
             RecordRefEnum::InstrumentDef(rec) => Self::from(rec),
             RecordRefEnum::Imbalance(rec) => Self::from(rec),
@@ -189,6 +236,23 @@ impl<'a> From<RecordRefEnum<'a>> for RecordRef<'a> {
     }
 }
 
+/// Compares the records' bytes, so references to equal records in different buffers
+/// are equal.
+impl<'a> PartialEq for RecordRef<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<'a> Eq for RecordRef<'a> {}
+
+/// Hashes the record's bytes, consistent with [`PartialEq`].
+impl<'a> Hash for RecordRef<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state);
+    }
+}
+
 impl<'a> Debug for RecordRef<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RecordRef")
@@ -200,13 +264,264 @@ impl<'a> Debug for RecordRef<'a> {
     }
 }
 
+/// A wrapper around a non-owning mutable reference to a DBN record, for editing
+/// records in place, e.g. within a decoding buffer before re-encoding them.
+pub struct RecordRefMut<'a> {
+    ptr: NonNull<RecordHeader>,
+    /// Associates the object with the lifetime of the memory pointed to by `ptr`.
+    _marker: PhantomData<&'a mut RecordHeader>,
+}
+
+// Safety: RecordRefMut exhibits mutable reference semantics similar to &mut T.
+unsafe impl Send for RecordRefMut<'_> {}
+unsafe impl Sync for RecordRefMut<'_> {}
+
+impl<'a> RecordRefMut<'a> {
+    /// Constructs a new mutable reference to the DBN record in `buffer`.
+    ///
+    /// # Safety
+    /// `buffer` should begin with a [`RecordHeader`] and contain a type implementing
+    /// [`HasRType`].
+    pub unsafe fn new(buffer: &'a mut [u8]) -> Self {
+        debug_assert!(buffer.len() >= mem::size_of::<RecordHeader>());
+        let raw_ptr = buffer.as_mut_ptr() as *mut RecordHeader;
+        debug_assert_eq!(
+            raw_ptr.align_offset(std::mem::align_of::<RecordHeader>()),
+            0
+        );
+        Self {
+            ptr: NonNull::new_unchecked(raw_ptr),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Constructs a new mutable reference to the DBN record at the start of `buffer`
+    /// after validating it. `buffer` may contain additional data after the record.
+    ///
+    /// # Errors
+    /// This function returns an error if `buffer` isn't aligned to a [`RecordHeader`],
+    /// is shorter than the record length encoded in the header, if the header
+    /// doesn't contain a known or user-defined rtype, or if the encoded length is
+    /// shorter than the record type of a known rtype.
+    pub fn try_new(buffer: &'a mut [u8]) -> crate::Result<Self> {
+        validate_record_buffer(buffer)?;
+        // Safety: validated length, alignment, and rtype above
+        Ok(unsafe { Self::new(buffer) })
+    }
+
+    /// Returns `true` if the object points to a record of type `T`.
+    pub fn has<T: HasRType>(&self) -> bool {
+        T::has_rtype(self.header().rtype)
+    }
+
+    /// Returns a reference to the underlying record of type `T` or `None` if it points
+    /// to another record type.
+    ///
+    /// # Panics
+    /// This function will panic if the rtype indicates it's of type `T` but the encoded
+    /// length of the record is less than the size of `T`.
+    pub fn get<T: HasRType>(&self) -> Option<&T> {
+        self.as_record_ref().get::<T>()
+    }
+
+    /// Returns a mutable reference to the underlying record of type `T` or `None` if
+    /// it points to another record type.
+    ///
+    /// # Panics
+    /// This function will panic if the rtype indicates it's of type `T` but the encoded
+    /// length of the record is less than the size of `T`.
+    pub fn get_mut<T: HasRType>(&mut self) -> Option<&mut T> {
+        if self.has::<T>() {
+            assert!(
+                self.record_size() >= mem::size_of::<T>(),
+                "Malformed `{}` record: expected length of at least {} bytes, found {} bytes. \
+                Confirm the DBN version in the Metadata header and the version upgrade policy",
+                std::any::type_name::<T>(),
+                mem::size_of::<T>(),
+                self.record_size()
+            );
+            // Safety: checked `rtype` in call to `has()`. Assumes the initial data based to
+            // `RecordRefMut` is indeed a record.
+            Some(unsafe { self.ptr.cast::<T>().as_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the underlying record of type `T` without
+    /// checking if this object references a record of type `T`.
+    ///
+    /// For a safe alternative, see [`get_mut()`](Self::get_mut).
+    ///
+    /// # Safety
+    /// The caller needs to validate this object points to a `T`.
+    pub unsafe fn get_unchecked_mut<T: HasRType>(&mut self) -> &mut T {
+        debug_assert!(self.has::<T>());
+        debug_assert!(self.record_size() >= mem::size_of::<T>());
+        self.ptr.cast::<T>().as_mut()
+    }
+
+    /// Returns an immutable [`RecordRef`] to the record.
+    pub fn as_record_ref(&self) -> RecordRef<'_> {
+        // Safety: `ptr` points to a record and the returned `RecordRef` borrows `self`
+        unsafe { RecordRef::unchecked_from_header(self.ptr.as_ptr()) }
+    }
+}
+
+impl<'a, R> From<&'a mut R> for RecordRefMut<'a>
+where
+    R: HasRType,
+{
+    /// Constructs a new mutable reference to a DBN record.
+    fn from(rec: &'a mut R) -> Self {
+        Self {
+            ptr: NonNull::from(rec.header_mut()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> AsRef<[u8]> for RecordRefMut<'a> {
+    fn as_ref(&self) -> &[u8] {
+        // # Safety
+        // Assumes the encoded record length is correct.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr() as *const u8, self.record_size()) }
+    }
+}
+
+impl<'a> Record for RecordRefMut<'a> {
+    fn header(&self) -> &RecordHeader {
+        // Safety: assumes `ptr` passes to a `RecordHeader`.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    fn raw_index_ts(&self) -> u64 {
+        self.as_record_ref().raw_index_ts()
+    }
+}
+
+impl<'a> RecordMut for RecordRefMut<'a> {
+    fn header_mut(&mut self) -> &mut RecordHeader {
+        // Safety: assumes `ptr` passes to a `RecordHeader`.
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<'a> Debug for RecordRefMut<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordRefMut")
+            .field(
+                "ptr",
+                &format_args!("{:?} --> {:?}", self.ptr, self.header()),
+            )
+            .finish()
+    }
+}
+
+/// Returns an iterator over the records in `buffer`, a sequence of concatenated DBN
+/// records without metadata, such as a shared memory region or a memory-mapped file.
+/// Records are located using the length in their headers and are not copied.
+///
+/// Iteration stops after the first error, which is yielded if the remaining bytes
+/// don't contain a valid record. See [`RecordRef::try_new()`].
+pub fn split_records(buffer: &[u8]) -> impl Iterator<Item = crate::Result<RecordRef<'_>>> {
+    let mut remaining = buffer;
+    std::iter::from_fn(move || {
+        if remaining.is_empty() {
+            return None;
+        }
+        match RecordRef::try_new(remaining) {
+            Ok(rec_ref) => {
+                remaining = &remaining[rec_ref.record_size()..];
+                Some(Ok(rec_ref))
+            }
+            Err(e) => {
+                remaining = &[];
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+/// Checks `buffer` begins with an aligned record of a known or user-defined rtype whose
+/// encoded length fits within `buffer`.
+fn validate_record_buffer(buffer: &[u8]) -> crate::Result<()> {
+    if buffer.len() < mem::size_of::<RecordHeader>() {
+        return Err(Error::decode(format!(
+            "buffer of {} bytes is too short for a record header of {} bytes",
+            buffer.len(),
+            mem::size_of::<RecordHeader>()
+        )));
+    }
+    let align = mem::align_of::<RecordHeader>();
+    if buffer.as_ptr().align_offset(align) != 0 {
+        return Err(Error::decode(format!(
+            "buffer is not aligned to {align} bytes"
+        )));
+    }
+    // Safety: checked length and alignment above
+    let header = unsafe { &*(buffer.as_ptr() as *const RecordHeader) };
+    let record_size = header.record_size();
+    if record_size < mem::size_of::<RecordHeader>() {
+        return Err(Error::decode(format!(
+            "invalid record length of {record_size} bytes, less than the size of the header"
+        )));
+    }
+    if record_size > buffer.len() {
+        return Err(Error::decode(format!(
+            "record length of {record_size} bytes exceeds buffer of {} bytes",
+            buffer.len()
+        )));
+    }
+    if !rtype::is_user_defined(header.rtype) {
+        let min_size = min_record_size(header.rtype()?);
+        if record_size < min_size {
+            return Err(Error::decode(format!(
+                "invalid record length of {record_size} bytes for rtype {:#04X}, \
+                expected at least {min_size} bytes",
+                header.rtype
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the size of the smallest struct of any DBN version for `rtype`.
+fn min_record_size(rtype: RType) -> usize {
+    #[allow(deprecated)]
+    match rtype {
+        RType::Mbo => mem::size_of::<MboMsg>(),
+        RType::Mbp0 => mem::size_of::<TradeMsg>(),
+        RType::Mbp1 => mem::size_of::<Mbp1Msg>(),
+        RType::Mbp10 => mem::size_of::<Mbp10Msg>(),
+        RType::OhlcvDeprecated
+        | RType::Ohlcv1S
+        | RType::Ohlcv1M
+        | RType::Ohlcv1H
+        | RType::Ohlcv1D
+        | RType::OhlcvEod => mem::size_of::<OhlcvMsg>(),
+        RType::Bbo1S | RType::Bbo1M => mem::size_of::<BboMsg>(),
+        RType::Cbbo | RType::Cbbo1S | RType::Cbbo1M | RType::Tcbbo => mem::size_of::<CbboMsg>(),
+        RType::Status => mem::size_of::<StatusMsg>(),
+        RType::InstrumentDef => mem::size_of::<InstrumentDefMsgV1>(),
+        RType::Imbalance => mem::size_of::<ImbalanceMsg>(),
+        RType::Statistics => mem::size_of::<StatMsg>(),
+        RType::Error => mem::size_of::<ErrorMsgV1>(),
+        RType::SymbolMapping => mem::size_of::<SymbolMappingMsgV1>(),
+        RType::System => mem::size_of::<SystemMsgV1>(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::c_char;
 
     use crate::{
         enums::rtype,
-        record::{ErrorMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, TradeMsg},
+        record::{
+            as_u8_slice, ErrorMsg, InstrumentDefMsg, MboMsg, Mbp10Msg, Mbp1Msg, OhlcvMsg, TradeMsg,
+            WithTsOut,
+        },
     };
 
     use super::*;
@@ -261,6 +576,142 @@ mod tests {
         assert_eq!(target.record_size(), byte_slice.len());
     }
 
+    #[test]
+    fn test_eq_and_hash() {
+        let mut other = SOURCE_RECORD;
+        let recs = [SOURCE_RECORD, SOURCE_RECORD];
+        let dedup = recs
+            .iter()
+            .map(RecordRef::from)
+            .chain(std::iter::once(RecordRef::from(&other)))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(dedup.len(), 1);
+        other.order_id += 1;
+        assert_ne!(RecordRef::from(&SOURCE_RECORD), RecordRef::from(&other));
+        assert_ne!(RecordEnum::from(SOURCE_RECORD), RecordEnum::from(other));
+    }
+
+    #[test]
+    fn test_to_owned() {
+        let owned = {
+            let src = SOURCE_RECORD;
+            RecordRef::from(&src).to_owned().unwrap()
+        };
+        assert!(matches!(owned, RecordEnum::Mbo(rec) if rec == SOURCE_RECORD));
+        let mut src = SOURCE_RECORD;
+        src.hd.rtype = 0xFF;
+        assert!(RecordRef::from(&src).to_owned().is_err());
+    }
+
+    #[test]
+    fn test_try_new() {
+        let recs = [SOURCE_RECORD, SOURCE_RECORD];
+        let bytes = unsafe { as_u8_slice(&recs) };
+        let size = SOURCE_RECORD.record_size();
+        let target = RecordRef::try_new(&bytes[..size]).unwrap();
+        assert_eq!(*target.get::<MboMsg>().unwrap(), SOURCE_RECORD);
+        // trailing data is allowed
+        let target = RecordRef::try_new(bytes).unwrap();
+        assert_eq!(target.record_size(), size);
+        // shorter than the header
+        assert!(RecordRef::try_new(&bytes[..8]).is_err());
+        // shorter than the encoded length
+        assert!(RecordRef::try_new(&bytes[..size - 8]).is_err());
+        // misaligned
+        assert!(RecordRef::try_new(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_try_new_invalid_header() {
+        let mut src = SOURCE_RECORD;
+        src.hd.rtype = 0xFF;
+        let bytes = unsafe { as_u8_slice(&src) };
+        assert!(RecordRef::try_new(bytes).is_err());
+        src.hd.rtype = rtype::USER_DEFINED_START;
+        let bytes = unsafe { as_u8_slice(&src) };
+        assert!(RecordRef::try_new(bytes).is_ok());
+        src.hd.rtype = rtype::MBO;
+        src.hd.length = 0;
+        let bytes = unsafe { as_u8_slice(&src) };
+        assert!(RecordRef::try_new(bytes).is_err());
+        // shorter than `MboMsg`
+        src.hd.length = 4;
+        let bytes = unsafe { as_u8_slice(&src) };
+        assert!(RecordRef::try_new(bytes).is_err());
+        // user-defined records only need a header
+        src.hd.rtype = rtype::USER_DEFINED_START;
+        let bytes = unsafe { as_u8_slice(&src) };
+        assert!(RecordRef::try_new(bytes).is_ok());
+    }
+
+    #[test]
+    fn test_try_new_v1_min_size() {
+        let rec = ErrorMsgV1::new(0, "test");
+        let bytes = unsafe { as_u8_slice(&rec) };
+        let target = RecordRef::try_new(bytes).unwrap();
+        assert!(target.get::<ErrorMsgV1>().is_some());
+        assert!(target.as_enum().is_err());
+    }
+
+    #[test]
+    fn test_ref_mut_get_mut() {
+        let mut src = SOURCE_RECORD;
+        let mut target = RecordRefMut::from(&mut src);
+        assert!(target.get_mut::<TradeMsg>().is_none());
+        target.get_mut::<MboMsg>().unwrap().hd.publisher_id = 2;
+        target.header_mut().instrument_id = 5;
+        assert_eq!(target.get::<MboMsg>().unwrap().hd.publisher_id, 2);
+        assert_eq!(target.as_record_ref().header().instrument_id, 5);
+        assert_eq!(src.hd.publisher_id, 2);
+        assert_eq!(src.hd.instrument_id, 5);
+    }
+
+    #[test]
+    fn test_ref_mut_in_buffer() {
+        let mut recs = [
+            WithTsOut::new(SOURCE_RECORD, 10),
+            WithTsOut::new(SOURCE_RECORD, 10),
+        ];
+        let size = mem::size_of::<WithTsOut<MboMsg>>();
+        let bytes =
+            unsafe { std::slice::from_raw_parts_mut(recs.as_mut_ptr().cast::<u8>(), size * 2) };
+        let mut target = RecordRefMut::try_new(&mut bytes[size..]).unwrap();
+        target.get_mut::<WithTsOut<MboMsg>>().unwrap().ts_out = 0;
+        assert!(RecordRefMut::try_new(&mut bytes[1..]).is_err());
+        // length shorter than `MboMsg`
+        bytes[0] = 4;
+        assert!(RecordRefMut::try_new(bytes).is_err());
+        assert_eq!(recs[0].ts_out, 10);
+        assert_eq!(recs[1].ts_out, 0);
+    }
+
+    #[test]
+    fn test_split_records() {
+        let recs = [
+            WithTsOut::new(SOURCE_RECORD, 10),
+            WithTsOut::new(SOURCE_RECORD, 11),
+        ];
+        let bytes = unsafe { as_u8_slice(&recs) };
+        let ts_outs = split_records(bytes)
+            .map(|rec_ref| rec_ref.unwrap().get::<WithTsOut<MboMsg>>().unwrap().ts_out)
+            .collect::<Vec<_>>();
+        assert_eq!(ts_outs, [10, 11]);
+        assert_eq!(split_records(&[]).count(), 0);
+        // truncated last record
+        let res = split_records(&bytes[..bytes.len() - 8]).collect::<Vec<_>>();
+        assert_eq!(res.len(), 2);
+        assert!(res[0].is_ok());
+        assert!(res[1].is_err());
+        // known rtype with a length shorter than its struct
+        let mut short = recs.clone();
+        short[1].rec.hd.length = 4;
+        let bytes = unsafe { as_u8_slice(&short) };
+        let res = split_records(bytes).collect::<Vec<_>>();
+        assert_eq!(res.len(), 2);
+        assert!(res[0].is_ok());
+        assert!(res[1].is_err());
+    }
+
     #[should_panic]
     #[test]
     fn test_get_too_short() {
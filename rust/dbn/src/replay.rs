@@ -0,0 +1,246 @@
+//! Replaying DBN records paced by their timestamps, e.g. for feeding simulators and
+//! integration tests as if the data were live.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    decode::{DbnMetadata, DecodeRecordRef, DynDecoder},
+    Metadata, Record, RecordRef, VersionUpgradePolicy, UNDEF_TIMESTAMP,
+};
+
+/// A source of time for a [`Replayer`]. Implement it to control the pacing of a
+/// replay, e.g. with simulated time in tests.
+pub trait Clock {
+    /// Returns the time elapsed since an arbitrary fixed point, such as the creation of
+    /// the clock.
+    fn now(&self) -> Duration;
+
+    /// Blocks for at least `duration`.
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// A [`Clock`] of the system's monotonic time.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// The timestamp of each record a [`Replayer`] paces the replay by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaceBy {
+    /// The matching-engine-received timestamp `ts_event`.
+    TsEvent,
+    /// The index timestamp of the record, which is the capture-server-received
+    /// timestamp `ts_recv` for records that have one, and `ts_event` otherwise.
+    #[default]
+    TsRecv,
+}
+
+/// Type for replaying the records of another decoder with the same delays between
+/// them as between their timestamps, scaled by a speed multiplier.
+///
+/// The first record is returned immediately. Records with an undefined timestamp or a
+/// timestamp before that of the first record are returned without delay. If
+/// decoding falls behind, records are returned as fast as possible until the replay
+/// catches up.
+#[derive(Debug)]
+pub struct Replayer<D, C = SystemClock> {
+    decoder: D,
+    clock: C,
+    pace_by: PaceBy,
+    speed: f64,
+    /// The timestamp of the first record and the clock time it was returned.
+    start: Option<(u64, Duration)>,
+}
+
+impl<D> Replayer<D> {
+    /// Creates a new [`Replayer`] of the records of `decoder` paced by the system clock.
+    /// A `speed` of 2.0 replays the records twice as fast as they originally occurred.
+    ///
+    /// # Errors
+    /// This function returns an error if `speed` isn't positive.
+    pub fn new(decoder: D, pace_by: PaceBy, speed: f64) -> crate::Result<Self> {
+        Self::with_clock(decoder, pace_by, speed, SystemClock::default())
+    }
+}
+
+impl Replayer<DynDecoder<'static, BufReader<File>>> {
+    /// Creates a new [`Replayer`] of the DBN file at `path`, which may be
+    /// Zstandard-compressed, paced by the system clock. Records from previous DBN
+    /// versions are upgraded. A `speed` of 2.0 replays the records twice as fast as
+    /// they originally occurred.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to open the file or decode its
+    /// metadata, or `speed` isn't positive.
+    pub fn from_file(path: impl AsRef<Path>, pace_by: PaceBy, speed: f64) -> crate::Result<Self> {
+        Self::new(
+            DynDecoder::from_file(path, VersionUpgradePolicy::Upgrade)?,
+            pace_by,
+            speed,
+        )
+    }
+}
+
+impl<D, C: Clock> Replayer<D, C> {
+    /// Creates a new [`Replayer`] of the records of `decoder` paced by `clock`. A
+    /// `speed` of 2.0 replays the records twice as fast as they originally occurred.
+    ///
+    /// # Errors
+    /// This function returns an error if `speed` isn't positive.
+    pub fn with_clock(decoder: D, pace_by: PaceBy, speed: f64, clock: C) -> crate::Result<Self> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(crate::Error::BadArgument {
+                param_name: "speed".to_owned(),
+                desc: "must be positive".to_owned(),
+            });
+        }
+        Ok(Self {
+            decoder,
+            clock,
+            pace_by,
+            speed,
+            start: None,
+        })
+    }
+
+    /// Returns a reference to the clock.
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Consumes the replayer and returns the inner decoder.
+    pub fn into_inner(self) -> D {
+        self.decoder
+    }
+
+    /// Waits until the time to replay a record with timestamp `ts`.
+    fn wait_for(clock: &mut C, start: &mut Option<(u64, Duration)>, speed: f64, ts: u64) {
+        if ts == UNDEF_TIMESTAMP {
+            return;
+        }
+        let Some((start_ts, start_time)) = *start else {
+            *start = Some((ts, clock.now()));
+            return;
+        };
+        let Some(delta) = ts.checked_sub(start_ts) else {
+            return;
+        };
+        let target = start_time + Duration::from_nanos((delta as f64 / speed).round() as u64);
+        let now = clock.now();
+        if target > now {
+            clock.sleep(target - now);
+        }
+    }
+}
+
+impl<D: DbnMetadata, C> DbnMetadata for Replayer<D, C> {
+    fn metadata(&self) -> &Metadata {
+        self.decoder.metadata()
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        self.decoder.metadata_mut()
+    }
+}
+
+impl<D: DecodeRecordRef, C: Clock> DecodeRecordRef for Replayer<D, C> {
+    fn decode_record_ref(&mut self) -> crate::Result<Option<RecordRef>> {
+        // Decode before waiting so decoding time counts toward the delay
+        let Some(rec) = self.decoder.decode_record_ref()? else {
+            return Ok(None);
+        };
+        let ts = match self.pace_by {
+            PaceBy::TsEvent => rec.header().ts_event,
+            PaceBy::TsRecv => rec.raw_index_ts(),
+        };
+        Self::wait_for(&mut self.clock, &mut self.start, self.speed, ts);
+        Ok(Some(rec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode::tests::TEST_DATA_PATH, MboMsg};
+
+    /// A clock that only advances when sleeping.
+    #[derive(Debug, Default)]
+    struct ManualClock {
+        now: Duration,
+        sleeps: Vec<Duration>,
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Duration {
+            self.now
+        }
+
+        fn sleep(&mut self, duration: Duration) {
+            self.now += duration;
+            self.sleeps.push(duration);
+        }
+    }
+
+    fn replay(pace_by: PaceBy, speed: f64) -> Vec<Duration> {
+        let decoder = DynDecoder::from_file(
+            format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst"),
+            VersionUpgradePolicy::AsIs,
+        )
+        .unwrap();
+        let mut target =
+            Replayer::with_clock(decoder, pace_by, speed, ManualClock::default()).unwrap();
+        let mut records = Vec::new();
+        while let Some(rec) = target.decode_record_ref().unwrap() {
+            records.push(rec.get::<MboMsg>().unwrap().clone());
+        }
+        assert_eq!(records.len(), 2);
+        target.clock().sleeps.clone()
+    }
+
+    #[test]
+    fn test_pacing() {
+        // The records are 7,284ns apart by `ts_recv` and 1,834ns apart by `ts_event`
+        assert_eq!(replay(PaceBy::TsRecv, 1.0), [Duration::from_nanos(7_284)]);
+        assert_eq!(replay(PaceBy::TsEvent, 1.0), [Duration::from_nanos(1_834)]);
+        assert_eq!(replay(PaceBy::TsRecv, 4.0), [Duration::from_nanos(1_821)]);
+    }
+
+    #[test]
+    fn test_invalid_speed() {
+        for speed in [0.0, -1.0, f64::NAN] {
+            assert!(matches!(
+                Replayer::from_file(
+                    format!("{TEST_DATA_PATH}/test_data.mbo.dbn"),
+                    PaceBy::TsRecv,
+                    speed
+                ),
+                Err(crate::Error::BadArgument { .. })
+            ));
+        }
+    }
+}
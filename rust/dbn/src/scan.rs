@@ -0,0 +1,125 @@
+//! Scanning of DBN files to recompute their record counts and time range, e.g. to
+//! repair files whose capture process crashed before finalizing the metadata.
+
+use std::{collections::BTreeMap, fs::OpenOptions, num::NonZeroU64, path::Path};
+
+use crate::{
+    decode::{dbn::MetadataDecoder, DecodeRecordRef, DynDecoder},
+    encode::dbn::MetadataEncoder,
+    Record, VersionUpgradePolicy, UNDEF_TIMESTAMP,
+};
+
+/// The result of [`recount()`]: the number of records of each type and the true
+/// time range of a DBN file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanSummary {
+    /// The number of records of each rtype.
+    pub record_counts: BTreeMap<u8, u64>,
+    /// The earliest index timestamp of the records or `None` if no record had a
+    /// defined index timestamp.
+    pub first_ts: Option<u64>,
+    /// The latest index timestamp of the records or `None` if no record had a defined
+    /// index timestamp.
+    pub last_ts: Option<u64>,
+}
+
+impl ScanSummary {
+    /// Returns the total number of records.
+    pub fn record_count(&self) -> u64 {
+        self.record_counts.values().sum()
+    }
+
+    /// Updates the `start` and `end` of the metadata of the DBN file at `path` in
+    /// place to the time range of the records. Because `end` is exclusive, it's set to
+    /// one nanosecond after `last_ts`. Fields are left unchanged if no record had a
+    /// defined index timestamp.
+    ///
+    /// # Errors
+    /// This function returns an error if it fails to open the file, the file is
+    /// compressed, or it fails to decode or update the metadata.
+    pub fn patch_metadata(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path.as_ref())
+            .map_err(|e| {
+                crate::Error::io(
+                    e,
+                    format!("opening DBN file at path '{}'", path.as_ref().display()),
+                )
+            })?;
+        let metadata = MetadataDecoder::new(&mut file).decode()?;
+        MetadataEncoder::new(&mut file).update_encoded(
+            metadata.version,
+            self.first_ts.unwrap_or(metadata.start),
+            self.last_ts
+                .and_then(|ts| NonZeroU64::new(ts.saturating_add(1)))
+                .or(metadata.end),
+            metadata.limit,
+        )
+    }
+}
+
+/// Decodes all the records of the DBN file at `path`, counting the records of each
+/// rtype and finding the first and last index timestamps. A partial record at the end
+/// of the file is ignored. Use [`ScanSummary::patch_metadata()`] to update the
+/// metadata of the file from the result.
+///
+/// # Errors
+/// This function returns an error if it fails to open the file or to decode the
+/// metadata or a record.
+pub fn recount(path: impl AsRef<Path>) -> crate::Result<ScanSummary> {
+    let mut decoder = DynDecoder::from_file(path, VersionUpgradePolicy::AsIs)?;
+    let mut summary = ScanSummary::default();
+    while let Some(rec) = decoder.decode_record_ref()? {
+        *summary.record_counts.entry(rec.header().rtype).or_default() += 1;
+        let index_ts = rec.raw_index_ts();
+        if index_ts != UNDEF_TIMESTAMP {
+            summary.first_ts = Some(summary.first_ts.map_or(index_ts, |ts| ts.min(index_ts)));
+            summary.last_ts = Some(summary.last_ts.map_or(index_ts, |ts| ts.max(index_ts)));
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decode::{dbn::Decoder, tests::TEST_DATA_PATH, DbnMetadata},
+        rtype,
+    };
+
+    #[test]
+    fn test_recount() {
+        let summary = recount(format!("{TEST_DATA_PATH}/test_data.mbo.dbn.zst")).unwrap();
+        assert_eq!(summary.record_counts, BTreeMap::from([(rtype::MBO, 2)]));
+        assert_eq!(summary.record_count(), 2);
+        assert!(summary.first_ts.unwrap() <= summary.last_ts.unwrap());
+    }
+
+    #[test]
+    fn test_patch_metadata() {
+        let path = std::env::temp_dir().join(format!(
+            "dbn_test_patch_metadata_{}.mbo.dbn",
+            std::process::id()
+        ));
+        std::fs::copy(format!("{TEST_DATA_PATH}/test_data.mbo.dbn"), &path).unwrap();
+        let summary = recount(&path).unwrap();
+        summary.patch_metadata(&path).unwrap();
+        let mut decoder = Decoder::from_file(&path).unwrap();
+        let start = decoder.metadata().start;
+        let end = decoder.metadata().end.unwrap().get();
+        assert_eq!(start, summary.first_ts.unwrap());
+        assert_eq!(end, summary.last_ts.unwrap() + 1);
+        // All records, including the last, are within [start, end)
+        let mut count = 0;
+        while let Some(rec) = decoder.decode_record_ref().unwrap() {
+            assert!((start..end).contains(&rec.raw_index_ts()));
+            count += 1;
+        }
+        assert_eq!(count, summary.record_count());
+        drop(decoder);
+        std::fs::remove_file(path).unwrap();
+    }
+}
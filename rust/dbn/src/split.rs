@@ -0,0 +1,376 @@
+//! Splitting a stream of records into multiple outputs, e.g. one file per instrument
+//! or per day, each with its own [`Metadata`].
+//!
+//! A [`Splitter`] determines the output of each record and adjusts the metadata for
+//! that output. Splitters can be combined by pairing them in a tuple: `(SplitByRType,
+//! SplitByTime::new(interval))` creates an output for each combination of rtype and
+//! time bucket. A [`SplitEncoder`] routes records to an encoder per output, creating
+//! them as needed.
+
+use std::{collections::HashMap, hash::Hash, num::NonZeroU64};
+
+use crate::{
+    encode::{DbnEncodable, EncodeDbn, EncodeRecord, EncodeRecordRef},
+    rtype, Metadata, Record, RecordRef, SType,
+};
+
+/// Trait for types that assign each record to one of multiple outputs.
+pub trait Splitter {
+    /// The type identifying an output.
+    type Key: Clone + Eq + Hash;
+
+    /// Returns the key of the output for `record`.
+    fn key(&self, record: RecordRef) -> Self::Key;
+
+    /// Adjusts `metadata`, which describes all records, to describe only the records
+    /// of the output for `key`.
+    fn adjust_metadata(&self, key: &Self::Key, metadata: &mut Metadata);
+}
+
+/// Splits records by instrument ID.
+///
+/// When the `stype_out` of the metadata is [`SType::InstrumentId`], the symbology of
+/// each output is narrowed to the symbols that map to its instrument.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SplitByInstrument;
+
+impl Splitter for SplitByInstrument {
+    type Key = u32;
+
+    fn key(&self, record: RecordRef) -> Self::Key {
+        record.header().instrument_id
+    }
+
+    fn adjust_metadata(&self, key: &Self::Key, metadata: &mut Metadata) {
+        if metadata.stype_out != SType::InstrumentId {
+            return;
+        }
+        let instrument_id = key.to_string();
+        for mapping in metadata.mappings.iter_mut() {
+            mapping
+                .intervals
+                .retain(|interval| interval.symbol == instrument_id);
+        }
+        metadata
+            .mappings
+            .retain(|mapping| !mapping.intervals.is_empty());
+        let mappings = &metadata.mappings;
+        let is_mapped =
+            |symbol: &String| mappings.iter().any(|mapping| mapping.raw_symbol == *symbol);
+        metadata.symbols.retain(is_mapped);
+        metadata.partial.retain(is_mapped);
+        metadata.not_found.clear();
+    }
+}
+
+/// Splits records into buckets of a fixed duration by their index timestamp, e.g.
+/// daily files. Buckets are aligned to the UNIX epoch and identified by their start.
+#[derive(Debug, Clone, Copy)]
+pub struct SplitByTime {
+    interval: NonZeroU64,
+}
+
+impl SplitByTime {
+    /// Creates a new [`SplitByTime`] with buckets of `interval` nanoseconds.
+    pub fn new(interval: NonZeroU64) -> Self {
+        Self { interval }
+    }
+}
+
+impl Splitter for SplitByTime {
+    type Key = u64;
+
+    fn key(&self, record: RecordRef) -> Self::Key {
+        let ts = record.raw_index_ts();
+        ts - ts % self.interval.get()
+    }
+
+    fn adjust_metadata(&self, key: &Self::Key, metadata: &mut Metadata) {
+        let end = NonZeroU64::new(key.saturating_add(self.interval.get())).unwrap();
+        // A bucket outside the query range, e.g. of definitions sent before the start
+        // of the query, keeps the original range
+        let _ = metadata.restrict(*key, end);
+    }
+}
+
+/// Splits records by rtype, e.g. to separate the definitions and trades of a file
+/// with mixed schemas.
+///
+/// The schema of each output is set to the schema corresponding to its rtype, if any.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SplitByRType;
+
+impl Splitter for SplitByRType {
+    type Key = u8;
+
+    fn key(&self, record: RecordRef) -> Self::Key {
+        record.header().rtype
+    }
+
+    fn adjust_metadata(&self, key: &Self::Key, metadata: &mut Metadata) {
+        metadata.schema = rtype::try_into_schema(*key);
+    }
+}
+
+impl<A, B> Splitter for (A, B)
+where
+    A: Splitter,
+    B: Splitter,
+{
+    type Key = (A::Key, B::Key);
+
+    fn key(&self, record: RecordRef) -> Self::Key {
+        (self.0.key(record), self.1.key(record))
+    }
+
+    fn adjust_metadata(&self, key: &Self::Key, metadata: &mut Metadata) {
+        self.0.adjust_metadata(&key.0, metadata);
+        self.1.adjust_metadata(&key.1, metadata);
+    }
+}
+
+/// Type for encoding records to a separate encoder for each output of a [`Splitter`].
+///
+/// The encoder for an output is created with `make_encoder` from its key and adjusted
+/// metadata when the first record of the output is encoded.
+#[derive(Debug)]
+pub struct SplitEncoder<S, E, F>
+where
+    S: Splitter,
+{
+    splitter: S,
+    metadata: Metadata,
+    make_encoder: F,
+    encoders: HashMap<S::Key, E>,
+}
+
+impl<S, E, F> SplitEncoder<S, E, F>
+where
+    S: Splitter,
+    E: EncodeDbn,
+    F: FnMut(&S::Key, &Metadata) -> crate::Result<E>,
+{
+    /// Creates a new [`SplitEncoder`] for records described by `metadata`.
+    pub fn new(splitter: S, metadata: Metadata, make_encoder: F) -> Self {
+        Self {
+            splitter,
+            metadata,
+            make_encoder,
+            encoders: HashMap::new(),
+        }
+    }
+
+    /// Returns a reference to the encoders created so far by key.
+    pub fn encoders(&self) -> &HashMap<S::Key, E> {
+        &self.encoders
+    }
+
+    /// Consumes the encoder and returns the encoders created by key.
+    pub fn into_encoders(self) -> HashMap<S::Key, E> {
+        self.encoders
+    }
+
+    /// Returns the encoder for the output of `record`, creating it if necessary.
+    fn encoder(&mut self, record: RecordRef) -> crate::Result<&mut E> {
+        let key = self.splitter.key(record);
+        if !self.encoders.contains_key(&key) {
+            let mut metadata = self.metadata.clone();
+            self.splitter.adjust_metadata(&key, &mut metadata);
+            let encoder = (self.make_encoder)(&key, &metadata)?;
+            self.encoders.insert(key.clone(), encoder);
+        }
+        Ok(self.encoders.get_mut(&key).unwrap())
+    }
+}
+
+impl<S, E, F> EncodeRecord for SplitEncoder<S, E, F>
+where
+    S: Splitter,
+    E: EncodeDbn,
+    F: FnMut(&S::Key, &Metadata) -> crate::Result<E>,
+{
+    fn encode_record<R: DbnEncodable>(&mut self, record: &R) -> crate::Result<()> {
+        // Safe: the header is part of a complete record of type `R`.
+        let rec_ref = unsafe { RecordRef::unchecked_from_header(record.header()) };
+        self.encoder(rec_ref)?.encode_record(record)
+    }
+
+    fn flush(&mut self) -> crate::Result<()> {
+        for encoder in self.encoders.values_mut() {
+            encoder.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<S, E, F> EncodeRecordRef for SplitEncoder<S, E, F>
+where
+    S: Splitter,
+    E: EncodeDbn,
+    F: FnMut(&S::Key, &Metadata) -> crate::Result<E>,
+{
+    fn encode_record_ref(&mut self, record: RecordRef) -> crate::Result<()> {
+        self.encoder(record)?.encode_record_ref(record)
+    }
+
+    unsafe fn encode_record_ref_ts_out(
+        &mut self,
+        record: RecordRef,
+        ts_out: bool,
+    ) -> crate::Result<()> {
+        self.encoder(record)?
+            .encode_record_ref_ts_out(record, ts_out)
+    }
+}
+
+impl<S, E, F> EncodeDbn for SplitEncoder<S, E, F>
+where
+    S: Splitter,
+    E: EncodeDbn,
+    F: FnMut(&S::Key, &Metadata) -> crate::Result<E>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+    use crate::{
+        decode::{tests::TEST_DATA_PATH, DbnDecoder, DbnMetadata, DecodeRecordRef},
+        encode::DbnEncoder,
+        MappingInterval, MboMsg, MetadataBuilder, RecordHeader, Schema, SymbolMapping, TradeMsg,
+    };
+
+    const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+    fn metadata() -> Metadata {
+        MetadataBuilder::new()
+            .dataset("XNAS.ITCH".to_owned())
+            .schema(None)
+            .start(0)
+            .end(NonZeroU64::new(3 * DAY))
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .symbols(vec!["AAPL".to_owned(), "TSLA".to_owned()])
+            .mappings(vec![
+                SymbolMapping {
+                    raw_symbol: "AAPL".to_owned(),
+                    intervals: vec![MappingInterval {
+                        start_date: date!(1970 - 01 - 01),
+                        end_date: date!(1970 - 01 - 04),
+                        symbol: "1".to_owned(),
+                    }],
+                },
+                SymbolMapping {
+                    raw_symbol: "TSLA".to_owned(),
+                    intervals: vec![MappingInterval {
+                        start_date: date!(1970 - 01 - 01),
+                        end_date: date!(1970 - 01 - 04),
+                        symbol: "2".to_owned(),
+                    }],
+                },
+            ])
+            .build()
+    }
+
+    fn split<S: Splitter>(splitter: S, records: &[RecordRef]) -> HashMap<S::Key, Vec<u8>> {
+        let mut target = SplitEncoder::new(splitter, metadata(), |_, metadata| {
+            DbnEncoder::new(Vec::new(), metadata)
+        });
+        for record in records {
+            target.encode_record_ref(*record).unwrap();
+        }
+        target.flush().unwrap();
+        target
+            .into_encoders()
+            .into_iter()
+            .map(|(key, encoder)| (key, encoder.get_ref().clone()))
+            .collect()
+    }
+
+    fn decode(buffer: &[u8]) -> (Metadata, Vec<u32>) {
+        let mut decoder = DbnDecoder::new(buffer).unwrap();
+        let mut instrument_ids = Vec::new();
+        while let Some(record) = decoder.decode_record_ref().unwrap() {
+            instrument_ids.push(record.header().instrument_id);
+        }
+        (decoder.metadata().clone(), instrument_ids)
+    }
+
+    #[test]
+    fn test_split_by_instrument() {
+        let mbo = |instrument_id| MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, instrument_id, 0),
+            ..Default::default()
+        };
+        let records = [mbo(1), mbo(2), mbo(1)];
+        let outputs = split(
+            SplitByInstrument,
+            &records.iter().map(RecordRef::from).collect::<Vec<_>>(),
+        );
+        assert_eq!(outputs.len(), 2);
+        let (metadata, instrument_ids) = decode(&outputs[&1]);
+        assert_eq!(instrument_ids, [1, 1]);
+        assert_eq!(metadata.symbols, ["AAPL"]);
+        assert_eq!(metadata.mappings.len(), 1);
+        assert_eq!(metadata.mappings[0].raw_symbol, "AAPL");
+        let (metadata, instrument_ids) = decode(&outputs[&2]);
+        assert_eq!(instrument_ids, [2]);
+        assert_eq!(metadata.symbols, ["TSLA"]);
+    }
+
+    #[test]
+    fn test_split_by_rtype_and_time() {
+        let mbo = MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, 1, DAY / 2),
+            ts_recv: DAY / 2,
+            ..Default::default()
+        };
+        let trades = [DAY / 2, DAY + 1, 2 * DAY - 1].map(|ts| TradeMsg {
+            hd: RecordHeader::new::<TradeMsg>(rtype::MBP_0, 1, 2, ts),
+            ts_recv: ts,
+            ..Default::default()
+        });
+        let mut records = vec![RecordRef::from(&mbo)];
+        records.extend(trades.iter().map(RecordRef::from));
+        let outputs = split(
+            (
+                SplitByRType,
+                SplitByTime::new(NonZeroU64::new(DAY).unwrap()),
+            ),
+            &records,
+        );
+        assert_eq!(outputs.len(), 3);
+        let (metadata, instrument_ids) = decode(&outputs[&(rtype::MBO, 0)]);
+        assert_eq!(instrument_ids, [1]);
+        assert_eq!(metadata.schema, Some(Schema::Mbo));
+        assert_eq!(metadata.end, NonZeroU64::new(DAY));
+        let (metadata, instrument_ids) = decode(&outputs[&(rtype::MBP_0, DAY)]);
+        assert_eq!(instrument_ids, [2, 2]);
+        assert_eq!(metadata.schema, Some(Schema::Trades));
+        assert_eq!(metadata.start, DAY);
+        assert_eq!(metadata.end, NonZeroU64::new(2 * DAY));
+        assert!(metadata
+            .mappings
+            .iter()
+            .all(
+                |mapping| mapping.intervals[0].start_date == date!(1970 - 01 - 02)
+                    && mapping.intervals[0].end_date == date!(1970 - 01 - 03)
+            ));
+    }
+
+    #[test]
+    fn test_encode_decoded() {
+        let decoder = DbnDecoder::from_file(format!("{TEST_DATA_PATH}/test_data.mbo.dbn")).unwrap();
+        let metadata = decoder.metadata().clone();
+        let mut target = SplitEncoder::new(SplitByRType, metadata, |_, metadata| {
+            DbnEncoder::new(Vec::new(), metadata)
+        });
+        target.encode_decoded(decoder).unwrap();
+        let encoders = target.into_encoders();
+        assert_eq!(encoders.len(), 1);
+        let (_, instrument_ids) = decode(encoders[&rtype::MBO].get_ref());
+        assert_eq!(instrument_ids.len(), 2);
+    }
+}
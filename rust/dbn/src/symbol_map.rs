@@ -102,6 +102,17 @@ impl TsSymbolMap {
         self.0.get(&(date, instrument_id)).map(Deref::deref)
     }
 
+    /// Returns the instrument ID mapped to `symbol` on the given date. Returns `None`
+    /// if no mapping exists.
+    ///
+    /// This performs a linear search over the mappings.
+    pub fn get_instrument_id(&self, date: time::Date, symbol: &str) -> Option<u32> {
+        self.0
+            .iter()
+            .find(|((d, _), s)| *d == date && s.as_str() == symbol)
+            .map(|((_, instrument_id), _)| *instrument_id)
+    }
+
     /// Returns a reference to the inner map.
     pub fn inner(&self) -> &HashMap<(time::Date, u32), Arc<String>> {
         &self.0
@@ -264,6 +275,17 @@ impl PitSymbolMap {
         self.0.get(&instrument_id)
     }
 
+    /// Returns the instrument ID mapped to `symbol`. Returns `None` if no mapping
+    /// exists.
+    ///
+    /// This performs a linear search over the mappings.
+    pub fn get_instrument_id(&self, symbol: &str) -> Option<u32> {
+        self.0
+            .iter()
+            .find(|(_, s)| s.as_str() == symbol)
+            .map(|(instrument_id, _)| *instrument_id)
+    }
+
     /// Returns a reference to the inner map.
     pub fn inner(&self) -> &HashMap<u32, String> {
         &self.0
@@ -864,6 +886,31 @@ mod tests {
         assert_eq!(symbol_map, inverse_target.symbol_map().unwrap());
     }
 
+    #[test]
+    fn test_get_instrument_id() {
+        let target = metadata_w_mappings();
+        let symbol_map = target.symbol_map().unwrap();
+        assert_eq!(
+            symbol_map.get_instrument_id(date!(2023 - 07 - 02), "TSLA"),
+            Some(10221)
+        );
+        assert_eq!(
+            symbol_map.get_instrument_id(date!(2023 - 07 - 31), "TSLA"),
+            Some(10163)
+        );
+        assert_eq!(
+            symbol_map.get_instrument_id(date!(2023 - 08 - 01), "TSLA"),
+            None
+        );
+        assert_eq!(
+            symbol_map.get_instrument_id(date!(2023 - 07 - 02), "GOOG"),
+            None
+        );
+        let pit_symbol_map = target.symbol_map_for_date(date!(2023 - 07 - 31)).unwrap();
+        assert_eq!(pit_symbol_map.get_instrument_id("AAPL"), Some(32));
+        assert_eq!(pit_symbol_map.get_instrument_id("GOOG"), None);
+    }
+
     #[test]
     fn test_other_stype_errors() {
         let mut target = metadata_w_mappings();
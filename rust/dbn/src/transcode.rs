@@ -0,0 +1,144 @@
+//! Concurrent transcoding of independent DBN files, e.g. converting a month of daily
+//! files to CSV.
+
+use std::{
+    num::NonZeroUsize,
+    panic,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    decode::{DbnMetadata, DynDecoder},
+    encode::EncodeDbn,
+    Metadata, VersionUpgradePolicy,
+};
+
+/// Decodes each of the DBN files at `inputs` and encodes its records with the encoder
+/// returned by `encoder_factory`, converting up to `threads` files concurrently.
+/// `encoder_factory` is called with the path of the input and its decoded metadata,
+/// and is responsible for opening the output.
+///
+/// Each file is streamed through its encoder rather than read into memory, so memory
+/// usage is bounded by `threads` decoders and encoders regardless of the size of the
+/// files. Records from previous DBN versions are decoded according to
+/// `upgrade_policy`.
+///
+/// Returns the result of converting each input in the same order as `inputs`. A
+/// failure to convert one file doesn't stop the conversion of the others.
+///
+/// # Panics
+/// This function panics if `encoder_factory` or the encoder panics.
+pub fn parallel<P, F, E>(
+    inputs: &[P],
+    encoder_factory: F,
+    threads: NonZeroUsize,
+    upgrade_policy: VersionUpgradePolicy,
+) -> Vec<crate::Result<()>>
+where
+    P: AsRef<Path> + Sync,
+    F: Fn(&Path, &Metadata) -> crate::Result<E> + Sync,
+    E: EncodeDbn,
+{
+    let next_input = AtomicUsize::new(0);
+    let mut results: Vec<(usize, crate::Result<()>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.get().min(inputs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let idx = next_input.fetch_add(1, Ordering::Relaxed);
+                        let Some(input) = inputs.get(idx) else {
+                            break;
+                        };
+                        results.push((
+                            idx,
+                            transcode_file(input.as_ref(), &encoder_factory, upgrade_policy),
+                        ));
+                    }
+                    results
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect()
+    });
+    results.sort_unstable_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, res)| res).collect()
+}
+
+fn transcode_file<F, E>(
+    input: &Path,
+    encoder_factory: &F,
+    upgrade_policy: VersionUpgradePolicy,
+) -> crate::Result<()>
+where
+    F: Fn(&Path, &Metadata) -> crate::Result<E>,
+    E: EncodeDbn,
+{
+    let decoder = DynDecoder::from_file(input, upgrade_policy)?;
+    let mut encoder = encoder_factory(input, decoder.metadata())?;
+    encoder.encode_decoded(decoder)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, path::PathBuf};
+
+    use super::*;
+    use crate::{decode::tests::TEST_DATA_PATH, encode::DynEncoder, Compression, Encoding};
+
+    #[test]
+    fn test_parallel() {
+        let inputs = [
+            format!("{TEST_DATA_PATH}/test_data.mbo.dbn"),
+            format!("{TEST_DATA_PATH}/test_data.trades.dbn.zst"),
+            format!("{TEST_DATA_PATH}/nonexistent.dbn"),
+            format!("{TEST_DATA_PATH}/test_data.mbp-1.dbn.zst"),
+        ];
+        let output_path = |input: &Path| -> PathBuf {
+            std::env::temp_dir().join(format!(
+                "dbn_test_parallel_{}.csv",
+                input.file_name().unwrap().to_str().unwrap()
+            ))
+        };
+        let results = parallel(
+            &inputs,
+            |input, metadata| {
+                let file = File::create(output_path(input))
+                    .map_err(|e| crate::Error::io(e, "creating output file"))?;
+                DynEncoder::new(
+                    file,
+                    Encoding::Csv,
+                    Compression::None,
+                    metadata,
+                    false,
+                    false,
+                    false,
+                )
+            },
+            NonZeroUsize::new(2).unwrap(),
+            VersionUpgradePolicy::AsIs,
+        );
+        assert_eq!(results.len(), inputs.len());
+        for (input, res) in inputs.iter().zip(results) {
+            if input.ends_with("nonexistent.dbn") {
+                assert!(matches!(res, Err(crate::Error::Io { .. })));
+                continue;
+            }
+            res.unwrap();
+            let output_path = output_path(Path::new(input));
+            let contents = std::fs::read_to_string(&output_path).unwrap();
+            // Header and two records
+            assert_eq!(contents.lines().count(), 3, "{input}");
+            std::fs::remove_file(output_path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,560 @@
+//! Checks for the consistency of DBN records with their [`Metadata`] and of the
+//! sequence numbers and receive timestamps of streams of records, e.g. for quality
+//! assurance of archived data or captures.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+use crate::{
+    decode::DecodeRecordRef, rtype, HasRType, Metadata, OhlcvMsg, RType, Record, RecordRef,
+    RecordRefEnum, SymbolIndex, UNDEF_TIMESTAMP,
+};
+
+/// The result of [`check()`]: the inconsistencies found between the metadata and the
+/// records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    /// The number of records checked.
+    pub record_count: u64,
+    /// The number of records whose index timestamp falls outside the range of `start`
+    /// (inclusive) and `end` (exclusive) in the metadata.
+    pub out_of_range_count: u64,
+    /// The instrument IDs of records without a symbol mapping in the metadata for the
+    /// date of the record.
+    pub unmapped_instrument_ids: BTreeSet<u32>,
+    /// The rtypes of records that don't match the schema in the metadata.
+    pub unexpected_rtypes: BTreeSet<u8>,
+}
+
+impl Report {
+    /// Returns `true` if no inconsistencies were found.
+    pub fn is_valid(&self) -> bool {
+        self.out_of_range_count == 0
+            && self.unmapped_instrument_ids.is_empty()
+            && self.unexpected_rtypes.is_empty()
+    }
+}
+
+/// Decodes all the records from `decoder` and checks that:
+/// - their index timestamps are within `start` and `end` of `metadata`
+/// - their instrument IDs have a symbol mapping in `metadata` for the date of the
+///   record. This check is skipped if `metadata` contains no mappings, such as for
+///   live data
+/// - their rtypes match the `schema` of `metadata`, if any
+///
+/// Gateway error, system, and symbol mapping records are excluded from the symbology
+/// and schema checks.
+///
+/// # Errors
+/// This function returns an error if it fails to decode a record or fails to create a
+/// symbol map from `metadata`.
+pub fn check<D: DecodeRecordRef>(metadata: &Metadata, mut decoder: D) -> crate::Result<Report> {
+    let symbol_map = if metadata.mappings.is_empty() {
+        None
+    } else {
+        Some(metadata.symbol_map()?)
+    };
+    let expected_rtype = metadata.schema.map(|schema| RType::from(schema) as u8);
+    let mut report = Report::default();
+    while let Some(rec) = decoder.decode_record_ref()? {
+        report.record_count += 1;
+        let index_ts = rec.raw_index_ts();
+        if index_ts != UNDEF_TIMESTAMP
+            && (index_ts < metadata.start
+                || metadata.end.map_or(false, |end| index_ts >= end.get()))
+        {
+            report.out_of_range_count += 1;
+        }
+        let rec_rtype = rec.header().rtype;
+        if matches!(
+            rec_rtype,
+            rtype::ERROR | rtype::SYSTEM | rtype::SYMBOL_MAPPING
+        ) {
+            continue;
+        }
+        if let Some(symbol_map) = symbol_map.as_ref() {
+            if symbol_map.get_for_rec(&rec).is_none() {
+                report
+                    .unmapped_instrument_ids
+                    .insert(rec.header().instrument_id);
+            }
+        }
+        if let Some(expected_rtype) = expected_rtype {
+            #[allow(deprecated)]
+            let is_deprecated_ohlcv =
+                rec_rtype == rtype::OHLCV_DEPRECATED && OhlcvMsg::has_rtype(expected_rtype);
+            if rec_rtype != expected_rtype && !is_deprecated_ohlcv {
+                report.unexpected_rtypes.insert(rec_rtype);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// How a [`SequenceChecker`] groups records into streams with their own sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SequenceGrouping {
+    /// Each instrument of each publisher has its own sequence.
+    #[default]
+    Instrument,
+    /// Each channel of each publisher has its own sequence. Records without a channel
+    /// ID are grouped by publisher.
+    Channel,
+}
+
+/// Identifies a stream of records checked by a [`SequenceChecker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamKey {
+    /// The publisher ID.
+    pub publisher_id: u16,
+    /// The instrument ID when grouping by [`SequenceGrouping::Instrument`].
+    pub instrument_id: Option<u32>,
+    /// The channel ID when grouping by [`SequenceGrouping::Channel`] and the record
+    /// has a channel ID.
+    pub channel_id: Option<u16>,
+}
+
+/// A problem found by a [`SequenceChecker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceIssueKind {
+    /// One or more sequence numbers were skipped.
+    Gap {
+        /// The next sequence number after that of the previous record.
+        expected: u32,
+        /// The sequence number of the record.
+        received: u32,
+    },
+    /// The sequence number was lower than that of the previous record.
+    Reorder {
+        /// The sequence number of the previous record.
+        previous: u32,
+        /// The sequence number of the record.
+        received: u32,
+    },
+    /// The record was identical to the previous record.
+    Duplicate {
+        /// The sequence number of the record.
+        sequence: u32,
+    },
+    /// The `ts_recv` was earlier than that of the previous record.
+    TsRecvReorder {
+        /// The `ts_recv` of the previous record.
+        previous: u64,
+        /// The `ts_recv` of the record.
+        received: u64,
+    },
+}
+
+/// A problem with a record found by a [`SequenceChecker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceIssue {
+    /// The zero-based index of the record among all the records passed to the checker.
+    pub record_index: u64,
+    /// The stream of the record.
+    pub stream: StreamKey,
+    /// The problem.
+    pub kind: SequenceIssueKind,
+}
+
+/// The counts of the problems found by a [`SequenceChecker`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SequenceReport {
+    /// The number of records with a sequence number checked.
+    pub record_count: u64,
+    /// The number of gaps.
+    pub gap_count: u64,
+    /// The total number of sequence numbers skipped by gaps.
+    pub missing_count: u64,
+    /// The number of records with a lower sequence number than the previous record.
+    pub reorder_count: u64,
+    /// The number of records identical to the previous record.
+    pub duplicate_count: u64,
+    /// The number of records with an earlier `ts_recv` than the previous record.
+    pub ts_recv_reorder_count: u64,
+}
+
+impl SequenceReport {
+    /// Returns `true` if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.gap_count == 0
+            && self.reorder_count == 0
+            && self.duplicate_count == 0
+            && self.ts_recv_reorder_count == 0
+    }
+}
+
+/// Checks that the `sequence` of each stream of records increases without gaps and its
+/// `ts_recv` never decreases, and that no record is repeated.
+///
+/// Consecutive records of a stream may share a sequence number, e.g. the MBO records
+/// from a single venue message, so only exact copies of the previous record are
+/// reported as duplicates. Only records with a `sequence` field are checked, other
+/// records are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceChecker {
+    grouping: SequenceGrouping,
+    record_index: u64,
+    streams: HashMap<StreamKey, StreamState>,
+    report: SequenceReport,
+}
+
+#[derive(Debug, Clone)]
+struct StreamState {
+    sequence: u32,
+    ts_recv: u64,
+    /// The bytes of the previous record, without any `ts_out`.
+    last_record: Vec<u8>,
+}
+
+impl SequenceChecker {
+    /// Creates a new [`SequenceChecker`] grouping records into streams according to
+    /// `grouping`.
+    pub fn new(grouping: SequenceGrouping) -> Self {
+        Self {
+            grouping,
+            ..Default::default()
+        }
+    }
+
+    /// Checks the next record of the stream and returns the problems found with it, if
+    /// any.
+    pub fn check(&mut self, record: RecordRef) -> Vec<SequenceIssue> {
+        let record_index = self.record_index;
+        self.record_index += 1;
+        let Ok(rec_enum) = RecordRefEnum::try_from(record) else {
+            return Vec::new();
+        };
+        let (sequence, ts_recv, channel_id, bytes) = match rec_enum {
+            RecordRefEnum::Mbo(rec) => (
+                rec.sequence,
+                rec.ts_recv,
+                Some(rec.channel_id as u16),
+                rec.as_ref(),
+            ),
+            RecordRefEnum::Trade(rec) => (rec.sequence, rec.ts_recv, None, rec.as_ref()),
+            RecordRefEnum::Mbp1(rec) => (rec.sequence, rec.ts_recv, None, rec.as_ref()),
+            RecordRefEnum::Mbp10(rec) => (rec.sequence, rec.ts_recv, None, rec.as_ref()),
+            RecordRefEnum::Bbo(rec) => (rec.sequence, rec.ts_recv, None, rec.as_ref()),
+            RecordRefEnum::Cbbo(rec) => (rec.sequence, rec.ts_recv, None, rec.as_ref()),
+            RecordRefEnum::Stat(rec) => (
+                rec.sequence,
+                rec.ts_recv,
+                Some(rec.channel_id),
+                rec.as_ref(),
+            ),
+            _ => return Vec::new(),
+        };
+        self.report.record_count += 1;
+        let header = record.header();
+        let stream = match self.grouping {
+            SequenceGrouping::Instrument => StreamKey {
+                publisher_id: header.publisher_id,
+                instrument_id: Some(header.instrument_id),
+                channel_id: None,
+            },
+            SequenceGrouping::Channel => StreamKey {
+                publisher_id: header.publisher_id,
+                instrument_id: None,
+                channel_id,
+            },
+        };
+        let mut issues = Vec::new();
+        let Some(state) = self.streams.get_mut(&stream) else {
+            self.streams.insert(
+                stream,
+                StreamState {
+                    sequence,
+                    ts_recv,
+                    last_record: bytes.to_vec(),
+                },
+            );
+            return issues;
+        };
+        let mut push_issue = |kind| {
+            issues.push(SequenceIssue {
+                record_index,
+                stream,
+                kind,
+            })
+        };
+        if bytes == state.last_record.as_slice() {
+            self.report.duplicate_count += 1;
+            push_issue(SequenceIssueKind::Duplicate { sequence });
+            return issues;
+        }
+        if sequence < state.sequence {
+            self.report.reorder_count += 1;
+            push_issue(SequenceIssueKind::Reorder {
+                previous: state.sequence,
+                received: sequence,
+            });
+        } else if sequence as u64 > state.sequence as u64 + 1 {
+            self.report.gap_count += 1;
+            self.report.missing_count += (sequence - state.sequence - 1) as u64;
+            push_issue(SequenceIssueKind::Gap {
+                expected: state.sequence + 1,
+                received: sequence,
+            });
+        }
+        if ts_recv != UNDEF_TIMESTAMP {
+            if state.ts_recv != UNDEF_TIMESTAMP && ts_recv < state.ts_recv {
+                self.report.ts_recv_reorder_count += 1;
+                push_issue(SequenceIssueKind::TsRecvReorder {
+                    previous: state.ts_recv,
+                    received: ts_recv,
+                });
+            }
+            state.ts_recv = state.ts_recv.max(ts_recv);
+        }
+        // A late record doesn't reset the sequence so the records after it aren't
+        // reported as a gap
+        state.sequence = state.sequence.max(sequence);
+        state.last_record.clear();
+        state.last_record.extend_from_slice(bytes);
+        issues
+    }
+
+    /// Returns the counts of the problems found so far.
+    pub fn report(&self) -> &SequenceReport {
+        &self.report
+    }
+}
+
+impl fmt::Display for StreamKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "publisher {}", self.publisher_id)?;
+        if let Some(instrument_id) = self.instrument_id {
+            write!(f, " instrument {instrument_id}")?;
+        }
+        if let Some(channel_id) = self.channel_id {
+            write!(f, " channel {channel_id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SequenceIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceIssueKind::Gap { expected, received } => {
+                write!(
+                    f,
+                    "sequence gap, expected {expected} but received {received}"
+                )
+            }
+            SequenceIssueKind::Reorder { previous, received } => {
+                write!(f, "sequence decreased from {previous} to {received}")
+            }
+            SequenceIssueKind::Duplicate { sequence } => {
+                write!(f, "duplicate record with sequence {sequence}")
+            }
+            SequenceIssueKind::TsRecvReorder { previous, received } => {
+                write!(f, "ts_recv decreased from {previous} to {received}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for SequenceIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "record {} ({}): {}",
+            self.record_index, self.stream, self.kind
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use time::macros::date;
+
+    use super::*;
+    use crate::{
+        decode::dbn::RecordDecoder,
+        encode::{DbnRecordEncoder, EncodeRecord},
+        Dataset, MappingInterval, MboMsg, RecordHeader, SType, Schema, SymbolMapping, SystemMsg,
+        TradeMsg,
+    };
+
+    // 2023-07-01T00:00:00Z
+    const START: u64 = 1_688_169_600_000_000_000;
+    // 2023-07-02T00:00:00Z
+    const END: u64 = 1_688_256_000_000_000_000;
+
+    fn metadata() -> Metadata {
+        Metadata::builder()
+            .dataset(Dataset::XnasItch.as_str().to_owned())
+            .schema(Some(Schema::Mbo))
+            .start(START)
+            .end(NonZeroU64::new(END))
+            .stype_in(Some(SType::RawSymbol))
+            .stype_out(SType::InstrumentId)
+            .mappings(vec![SymbolMapping {
+                raw_symbol: "AAPL".to_owned(),
+                intervals: vec![MappingInterval {
+                    start_date: date!(2023 - 07 - 01),
+                    end_date: date!(2023 - 07 - 02),
+                    symbol: "32".to_owned(),
+                }],
+            }])
+            .build()
+    }
+
+    fn mbo(instrument_id: u32, ts_recv: u64) -> MboMsg {
+        MboMsg {
+            hd: RecordHeader::new::<MboMsg>(rtype::MBO, 1, instrument_id, ts_recv),
+            ts_recv,
+            ..Default::default()
+        }
+    }
+
+    fn seq_mbo(instrument_id: u32, sequence: u32, ts_recv: u64) -> MboMsg {
+        MboMsg {
+            sequence,
+            ..mbo(instrument_id, ts_recv)
+        }
+    }
+
+    #[test]
+    fn test_sequence_checker() {
+        let mut target = SequenceChecker::new(SequenceGrouping::Instrument);
+        let mut check = |rec: &MboMsg| target.check(RecordRef::from(rec));
+        assert!(check(&seq_mbo(1, 10, START)).is_empty());
+        // Records from one message share a sequence number
+        assert!(check(&seq_mbo(1, 10, START + 1)).is_empty());
+        // Other instruments have their own sequence
+        assert!(check(&seq_mbo(2, 100, START)).is_empty());
+        let issues = check(&seq_mbo(1, 13, START + 2));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0],
+            SequenceIssue {
+                record_index: 3,
+                stream: StreamKey {
+                    publisher_id: 1,
+                    instrument_id: Some(1),
+                    channel_id: None
+                },
+                kind: SequenceIssueKind::Gap {
+                    expected: 11,
+                    received: 13
+                }
+            }
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "record 3 (publisher 1 instrument 1): sequence gap, expected 11 but received 13"
+        );
+        let issues = check(&seq_mbo(1, 11, START + 1));
+        assert_eq!(
+            issues.iter().map(|issue| issue.kind).collect::<Vec<_>>(),
+            [
+                SequenceIssueKind::Reorder {
+                    previous: 13,
+                    received: 11
+                },
+                SequenceIssueKind::TsRecvReorder {
+                    previous: START + 2,
+                    received: START + 1
+                }
+            ]
+        );
+        let issues = check(&seq_mbo(1, 11, START + 1));
+        assert_eq!(
+            issues[0].kind,
+            SequenceIssueKind::Duplicate { sequence: 11 }
+        );
+        // The late record doesn't cause a gap
+        assert!(check(&seq_mbo(1, 14, START + 3)).is_empty());
+        let report = target.report();
+        assert!(!report.is_valid());
+        assert_eq!(
+            *report,
+            SequenceReport {
+                record_count: 7,
+                gap_count: 1,
+                missing_count: 2,
+                reorder_count: 1,
+                duplicate_count: 1,
+                ts_recv_reorder_count: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_sequence_checker_by_channel() {
+        let mut target = SequenceChecker::new(SequenceGrouping::Channel);
+        let mut channel_1 = seq_mbo(1, 1, START);
+        channel_1.channel_id = 1;
+        assert!(target.check(RecordRef::from(&channel_1)).is_empty());
+        assert!(target
+            .check(RecordRef::from(&seq_mbo(2, 5, START)))
+            .is_empty());
+        channel_1.hd.instrument_id = 2;
+        channel_1.sequence = 2;
+        assert!(target.check(RecordRef::from(&channel_1)).is_empty());
+        let issues = target.check(RecordRef::from(&seq_mbo(1, 7, START)));
+        assert_eq!(issues[0].stream.to_string(), "publisher 1 channel 0");
+        // Records without a sequence are ignored
+        assert!(target
+            .check(RecordRef::from(&SystemMsg::heartbeat(START)))
+            .is_empty());
+        assert_eq!(target.report().record_count, 4);
+    }
+
+    #[test]
+    fn test_check_valid() {
+        let mut buffer = Vec::new();
+        let mut encoder = DbnRecordEncoder::new(&mut buffer);
+        encoder.encode_record(&mbo(32, START)).unwrap();
+        encoder.encode_record(&SystemMsg::heartbeat(START)).unwrap();
+        encoder.encode_record(&mbo(32, START + 1)).unwrap();
+        let report = check(&metadata(), RecordDecoder::new(buffer.as_slice())).unwrap();
+        assert!(report.is_valid(), "{report:?}");
+        assert_eq!(report.record_count, 3);
+    }
+
+    #[test]
+    fn test_check_end_exclusive() {
+        let mut buffer = Vec::new();
+        let mut encoder = DbnRecordEncoder::new(&mut buffer);
+        encoder.encode_record(&mbo(32, END - 1)).unwrap();
+        encoder.encode_record(&mbo(32, END)).unwrap();
+        let report = check(&metadata(), RecordDecoder::new(buffer.as_slice())).unwrap();
+        assert_eq!(report.record_count, 2);
+        assert_eq!(report.out_of_range_count, 1);
+    }
+
+    #[test]
+    fn test_check_invalid() {
+        let mut buffer = Vec::new();
+        let mut encoder = DbnRecordEncoder::new(&mut buffer);
+        encoder.encode_record(&mbo(32, START - 1)).unwrap();
+        encoder.encode_record(&mbo(33, START)).unwrap();
+        encoder.encode_record(&mbo(32, END + 1)).unwrap();
+        encoder
+            .encode_record(&TradeMsg {
+                hd: RecordHeader::new::<TradeMsg>(rtype::MBP_0, 1, 32, START),
+                ts_recv: START,
+                ..Default::default()
+            })
+            .unwrap();
+        let report = check(&metadata(), RecordDecoder::new(buffer.as_slice())).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(
+            report,
+            Report {
+                record_count: 4,
+                out_of_range_count: 2,
+                // Also contains 32 because of the records outside the mapping dates
+                unmapped_instrument_ids: BTreeSet::from([32, 33]),
+                unexpected_rtypes: BTreeSet::from([rtype::MBP_0]),
+            }
+        );
+    }
+}